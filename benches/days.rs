@@ -0,0 +1,83 @@
+//! Criterion benchmarks for every registered day and part, solving the
+//! puzzle examples embedded in the solvers.
+//!
+//! Set `AOC_BENCH_INPUTS=1` to also benchmark the real inputs under `data/`,
+//! days without an input file are skipped. Parts with alternative
+//! implementations bench each of them on the example, but only the default on
+//! the real input.
+//!
+//! To compare a change against the current code, save a baseline first and
+//! then run the benches again against it:
+//!
+//! ```text
+//! cargo bench --bench days -- --save-baseline before
+//! # make the change
+//! cargo bench --bench days -- --baseline before
+//! ```
+//!
+//! A filter after `--` limits the run, eg. `cargo bench --bench days -- day05`.
+
+use std::{fs::read_to_string, path::PathBuf};
+
+use aoc_2023::{registry::Registry, solver::Solver};
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+    Throughput,
+};
+
+/// Days whose solve time is dominated by reading the input, reported in
+/// lines per second rather than just time per iteration.
+const PARSING_HEAVY: &[u8] = &[1, 2, 3, 4, 7];
+
+fn real_inputs_enabled() -> bool {
+    std::env::var_os("AOC_BENCH_INPUTS").is_some_and(|value| value != "0")
+}
+
+fn bench_days(c: &mut Criterion) {
+    let registry = Registry::builtin();
+    let real_inputs = real_inputs_enabled();
+    for (day, puzzle) in registry.days() {
+        let input = real_inputs
+            .then(|| read_to_string(PathBuf::from(format!("data/{:02}/input", day))).ok())
+            .flatten();
+
+        let mut group = c.benchmark_group(format!("day{:02}", day));
+        for part in [1, 2] {
+            let implementations = puzzle.implementations(part);
+            for (index, solver) in implementations.iter().enumerate() {
+                let name = match implementations.len() {
+                    1 => format!("part{}", part),
+                    _ => format!("part{}/{}", part, solver.implementation()),
+                };
+                if let Some((example, _)) = solver.example() {
+                    bench_input(&mut group, day, &name, "example", example, solver.as_ref());
+                }
+                // alternatives are often brute forces that take far too long
+                // on a real input to sample repeatedly
+                if let Some(input) = input.as_ref().filter(|_| index == 0) {
+                    bench_input(&mut group, day, &name, "input", input, solver.as_ref());
+                }
+            }
+        }
+        group.finish();
+    }
+}
+
+fn bench_input(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    day: u8,
+    part: &str,
+    name: &str,
+    input: &str,
+    solver: &dyn Solver,
+) {
+    if PARSING_HEAVY.contains(&day) {
+        group.throughput(Throughput::Elements(input.lines().count() as u64));
+    }
+    group.bench_function(format!("{}/{}", part, name), |b| {
+        b.iter(|| solver.solve_str(black_box(input)))
+    });
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! Records `git describe` and the commit's hash for the binary to report
+//! which commit produced a set of results, when it's built from a git
+//! checkout.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+    if let Some(describe) = git(&["describe", "--always", "--dirty", "--tags"]) {
+        println!("cargo:rustc-env=AOC_GIT_DESCRIBE={}", describe);
+    }
+    if let Some(sha) = git(&["rev-parse", "HEAD"]) {
+        println!("cargo:rustc-env=AOC_GIT_SHA={}", sha);
+    }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|output| output.trim().to_string())
+}
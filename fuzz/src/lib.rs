@@ -0,0 +1,20 @@
+//! What the fuzz targets share: every day's parts are solved on whatever
+//! bytes the fuzzer comes up with, read as UTF-8 with invalid sequences
+//! replaced. Errors are fine, only a panic is a finding. Whatever one finds
+//! gets a `test_fuzz_regressions` case in its day's module, so the tests
+//! cover it without the fuzzer.
+
+use aoc_2023::registry::Registry;
+
+pub fn solve(day: u8, data: &[u8]) {
+    let input = String::from_utf8_lossy(data);
+    let registry = Registry::builtin();
+    let solvers = registry
+        .get(day)
+        .unwrap_or_else(|| panic!("Day {} isn't built", day));
+    for part in 1..=2 {
+        if let Some(solver) = solvers.solver(part) {
+            let _ = solver.solve_str(&input);
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! The known right answers, kept in `answers.toml` in the data root once
+//! they've been accepted, so a run of every day and `verify` can tell which
+//! are still right. `--record` adds to it. It says which event it's for, so
+//! one year's answers are never taken for another's, and each day is a table
+//! of its parts' answers:
+//!
+//! ```toml
+//! year = 2023
+//!
+//! [7]
+//! part1 = 6440
+//! part2 = 5905
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path};
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::solver::Answer;
+
+/// The file in the data root the answers are kept in.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnswersFile {
+    year: Option<u16>,
+    #[serde(flatten)]
+    days: BTreeMap<String, DayAnswers>,
+}
+
+/// The right answer to each part that has one, by day and part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    /// The event they're for, `None` in files from before it was recorded.
+    year: Option<u16>,
+    answers: BTreeMap<(u8, u32), Answer>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self> {
+        let AnswersFile { year, days } = toml::from_str(text)?;
+        let mut answers = BTreeMap::new();
+        for (key, day_answers) in days {
+            let day = key
+                .parse::<u8>()
+                .ok()
+                .filter(|day| (1..=25).contains(day))
+                .ok_or_else(|| anyhow!("[{}] isn't a day, the tables are days 1 to 25", key))?;
+            for (part, answer) in [(1, day_answers.part1), (2, day_answers.part2)] {
+                if let Some(answer) = answer {
+                    answers.insert((day, part), answer);
+                }
+            }
+        }
+        Ok(Self { year, answers })
+    }
+
+    /// The answers in `data_root`, `None` when it has no answers file.
+    pub fn load(data_root: &Path) -> Result<Option<Self>> {
+        let path = data_root.join(ANSWERS_FILE);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Can't read {}", path.display()));
+            }
+        };
+        Self::parse(&text)
+            .map(Some)
+            .with_context(|| format!("Bad answers file {}", path.display()))
+    }
+
+    pub fn year(&self) -> Option<u16> {
+        self.year
+    }
+
+    /// Whether these are the answers to `year`'s event, which those from
+    /// before the year was recorded are taken to be.
+    pub fn is_for(&self, year: u16) -> bool {
+        self.year.is_none_or(|known| known == year)
+    }
+
+    pub fn get(&self, day: u8, part: u32) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+/// What [`record`] made of the answers it was given.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recorded {
+    /// The parts that had no answer before, by day and part.
+    pub added: Vec<(u8, u32)>,
+    /// The parts already recorded with a different answer, which is kept.
+    pub conflicting: Vec<(u8, u32, Answer)>,
+}
+
+/// Adds `answers`, by day and part, to the answers file in `data_root` for
+/// `year`'s event, creating it if need be and erroring if it's another
+/// year's. Everything else in it is kept, comments included, and so are the
+/// answers already there. It's replaced in one go, by writing
+/// a new file and renaming it over the old, so runs recording at once never
+/// leave it half written.
+pub fn record(data_root: &Path, year: u16, answers: &[(u8, u32, Answer)]) -> Result<Recorded> {
+    let path = data_root.join(ANSWERS_FILE);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Can't read {}", path.display())),
+    };
+    let bad_file = || format!("Bad answers file {}", path.display());
+    let known = Answers::parse(&text).with_context(bad_file)?;
+    let mut document = text.parse::<DocumentMut>().with_context(bad_file)?;
+    match known.year() {
+        Some(known) if known != year => {
+            return Err(anyhow!(
+                "{} has the answers to {}, not {}",
+                path.display(),
+                known,
+                year
+            ))
+        }
+        Some(_) => {}
+        None => {
+            document.insert("year", toml_edit::value(i64::from(year)));
+        }
+    }
+
+    let mut recorded = Recorded::default();
+    for (day, part, answer) in answers {
+        if let Some(known) = known.get(*day, *part) {
+            if known != answer {
+                recorded.conflicting.push((*day, *part, known.clone()));
+            }
+            continue;
+        }
+        if recorded.added.contains(&(*day, *part)) {
+            continue;
+        }
+        // the table may be [07] rather than [7]
+        let key = document
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .find(|key| key.parse::<u8>().ok() == Some(*day))
+            .unwrap_or_else(|| day.to_string());
+        let table = document
+            .entry(&key)
+            .or_insert_with(|| Item::Table(Table::new()))
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("[{}] isn't a table", key))
+            .with_context(bad_file)?;
+        let value = match answer {
+            Answer::Integer(n) => toml_edit::value(*n),
+            Answer::Text(text) => toml_edit::value(text.as_str()),
+        };
+        table.insert(&format!("part{}", part), value);
+        recorded.added.push((*day, *part));
+    }
+    if recorded.added.is_empty() {
+        return Ok(recorded);
+    }
+
+    let temp = data_root.join(format!(".{}.{}.tmp", ANSWERS_FILE, std::process::id()));
+    fs::write(&temp, document.to_string())
+        .and_then(|()| fs::rename(&temp, &path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+        .with_context(|| format!("Can't record answers in {}", path.display()))?;
+    Ok(recorded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let answers = Answers::parse("[1]\npart1 = 142\npart2 = 281\n\n[07]\npart2 = \"5905\"\n")?;
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers.get(1, 2), Some(&281.into()));
+        assert_eq!(answers.get(7, 1), None);
+        assert_eq!(answers.get(7, 2), Some(&"5905".into()));
+        assert_eq!(answers.year(), None);
+        assert!(answers.is_for(2023));
+        let older = Answers::parse("year = 2022\n[1]\npart1 = 1\n")?;
+        assert_eq!(older.year(), Some(2022));
+        assert!(older.is_for(2022));
+        assert!(!older.is_for(2023));
+
+        let error = |text| Answers::parse(text).unwrap_err().to_string();
+        assert!(error("[26]\npart1 = 1\n").starts_with("[26] isn't a day"));
+        assert!(error("[day1]\npart1 = 1\n").starts_with("[day1] isn't a day"));
+        // a misspelt part would otherwise never be checked
+        assert!(Answers::parse("[1]\npart_1 = 1\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_record() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-record-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(ANSWERS_FILE);
+        let existing = "\
+# accepted on the day
+[01]
+part1 = 142 # after two wrong guesses
+
+[6]
+part1 = 288
+";
+        fs::write(&path, existing)?;
+        let recorded = record(
+            &dir,
+            2023,
+            &[
+                (1, 2, 281.into()),
+                (6, 1, 289.into()),
+                (7, 1, "6440".into()),
+                (7, 1, "6440".into()),
+            ],
+        )?;
+        let text = fs::read_to_string(&path)?;
+        let again = record(&dir, 2023, &[(1, 1, 142.into())])?;
+        let other_year = record(&dir, 2022, &[(2, 1, 8.into())]);
+        let created = record(&dir.join("missing"), 2023, &[(1, 1, 142.into())]);
+        let files = fs::read_dir(&dir)?.count();
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(recorded.added, [(1, 2), (7, 1)]);
+        // the answer already there is kept
+        assert_eq!(recorded.conflicting, [(6, 1, 288.into())]);
+        assert_eq!(
+            text,
+            "\
+year = 2023
+# accepted on the day
+[01]
+part1 = 142 # after two wrong guesses
+part2 = 281
+
+[6]
+part1 = 288
+
+[7]
+part1 = \"6440\"
+"
+        );
+        let answers = Answers::parse(&text)?;
+        assert_eq!(answers.year(), Some(2023));
+        assert_eq!(answers.len(), 4);
+        assert_eq!(answers.get(7, 1), Some(&"6440".into()));
+        assert_eq!(again, Recorded::default());
+        assert_eq!(
+            other_year.unwrap_err().to_string(),
+            format!("{} has the answers to 2023, not 2022", path.display())
+        );
+        assert!(created.is_err());
+        // no temporary files are left behind
+        assert_eq!(files, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-answers-{}", std::process::id()));
+        let missing = Answers::load(&dir)?;
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(ANSWERS_FILE), "[6]\npart1 = 288\n")?;
+        let loaded = Answers::load(&dir);
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(missing, None);
+        assert_eq!(
+            loaded?.and_then(|answers| answers.get(6, 1).cloned()),
+            Some(288.into())
+        );
+        Ok(())
+    }
+}
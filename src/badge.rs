@@ -0,0 +1,137 @@
+//! Star progress as a badge, for `aoc report badge`. It's written in the
+//! schema of shields.io's endpoint badges, so a README can show it with eg.
+//! `https://img.shields.io/endpoint?url=<where badge.json is served>`.
+//!
+//! A star is a part with an answer in the answers file, ie. one that's been
+//! confirmed, when the file is the event's.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use crate::answers::Answers;
+
+/// The stars there are to get, two a day.
+pub const TOTAL_STARS: u32 = 50;
+
+/// A badge as shields.io reads it, see <https://shields.io/badges/endpoint-badge>.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Badge {
+    pub schema_version: u32,
+    pub label: String,
+    pub message: String,
+    pub color: &'static str,
+}
+
+impl Badge {
+    fn new(label: String, stars: u32, total: u32) -> Self {
+        Self {
+            schema_version: 1,
+            label,
+            message: format!("{}/{} ⭐", stars, total),
+            color: color(stars, total),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a badge is always valid JSON")
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, format!("{}\n", self.to_json()))
+            .with_context(|| format!("Can't write {}", path.display()))
+    }
+}
+
+/// How far along `stars` out of `total` is: red under a fifth, yellow under
+/// four fifths and green from there, so under 10 and under 40 of the 50.
+pub fn color(stars: u32, total: u32) -> &'static str {
+    if stars * 5 < total {
+        "red"
+    } else if stars * 5 < total * 4 {
+        "yellow"
+    } else {
+        "green"
+    }
+}
+
+/// The badge's label, eg. `AoC 2023`.
+pub fn label(year: u16) -> String {
+    format!("AoC {}", year)
+}
+
+/// The stars of `year`'s whole event.
+pub fn event(answers: &Answers, year: u16) -> Badge {
+    let stars = (1..=25).map(|day| stars(answers, year, day)).sum();
+    Badge::new(label(year), stars, TOTAL_STARS)
+}
+
+/// The stars of one of `year`'s days, out of its two.
+pub fn day(answers: &Answers, year: u16, day: u8) -> Badge {
+    Badge::new(
+        format!("{} day {}", label(year), day),
+        stars(answers, year, day),
+        2,
+    )
+}
+
+fn stars(answers: &Answers, year: u16, day: u8) -> u32 {
+    if !answers.is_for(year) {
+        return 0;
+    }
+    (1..=2)
+        .filter(|part| answers.get(day, *part).is_some())
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color() {
+        assert_eq!(color(0, 50), "red");
+        assert_eq!(color(9, 50), "red");
+        assert_eq!(color(10, 50), "yellow");
+        assert_eq!(color(39, 50), "yellow");
+        assert_eq!(color(40, 50), "green");
+        assert_eq!(color(50, 50), "green");
+        // a day's two stars
+        assert_eq!(color(0, 2), "red");
+        assert_eq!(color(1, 2), "yellow");
+        assert_eq!(color(2, 2), "green");
+    }
+
+    #[test]
+    fn test_badges() -> Result<()> {
+        let answers = Answers::parse("[1]\npart1 = 142\npart2 = 281\n\n[4]\npart1 = 13\n")?;
+        assert_eq!(
+            event(&answers, 2023).to_json(),
+            r#"{
+  "schemaVersion": 1,
+  "label": "AoC 2023",
+  "message": "3/50 ⭐",
+  "color": "red"
+}"#
+        );
+        assert_eq!(
+            day(&answers, 2023, 4),
+            Badge {
+                schema_version: 1,
+                label: "AoC 2023 day 4".to_string(),
+                message: "1/2 ⭐".to_string(),
+                color: "yellow",
+            }
+        );
+        assert_eq!(day(&answers, 2023, 9).message, "0/2 ⭐");
+
+        // another event's answers are no stars of this one's
+        let answers = Answers::parse("year = 2022\n[1]\npart1 = 24000\n")?;
+        assert_eq!(event(&answers, 2022).message, "1/50 ⭐");
+        let badge = event(&answers, 2023);
+        assert_eq!(badge.label, "AoC 2023");
+        assert_eq!(badge.message, "0/50 ⭐");
+        Ok(())
+    }
+}
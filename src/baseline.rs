@@ -0,0 +1,363 @@
+//! Timing baselines, saved with `--save-baseline` and compared against with
+//! `--compare-baseline` to catch a change making a part slower.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    registry, report,
+    solver::{format_duration, millis},
+};
+
+/// Where baselines are kept, relative to the working directory like
+/// `aoc.toml`, a file per baseline named after it.
+pub const BASELINE_DIR: &str = ".aoc/baselines";
+
+/// Smallest slowdown that counts as a regression whatever its percentage,
+/// parts taking microseconds vary by more than any sensible threshold from
+/// one run to the next.
+pub const NOISE_FLOOR: Duration = Duration::from_millis(1);
+
+/// How much slower than its baseline a part may get, in percent, unless
+/// `--regression-threshold` says otherwise.
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct BaselineOptions {
+    /// The baseline to record the run's times as.
+    pub save: Option<String>,
+    /// The baseline to compare the run's times with.
+    pub compare: Option<String>,
+    /// See [`DEFAULT_THRESHOLD`].
+    pub threshold: f64,
+}
+
+impl BaselineOptions {
+    pub fn is_empty(&self) -> bool {
+        self.save.is_none() && self.compare.is_none()
+    }
+}
+
+/// How long a part took, with what it was timed on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartTime {
+    /// The event the day's from, so a baseline shared between years'
+    /// solutions keeps them apart. Baselines from before it was recorded are
+    /// all 2023's.
+    #[serde(default = "registry_year")]
+    pub year: u16,
+    pub day: u8,
+    pub part: u32,
+    /// See [`crate::input::hash`].
+    pub input_hash: String,
+    /// The build that timed the part, see [`report::build_version`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The best of the `samples` runs.
+    #[serde(rename = "duration_ms", with = "millis")]
+    pub duration: Duration,
+    pub samples: usize,
+}
+
+impl PartTime {
+    /// What a part's time is kept under in a baseline.
+    pub fn key(&self) -> (u16, u8, u32) {
+        (self.year, self.day, self.part)
+    }
+}
+
+fn registry_year() -> u16 {
+    registry::YEAR
+}
+
+/// Where the baseline `name` is kept in `dir`, names are kept to letters,
+/// digits, `-`, `_` and `.` so they can't point anywhere else.
+pub fn file(dir: &Path, name: &str) -> Result<PathBuf> {
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if name.is_empty() || name.starts_with('.') || !valid {
+        return Err(anyhow!(
+            "'{}' isn't a baseline name, use letters, digits, -, _ and .",
+            name
+        ));
+    }
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// The times of every part saved as a baseline, in day and part order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub parts: Vec<PartTime>,
+}
+
+impl Baseline {
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let path = file(dir, name)?;
+        if !path.is_file() {
+            return Err(anyhow!(
+                "There's no baseline '{}' in {}, save one with --save-baseline {}",
+                name,
+                dir.display(),
+                name
+            ));
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Can't read the baseline {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("{} isn't a baseline", path.display()))
+    }
+
+    /// Like [`Baseline::load`], but an empty baseline when there isn't one.
+    pub fn load_or_default(dir: &Path, name: &str) -> Result<Self> {
+        if file(dir, name)?.is_file() {
+            Self::load(dir, name)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, dir: &Path, name: &str) -> Result<()> {
+        let path = file(dir, name)?;
+        let text = serde_json::to_string_pretty(self)? + "\n";
+        fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&path, text))
+            .with_context(|| format!("Can't save the baseline {}", path.display()))
+    }
+
+    /// Replaces the times of the parts in `times`, keeping those of any other
+    /// parts so a run of a few days doesn't lose the rest.
+    pub fn update(&mut self, times: &[PartTime]) {
+        for time in times {
+            match self
+                .parts
+                .iter_mut()
+                .find(|saved| saved.key() == time.key())
+            {
+                Some(saved) => *saved = time.clone(),
+                None => self.parts.push(time.clone()),
+            }
+        }
+        self.parts.sort_by_key(PartTime::key);
+    }
+
+    /// How each of `times` compares with the baseline. Refuses to compare
+    /// parts that were timed on a different input, as the times say nothing
+    /// about the code then.
+    pub fn compare(&self, times: &[PartTime]) -> Result<Vec<Delta>> {
+        let saved = |time: &PartTime| self.parts.iter().find(|saved| saved.key() == time.key());
+        let mut mismatched = times
+            .iter()
+            .filter(|time| saved(time).is_some_and(|saved| saved.input_hash != time.input_hash))
+            .map(|time| time.day.to_string())
+            .collect::<Vec<_>>();
+        mismatched.dedup();
+        if !mismatched.is_empty() {
+            return Err(anyhow!(
+                "The input of day(s) {} isn't the one the baseline was saved with, save the baseline again to compare with it",
+                mismatched.join(", ")
+            ));
+        }
+        Ok(times
+            .iter()
+            .map(|time| Delta {
+                day: time.day,
+                part: time.part,
+                baseline: saved(time).map(|saved| saved.duration),
+                current: time.duration,
+            })
+            .collect())
+    }
+}
+
+/// A part's time against its baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Delta {
+    pub day: u8,
+    pub part: u32,
+    /// `None` for a part the baseline doesn't have.
+    pub baseline: Option<Duration>,
+    pub current: Duration,
+}
+
+impl Delta {
+    /// The change in percent, positive when the part got slower.
+    pub fn percent(&self) -> Option<f64> {
+        let baseline = self.baseline?.as_nanos() as f64;
+        (baseline > 0.0).then(|| (self.current.as_nanos() as f64 - baseline) / baseline * 100.0)
+    }
+
+    /// Whether the part got more than `threshold` percent slower, and by more
+    /// than the [`NOISE_FLOOR`].
+    pub fn regressed(&self, threshold: f64) -> bool {
+        let Some(baseline) = self.baseline else {
+            return false;
+        };
+        self.current.saturating_sub(baseline) > NOISE_FLOOR
+            && self.percent().is_some_and(|percent| percent > threshold)
+    }
+}
+
+/// The columns of [`table`].
+pub const COLUMNS: [&str; 7] = ["Day", "Part", "Baseline", "Now", "Change", "Change %", ""];
+
+/// The deltas as a markdown table, marking the parts that regressed by more
+/// than `threshold` percent.
+pub fn table(deltas: &[Delta], threshold: f64) -> String {
+    let rows = deltas
+        .iter()
+        .map(|delta| {
+            let change = delta.baseline.map(|baseline| {
+                if delta.current >= baseline {
+                    format!("+{}", format_duration(delta.current - baseline))
+                } else {
+                    format!("-{}", format_duration(baseline - delta.current))
+                }
+            });
+            let verdict = match delta.baseline {
+                None => "new",
+                Some(_) if delta.regressed(threshold) => "regressed",
+                Some(_) => "ok",
+            };
+            [
+                delta.day.to_string(),
+                delta.part.to_string(),
+                delta.baseline.map(format_duration).unwrap_or_default(),
+                format_duration(delta.current),
+                change.unwrap_or_default(),
+                delta
+                    .percent()
+                    .map(|percent| format!("{:+.1}%", percent))
+                    .unwrap_or_default(),
+                verdict.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    report::markdown(COLUMNS, [true, true, true, true, true, true, false], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(day: u8, part: u32, input: &str, millis: u64) -> PartTime {
+        PartTime {
+            year: 2023,
+            day,
+            part,
+            input_hash: crate::input::hash(input),
+            version: None,
+            duration: Duration::from_millis(millis),
+            samples: 1,
+        }
+    }
+
+    #[test]
+    fn test_file_names() {
+        let dir = Path::new("baselines");
+        assert_eq!(file(dir, "main").unwrap(), dir.join("main.json"));
+        assert_eq!(file(dir, "v1.2_x-y").unwrap(), dir.join("v1.2_x-y.json"));
+        for name in ["", "../main", "a/b", ".hidden", "with space"] {
+            assert!(file(dir, name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_save_update_and_load() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-baselines-{}", std::process::id()));
+        assert!(Baseline::load(&dir, "main")
+            .unwrap_err()
+            .to_string()
+            .contains("save one with --save-baseline main"));
+
+        let mut baseline = Baseline::load_or_default(&dir, "main")?;
+        baseline.update(&[time(7, 2, "x", 20), time(7, 1, "x", 10)]);
+        baseline.save(&dir, "main")?;
+        let mut baseline = Baseline::load(&dir, "main")?;
+        baseline.update(&[time(7, 1, "x", 12), time(1, 1, "y", 3)]);
+        baseline.save(&dir, "main")?;
+        let loaded = Baseline::load(&dir, "main")?;
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(
+            loaded.parts,
+            vec![time(1, 1, "y", 3), time(7, 1, "x", 12), time(7, 2, "x", 20)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() -> Result<()> {
+        let mut baseline = Baseline::default();
+        baseline.update(&[
+            time(1, 1, "x", 100),
+            time(1, 2, "x", 100),
+            time(2, 1, "y", 1),
+        ]);
+        let deltas = baseline.compare(&[
+            time(1, 1, "x", 105),
+            time(1, 2, "x", 150),
+            time(2, 1, "y", 2),
+            time(3, 1, "z", 5),
+        ])?;
+        let regressed = deltas
+            .iter()
+            .map(|delta| delta.regressed(DEFAULT_THRESHOLD))
+            .collect::<Vec<_>>();
+        // 5% is within the threshold, and day 2 doubled but only by 1ms
+        assert_eq!(regressed, vec![false, true, false, false]);
+        assert_eq!(deltas[1].percent(), Some(50.0));
+        assert_eq!(deltas[3].baseline, None);
+        assert!(!deltas[1].regressed(60.0));
+
+        let table = table(&deltas, DEFAULT_THRESHOLD);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[3].contains("+50.0ms") && lines[3].contains("+50.0%"));
+        assert!(lines[3].ends_with("| regressed |"), "{}", lines[3]);
+        assert!(lines[5].ends_with("| new       |"), "{}", lines[5]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_years_kept_apart() -> Result<()> {
+        let older = PartTime {
+            year: 2022,
+            ..time(1, 1, "old", 50)
+        };
+        let mut baseline = Baseline::default();
+        baseline.update(&[time(1, 1, "x", 100), older.clone()]);
+        assert_eq!(baseline.parts, vec![older, time(1, 1, "x", 100)]);
+        // another year's day 1 is a new part, not one on another input
+        let deltas = baseline.compare(&[PartTime {
+            year: 2024,
+            ..time(1, 1, "y", 10)
+        }])?;
+        assert_eq!(deltas[0].baseline, None);
+
+        // baselines from before the year was recorded are 2023's
+        let saved: Baseline = serde_json::from_str(
+            r#"{"parts":[{"day":1,"part":1,"input_hash":"h","duration_ms":1.0,"samples":1}]}"#,
+        )?;
+        assert_eq!(saved.parts[0].year, 2023);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_refuses_other_inputs() {
+        let mut baseline = Baseline::default();
+        baseline.update(&[time(1, 1, "x", 100), time(1, 2, "x", 100)]);
+        let error = baseline
+            .compare(&[time(1, 1, "changed", 100), time(1, 2, "changed", 100)])
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with("The input of day(s) 1 isn't"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Running a list of jobs from a file, for `aoc batch`, to sweep many inputs
+//! at once and check each gives the answer it's expected to.
+//!
+//! A jobs file is TOML with a `[[job]]` table per job, whose input is relative
+//! to the file:
+//!
+//! ```toml
+//! [[job]]
+//! day = 7
+//! part = 2
+//! input = "inputs/camel-cards.txt"
+//! expected = 5905
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+use serde::Deserialize;
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    registry::Registry,
+    report,
+    runner::{self, PartOptions, RunResult, Status},
+    solver::{format_duration, Answer},
+    style,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Job {
+    pub day: u8,
+    pub part: u32,
+    pub input: PathBuf,
+    /// The answer the part has to give, any answer will do without one.
+    #[serde(default)]
+    pub expected: Option<Answer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobsFile {
+    #[serde(default, rename = "job")]
+    jobs: Vec<Job>,
+}
+
+/// Parses a jobs file, with each input relative to `dir`.
+pub fn parse(text: &str, dir: &Path) -> Result<Vec<Job>> {
+    let file: JobsFile = toml::from_str(text)?;
+    if file.jobs.is_empty() {
+        return Err(anyhow!("There are no jobs, add a [[job]] table for each"));
+    }
+    file.jobs
+        .into_iter()
+        .enumerate()
+        .map(|(index, job)| match job.part {
+            1 | 2 => Ok(Job {
+                input: dir.join(&job.input),
+                ..job
+            }),
+            part => Err(anyhow!(
+                "Job {} is for part {}, there are only parts 1 and 2",
+                index + 1,
+                part
+            )),
+        })
+        .collect()
+}
+
+/// Reads the jobs file at `path`, see [`parse`].
+pub fn load(path: &Path) -> Result<Vec<Job>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    parse(&text, dir).with_context(|| format!("Bad jobs file {}", path.display()))
+}
+
+/// How a job went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Gave the expected answer.
+    Passed,
+    /// Gave an answer, with none expected.
+    Solved,
+    Mismatch,
+    /// Failed or timed out, so there's no answer.
+    Error,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Verdict::Passed => "passed",
+            Verdict::Solved => "solved",
+            Verdict::Mismatch => "mismatch",
+            Verdict::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobResult {
+    pub job: Job,
+    pub result: RunResult,
+}
+
+impl JobResult {
+    pub fn verdict(&self) -> Verdict {
+        match (&self.result.answer, &self.job.expected) {
+            (None, _) => Verdict::Error,
+            (Some(_), None) => Verdict::Solved,
+            (Some(answer), Some(expected)) if answer == expected => Verdict::Passed,
+            (Some(_), Some(_)) => Verdict::Mismatch,
+        }
+    }
+}
+
+/// Runs every job, `threads` at a time, returning the results in the order
+/// the jobs were given.
+pub fn run(
+    registry: &Registry,
+    jobs: &[Job],
+    threads: usize,
+    options: &PartOptions,
+) -> Result<Vec<JobResult>> {
+    let run_job = |job: &Job| {
+        let mut results = runner::run_day(registry, job.day, &[job.part], &job.input, options);
+        JobResult {
+            job: job.clone(),
+            result: results.remove(0),
+        }
+    };
+    if threads <= 1 {
+        return Ok(jobs.iter().map(run_job).collect());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+    Ok(pool.install(|| jobs.par_iter().map(run_job).collect()))
+}
+
+/// The columns of [`table`].
+pub const COLUMNS: [&str; 8] = [
+    "Job", "Day", "Part", "Input", "Answer", "Expected", "Time", "Result",
+];
+
+/// The results as a markdown table, a row per job.
+pub fn table(results: &[JobResult]) -> String {
+    let rows = results
+        .iter()
+        .zip(1..)
+        .map(|(job_result, number)| {
+            let JobResult { job, result } = job_result;
+            let answer = match (&result.status, &result.answer) {
+                (_, Some(answer)) => style::answer_text(answer),
+                (Status::TimedOut(_), _) => "timed out".to_string(),
+                _ => "failed".to_string(),
+            };
+            [
+                number.to_string(),
+                job.day.to_string(),
+                job.part.to_string(),
+                job.input.display().to_string(),
+                answer,
+                job.expected
+                    .as_ref()
+                    .map(style::answer_text)
+                    .unwrap_or_default(),
+                result
+                    .timing
+                    .map(|timing| format_duration(timing.total))
+                    .unwrap_or_default(),
+                job_result.verdict().to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    report::markdown(
+        COLUMNS,
+        [true, true, true, false, true, true, true, false],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOBS: &str = r#"
+[[job]]
+day = 7
+part = 1
+input = "07/input"
+expected = 6440
+
+[[job]]
+day = 7
+part = 2
+input = "/elsewhere/input"
+"#;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let jobs = parse(JOBS, Path::new("data"))?;
+        assert_eq!(
+            jobs,
+            vec![
+                Job {
+                    day: 7,
+                    part: 1,
+                    input: PathBuf::from("data/07/input"),
+                    expected: Some(6440.into()),
+                },
+                Job {
+                    day: 7,
+                    part: 2,
+                    input: PathBuf::from("/elsewhere/input"),
+                    expected: None,
+                },
+            ]
+        );
+        let error = |text| parse(text, Path::new("")).unwrap_err().to_string();
+        assert!(error("").starts_with("There are no jobs"));
+        assert!(
+            error("[[job]]\nday = 7\npart = 3\ninput = \"x\"\n").starts_with("Job 1 is for part 3")
+        );
+        // a misspelt expected answer would otherwise be silently ignored
+        assert!(parse(
+            "[[job]]\nday = 7\npart = 1\ninput = \"x\"\nexpect = 1\n",
+            Path::new("")
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let job_result = |answer: Option<i32>, expected: Option<i32>| JobResult {
+            job: Job {
+                day: 1,
+                part: 1,
+                input: PathBuf::from("input"),
+                expected: expected.map(Into::into),
+            },
+            result: RunResult {
+                day: 1,
+                part: 1,
+                title: "Day 1: Trebuchet?!".to_string(),
+                description: "Answer".to_string(),
+                answer: answer.map(Into::into),
+                timing: None,
+                peak_memory: None,
+                implementation: None,
+                status: match answer {
+                    Some(_) => Status::Solved,
+                    None => Status::Failed("bad".to_string()),
+                },
+                failure: None,
+                truncated: None,
+            },
+        };
+        assert_eq!(job_result(Some(1), Some(1)).verdict(), Verdict::Passed);
+        assert_eq!(job_result(Some(1), None).verdict(), Verdict::Solved);
+        assert_eq!(job_result(Some(1), Some(2)).verdict(), Verdict::Mismatch);
+        assert_eq!(job_result(None, Some(2)).verdict(), Verdict::Error);
+    }
+}
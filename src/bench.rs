@@ -0,0 +1,250 @@
+use anyhow::{anyhow, Result};
+use core::fmt;
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::{
+    solver::{format_duration, millis, Answer, Solver},
+    style,
+};
+
+/// Longest a single (warm-up) run may take before benchmarking is refused
+/// without `--force`.
+pub const MAX_SINGLE_RUN: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// Total number of runs, including the discarded warm-up run.
+    pub iterations: usize,
+    pub force: bool,
+    pub max_single_run: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchStats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+    pub std_dev: Duration,
+}
+
+impl BenchStats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let count = sorted.len();
+        let median = if count.is_multiple_of(2) {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2
+        } else {
+            sorted[count / 2]
+        };
+        let mean_secs = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / count as f64;
+        let variance = if count > 1 {
+            sorted
+                .iter()
+                .map(|sample| (sample.as_secs_f64() - mean_secs).powi(2))
+                .sum::<f64>()
+                / (count - 1) as f64
+        } else {
+            0.0
+        };
+        Some(Self {
+            samples: count,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean_secs),
+            max: sorted[count - 1],
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// One benched part as printed by `bench --json`, the answer a bare number
+/// where it is one and the statistics in fractional milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchRecord {
+    pub day: u8,
+    pub part: u32,
+    pub answer: Answer,
+    pub samples: usize,
+    #[serde(rename = "min_ms", with = "millis")]
+    pub min: Duration,
+    #[serde(rename = "median_ms", with = "millis")]
+    pub median: Duration,
+    #[serde(rename = "mean_ms", with = "millis")]
+    pub mean: Duration,
+    #[serde(rename = "max_ms", with = "millis")]
+    pub max: Duration,
+    #[serde(rename = "std_dev_ms", with = "millis")]
+    pub std_dev: Duration,
+}
+
+impl BenchRecord {
+    pub fn new(day: u8, part: u32, answer: Answer, stats: &BenchStats) -> Self {
+        Self {
+            day,
+            part,
+            answer,
+            samples: stats.samples,
+            min: stats.min,
+            median: stats.median,
+            mean: stats.mean,
+            max: stats.max,
+            std_dev: stats.std_dev,
+        }
+    }
+}
+
+impl fmt::Display for BenchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} samples: min {}, median {}, mean {}, max {}, std dev {}",
+            self.samples,
+            format_duration(self.min),
+            format_duration(self.median),
+            format_duration(self.mean),
+            format_duration(self.max),
+            format_duration(self.std_dev)
+        )
+    }
+}
+
+/// Solves the same in-memory input repeatedly, discarding the first run as a
+/// warm-up and checking that every run produces the same answer.
+pub fn bench<S: Solver + ?Sized>(
+    solver: &S,
+    input: &str,
+    options: &BenchOptions,
+) -> Result<(Answer, BenchStats)> {
+    if options.iterations < 2 {
+        return Err(anyhow!(
+            "Benchmarking needs at least 2 iterations, the first is a discarded warm-up"
+        ));
+    }
+
+    let (expected, warm_up) = solver.solve_timed(input)?;
+    if warm_up.total > options.max_single_run && !options.force {
+        return Err(anyhow!(
+            "A single run took {}, which exceeds the {} bench limit, pass --force to bench anyway",
+            format_duration(warm_up.total),
+            format_duration(options.max_single_run)
+        ));
+    }
+
+    let mut samples = Vec::with_capacity(options.iterations - 1);
+    for iteration in 1..options.iterations {
+        let (answer, timing) = solver.solve_timed(input)?;
+        if answer != expected {
+            return Err(anyhow!(
+                "Answer changed on iteration {}, expected: {}, actual: {}",
+                iteration,
+                style::answer_text(&expected),
+                style::answer_text(&answer)
+            ));
+        }
+        samples.push(timing.total);
+    }
+
+    let stats = BenchStats::from_samples(&samples).expect("at least one sample was taken");
+    Ok((expected, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::RunContext;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    struct Counter(AtomicI32);
+
+    impl Solver for Counter {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Call count")
+        }
+
+        fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+            Ok(self.0.fetch_add(1, Ordering::SeqCst).into())
+        }
+    }
+
+    struct Constant;
+
+    impl Solver for Constant {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Constant")
+        }
+
+        fn solve(&self, input: &str, _ctx: &RunContext) -> Result<Answer> {
+            Ok(input.len().to_string().into())
+        }
+    }
+
+    fn options(iterations: usize) -> BenchOptions {
+        BenchOptions {
+            iterations,
+            force: false,
+            max_single_run: MAX_SINGLE_RUN,
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = BenchStats::from_samples(&samples).unwrap();
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(4));
+        assert_eq!(stats.median, Duration::from_micros(2_500));
+        assert_eq!(stats.mean, Duration::from_micros(2_500));
+        // sample standard deviation of 1, 2, 3, 4 is sqrt(5/3)
+        assert!((stats.std_dev.as_secs_f64() * 1_000.0 - (5.0_f64 / 3.0).sqrt()).abs() < 1e-6);
+        assert_eq!(BenchStats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_bench_discards_warm_up() -> Result<()> {
+        let (answer, stats) = bench(&Constant, "abc", &options(5))?;
+        assert_eq!(answer, "3".into());
+        assert_eq!(stats.samples, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bench_detects_changing_answer() {
+        let error = bench(&Counter(AtomicI32::new(0)), "", &options(3))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("iteration 1"));
+        assert!(error.contains("expected: 0, actual: 1"));
+    }
+
+    #[test]
+    fn test_bench_refuses_slow_runs_unless_forced() {
+        let mut options = options(2);
+        options.max_single_run = Duration::ZERO;
+        assert!(bench(&Constant, "abc", &options).is_err());
+        options.force = true;
+        assert!(bench(&Constant, "abc", &options).is_ok());
+    }
+
+    #[test]
+    fn test_bench_json() -> Result<()> {
+        let stats = BenchStats::from_samples(&[Duration::from_millis(2)]).unwrap();
+        assert_eq!(
+            serde_json::to_string(&BenchRecord::new(7, 2, 250.into(), &stats))?,
+            "{\"day\":7,\"part\":2,\"answer\":250,\"samples\":1,\"min_ms\":2.0,\"median_ms\":2.0,\"mean_ms\":2.0,\"max_ms\":2.0,\"std_dev_ms\":0.0}"
+        );
+        let text = BenchRecord::new(7, 2, "say \"hi\"".into(), &stats);
+        assert_eq!(
+            serde_json::to_value(&text)?["answer"],
+            serde_json::json!("say \"hi\"")
+        );
+        Ok(())
+    }
+}
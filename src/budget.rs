@@ -0,0 +1,200 @@
+//! Runtime budgets, for goals like every day solving in under a second: one
+//! for a whole run with `--budget`, and one per day in the `[budgets]` table
+//! of `aoc.toml`, eg. `23 = "5s"` for a day known to be slow. Only the time
+//! spent solving counts, not reading or parsing the input.
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use crate::{runner::RunResult, solver::format_duration};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Budgets {
+    /// How long all the parts run may take together.
+    pub overall: Option<Duration>,
+    /// How long each day's parts may take together, by day.
+    pub days: BTreeMap<u8, Duration>,
+}
+
+impl Budgets {
+    pub fn day(&self, day: u8) -> Option<Duration> {
+        self.days.get(&day).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overall.is_none() && self.days.is_empty()
+    }
+}
+
+/// How long `results` spent solving, see
+/// [`crate::solver::Timing::solve`].
+pub fn solve_time<'a>(results: impl IntoIterator<Item = &'a RunResult>) -> Duration {
+    results
+        .into_iter()
+        .filter_map(|result| result.timing)
+        .map(|timing| timing.solve())
+        .sum()
+}
+
+/// A day, or the whole run when `day` is `None`, that took longer than its
+/// budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overrun {
+    pub day: Option<u8>,
+    pub budget: Duration,
+    pub actual: Duration,
+}
+
+impl fmt::Display for Overrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (what, whose) = match self.day {
+            Some(day) => (format!("Day {}", day), "its"),
+            None => ("The run".to_string(), "the"),
+        };
+        write!(
+            f,
+            "{} took {} to solve, over {} {} budget",
+            what,
+            format_duration(self.actual),
+            whose,
+            format_duration(self.budget)
+        )
+    }
+}
+
+/// The days among `results` that ran over their budget, in day order,
+/// followed by the run as a whole if it did.
+pub fn overruns(results: &[RunResult], budgets: &Budgets) -> Vec<Overrun> {
+    let mut overruns = budgets
+        .days
+        .iter()
+        .filter_map(|(day, budget)| {
+            let actual = solve_time(results.iter().filter(|result| result.day == *day));
+            (actual > *budget).then_some(Overrun {
+                day: Some(*day),
+                budget: *budget,
+                actual,
+            })
+        })
+        .collect::<Vec<_>>();
+    if let Some(budget) = budgets.overall {
+        let actual = solve_time(results);
+        if actual > budget {
+            overruns.push(Overrun {
+                day: None,
+                budget,
+                actual,
+            });
+        }
+    }
+    overruns
+}
+
+/// A day's budget next to what it took, eg. `1.2ms / 1.00s`, marked ✗ when
+/// it ran over. Only the budget is shown without `show_timing`, and nothing
+/// for a day with no budget.
+pub fn cell(results: &[&RunResult], budget: Option<Duration>, show_timing: bool) -> String {
+    let Some(budget) = budget else {
+        return String::new();
+    };
+    let actual = solve_time(results.iter().copied());
+    let over = if actual > budget { " ✗" } else { "" };
+    if show_timing {
+        format!(
+            "{} / {}{}",
+            format_duration(actual),
+            format_duration(budget),
+            over
+        )
+    } else {
+        format!("{}{}", format_duration(budget), over)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{runner::Status, solver::Timing};
+
+    fn result(day: u8, part: u32, parse_ms: u64, total_ms: u64) -> RunResult {
+        RunResult {
+            day,
+            part,
+            title: format!("Day {}", day),
+            description: "Answer".to_string(),
+            answer: Some(1.into()),
+            timing: Some(Timing {
+                parse: Some(Duration::from_millis(parse_ms)),
+                total: Duration::from_millis(total_ms),
+            }),
+            peak_memory: None,
+            implementation: None,
+            status: Status::Solved,
+            failure: None,
+            truncated: None,
+        }
+    }
+
+    #[test]
+    fn test_overruns() {
+        let results = [
+            result(1, 1, 0, 300),
+            result(1, 2, 0, 400),
+            // parsing doesn't count
+            result(5, 1, 900, 1000),
+            result(23, 1, 0, 2000),
+            result(23, 2, 0, 3500),
+        ];
+        let budgets = Budgets {
+            overall: Some(Duration::from_secs(5)),
+            days: BTreeMap::from([
+                (1, Duration::from_millis(500)),
+                (5, Duration::from_millis(500)),
+                (23, Duration::from_secs(5)),
+            ]),
+        };
+        assert_eq!(
+            overruns(&results, &budgets),
+            [
+                Overrun {
+                    day: Some(1),
+                    budget: Duration::from_millis(500),
+                    actual: Duration::from_millis(700),
+                },
+                Overrun {
+                    day: Some(23),
+                    budget: Duration::from_secs(5),
+                    actual: Duration::from_millis(5500),
+                },
+                Overrun {
+                    day: None,
+                    budget: Duration::from_secs(5),
+                    actual: Duration::from_millis(6300),
+                },
+            ]
+        );
+        assert_eq!(
+            overruns(&results, &budgets)[0].to_string(),
+            "Day 1 took 700.0ms to solve, over its 500.0ms budget"
+        );
+        assert!(overruns(&results, &Budgets::default()).is_empty());
+    }
+
+    #[test]
+    fn test_cell() {
+        let results = [result(1, 1, 0, 300), result(1, 2, 0, 400)];
+        let results = results.iter().collect::<Vec<_>>();
+        assert_eq!(
+            cell(&results, Some(Duration::from_secs(1)), true),
+            "700.0ms / 1.00s"
+        );
+        assert_eq!(
+            cell(&results, Some(Duration::from_millis(500)), true),
+            "700.0ms / 500.0ms ✗"
+        );
+        assert_eq!(
+            cell(&results, Some(Duration::from_millis(500)), false),
+            "500.0ms ✗"
+        );
+        assert_eq!(cell(&results, None, true), "");
+    }
+}
@@ -0,0 +1,228 @@
+//! Working out which puzzle is today's, and waiting for the next one.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, TimeZone, Utc};
+use serde::Deserialize;
+use std::{fmt, str::FromStr, time::Duration};
+
+use crate::{failure::FailureKind, http::Clock};
+
+/// Puzzles come out at midnight in this offset from UTC, US Eastern time.
+const RELEASE_OFFSET_HOURS: i32 = -5;
+const LAST_PUZZLE_DAY: u32 = 25;
+
+/// How often [`wait_until`] reports the time left.
+pub const COUNTDOWN_TICK: Duration = Duration::from_secs(1);
+
+/// Which clock decides what day it is.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Timezone {
+    /// The machine's own timezone.
+    #[default]
+    Local,
+    /// UTC-5, where a new day is when the next puzzle comes out.
+    Est,
+}
+
+impl FromStr for Timezone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "local" => Ok(Timezone::Local),
+            "est" => Ok(Timezone::Est),
+            _ => Err(anyhow!("'{}' isn't a timezone, expected local or est", s)),
+        }
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timezone::Local => write!(f, "local"),
+            Timezone::Est => write!(f, "est"),
+        }
+    }
+}
+
+/// The date at `now` according to `timezone`.
+pub fn date_at(now: DateTime<Utc>, timezone: Timezone) -> NaiveDate {
+    match timezone {
+        Timezone::Local => now.with_timezone(&Local).date_naive(),
+        Timezone::Est => now.with_timezone(&release_offset()).date_naive(),
+    }
+}
+
+fn release_offset() -> FixedOffset {
+    FixedOffset::east_opt(RELEASE_OFFSET_HOURS * 3600).expect("UTC-5 is a valid offset")
+}
+
+/// When day `day` of `year`'s event comes out, midnight EST on December
+/// `day`.
+pub fn unlock_time(year: u16, day: u8) -> DateTime<Utc> {
+    release_offset()
+        .with_ymd_and_hms(year.into(), 12, day.into(), 0, 0, 0)
+        .single()
+        .expect("a fixed offset has no gaps")
+        .with_timezone(&Utc)
+}
+
+/// The next puzzle of `year`'s event to come out after `now` and when it
+/// does, or `None` once they're all out.
+pub fn next_unlock(now: DateTime<Utc>, year: u16) -> Option<(u8, DateTime<Utc>)> {
+    (1..=LAST_PUZZLE_DAY as u8)
+        .map(|day| (day, unlock_time(year, day)))
+        .find(|(_, at)| *at > now)
+}
+
+/// Sleeps on `clock` until `at`, calling `tick` with the time left before
+/// each sleep, which are [`COUNTDOWN_TICK`] at most.
+pub fn wait_until(clock: impl Clock, at: DateTime<Utc>, mut tick: impl FnMut(Duration)) {
+    let at = Duration::from_millis(at.timestamp_millis().max(0) as u64);
+    loop {
+        let left = at.saturating_sub(clock.now());
+        if left.is_zero() {
+            break;
+        }
+        tick(left);
+        clock.sleep(left.min(COUNTDOWN_TICK));
+    }
+}
+
+/// The time left in a countdown, to the second, eg. `2h 03m 09s`.
+pub fn format_countdown(left: Duration) -> String {
+    // rounded up, so it never shows 0s with time to go
+    let seconds = left.as_millis().div_ceil(1000);
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, s) => format!("{}h {:02}m {:02}s", h, m, s),
+    }
+}
+
+/// The day of the puzzle that came out on `date`, which has to be from the
+/// 1st to the 25th of December, as a usage error like any other bad day.
+pub fn puzzle_day(date: NaiveDate) -> Result<u8> {
+    if date.month() == 12 && date.day() <= LAST_PUZZLE_DAY {
+        Ok(date.day() as u8)
+    } else {
+        Err(FailureKind::Usage.error(anyhow!(
+            "Today is {}, 'today' only picks a day from the 1st to the 25th of December",
+            date.format("%-d %B")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        text.parse().expect("a valid RFC 3339 timestamp")
+    }
+
+    #[test]
+    fn test_puzzle_day() -> Result<()> {
+        let date = |month, day| NaiveDate::from_ymd_opt(2023, month, day).unwrap();
+        assert_eq!(puzzle_day(date(12, 1))?, 1);
+        assert_eq!(puzzle_day(date(12, 25))?, 25);
+        assert_eq!(
+            puzzle_day(date(12, 26)).unwrap_err().to_string(),
+            "Today is 26 December, 'today' only picks a day from the 1st to the 25th of December"
+        );
+        assert!(puzzle_day(date(11, 30)).is_err());
+        assert!(puzzle_day(date(1, 5)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_release_time() {
+        // day 5 comes out at 05:00 UTC
+        let before = utc("2023-12-05T04:59:59Z");
+        let after = utc("2023-12-05T05:00:00Z");
+        assert_eq!(date_at(before, Timezone::Est).day(), 4);
+        assert_eq!(date_at(after, Timezone::Est).day(), 5);
+        // the last puzzle stays today's until midnight EST on the 25th
+        let last = date_at(utc("2023-12-26T04:00:00Z"), Timezone::Est);
+        assert_eq!(puzzle_day(last).ok(), Some(25));
+    }
+
+    #[test]
+    fn test_unlocks() {
+        assert_eq!(unlock_time(2023, 5), utc("2023-12-05T05:00:00Z"));
+        assert_eq!(
+            next_unlock(utc("2023-11-20T12:00:00Z"), 2023),
+            Some((1, utc("2023-12-01T05:00:00Z")))
+        );
+        // an hour before the release, on the evening before in EST
+        assert_eq!(
+            next_unlock(utc("2023-12-07T04:00:00Z"), 2023),
+            Some((7, utc("2023-12-07T05:00:00Z")))
+        );
+        // once it's out, it's the next day's that's waited for
+        assert_eq!(
+            next_unlock(utc("2023-12-07T05:00:00Z"), 2023),
+            Some((8, utc("2023-12-08T05:00:00Z")))
+        );
+        assert_eq!(next_unlock(utc("2023-12-25T05:00:00Z"), 2023), None);
+        assert_eq!(
+            next_unlock(utc("2023-12-25T05:00:00Z"), 2024),
+            Some((1, utc("2024-12-01T05:00:00Z")))
+        );
+    }
+
+    /// A clock that only moves when slept on.
+    struct Fake(std::cell::Cell<Duration>);
+
+    impl Clock for &Fake {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.0.set(self.0.get() + duration);
+        }
+    }
+
+    #[test]
+    fn test_wait_until() {
+        let at = utc("2023-12-07T05:00:00Z");
+        let start =
+            Duration::from_millis(at.timestamp_millis() as u64) - Duration::from_millis(2500);
+        let clock = Fake(start.into());
+        let mut ticks = vec![];
+        wait_until(&clock, at, |left| ticks.push(format_countdown(left)));
+        assert_eq!(ticks, ["3s", "2s", "1s"]);
+        assert_eq!(clock.0.get().as_millis() as i64, at.timestamp_millis());
+
+        // nothing to wait for once it's past
+        wait_until(&clock, utc("2023-12-01T05:00:00Z"), |_| panic!("no wait"));
+        assert_eq!(clock.0.get().as_millis() as i64, at.timestamp_millis());
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(Duration::from_millis(400)), "1s");
+        assert_eq!(format_countdown(Duration::from_secs(59)), "59s");
+        assert_eq!(format_countdown(Duration::from_secs(65)), "1m 05s");
+        assert_eq!(format_countdown(Duration::from_secs(7389)), "2h 03m 09s");
+    }
+
+    #[test]
+    fn test_local_time() {
+        let now = utc("2023-12-10T12:00:00Z");
+        // every timezone is within a day of UTC at noon
+        let local = date_at(now, Timezone::Local);
+        assert!((9..=11).contains(&local.day()), "{}", local);
+        assert_eq!(local, now.with_timezone(&Local).date_naive());
+    }
+
+    #[test]
+    fn test_parse_timezone() -> Result<()> {
+        assert_eq!("EST".parse::<Timezone>()?, Timezone::Est);
+        assert_eq!("local".parse::<Timezone>()?, Timezone::Local);
+        assert!("pst".parse::<Timezone>().is_err());
+        Ok(())
+    }
+}
@@ -1,90 +1,107 @@
-use crate::solver::{MultiSolver, Solver};
+use crate::{
+    context::RunContext,
+    metadata::{Difficulty, Metadata, RuntimeClass},
+    solver::{Answer, MultiSolver, Solver},
+    statement::puzzle_struct,
+    validate::{parse_lines, strict, Malformed, ParseStats},
+};
 use anyhow::{anyhow, Error, Result};
-use std::{cmp::Reverse, fs::read_to_string, path::PathBuf};
-
-///     --- Day 7: Camel Cards ---
-///
-/// Your all-expenses-paid trip turns out to be a one-way, five-minute ride in an airship. (At least it's a cool airship!) It drops you off at the edge of a vast desert and descends back to Island Island.
-///
-/// "Did you bring the parts?"
-///
-/// You turn around to see an Elf completely covered in white clothing, wearing goggles, and riding a large camel.
-///
-/// "Did you bring the parts?" she asks again, louder this time. You aren't sure what parts she's looking for; you're here to figure out why the sand stopped.
-///
-/// "The parts! For the sand, yes! Come with me; I will show you." She beckons you onto the camel.
-///
-/// After riding a bit across the sands of Desert Island, you can see what look like very large rocks covering half of the horizon. The Elf explains that the rocks are all along the part of Desert Island that is directly above Island Island, making it hard to even get there. Normally, they use big machines to move the rocks and filter the sand, but the machines have broken down because Desert Island recently stopped receiving the parts they need to fix the machines.
-///
-/// You've already assumed it'll be your job to figure out why the parts stopped when she asks if you can help. You agree automatically.
-///
-/// Because the journey will take a few days, she offers to teach you the game of Camel Cards. Camel Cards is sort of similar to poker except it's designed to be easier to play while riding a camel.
-///
-/// In Camel Cards, you get a list of hands, and your goal is to order them based on the strength of each hand. A hand consists of five cards labeled one of A, K, Q, J, T, 9, 8, 7, 6, 5, 4, 3, or 2. The relative strength of each card follows this order, where A is the highest and 2 is the lowest.
-///
-/// Every hand is exactly one type. From strongest to weakest, they are:
-///
-///     - Five of a kind, where all five cards have the same label: AAAAA
-///     - Four of a kind, where four cards have the same label and one card has a different label: AA8AA
-///     - Full house, where three cards have the same label, and the remaining two cards share a different label: 23332
-///     - Three of a kind, where three cards have the same label, and the remaining two cards are each different from any other card in the hand: TTT98
-///     - Two pair, where two cards share one label, two other cards share a second label, and the remaining card has a third label: 23432
-///     - One pair, where two cards share one label, and the other three cards have a different label from the pair and each other: A23A4
-///     - High card, where all cards' labels are distinct: 23456
-///
-/// Hands are primarily ordered based on type; for example, every full house is stronger than any three of a kind.
-///
-/// If two hands have the same type, a second ordering rule takes effect. Start by comparing the first card in each hand. If these cards are different, the hand with the stronger first card is considered stronger. If the first card in each hand have the same label, however, then move on to considering the second card in each hand. If they differ, the hand with the higher second card wins; otherwise, continue with the third card in each hand, then the fourth, then the fifth.
-///
-/// So, 33332 and 2AAAA are both four of a kind hands, but 33332 is stronger because its first card is stronger. Similarly, 77888 and 77788 are both a full house, but 77888 is stronger because its third card is stronger (and both hands have the same first and second card).
-///
-/// To play Camel Cards, you are given a list of hands and their corresponding bid (your puzzle input). For example:
-/// ```
-/// 32T3K 765
-/// T55J5 684
-/// KK677 28
-/// KTJJT 220
-/// QQQJA 483
-/// ```
-/// This example shows five hands; each hand is followed by its bid amount. Each hand wins an amount equal to its bid multiplied by its rank, where the weakest hand gets rank 1, the second-weakest hand gets rank 2, and so on up to the strongest hand. Because there are five hands in this example, the strongest hand will have rank 5 and its bid will be multiplied by 5.
-///
-/// So, the first step is to put the hands in order of strength:
-///
-///     - 32T3K is the only one pair and the other hands are all a stronger type, so it gets rank 1.
-///     - KK677 and KTJJT are both two pair. Their first cards both have the same label, but the second card of KK677 is stronger (K vs T), so KTJJT gets rank 2 and KK677 gets rank 3.
-///     - T55J5 and QQQJA are both three of a kind. QQQJA has a stronger first card, so it gets rank 5 and T55J5 gets rank 4.
-///
-/// Now, you can determine the total winnings of this set of hands by adding up the result of multiplying each hand's bid with its rank (765 * 1 + 220 * 2 + 28 * 3 + 684 * 4 + 483 * 5). So the total winnings in this example are 6440.
-///
-/// Find the rank of every hand in your set. What are the total winnings?
-///
-///     --- Part Two ---
-///
-/// To make things a little more interesting, the Elf introduces one additional rule. Now, J cards are jokers - wildcards that can act like whatever card would make the hand the strongest type possible.
-///
-/// To balance this, J cards are now the weakest individual cards, weaker even than 2. The other cards stay in the same order: A, K, Q, T, 9, 8, 7, 6, 5, 4, 3, 2, J.
-///
-/// J cards can pretend to be whatever card is best for the purpose of determining hand type; for example, QJJQ2 is now considered four of a kind. However, for the purpose of breaking ties between two hands of the same type, J is always treated as J, not the card it's pretending to be: JKKK2 is weaker than QQQQ2 because J is weaker than Q.
-///
-/// Now, the above example goes very differently:
-/// ```
-/// 32T3K 765
-/// T55J5 684
-/// KK677 28
-/// KTJJT 220
-/// QQQJA 483
-/// ```
-///     - 32T3K is still the only one pair; it doesn't contain any jokers, so its strength doesn't increase.
-///     - KK677 is now the only two pair, making it the second-weakest hand.
-///     - T55J5, KTJJT, and QQQJA are now all four of a kind! T55J5 gets rank 3, QQQJA gets rank 4, and KTJJT gets rank 5.
-///
-/// With the new joker rule, the total winnings in this example are 5905.
-///
-/// Using the new joker rule, find the rank of every hand in your set. What are the new total winnings?
-pub struct CamelCards;
+use serde::Serialize;
+use std::cmp::Reverse;
+
+puzzle_struct! {
+    ///     --- Day 7: Camel Cards ---
+    ///
+    /// Your all-expenses-paid trip turns out to be a one-way, five-minute ride in an airship. (At least it's a cool airship!) It drops you off at the edge of a vast desert and descends back to Island Island.
+    ///
+    /// "Did you bring the parts?"
+    ///
+    /// You turn around to see an Elf completely covered in white clothing, wearing goggles, and riding a large camel.
+    ///
+    /// "Did you bring the parts?" she asks again, louder this time. You aren't sure what parts she's looking for; you're here to figure out why the sand stopped.
+    ///
+    /// "The parts! For the sand, yes! Come with me; I will show you." She beckons you onto the camel.
+    ///
+    /// After riding a bit across the sands of Desert Island, you can see what look like very large rocks covering half of the horizon. The Elf explains that the rocks are all along the part of Desert Island that is directly above Island Island, making it hard to even get there. Normally, they use big machines to move the rocks and filter the sand, but the machines have broken down because Desert Island recently stopped receiving the parts they need to fix the machines.
+    ///
+    /// You've already assumed it'll be your job to figure out why the parts stopped when she asks if you can help. You agree automatically.
+    ///
+    /// Because the journey will take a few days, she offers to teach you the game of Camel Cards. Camel Cards is sort of similar to poker except it's designed to be easier to play while riding a camel.
+    ///
+    /// In Camel Cards, you get a list of hands, and your goal is to order them based on the strength of each hand. A hand consists of five cards labeled one of A, K, Q, J, T, 9, 8, 7, 6, 5, 4, 3, or 2. The relative strength of each card follows this order, where A is the highest and 2 is the lowest.
+    ///
+    /// Every hand is exactly one type. From strongest to weakest, they are:
+    ///
+    ///     - Five of a kind, where all five cards have the same label: AAAAA
+    ///     - Four of a kind, where four cards have the same label and one card has a different label: AA8AA
+    ///     - Full house, where three cards have the same label, and the remaining two cards share a different label: 23332
+    ///     - Three of a kind, where three cards have the same label, and the remaining two cards are each different from any other card in the hand: TTT98
+    ///     - Two pair, where two cards share one label, two other cards share a second label, and the remaining card has a third label: 23432
+    ///     - One pair, where two cards share one label, and the other three cards have a different label from the pair and each other: A23A4
+    ///     - High card, where all cards' labels are distinct: 23456
+    ///
+    /// Hands are primarily ordered based on type; for example, every full house is stronger than any three of a kind.
+    ///
+    /// If two hands have the same type, a second ordering rule takes effect. Start by comparing the first card in each hand. If these cards are different, the hand with the stronger first card is considered stronger. If the first card in each hand have the same label, however, then move on to considering the second card in each hand. If they differ, the hand with the higher second card wins; otherwise, continue with the third card in each hand, then the fourth, then the fifth.
+    ///
+    /// So, 33332 and 2AAAA are both four of a kind hands, but 33332 is stronger because its first card is stronger. Similarly, 77888 and 77788 are both a full house, but 77888 is stronger because its third card is stronger (and both hands have the same first and second card).
+    ///
+    /// To play Camel Cards, you are given a list of hands and their corresponding bid (your puzzle input). For example:
+    /// ```
+    /// 32T3K 765
+    /// T55J5 684
+    /// KK677 28
+    /// KTJJT 220
+    /// QQQJA 483
+    /// ```
+    /// This example shows five hands; each hand is followed by its bid amount. Each hand wins an amount equal to its bid multiplied by its rank, where the weakest hand gets rank 1, the second-weakest hand gets rank 2, and so on up to the strongest hand. Because there are five hands in this example, the strongest hand will have rank 5 and its bid will be multiplied by 5.
+    ///
+    /// So, the first step is to put the hands in order of strength:
+    ///
+    ///     - 32T3K is the only one pair and the other hands are all a stronger type, so it gets rank 1.
+    ///     - KK677 and KTJJT are both two pair. Their first cards both have the same label, but the second card of KK677 is stronger (K vs T), so KTJJT gets rank 2 and KK677 gets rank 3.
+    ///     - T55J5 and QQQJA are both three of a kind. QQQJA has a stronger first card, so it gets rank 5 and T55J5 gets rank 4.
+    ///
+    /// Now, you can determine the total winnings of this set of hands by adding up the result of multiplying each hand's bid with its rank (765 * 1 + 220 * 2 + 28 * 3 + 684 * 4 + 483 * 5). So the total winnings in this example are 6440.
+    ///
+    /// Find the rank of every hand in your set. What are the total winnings?
+    ///
+    ///     --- Part Two ---
+    ///
+    /// To make things a little more interesting, the Elf introduces one additional rule. Now, J cards are jokers - wildcards that can act like whatever card would make the hand the strongest type possible.
+    ///
+    /// To balance this, J cards are now the weakest individual cards, weaker even than 2. The other cards stay in the same order: A, K, Q, T, 9, 8, 7, 6, 5, 4, 3, 2, J.
+    ///
+    /// J cards can pretend to be whatever card is best for the purpose of determining hand type; for example, QJJQ2 is now considered four of a kind. However, for the purpose of breaking ties between two hands of the same type, J is always treated as J, not the card it's pretending to be: JKKK2 is weaker than QQQQ2 because J is weaker than Q.
+    ///
+    /// Now, the above example goes very differently:
+    /// ```
+    /// 32T3K 765
+    /// T55J5 684
+    /// KK677 28
+    /// KTJJT 220
+    /// QQQJA 483
+    /// ```
+    ///     - 32T3K is still the only one pair; it doesn't contain any jokers, so its strength doesn't increase.
+    ///     - KK677 is now the only two pair, making it the second-weakest hand.
+    ///     - T55J5, KTJJT, and QQQJA are now all four of a kind! T55J5 gets rank 3, QQQJA gets rank 4, and KTJJT gets rank 5.
+    ///
+    /// With the new joker rule, the total winnings in this example are 5905.
+    ///
+    /// Using the new joker rule, find the rank of every hand in your set. What are the new total winnings?
+    pub struct CamelCards;
+}
 pub struct PartOne;
 pub struct PartTwo;
 
+const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
 impl MultiSolver for CamelCards {
     type PartOne = PartOne;
     type PartTwo = PartTwo;
@@ -93,6 +110,18 @@ impl MultiSolver for CamelCards {
         "Day 7: Camel Cards"
     }
 
+    fn input_hint(&self) -> Option<&str> {
+        Some("lines of a five card hand and a bid like '32T3K 765'")
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        Some(Self::STATEMENT)
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(7)
+    }
+
     fn get_part_one(&self) -> Self::PartOne {
         PartOne
     }
@@ -100,6 +129,24 @@ impl MultiSolver for CamelCards {
     fn get_part_two(&self) -> Self::PartTwo {
         PartTwo
     }
+
+    fn parse_stats(&self, input: &str) -> Option<ParseStats> {
+        let (hands, malformed) = parse_hands::<Card>(input);
+        let bid_total = hands.iter().map(|hand| hand.bid as usize).sum();
+        Some(
+            ParseStats::new(input, malformed)
+                .with_count("hands", hands.len())
+                .with_count("total bid", bid_total),
+        )
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            tags: &["sorting", "parsing"],
+            difficulty: Some(Difficulty::Medium),
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
@@ -141,6 +188,26 @@ impl TryFrom<char> for Card {
     }
 }
 
+impl From<Card> for char {
+    fn from(card: Card) -> Self {
+        match card {
+            Card::A => 'A',
+            Card::K => 'K',
+            Card::Q => 'Q',
+            Card::J => 'J',
+            Card::T => 'T',
+            Card::_9 => '9',
+            Card::_8 => '8',
+            Card::_7 => '7',
+            Card::_6 => '6',
+            Card::_5 => '5',
+            Card::_4 => '4',
+            Card::_3 => '3',
+            Card::_2 => '2',
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
 pub enum CardJokerRule {
     A = 14,
@@ -200,7 +267,8 @@ impl From<CardJokerRule> for Card {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HandType {
     FiveOfAKind = 7,
     FourOfAKind = 6,
@@ -261,7 +329,7 @@ impl HandOfCards for Hand<Card> {
 impl HandOfCards for Hand<CardJokerRule> {
     fn get_type(&self) -> HandType {
         let new_hand: Hand<Card> = if self.cards.iter().any(|card| card == &CardJokerRule::J) {
-            let mut sorted_cards = self.cards.clone();
+            let mut sorted_cards = self.cards;
             sorted_cards.sort_by_key(|card| Reverse(*card));
             let mode_card = sorted_cards
                 .get(
@@ -278,29 +346,17 @@ impl HandOfCards for Hand<CardJokerRule> {
                 .unwrap();
 
             Hand {
-                cards: self
-                    .cards
-                    .iter()
-                    .map(|card| {
-                        Card::from(match card {
-                            CardJokerRule::J => *mode_card,
-                            _ => *card,
-                        })
+                cards: self.cards.map(|card| {
+                    Card::from(match card {
+                        CardJokerRule::J => *mode_card,
+                        _ => card,
                     })
-                    .collect::<Vec<Card>>()
-                    .try_into()
-                    .unwrap(),
+                }),
                 bid: self.bid,
             }
         } else {
             Hand {
-                cards: self
-                    .cards
-                    .iter()
-                    .map(|card| Card::from(*card))
-                    .collect::<Vec<Card>>()
-                    .try_into()
-                    .unwrap(),
+                cards: self.cards.map(Card::from),
                 bid: self.bid,
             }
         };
@@ -323,7 +379,7 @@ where
 
         for (card_a, card_b) in self.cards.iter().zip(other.cards.iter()) {
             if card_a != card_b {
-                return card_a.cmp(&card_b);
+                return card_a.cmp(card_b);
             }
         }
 
@@ -355,53 +411,108 @@ impl<CardType: TryFrom<char, Error = anyhow::Error> + core::fmt::Debug> TryFrom<
 {
     type Error = anyhow::Error;
     fn try_from(s: &str) -> Result<Self, Error> {
-        let cards_bid = s
-            .split_whitespace()
-            .take(2)
-            .map(|s| s.trim())
-            .collect::<Vec<&str>>();
+        let (cards, bid) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or(anyhow!("Expected a hand and a bid in {:?}", s))?;
+        let cards = cards
+            .chars()
+            .map(CardType::try_from)
+            .collect::<Result<Vec<CardType>, _>>()?;
+        let card_count = cards.len();
 
         Ok(Self {
-            cards: cards_bid[0]
-                .chars()
-                .take(5)
-                .map(|c| CardType::try_from(c))
-                .collect::<Result<Vec<CardType>, _>>()?
+            cards: cards
                 .try_into()
-                .unwrap(),
-            bid: cards_bid[1].parse::<u64>()?,
+                .map_err(|_| anyhow!("Expected 5 cards in a hand, found {}", card_count))?,
+            bid: bid.trim().parse::<u64>()?,
         })
     }
 }
 
-fn solve<CardType>(data: &str) -> Result<i32>
+fn parse_hands<CardType>(data: &str) -> (Vec<Hand<CardType>>, Vec<Malformed>)
 where
-    CardType: TryFrom<char, Error = anyhow::Error> + core::fmt::Debug + Copy + Ord,
+    CardType: TryFrom<char, Error = anyhow::Error> + core::fmt::Debug,
+{
+    parse_lines(data, |line| Hand::try_from(line))
+}
+
+/// The hands ranked from the weakest up, under both parts' rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Day7Analysis {
+    /// With J for jack.
+    pub hands: Vec<RankedHand>,
+    /// With J for joker, which stands in for whichever card makes the hand
+    /// strongest.
+    pub with_jokers: Vec<RankedHand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RankedHand {
+    /// As they're written, eg. `32T3K`.
+    pub cards: String,
+    pub bid: u64,
+    pub hand_type: HandType,
+    /// 1 for the weakest hand, tied hands share a rank.
+    pub rank: u64,
+    /// The bid times the rank.
+    pub winnings: u64,
+}
+
+/// Ranks the hands in `input` under the rules of both parts.
+pub fn analyze(input: &str) -> Result<Day7Analysis> {
+    Ok(Day7Analysis {
+        hands: rank_hands(strict(parse_hands::<Card>(input))?)?,
+        with_jokers: rank_hands(strict(parse_hands::<CardJokerRule>(input))?)?,
+    })
+}
+
+fn rank_hands<CardType>(mut hands: Vec<Hand<CardType>>) -> Result<Vec<RankedHand>>
+where
+    CardType: Copy + Ord + Into<Card>,
     Hand<CardType>: HandOfCards,
 {
-    let mut hands = data
-        .lines()
-        .map(|line| Hand::try_from(line))
-        .collect::<Result<Vec<Hand<CardType>>, _>>()?;
+    if hands.is_empty() {
+        return Err(anyhow!("No hands in the input"));
+    }
     hands.sort();
     let mut rank: u64 = 1;
-    let mut ranked_hands = hands
-        .windows(2)
-        .map(|hands| {
-            let hand_a = &hands[0];
-            let hand_b = &hands[1];
-            if hand_a != hand_b {
-                rank += 1;
-            }
-            (hand_a.clone(), rank - 1)
-        })
-        .collect::<Vec<(Hand<CardType>, u64)>>();
-    ranked_hands.push((hands.last().unwrap().clone(), rank));
-    let total = ranked_hands
+    let mut ranked_hands = Vec::with_capacity(hands.len());
+    for (i, hand) in hands.iter().enumerate() {
+        // tied hands share a rank
+        if i > 0 && hands[i - 1] != *hand {
+            rank += 1;
+        }
+        ranked_hands.push(RankedHand {
+            cards: hand
+                .cards
+                .iter()
+                .map(|card| char::from((*card).into()))
+                .collect(),
+            bid: hand.bid,
+            hand_type: hand.get_type(),
+            rank,
+            winnings: hand
+                .bid
+                .checked_mul(rank)
+                .ok_or(anyhow!("The total winnings are too big"))?,
+        });
+    }
+    Ok(ranked_hands)
+}
+
+fn solve<CardType>(data: &str, ctx: &RunContext) -> Result<Answer>
+where
+    CardType: TryFrom<char, Error = anyhow::Error> + core::fmt::Debug + Copy + Ord + Into<Card>,
+    Hand<CardType>: HandOfCards,
+{
+    let hands = ctx.parse(|| strict(parse_hands::<CardType>(data)))?;
+    let total = rank_hands(hands)?
         .iter()
-        .map(|(hand, rank)| (hand.bid * rank) as u64)
-        .sum::<u64>();
-    Ok(total as i32)
+        .try_fold(0u64, |total, hand| total.checked_add(hand.winnings))
+        .and_then(|total| i64::try_from(total).ok())
+        .ok_or(anyhow!("The total winnings are too big"))?;
+    Ok(total.into())
 }
 
 impl Solver for PartOne {
@@ -409,9 +520,12 @@ impl Solver for PartOne {
         (1, "Total winnings")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let data = read_to_string(filepath)?;
-        solve::<Card>(&data)
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        solve::<Card>(input, ctx)
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 6440.into()))
     }
 }
 
@@ -420,31 +534,189 @@ impl Solver for PartTwo {
         (2, "Total winnings")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let data = read_to_string(filepath)?;
-        solve::<CardJokerRule>(&data)
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        solve::<CardJokerRule>(input, ctx)
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 5905.into()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::assert_part;
+    use proptest::prelude::*;
+    use std::cmp::Ordering;
 
+    #[test]
+    fn test_part_one_example() {
+        assert_part!(CamelCards, 1, EXAMPLE, 6440);
+    }
+
+    #[test]
+    fn test_part_two_example() {
+        assert_part!(CamelCards, 2, EXAMPLE, 5905);
+    }
+
+    #[test]
+    fn test_analyze_example() -> Result<()> {
+        let analysis = analyze(EXAMPLE)?;
+        let ranked = |hands: &[RankedHand]| {
+            hands
+                .iter()
+                .map(|hand| (hand.cards.clone(), hand.hand_type, hand.rank, hand.winnings))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ranked(&analysis.hands),
+            [
+                ("32T3K".to_string(), HandType::OnePair, 1, 765),
+                ("KTJJT".to_string(), HandType::TwoPair, 2, 440),
+                ("KK677".to_string(), HandType::TwoPair, 3, 84),
+                ("T55J5".to_string(), HandType::ThreeOfAKind, 4, 2736),
+                ("QQQJA".to_string(), HandType::ThreeOfAKind, 5, 2415),
+            ]
+        );
+        assert_eq!(
+            ranked(&analysis.with_jokers),
+            [
+                ("32T3K".to_string(), HandType::OnePair, 1, 765),
+                ("KK677".to_string(), HandType::TwoPair, 2, 56),
+                ("T55J5".to_string(), HandType::FourOfAKind, 3, 2052),
+                ("QQQJA".to_string(), HandType::FourOfAKind, 4, 1932),
+                ("KTJJT".to_string(), HandType::FourOfAKind, 5, 1100),
+            ]
+        );
+        assert_eq!(analysis.hands[0].bid, 765);
+        let json = serde_json::to_value(&analysis)?;
+        assert_eq!(json["with_jokers"][4]["hand_type"], "four_of_a_kind");
+        assert!(analyze("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tied_hands() {
+        // both rank 1, whichever comes first, and the next hand ranks 2
+        for input in ["KJ2K2 124\nKJ2K2 921\n", "KJ2K2 921\nKJ2K2 124\n"] {
+            assert_part!(CamelCards, 1, input, 1045);
+            assert_part!(CamelCards, 1, &format!("{}AAAAA 1\n", input), 1047);
+        }
+    }
+
+    // inputs `cargo fuzz run day07` found panicking
+    #[test]
+    fn test_fuzz_regressions() {
+        let input = "AJAJ2 0\n2A22A 10000000000000000000";
+        for solver in [&PartOne as &dyn Solver, &PartTwo] {
+            assert_eq!(
+                solver.solve_str(input).unwrap_err().to_string(),
+                "The total winnings are too big"
+            );
+        }
+    }
+
+    // personal inputs aren't in every clone, see crate::input::real
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_one() -> Result<()> {
-        let filepath = std::path::PathBuf::from("data/07/input");
+        let filepath = crate::input::real(7);
         let solver = PartOne;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 251121738);
+        assert_eq!(solution, 251121738.into());
         Ok(())
     }
 
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_two() -> Result<()> {
-        let filepath = std::path::PathBuf::from("data/07/input");
+        let filepath = crate::input::real(7);
         let solver = PartTwo;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 251421071);
+        assert_eq!(solution, 251421071.into());
+        Ok(())
+    }
+
+    /// Hands of five cards with a bid, as lines of input. Half the time the
+    /// cards come from only a few labels, jokers among them, so there are
+    /// plenty of pairs, full houses and hands that tie.
+    fn hand_lines(count: std::ops::Range<usize>) -> impl Strategy<Value = Vec<String>> {
+        let cards = prop_oneof![
+            prop::sample::select(
+                &['A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2'][..]
+            ),
+            prop::sample::select(&['K', 'J', '2'][..]),
+        ];
+        let line = (prop::collection::vec(cards, 5), 1u64..1000)
+            .prop_map(|(cards, bid)| format!("{} {}", cards.into_iter().collect::<String>(), bid));
+        prop::collection::vec(line, count)
+    }
+
+    fn hands<CardType>(lines: &[String]) -> Vec<Hand<CardType>>
+    where
+        CardType: TryFrom<char, Error = anyhow::Error> + core::fmt::Debug,
+    {
+        lines
+            .iter()
+            .map(|line| Hand::try_from(line.as_str()).unwrap())
+            .collect()
+    }
+
+    /// Antisymmetry, consistency of `==` with `cmp` and transitivity, over
+    /// every ordered triple of `hands`.
+    fn check_total_order<CardType>(hands: &[Hand<CardType>]) -> Result<(), TestCaseError>
+    where
+        CardType: Ord + core::fmt::Debug,
+        Hand<CardType>: HandOfCards,
+    {
+        for a in hands {
+            prop_assert_eq!(a.cmp(a), Ordering::Equal);
+            for b in hands {
+                let ab = a.cmp(b);
+                prop_assert_eq!(ab, b.cmp(a).reverse(), "{:?} and {:?}", a, b);
+                prop_assert_eq!(a == b, ab == Ordering::Equal, "{:?} and {:?}", a, b);
+                prop_assert_eq!(a.partial_cmp(b), Some(ab));
+                for c in hands {
+                    if ab != Ordering::Greater && b.cmp(c) != Ordering::Greater {
+                        prop_assert_ne!(a.cmp(c), Ordering::Greater, "{:?} {:?} {:?}", a, b, c);
+                    }
+                }
+            }
+        }
         Ok(())
     }
+
+    fn sorted_cards<CardType>(lines: &[String]) -> Vec<[CardType; 5]>
+    where
+        CardType: TryFrom<char, Error = anyhow::Error> + core::fmt::Debug + Ord,
+        Hand<CardType>: HandOfCards,
+    {
+        let mut hands = hands::<CardType>(lines);
+        hands.sort();
+        hands.into_iter().map(|hand| hand.cards).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_hands_are_totally_ordered(lines in hand_lines(1..12)) {
+            check_total_order(&hands::<Card>(&lines))?;
+            check_total_order(&hands::<CardJokerRule>(&lines))?;
+        }
+
+        #[test]
+        fn prop_sorting_ignores_the_order_of_the_input(
+            (lines, shuffled) in hand_lines(1..30)
+                .prop_flat_map(|lines| (Just(lines.clone()), Just(lines).prop_shuffle()))
+        ) {
+            prop_assert_eq!(sorted_cards::<Card>(&lines), sorted_cards::<Card>(&shuffled));
+            prop_assert_eq!(
+                sorted_cards::<CardJokerRule>(&lines),
+                sorted_cards::<CardJokerRule>(&shuffled)
+            );
+            let (input, shuffled_input) = (lines.join("\n"), shuffled.join("\n"));
+            prop_assert_eq!(PartOne.solve_str(&input).unwrap(), PartOne.solve_str(&shuffled_input).unwrap());
+            prop_assert_eq!(PartTwo.solve_str(&input).unwrap(), PartTwo.solve_str(&shuffled_input).unwrap());
+        }
+    }
 }
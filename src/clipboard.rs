@@ -0,0 +1,104 @@
+//! Reading a puzzle input off the clipboard for `--paste`, only with the
+//! `clipboard` feature as it pulls in the platform's clipboard libraries,
+//! which minimal builds can do without. Without it [`read`] always errors.
+
+use anyhow::{anyhow, Context, Result};
+use std::{borrow::Cow, fs, path::Path};
+
+use crate::failure::FailureKind;
+
+/// The text on the clipboard, which has to be something other than
+/// whitespace.
+pub fn read() -> Result<String> {
+    let text = read_text()?;
+    if text.trim().is_empty() {
+        return Err(FailureKind::MissingInput.error(anyhow!(
+            "The clipboard is empty, copy the puzzle input first"
+        )));
+    }
+    Ok(text)
+}
+
+#[cfg(feature = "clipboard")]
+fn read_text() -> Result<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| {
+            FailureKind::MissingInput.error(anyhow!(
+                "Can't read the clipboard: {}. Without a desktop session, eg. over SSH, save the input to a file instead",
+                e
+            ))
+        })
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn read_text() -> Result<String> {
+    Err(FailureKind::Usage.error(anyhow!(
+        "This build can't read the clipboard, build it with the clipboard feature or save the input to a file instead"
+    )))
+}
+
+/// Saves a pasted `input` to `path` for `--save`, ending it with a newline
+/// like a downloaded input when the copy left that off. An input already at
+/// `path` is never replaced by a different one.
+pub fn save(path: &Path, input: &str) -> Result<()> {
+    let input = if input.ends_with('\n') {
+        Cow::Borrowed(input)
+    } else {
+        Cow::Owned(format!("{}\n", input))
+    };
+    if path.exists() {
+        return match fs::read_to_string(path) {
+            Ok(saved) if saved == input => Ok(()),
+            _ => Err(FailureKind::Usage.error(anyhow!(
+                "{} already exists, remove it to save the pasted input there",
+                path.display()
+            ))),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Can't create {} to save the input in", dir.display()))?;
+    }
+    fs::write(path, input.as_bytes())
+        .with_context(|| format!("Can't save the input to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-paste-{}", std::process::id()));
+        let path = dir.join("06").join("input");
+        save(&path, "Time: 7\nDistance: 9")?;
+        let saved = fs::read_to_string(&path)?;
+        // pasting the same input again is fine, a different one isn't
+        let again = save(&path, "Time: 7\nDistance: 9\n");
+        let different = save(&path, "Time: 8\nDistance: 9\n");
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(saved, "Time: 7\nDistance: 9\n");
+        assert!(again.is_ok());
+        let error = different.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .ends_with("already exists, remove it to save the pasted input there"),
+            "{}",
+            error
+        );
+        Ok(())
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    #[test]
+    fn test_read_needs_the_feature() {
+        let error = read().unwrap_err();
+        assert_eq!(crate::failure::kind_of(&error), FailureKind::Usage);
+        assert!(error
+            .to_string()
+            .starts_with("This build can't read the clipboard"));
+    }
+}
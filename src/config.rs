@@ -0,0 +1,695 @@
+//! Settings that can come from the command line, the environment (including a
+//! `.env` file) or `aoc.toml`, in that order of precedence.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use toml_edit::DocumentMut;
+
+use crate::{calendar::Timezone, layout::Layout, registry, solver::parse_duration};
+
+/// The optional config file, looked for in the current directory.
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Environment variable naming the data root to look for inputs in.
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+/// Environment variable holding the adventofcode.com session cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Environment variable picking the event year's directory under the data
+/// root, for data directories shared between several years' solutions.
+pub const YEAR_VAR: &str = "AOC_YEAR";
+/// Environment variable picking the clock `today` goes by, see [`Timezone`].
+pub const TIMEZONE_VAR: &str = "AOC_TIMEZONE";
+/// Environment variable naming each day's input file in the nested layout.
+pub const INPUT_NAME_VAR: &str = "AOC_INPUT_NAME";
+/// Environment variable picking where inputs are under the data root, see
+/// [`Layout`].
+pub const LAYOUT_VAR: &str = "AOC_LAYOUT";
+/// Environment variable holding the webhook `--notify-webhook` posts to
+/// when it's given without one.
+pub const NOTIFY_WEBHOOK_VAR: &str = "AOC_NOTIFY_WEBHOOK";
+/// Environment variable picking one of the profiles in `aoc.toml`, see
+/// [`Profile`].
+pub const PROFILE_VAR: &str = "AOC_PROFILE";
+
+pub const DEFAULT_DATA_DIR: &str = "data";
+pub const DEFAULT_YEAR: u16 = registry::YEAR;
+
+/// Describes the variables, for the end of `--help`.
+pub const ENVIRONMENT_HELP: &str = "\
+Environment variables, also read from a .env file:
+  AOC_DATA_DIR    The data root, where inputs are looked for [default: data]
+  AOC_SESSION     The adventofcode.com session cookie, for downloading inputs
+  AOC_YEAR        The event year, for downloading and submitting, with inputs and answers under
+                  <DATA_DIR>/<YEAR> instead, for data shared between years; only 2023 can be run
+  AOC_TIMEZONE    Whether 'today' is the local date or the date in UTC-5, when puzzles come out,
+                  local or est [default: local]
+  AOC_LAYOUT      Where each day's input is in the data root: nested for <DAY>/<INPUT_NAME>, flat
+                  for input<DAY>.txt, or a template like inputs/day{day:02}.txt [default: nested]
+  AOC_INPUT_NAME  What each day's input file is called in the nested layout [default: input]
+  AOC_NOTIFY_WEBHOOK
+                  The Discord or Slack webhook --notify-webhook posts a run's summary to
+  AOC_PROFILE     The profile in ./aoc.toml to use, for a second adventofcode.com account
+
+In templates and input names {day} is replaced by the day and {day:02} by the two digit day.
+
+Each can also be set as data_dir, session, year, timezone, layout, input_name or notify_webhook in
+./aoc.toml. The --data-dir, --year, --layout and --input-name options take precedence over the
+environment, which takes precedence over aoc.toml.
+
+How long each day may take to solve goes in a [budgets] table in ./aoc.toml, eg. 23 = \"5s\", see
+--budget.
+
+Each account gets a profile in ./aoc.toml, eg. [profiles.work] with session_file = \"work.session\",
+a file holding its session cookie, which takes precedence over AOC_SESSION. Its inputs, answers and
+submissions are kept under <DATA_DIR>/<PROFILE>, or data_subdir if it's set. The profile is picked
+with --profile, then AOC_PROFILE, then default_profile in ./aoc.toml, see the profile command.";
+
+/// The settings as given in `aoc.toml`, every one optional.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub data_dir: Option<PathBuf>,
+    pub session: Option<String>,
+    pub year: Option<u16>,
+    pub timezone: Option<Timezone>,
+    pub layout: Option<Layout>,
+    pub input_name: Option<String>,
+    pub notify_webhook: Option<String>,
+    /// Each day's runtime budget, eg. `23 = "5s"`, see [`crate::budget`].
+    #[serde(default)]
+    pub budgets: BTreeMap<String, String>,
+    /// The profile used when none is picked otherwise.
+    pub default_profile: Option<String>,
+    /// Each account's settings, by the profile's name.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// A profile as it's given in `aoc.toml`, as `[profiles.<name>]`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// A file holding the account's session cookie.
+    pub session_file: Option<PathBuf>,
+    /// Where its data is under the data directory, the profile's name by
+    /// default.
+    pub data_subdir: Option<PathBuf>,
+}
+
+impl ConfigFile {
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Reads the config at `path`, which doesn't have to exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).with_context(|| format!("Bad {}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Can't read {}", path.display())),
+        }
+    }
+
+    /// The data directory from `overrides`, then `env`, then this file.
+    pub fn data_dir(&self, overrides: &Overrides, env: impl Fn(&str) -> Option<String>) -> PathBuf {
+        overrides
+            .data_dir
+            .clone()
+            .or_else(|| {
+                env(DATA_DIR_VAR)
+                    .filter(|dir| !dir.is_empty())
+                    .map(PathBuf::from)
+            })
+            .or_else(|| self.data_dir.clone())
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR))
+    }
+
+    /// The name of the profile picked by `overrides`, then `env`, then
+    /// `default_profile`, if any is, whether or not there's such a profile.
+    pub fn profile_name(
+        &self,
+        overrides: &Overrides,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Option<String> {
+        overrides
+            .profile
+            .clone()
+            .or_else(|| env(PROFILE_VAR).filter(|name| !name.is_empty()))
+            .or_else(|| self.default_profile.clone())
+    }
+
+    /// The profile called `name`, erroring with the ones there are when
+    /// there's no such profile.
+    pub fn profile(&self, name: &str) -> Result<&ProfileConfig> {
+        self.profiles.get(name).ok_or_else(|| {
+            let names = self.profiles.keys().cloned().collect::<Vec<_>>();
+            if names.is_empty() {
+                anyhow!(
+                    "There's no profile {} in {}, it has none",
+                    name,
+                    CONFIG_FILE
+                )
+            } else {
+                anyhow!(
+                    "There's no profile {} in {}, only {}",
+                    name,
+                    CONFIG_FILE,
+                    names.join(", ")
+                )
+            }
+        })
+    }
+}
+
+/// Makes `name` the default profile in the config at `path`, keeping the
+/// rest of it as it is.
+pub fn set_default_profile(path: &Path, name: &str) -> Result<()> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+    ConfigFile::parse(&text)
+        .with_context(|| format!("Bad {}", path.display()))?
+        .profile(name)?;
+    let mut document = text
+        .parse::<DocumentMut>()
+        .with_context(|| format!("Bad {}", path.display()))?;
+    document.insert("default_profile", toml_edit::value(name));
+    fs::write(path, document.to_string()).with_context(|| format!("Can't write {}", path.display()))
+}
+
+/// The profile in use, an account's session and data kept apart from the
+/// others'.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    /// Where its data is under the data directory.
+    pub data_subdir: PathBuf,
+}
+
+/// Settings given on the command line, which override everything else.
+#[derive(Debug, Default, Clone)]
+pub struct Overrides {
+    pub data_dir: Option<PathBuf>,
+    pub year: Option<u16>,
+    pub layout: Option<Layout>,
+    pub input_name: Option<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub data_dir: PathBuf,
+    pub session: Option<String>,
+    /// The profile in use, `None` without any.
+    pub profile: Option<Profile>,
+    /// The year, only when one was set, the data root is `data_dir` otherwise.
+    pub year: Option<u16>,
+    pub timezone: Timezone,
+    /// Where each day's input is under the data root.
+    pub layout: Layout,
+    /// How long each day's parts may take to solve, by day.
+    pub budgets: BTreeMap<u8, Duration>,
+    /// Where `--notify-webhook` posts to when it isn't given a URL.
+    pub notify_webhook: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            data_dir: PathBuf::from(DEFAULT_DATA_DIR),
+            session: None,
+            profile: None,
+            year: None,
+            timezone: Timezone::default(),
+            layout: Layout::default(),
+            budgets: BTreeMap::new(),
+            notify_webhook: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Resolves each setting from the first of `overrides`, `env` and `file`
+    /// that has it, but the session, which comes from the profile's session
+    /// file first when there's a profile. `env` looks up a variable, empty
+    /// ones count as unset.
+    pub fn resolve(
+        overrides: &Overrides,
+        env: impl Fn(&str) -> Option<String>,
+        file: ConfigFile,
+    ) -> Result<Self> {
+        let env = |name: &str| env(name).filter(|value| !value.is_empty());
+        let data_dir = file.data_dir(overrides, env);
+        let (profile, profile_session) = match file.profile_name(overrides, env) {
+            Some(name) => {
+                let config = file.profile(&name)?;
+                let session = config
+                    .session_file
+                    .as_deref()
+                    .map(|path| read_session(&name, path))
+                    .transpose()?;
+                let profile = Profile {
+                    data_subdir: config
+                        .data_subdir
+                        .clone()
+                        .unwrap_or_else(|| PathBuf::from(&name)),
+                    name,
+                };
+                (Some(profile), session)
+            }
+            None => (None, None),
+        };
+        let env_year = env(YEAR_VAR)
+            .map(|year| {
+                year.parse::<u16>()
+                    .map_err(|_| anyhow!("{} should be a year, eg. 2023, not '{}'", YEAR_VAR, year))
+            })
+            .transpose()?;
+        let env_timezone = env(TIMEZONE_VAR)
+            .map(|timezone| {
+                timezone
+                    .parse::<Timezone>()
+                    .map_err(|e| anyhow!("{} is set, but {}", TIMEZONE_VAR, e))
+            })
+            .transpose()?;
+        let env_layout = env(LAYOUT_VAR)
+            .map(|layout| {
+                layout
+                    .parse::<Layout>()
+                    .map_err(|e| anyhow!("{} is set, but {}", LAYOUT_VAR, e))
+            })
+            .transpose()?;
+        if file.input_name.as_deref() == Some("") {
+            return Err(anyhow!("input_name in {} can't be empty", CONFIG_FILE));
+        }
+        let layout = overrides
+            .layout
+            .clone()
+            .or(env_layout)
+            .or(file.layout)
+            .unwrap_or_default();
+        let input_name = overrides
+            .input_name
+            .clone()
+            .or_else(|| env(INPUT_NAME_VAR))
+            .or(file.input_name);
+        let budgets = file
+            .budgets
+            .iter()
+            .map(|(key, budget)| {
+                let day = key
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|day| (1..=25).contains(day))
+                    .ok_or_else(|| anyhow!("budgets.{} in {} isn't a day", key, CONFIG_FILE))?;
+                let budget = parse_duration(budget)
+                    .with_context(|| format!("budgets.{} in {}", key, CONFIG_FILE))?;
+                Ok((day, budget))
+            })
+            .collect::<Result<_>>()?;
+        let defaults = Self::default();
+        Ok(Self {
+            data_dir,
+            session: profile_session
+                .or_else(|| env(SESSION_VAR))
+                .or(file.session),
+            profile,
+            year: overrides.year.or(env_year).or(file.year),
+            timezone: env_timezone.or(file.timezone).unwrap_or(defaults.timezone),
+            layout: match input_name {
+                Some(input_name) => layout.with_input_name(&input_name),
+                None => layout,
+            },
+            budgets,
+            notify_webhook: env(NOTIFY_WEBHOOK_VAR).or(file.notify_webhook),
+        })
+    }
+
+    /// Resolves the settings from the process environment and [`CONFIG_FILE`].
+    pub fn from_env(overrides: &Overrides) -> Result<Self> {
+        let file = ConfigFile::load(Path::new(CONFIG_FILE))?;
+        Self::resolve(overrides, |name| std::env::var(name).ok(), file)
+    }
+
+    /// Where the days' data directories are: the data directory, or the
+    /// profile's directory under it, then the year's directory under that
+    /// when a year is set.
+    pub fn data_root(&self) -> PathBuf {
+        let dir = match &self.profile {
+            Some(profile) => self.data_dir.join(&profile.data_subdir),
+            None => self.data_dir.clone(),
+        };
+        match self.year {
+            Some(year) => dir.join(year.to_string()),
+            None => dir,
+        }
+    }
+
+    /// The event year, for puzzle and input URLs.
+    pub fn event_year(&self) -> u16 {
+        self.year.unwrap_or(DEFAULT_YEAR)
+    }
+}
+
+/// The session cookie in profile `name`'s session file at `path`.
+fn read_session(name: &str, path: &Path) -> Result<String> {
+    let session = fs::read_to_string(path).with_context(|| {
+        format!(
+            "Can't read profile {}'s session file {}",
+            name,
+            path.display()
+        )
+    })?;
+    let session = session.trim();
+    if session.is_empty() {
+        return Err(anyhow!(
+            "Profile {}'s session file {} is empty",
+            name,
+            path.display()
+        ));
+    }
+    Ok(session.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(overrides: &Overrides, env: &[(&str, &str)], file: &str) -> Result<Settings> {
+        let env = env
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        Settings::resolve(
+            overrides,
+            |name| env.get(name).cloned(),
+            ConfigFile::parse(file)?,
+        )
+    }
+
+    #[test]
+    fn test_defaults() -> Result<()> {
+        let settings = resolve(&Overrides::default(), &[], "")?;
+        assert_eq!(settings, Settings::default());
+        assert_eq!(settings.data_root(), Path::new("data"));
+        assert_eq!(settings.event_year(), 2023);
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_dir() -> Result<()> {
+        let flag = Overrides {
+            data_dir: Some("flag".into()),
+            ..Default::default()
+        };
+        let env = [(DATA_DIR_VAR, "env")];
+        let file = "data_dir = \"file\"";
+        assert_eq!(resolve(&flag, &env, file)?.data_root(), Path::new("flag"));
+        let none = Overrides::default();
+        assert_eq!(resolve(&none, &env, file)?.data_root(), Path::new("env"));
+        assert_eq!(resolve(&none, &[], file)?.data_root(), Path::new("file"));
+        // an empty variable is as good as unset
+        let empty = [(DATA_DIR_VAR, "")];
+        assert_eq!(resolve(&none, &empty, file)?.data_root(), Path::new("file"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_session() -> Result<()> {
+        let none = Overrides::default();
+        let file = "session = \"from-file\"";
+        let env = [(SESSION_VAR, "from-env")];
+        assert_eq!(
+            resolve(&none, &env, file)?.session.as_deref(),
+            Some("from-env")
+        );
+        assert_eq!(
+            resolve(&none, &[], file)?.session.as_deref(),
+            Some("from-file")
+        );
+        assert_eq!(resolve(&none, &[], "")?.session, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_notify_webhook() -> Result<()> {
+        let none = Overrides::default();
+        let file = "notify_webhook = \"https://example.com/file\"";
+        let env = [(NOTIFY_WEBHOOK_VAR, "https://example.com/env")];
+        assert_eq!(
+            resolve(&none, &env, file)?.notify_webhook.as_deref(),
+            Some("https://example.com/env")
+        );
+        assert_eq!(
+            resolve(&none, &[], file)?.notify_webhook.as_deref(),
+            Some("https://example.com/file")
+        );
+        assert_eq!(resolve(&none, &[], "")?.notify_webhook, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_year() -> Result<()> {
+        let flag = Overrides {
+            year: Some(2021),
+            ..Default::default()
+        };
+        let env = [(YEAR_VAR, "2022"), (DATA_DIR_VAR, "shared")];
+        let file = "year = 2015";
+        let settings = resolve(&flag, &env, file)?;
+        assert_eq!(settings.data_root(), Path::new("shared/2021"));
+        assert_eq!(settings.event_year(), 2021);
+        let none = Overrides::default();
+        assert_eq!(resolve(&none, &env, file)?.event_year(), 2022);
+        assert_eq!(
+            resolve(&none, &[], file)?.data_root(),
+            Path::new("data/2015")
+        );
+
+        // each year's inputs are apart, whatever the layout
+        let input = |settings: Settings| settings.layout.input(&settings.data_root(), 3);
+        assert_eq!(
+            input(resolve(&flag, &env, file)?),
+            Path::new("shared/2021/03/input")
+        );
+        assert_eq!(
+            input(resolve(&none, &[], "year = 2024\nlayout = \"flat\"")?),
+            Path::new("data/2024/input03.txt")
+        );
+        // without a year, the data directory is the data root
+        assert_eq!(input(resolve(&none, &[], "")?), Path::new("data/03/input"));
+
+        let error = resolve(&none, &[(YEAR_VAR, "twenty")], "").unwrap_err();
+        assert!(error.to_string().contains("AOC_YEAR"), "{}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn test_profiles() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-profiles-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let personal = dir.join("personal.session");
+        let work = dir.join("work.session");
+        fs::write(&personal, "personal-cookie\n")?;
+        fs::write(&work, "work-cookie")?;
+        let file = format!(
+            "session = \"from-file\"\ndefault_profile = \"personal\"\n\
+             [profiles.personal]\nsession_file = {:?}\n\
+             [profiles.work]\nsession_file = {:?}\ndata_subdir = \"day-job\"\n\
+             [profiles.bare]\n",
+            personal, work
+        );
+        let flag = |profile: &str| Overrides {
+            profile: Some(profile.to_string()),
+            ..Default::default()
+        };
+        let none = Overrides::default();
+        let env = [(PROFILE_VAR, "work"), (SESSION_VAR, "from-env")];
+
+        // the flag, then the environment, then the default
+        let profile = |settings: Settings| settings.profile.map(|p| p.name);
+        assert_eq!(
+            profile(resolve(&flag("personal"), &env, &file)?).as_deref(),
+            Some("personal")
+        );
+        assert_eq!(
+            profile(resolve(&none, &env, &file)?).as_deref(),
+            Some("work")
+        );
+        assert_eq!(
+            profile(resolve(&none, &[], &file)?).as_deref(),
+            Some("personal")
+        );
+        assert_eq!(profile(resolve(&none, &[], "")?), None);
+
+        // the session file comes before the environment, which comes before
+        // the session in aoc.toml
+        let session = |settings: Settings| settings.session;
+        assert_eq!(
+            session(resolve(&none, &env, &file)?).as_deref(),
+            Some("work-cookie")
+        );
+        assert_eq!(
+            session(resolve(&none, &[], &file)?).as_deref(),
+            Some("personal-cookie")
+        );
+        let bare = resolve(&flag("bare"), &env, &file)?;
+        assert_eq!(bare.session.as_deref(), Some("from-env"));
+        assert_eq!(
+            session(resolve(&flag("bare"), &[], &file)?).as_deref(),
+            Some("from-file")
+        );
+
+        // each profile's inputs, answers and submissions are apart
+        let work = resolve(&none, &env, &file)?;
+        assert_eq!(work.data_root(), Path::new("data/day-job"));
+        assert_eq!(
+            work.layout.input(&work.data_root(), 3),
+            Path::new("data/day-job/03/input")
+        );
+        assert_eq!(bare.data_root(), Path::new("data/bare"));
+        let year = Overrides {
+            year: Some(2022),
+            data_dir: Some("shared".into()),
+            ..flag("personal")
+        };
+        let personal = resolve(&year, &[], &file)?;
+        assert_eq!(personal.data_root(), Path::new("shared/personal/2022"));
+        assert_eq!(
+            crate::submit::log::path(&personal.data_root(), 7),
+            Path::new("shared/personal/2022/07/submissions.toml")
+        );
+
+        let error = |overrides: &Overrides, env: &[(&str, &str)], file: &str| {
+            resolve(overrides, env, file).unwrap_err().to_string()
+        };
+        assert_eq!(
+            error(&flag("team"), &[], &file),
+            "There's no profile team in aoc.toml, only bare, personal, work"
+        );
+        assert_eq!(
+            error(&none, &[(PROFILE_VAR, "team")], ""),
+            "There's no profile team in aoc.toml, it has none"
+        );
+        let missing = "[profiles.gone]\nsession_file = \"no-such.session\"\n";
+        assert!(error(&flag("gone"), &[], missing)
+            .starts_with("Can't read profile gone's session file no-such.session"));
+        fs::write(dir.join("empty.session"), "\n")?;
+        let empty = format!(
+            "[profiles.empty]\nsession_file = {:?}\n",
+            dir.join("empty.session")
+        );
+        assert!(error(&flag("empty"), &[], &empty).ends_with("is empty"));
+        assert!(ConfigFile::parse("[profiles.x]\nsession = \"abc\"\n").is_err());
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_default_profile() -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("aoc-2023-config-{}.toml", std::process::id()));
+        let text = "# the data\ndata_dir = \"data\"\n\n[profiles.personal]\n\n[profiles.work]\n";
+        fs::write(&path, text)?;
+        set_default_profile(&path, "work")?;
+        let config = ConfigFile::load(&path)?;
+        assert_eq!(config.default_profile.as_deref(), Some("work"));
+        set_default_profile(&path, "personal")?;
+        let updated = fs::read_to_string(&path)?;
+        assert!(
+            updated
+                .starts_with("# the data\ndata_dir = \"data\"\ndefault_profile = \"personal\"\n"),
+            "{}",
+            updated
+        );
+        assert!(set_default_profile(&path, "team").is_err());
+        assert_eq!(fs::read_to_string(&path)?, updated);
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_budgets() -> Result<()> {
+        let none = Overrides::default();
+        let settings = resolve(&none, &[], "[budgets]\n23 = \"5s\"\n01 = \"250ms\"\n")?;
+        assert_eq!(
+            settings.budgets,
+            BTreeMap::from([
+                (1, Duration::from_millis(250)),
+                (23, Duration::from_secs(5))
+            ])
+        );
+        let error = |file| resolve(&none, &[], file).unwrap_err().to_string();
+        assert!(error("[budgets]\n26 = \"1s\"\n").starts_with("budgets.26 in aoc.toml isn't a day"));
+        assert!(error("[budgets]\n1 = \"soon\"\n").starts_with("budgets.1 in aoc.toml"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_timezone() -> Result<()> {
+        let none = Overrides::default();
+        let file = "timezone = \"est\"";
+        assert_eq!(resolve(&none, &[], "")?.timezone, Timezone::Local);
+        assert_eq!(resolve(&none, &[], file)?.timezone, Timezone::Est);
+        let env = [(TIMEZONE_VAR, "local")];
+        assert_eq!(resolve(&none, &env, file)?.timezone, Timezone::Local);
+        assert!(resolve(&none, &[(TIMEZONE_VAR, "pst")], "").is_err());
+        assert!(ConfigFile::parse("timezone = \"pst\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_layout() -> Result<()> {
+        let none = Overrides::default();
+        let input = |settings: Settings| settings.layout.input(Path::new("data"), 3);
+        assert_eq!(input(resolve(&none, &[], "")?), Path::new("data/03/input"));
+        let file = "layout = \"flat\"\ninput_name = \"{day}.txt\"";
+        assert_eq!(
+            input(resolve(&none, &[], file)?),
+            Path::new("data/input03.txt")
+        );
+        let env = [(LAYOUT_VAR, "nested")];
+        assert_eq!(
+            input(resolve(&none, &env, file)?),
+            Path::new("data/03/3.txt")
+        );
+        let env = [(LAYOUT_VAR, "nested"), (INPUT_NAME_VAR, "day{day:02}")];
+        assert_eq!(
+            input(resolve(&none, &env, file)?),
+            Path::new("data/03/day03")
+        );
+        let flag = Overrides {
+            layout: Some("inputs/{day:02}.txt".parse()?),
+            input_name: Some("puzzle.txt".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            input(resolve(&flag, &env, file)?),
+            Path::new("data/inputs/03.txt")
+        );
+
+        assert!(resolve(&none, &[], "input_name = \"\"").is_err());
+        assert!(resolve(&none, &[], "layout = \"tree\"").is_err());
+        let error = resolve(&none, &[(LAYOUT_VAR, "tree")], "").unwrap_err();
+        assert!(error.to_string().contains("AOC_LAYOUT"), "{}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_file() -> Result<()> {
+        assert!(ConfigFile::parse("data_dir = 3").is_err());
+        assert!(ConfigFile::parse("datadir = \"typo\"").is_err());
+        assert_eq!(
+            ConfigFile::load(Path::new("no-such-aoc.toml"))?,
+            ConfigFile::default()
+        );
+        Ok(())
+    }
+}
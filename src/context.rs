@@ -0,0 +1,202 @@
+use anyhow::Result;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    input::ParseMode,
+    progress::{no_progress, Progress},
+};
+
+/// How much a solver should say about how it got its answer, set with `-v`
+/// and `-vv`. It's said on stderr, see [`RunContext::explain`], so stdout is
+/// still only the answers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Verbosity {
+    /// Nothing at all, eg. when benchmarking.
+    Quiet,
+    /// Only what's worth knowing on every run, which for most days is nothing.
+    #[default]
+    Normal,
+    /// Showing the work, eg. each card's matches.
+    Verbose,
+    /// Everything, for chasing a bug.
+    Debug,
+}
+
+impl Verbosity {
+    /// The verbosity for `count` `-v` flags.
+    pub fn from_count(count: u8) -> Self {
+        match count {
+            0 => Verbosity::Normal,
+            1 => Verbosity::Verbose,
+            _ => Verbosity::Debug,
+        }
+    }
+}
+
+/// State shared between the runner and a solver for the duration of a single
+/// part run.
+pub struct RunContext {
+    parse_time: Mutex<Option<Duration>>,
+    parse_failed: AtomicBool,
+    cancelled: AtomicBool,
+    progress: Arc<dyn Progress>,
+    parse_mode: ParseMode,
+    verbosity: Verbosity,
+}
+
+impl Default for RunContext {
+    fn default() -> Self {
+        Self::with_progress(no_progress())
+    }
+}
+
+impl RunContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_progress(progress: Arc<dyn Progress>) -> Self {
+        Self {
+            parse_time: Mutex::new(None),
+            parse_failed: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            progress,
+            parse_mode: ParseMode::default(),
+            verbosity: Verbosity::default(),
+        }
+    }
+
+    pub fn with_parse_mode(self, parse_mode: ParseMode) -> Self {
+        Self { parse_mode, ..self }
+    }
+
+    pub fn with_verbosity(self, verbosity: Verbosity) -> Self {
+        Self { verbosity, ..self }
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    /// Writes `message` to stderr when the verbosity is at least `level`. It's
+    /// only formatted then, so solvers can explain themselves in hot loops.
+    pub fn explain(&self, level: Verbosity, message: impl FnOnce() -> String) {
+        if self.verbosity >= level {
+            eprintln!("{}", message());
+        }
+    }
+
+    /// How strictly the input was checked before the solver got it, see
+    /// [`crate::input::prepare`]. Solvers can use it to decide whether to
+    /// tolerate anything else unusual in the input.
+    pub fn parse_mode(&self) -> ParseMode {
+        self.parse_mode
+    }
+
+    /// Where a long running solver should report how far through it is. This
+    /// discards everything unless the user asked for `--progress`.
+    pub fn progress(&self) -> &dyn Progress {
+        self.progress.as_ref()
+    }
+
+    /// Runs the parsing stage of a solver, recording how long it took so the
+    /// runner can report parse and solve time separately.
+    pub fn parse<T>(&self, parser: impl FnOnce() -> Result<T>) -> Result<T> {
+        let start = Instant::now();
+        let parsed = parser();
+        let elapsed = start.elapsed();
+        let mut parse_time = self.parse_time.lock().unwrap();
+        *parse_time = Some(parse_time.unwrap_or_default() + elapsed);
+        if parsed.is_err() {
+            self.parse_failed.store(true, Ordering::Relaxed);
+        }
+        parsed
+    }
+
+    pub fn parse_time(&self) -> Option<Duration> {
+        *self.parse_time.lock().unwrap()
+    }
+
+    /// Whether an error came out of the parsing stage, so the runner can tell
+    /// a malformed input apart from a solver failing on a valid one.
+    pub fn parse_failed(&self) -> bool {
+        self.parse_failed.load(Ordering::Relaxed)
+    }
+
+    /// Set once the runner has given up on this part, eg. because it hit the
+    /// `--timeout`. Long running solvers can check it in their main loop and
+    /// bail out early, otherwise they run to completion in the background.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity() {
+        assert_eq!(RunContext::new().verbosity(), Verbosity::Normal);
+        assert_eq!(Verbosity::from_count(0), Verbosity::Normal);
+        assert_eq!(Verbosity::from_count(1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_count(5), Verbosity::Debug);
+
+        let ctx = RunContext::new().with_verbosity(Verbosity::Verbose);
+        let mut formatted = vec![];
+        for level in [Verbosity::Normal, Verbosity::Verbose, Verbosity::Debug] {
+            ctx.explain(level, || {
+                formatted.push(level);
+                String::new()
+            });
+        }
+        // nothing is formatted that won't be shown
+        assert_eq!(formatted, [Verbosity::Normal, Verbosity::Verbose]);
+    }
+
+    #[test]
+    fn test_parse_time_recorded() -> Result<()> {
+        let ctx = RunContext::new();
+        assert_eq!(ctx.parse_time(), None);
+        let value = ctx.parse(|| Ok(7))?;
+        assert_eq!(value, 7);
+        assert!(ctx.parse_time().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_failed() {
+        let ctx = RunContext::new();
+        assert!(ctx.parse(|| Ok(1)).is_ok());
+        assert!(!ctx.parse_failed());
+        assert!(ctx
+            .parse(|| "x".parse::<i32>().map_err(Into::into))
+            .is_err());
+        assert!(ctx.parse_failed());
+    }
+
+    #[test]
+    fn test_parse_time_accumulates() -> Result<()> {
+        let ctx = RunContext::new();
+        ctx.parse(|| {
+            std::thread::sleep(Duration::from_millis(2));
+            Ok(())
+        })?;
+        ctx.parse(|| {
+            std::thread::sleep(Duration::from_millis(2));
+            Ok(())
+        })?;
+        assert!(ctx.parse_time().unwrap() >= Duration::from_millis(4));
+        Ok(())
+    }
+}
@@ -1,64 +1,80 @@
-use std::{collections::HashSet, fs::read_to_string, path::PathBuf, str::FromStr};
+use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
 
-use crate::solver::{MultiSolver, Solver};
-
-///     --- Day 2: Cube Conundrum ---
-///
-/// You're launched high into the atmosphere! The apex of your trajectory just barely reaches the surface of a large island floating in the sky. You gently land in a fluffy pile of leaves. It's quite cold, but you don't see much snow. An Elf runs over to greet you.
-///
-/// The Elf explains that you've arrived at Snow Island and apologizes for the lack of snow. He'll be happy to explain the situation, but it's a bit of a walk, so you have some time. They don't get many visitors up here; would you like to play a game in the meantime?
-///
-/// As you walk, the Elf shows you a small bag and some cubes which are either red, green, or blue. Each time you play this game, he will hide a secret number of cubes of each color in the bag, and your goal is to figure out information about the number of cubes.
-///
-/// To get information, once a bag has been loaded with cubes, the Elf will reach into the bag, grab a handful of random cubes, show them to you, and then put them back in the bag. He'll do this a few times per game.
-///
-/// You play several games and record the information from each game (your puzzle input). Each game is listed with its ID number (like the 11 in Game 11: ...) followed by a semicolon-separated list of subsets of cubes that were revealed from the bag (like 3 red, 5 green, 4 blue).
-///
-/// For example, the record of a few games might look like this:
-/// ```
-/// Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-/// Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-/// Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-/// Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-/// Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-/// ```
-/// In game 1, three sets of cubes are revealed from the bag (and then put back again). The first set is 3 blue cubes and 4 red cubes; the second set is 1 red cube, 2 green cubes, and 6 blue cubes; the third set is only 2 green cubes.
-///
-/// The Elf would first like to know which games would have been possible if the bag contained only 12 red cubes, 13 green cubes, and 14 blue cubes?
-///
-/// In the example above, games 1, 2, and 5 would have been possible if the bag had been loaded with that configuration. However, game 3 would have been impossible because at one point the Elf showed you 20 red cubes at once; similarly, game 4 would also have been impossible because the Elf showed you 15 blue cubes at once. If you add up the IDs of the games that would have been possible, you get 8.
-///
-/// Determine which games would have been possible if the bag had been loaded with only 12 red cubes, 13 green cubes, and 14 blue cubes. What is the sum of the IDs of those games?
-///
-///     --- Part Two ---
-///
-/// The Elf says they've stopped producing snow because they aren't getting any water! He isn't sure why the water stopped; however, he can show you how to get to the water source to check it out for yourself. It's just up ahead!
-///
-/// As you continue your walk, the Elf poses a second question: in each game you played, what is the fewest number of cubes of each color that could have been in the bag to make the game possible?
-///
-/// Again consider the example games from earlier:
-/// ```
-/// Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-/// Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-/// Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-/// Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-/// Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-/// ```
-///    - In game 1, the game could have been played with as few as 4 red, 2 green, and 6 blue cubes. If any color had even one fewer cube, the game would have been impossible.
-///    - Game 2 could have been played with a minimum of 1 red, 3 green, and 4 blue cubes.
-///    - Game 3 must have been played with at least 20 red, 13 green, and 6 blue cubes.
-///    - Game 4 required at least 14 red, 3 green, and 15 blue cubes.
-///    - Game 5 needed no fewer than 6 red, 3 green, and 2 blue cubes in the bag.
-///
-/// The power of a set of cubes is equal to the numbers of red, green, and blue cubes multiplied together. The power of the minimum set of cubes in game 1 is 48. In games 2-5 it was 12, 1560, 630, and 36, respectively. Adding up these five powers produces the sum 2286.
-///
-/// For each game, find the minimum set of cubes that must have been present. What is the sum of the power of these sets?
-pub struct CubeConundrum;
+use crate::{
+    context::RunContext,
+    metadata::{Difficulty, Metadata, RuntimeClass},
+    solver::{Answer, MultiSolver, Solver},
+    statement::puzzle_struct,
+    validate::{parse_lines, strict, Malformed, ParseStats},
+};
+
+puzzle_struct! {
+    ///     --- Day 2: Cube Conundrum ---
+    ///
+    /// You're launched high into the atmosphere! The apex of your trajectory just barely reaches the surface of a large island floating in the sky. You gently land in a fluffy pile of leaves. It's quite cold, but you don't see much snow. An Elf runs over to greet you.
+    ///
+    /// The Elf explains that you've arrived at Snow Island and apologizes for the lack of snow. He'll be happy to explain the situation, but it's a bit of a walk, so you have some time. They don't get many visitors up here; would you like to play a game in the meantime?
+    ///
+    /// As you walk, the Elf shows you a small bag and some cubes which are either red, green, or blue. Each time you play this game, he will hide a secret number of cubes of each color in the bag, and your goal is to figure out information about the number of cubes.
+    ///
+    /// To get information, once a bag has been loaded with cubes, the Elf will reach into the bag, grab a handful of random cubes, show them to you, and then put them back in the bag. He'll do this a few times per game.
+    ///
+    /// You play several games and record the information from each game (your puzzle input). Each game is listed with its ID number (like the 11 in Game 11: ...) followed by a semicolon-separated list of subsets of cubes that were revealed from the bag (like 3 red, 5 green, 4 blue).
+    ///
+    /// For example, the record of a few games might look like this:
+    /// ```
+    /// Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+    /// Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+    /// Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+    /// Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+    /// Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+    /// ```
+    /// In game 1, three sets of cubes are revealed from the bag (and then put back again). The first set is 3 blue cubes and 4 red cubes; the second set is 1 red cube, 2 green cubes, and 6 blue cubes; the third set is only 2 green cubes.
+    ///
+    /// The Elf would first like to know which games would have been possible if the bag contained only 12 red cubes, 13 green cubes, and 14 blue cubes?
+    ///
+    /// In the example above, games 1, 2, and 5 would have been possible if the bag had been loaded with that configuration. However, game 3 would have been impossible because at one point the Elf showed you 20 red cubes at once; similarly, game 4 would also have been impossible because the Elf showed you 15 blue cubes at once. If you add up the IDs of the games that would have been possible, you get 8.
+    ///
+    /// Determine which games would have been possible if the bag had been loaded with only 12 red cubes, 13 green cubes, and 14 blue cubes. What is the sum of the IDs of those games?
+    ///
+    ///     --- Part Two ---
+    ///
+    /// The Elf says they've stopped producing snow because they aren't getting any water! He isn't sure why the water stopped; however, he can show you how to get to the water source to check it out for yourself. It's just up ahead!
+    ///
+    /// As you continue your walk, the Elf poses a second question: in each game you played, what is the fewest number of cubes of each color that could have been in the bag to make the game possible?
+    ///
+    /// Again consider the example games from earlier:
+    /// ```
+    /// Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+    /// Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+    /// Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+    /// Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+    /// Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+    /// ```
+    ///    - In game 1, the game could have been played with as few as 4 red, 2 green, and 6 blue cubes. If any color had even one fewer cube, the game would have been impossible.
+    ///    - Game 2 could have been played with a minimum of 1 red, 3 green, and 4 blue cubes.
+    ///    - Game 3 must have been played with at least 20 red, 13 green, and 6 blue cubes.
+    ///    - Game 4 required at least 14 red, 3 green, and 15 blue cubes.
+    ///    - Game 5 needed no fewer than 6 red, 3 green, and 2 blue cubes in the bag.
+    ///
+    /// The power of a set of cubes is equal to the numbers of red, green, and blue cubes multiplied together. The power of the minimum set of cubes in game 1 is 48. In games 2-5 it was 12, 1560, 630, and 36, respectively. Adding up these five powers produces the sum 2286.
+    ///
+    /// For each game, find the minimum set of cubes that must have been present. What is the sum of the power of these sets?
+    pub struct CubeConundrum;
+}
 pub struct PartOne;
 pub struct PartTwo;
 
+const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
 #[derive(PartialEq, Eq, Hash)]
 enum CubeColour {
     Red,
@@ -72,6 +88,16 @@ struct Cube {
     count: u32,
 }
 
+/// One line of the input, the cubes revealed in each round of a game.
+struct Game {
+    id: i32,
+    rounds: Vec<Vec<Cube>>,
+}
+
+fn parse_games(input: &str) -> (Vec<Game>, Vec<Malformed>) {
+    parse_lines(input, str::parse)
+}
+
 impl MultiSolver for CubeConundrum {
     type PartOne = PartOne;
     type PartTwo = PartTwo;
@@ -80,6 +106,18 @@ impl MultiSolver for CubeConundrum {
         "Day 2: Cube Conundrum"
     }
 
+    fn input_hint(&self) -> Option<&str> {
+        Some("lines like 'Game 1: 3 blue, 4 red; 1 red, 2 green'")
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        Some(Self::STATEMENT)
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(2)
+    }
+
     fn get_part_one(&self) -> Self::PartOne {
         PartOne
     }
@@ -87,6 +125,30 @@ impl MultiSolver for CubeConundrum {
     fn get_part_two(&self) -> Self::PartTwo {
         PartTwo
     }
+
+    fn parse_stats(&self, input: &str) -> Option<ParseStats> {
+        let (games, malformed) = parse_games(input);
+        let rounds = games.iter().map(|game| game.rounds.len()).sum();
+        let cubes = games
+            .iter()
+            .flat_map(|game| &game.rounds)
+            .map(Vec::len)
+            .sum();
+        Some(
+            ParseStats::new(input, malformed)
+                .with_count("games", games.len())
+                .with_count("rounds", rounds)
+                .with_count("cube counts", cubes),
+        )
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            tags: &["parsing"],
+            difficulty: Some(Difficulty::Easy),
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 impl Solver for PartOne {
@@ -94,8 +156,8 @@ impl Solver for PartOne {
         (1, "Sum of ganme IDs")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        fn max_cube_count(colour: CubeColour) -> u32 {
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        fn max_cube_count(colour: &CubeColour) -> u32 {
             match colour {
                 CubeColour::Red => 12,
                 CubeColour::Green => 13,
@@ -103,42 +165,22 @@ impl Solver for PartOne {
             }
         }
 
-        fn process(line: &str) -> Result<i32> {
-            let (game_id_str, games) = line.split_at(line.find(':').ok_or(anyhow!(
-                "Expected a ':' character in the input string '{}'",
-                line
-            ))?);
-            let id = game_id_str
-                .split_whitespace()
-                .rev()
-                .next()
-                .ok_or(anyhow!(
-                    "Expected whitespace in the game id string '{}'",
-                    game_id_str
-                ))?
-                .parse::<i32>()?;
-            let games = games.trim_start_matches(":").trim();
-            for game in games.split(';') {
-                let cubes = game
-                    .trim_start_matches(";")
-                    .trim()
-                    .split(',')
-                    .map(|s| s.trim_start_matches(",").trim().parse::<Cube>())
-                    .collect::<Result<HashSet<Cube>>>()?;
-                for cube in cubes {
-                    if cube.count > max_cube_count(cube.colour) {
-                        return Ok(0);
-                    }
-                }
-            }
-            Ok(id)
-        }
+        let games = ctx.parse(|| strict(parse_games(input)))?;
+        let sum = games
+            .iter()
+            .filter(|game| {
+                game.rounds
+                    .iter()
+                    .flatten()
+                    .all(|cube| cube.count <= max_cube_count(&cube.colour))
+            })
+            .try_fold(0_i32, |sum, game| sum.checked_add(game.id))
+            .ok_or_else(|| anyhow!("The sum of the game IDs is too big"))?;
+        Ok(sum.into())
+    }
 
-        let mut sum = 0;
-        for line in read_to_string(filepath)?.lines() {
-            sum += process(line)?;
-        }
-        Ok(sum)
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 8.into()))
     }
 }
 
@@ -147,44 +189,66 @@ impl Solver for PartTwo {
         (2, "Sum of Sum of power of sets")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        fn process(line: &str) -> Result<i32> {
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        fn power(game: &Game) -> Option<u32> {
             let (mut min_red, mut min_green, mut min_blue) = (0, 0, 0);
-            let (_game_id_str, games) = line.split_at(line.find(':').ok_or(anyhow!(
-                "Expected a ':' character in the input string '{}'",
-                line
-            ))?);
-            let games = games.trim_start_matches(":").trim();
-            for game in games.split(';') {
-                let cubes = game
-                    .trim_start_matches(";")
-                    .trim()
-                    .split(',')
-                    .map(|s| s.trim_start_matches(",").trim().parse::<Cube>())
-                    .collect::<Result<HashSet<Cube>>>()?;
-                for cube in cubes {
-                    match cube.colour {
-                        CubeColour::Red => {
-                            min_red = std::cmp::max(min_red, cube.count);
-                        }
-                        CubeColour::Green => {
-                            min_green = std::cmp::max(min_green, cube.count);
-                        }
-                        CubeColour::Blue => {
-                            min_blue = std::cmp::max(min_blue, cube.count);
-                        }
+            for cube in game.rounds.iter().flatten() {
+                match cube.colour {
+                    CubeColour::Red => {
+                        min_red = std::cmp::max(min_red, cube.count);
+                    }
+                    CubeColour::Green => {
+                        min_green = std::cmp::max(min_green, cube.count);
+                    }
+                    CubeColour::Blue => {
+                        min_blue = std::cmp::max(min_blue, cube.count);
                     }
                 }
             }
-            Ok((min_red * min_green * min_blue) as i32)
+            min_red.checked_mul(min_green)?.checked_mul(min_blue)
         }
 
-        let mut sum = 0;
-        for line in read_to_string(filepath)?.lines() {
-            let actual = process(line);
-            sum += actual?;
-        }
-        Ok(sum)
+        let games = ctx.parse(|| strict(parse_games(input)))?;
+        let sum = games
+            .iter()
+            .try_fold(0_u32, |sum, game| sum.checked_add(power(game)?))
+            .ok_or_else(|| anyhow!("The sum of the powers is too big"))?;
+        Ok(sum.into())
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 2286.into()))
+    }
+}
+
+impl FromStr for Game {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (game_id_str, games) = line.split_once(':').ok_or(anyhow!(
+            "Expected a ':' character in the input string '{}'",
+            line
+        ))?;
+        let id = game_id_str
+            .split_whitespace()
+            .next_back()
+            .ok_or(anyhow!(
+                "Expected whitespace in the game id string '{}'",
+                game_id_str
+            ))?
+            .parse::<i32>()?;
+        let rounds = games
+            .trim()
+            .split(';')
+            .map(|round| {
+                round
+                    .trim()
+                    .split(',')
+                    .map(|s| s.trim().parse::<Cube>())
+                    .collect::<Result<Vec<Cube>>>()
+            })
+            .collect::<Result<Vec<Vec<Cube>>>>()?;
+        Ok(Game { id, rounds })
     }
 }
 
@@ -212,22 +276,93 @@ impl FromStr for Cube {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::assert_part;
 
+    #[test]
+    fn test_part_one_example() {
+        assert_part!(CubeConundrum, 1, EXAMPLE, 8);
+    }
+
+    #[test]
+    fn test_part_two_example() {
+        assert_part!(CubeConundrum, 2, EXAMPLE, 2286);
+    }
+
+    // inputs `cargo fuzz run day02` found panicking
+    #[test]
+    fn test_fuzz_regressions() {
+        let error = |solver: &dyn Solver, input| solver.solve_str(input).unwrap_err().to_string();
+        assert_eq!(
+            error(&PartOne, "Game 2147483647: 1 red\nGame 1: 1 red\n"),
+            "The sum of the game IDs is too big"
+        );
+        assert_eq!(
+            error(&PartTwo, "Game 1: 100000 red, 100000 green, 100000 blue\n"),
+            "The sum of the powers is too big"
+        );
+    }
+
+    // personal inputs aren't in every clone, see crate::input::real
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_one() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/02/input");
+        let filepath = crate::input::real(2);
         let solver = PartOne;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 2348);
+        assert_eq!(solution, 2348.into());
         Ok(())
     }
 
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_two() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/02/input");
+        let filepath = crate::input::real(2);
         let solver = PartTwo;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 76008);
+        assert_eq!(solution, 76008.into());
         Ok(())
     }
+
+    #[test]
+    fn test_parse_stats() {
+        let input = "Game 1: 3 blue, 4 red; 1 red\nGame x: 2 red\n\nGame 3: 4 purple\n";
+        let stats = CubeConundrum.parse_stats(input).unwrap();
+        assert_eq!(stats.lines, 4);
+        assert_eq!(
+            stats.counts,
+            vec![("games", 1), ("rounds", 2), ("cube counts", 3)]
+        );
+        let lines = stats.malformed.iter().map(|m| m.line).collect::<Vec<_>>();
+        assert_eq!(lines, vec![Some(2), Some(4)]);
+        assert!(CubeConundrum
+            .parse_stats("Game 1: 1 green")
+            .unwrap()
+            .is_valid());
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        use crate::{
+            input::ParseMode,
+            runner::{run_part, PartOptions, Status},
+        };
+        // a hand-made copy of the example, with trailing spaces and a blank line
+        let messy = EXAMPLE.replacen('\n', "  \n\n", 1);
+        let column = EXAMPLE.lines().next().unwrap().len() + 1;
+        let strict = run_part(2, &CubeConundrum, 1, &messy, &PartOptions::default());
+        let Status::Failed(error) = strict.status else {
+            panic!("expected strict parsing to fail, got {:?}", strict.status);
+        };
+        assert!(
+            error.starts_with(&format!("Line 1, column {}: trailing whitespace", column)),
+            "{}",
+            error
+        );
+        let options = PartOptions {
+            parse_mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+        let lenient = run_part(2, &CubeConundrum, 1, &messy, &options);
+        assert_eq!(lenient.answer, Some(8.into()));
+    }
 }
@@ -0,0 +1,323 @@
+//! Checking the data directory before a long run, for `aoc doctor`, so a
+//! missing or broken input shows up up front rather than part way through.
+//! The same checks keep a bad download from being saved, see [`validate`].
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{input, layout::Layout, registry::Registry};
+
+/// What can be wrong with a day's input, in the order they're checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    Missing,
+    Unreadable(String),
+    Empty,
+    /// adventofcode.com answers with an HTML page or a "please log in"
+    /// message instead of the input once the session cookie has expired.
+    ErrorPage,
+    /// Fewer than [`MIN_INPUT_LEN`] bytes, eg. a download cut short.
+    TooSmall(usize),
+    NoTrailingNewline,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Missing => write!(f, "missing"),
+            Problem::Unreadable(e) => write!(f, "can't be read: {}", e),
+            Problem::Empty => write!(f, "empty"),
+            Problem::ErrorPage => write!(
+                f,
+                "looks like an AoC error page, re-download it with a fresh session"
+            ),
+            Problem::TooSmall(len) => write!(
+                f,
+                "is only {} {}, suspiciously small for an input",
+                len,
+                if *len == 1 { "byte" } else { "bytes" }
+            ),
+            Problem::NoTrailingNewline => write!(f, "doesn't end with a newline"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Ready,
+    Problem(Problem),
+    /// There's an input for a day that isn't implemented, worth knowing but
+    /// not a problem.
+    Unimplemented,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Ready => write!(f, "ok"),
+            Verdict::Problem(problem) => write!(f, "input {}", problem),
+            Verdict::Unimplemented => write!(f, "has an input but isn't implemented"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayCheck {
+    pub day: u8,
+    /// The puzzle's title, `None` when the day isn't implemented.
+    pub title: Option<String>,
+    pub input: PathBuf,
+    pub verdict: Verdict,
+}
+
+impl DayCheck {
+    pub fn is_problem(&self) -> bool {
+        matches!(self.verdict, Verdict::Problem(_))
+    }
+}
+
+/// How an HTML page starts, in any case.
+const DOCTYPE: &str = "<!doctype";
+
+/// The fewest bytes, leaving out surrounding whitespace, an input can have.
+/// The smallest inputs are a single number of a few digits, eg. 2017 day
+/// 17's.
+pub const MIN_INPUT_LEN: usize = 3;
+
+/// Whether `text` is the page adventofcode.com sends in place of an input,
+/// eg. when the session cookie has expired. Cheap enough to run on every
+/// input before it's solved: only its start is looked at closely, as inputs
+/// can have a `<` further in.
+pub fn looks_like_error_page(text: &str) -> bool {
+    let start = text.trim_start();
+    let starts_with = |prefix: &str| {
+        start
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    };
+    start.starts_with('<')
+        || starts_with("puzzle inputs differ by user")
+        // eg. after a BOM, or response headers saved along with the page
+        || text.match_indices("<!").any(|(i, _)| {
+            text.get(i..i + DOCTYPE.len())
+                .is_some_and(|tag| tag.eq_ignore_ascii_case(DOCTYPE))
+        })
+}
+
+/// Checks that `text` could be an input from adventofcode.com, rather than
+/// an error page or what's left of a download cut short.
+pub fn validate(text: &str) -> Result<(), Problem> {
+    let len = text.trim().len();
+    if len == 0 {
+        return Err(Problem::Empty);
+    }
+    if looks_like_error_page(text) {
+        return Err(Problem::ErrorPage);
+    }
+    if len < MIN_INPUT_LEN {
+        return Err(Problem::TooSmall(len));
+    }
+    Ok(())
+}
+
+/// Checks that the input at `path` looks like one downloaded from
+/// adventofcode.com, returning the first problem with it.
+pub fn check_input(path: &Path) -> Result<(), Problem> {
+    if !path.is_file() {
+        return Err(Problem::Missing);
+    }
+    let data = fs::read(path)
+        .and_then(|data| input::decompress(path, data))
+        .map_err(|e| Problem::Unreadable(e.to_string()))?;
+    let text = String::from_utf8_lossy(&data);
+    validate(&text)?;
+    if !text.ends_with('\n') {
+        return Err(Problem::NoTrailingNewline);
+    }
+    Ok(())
+}
+
+/// Checks the input of every implemented day under `data_root`, and looks
+/// for inputs of days that aren't implemented, in day order.
+pub fn examine(registry: &Registry, data_root: &Path, layout: &Layout) -> Vec<DayCheck> {
+    let implemented = registry.days().map(|(day, puzzle)| {
+        let day = puzzle.day_number().unwrap_or(day);
+        let input = layout.input(data_root, day);
+        let verdict = match check_input(&input) {
+            Ok(()) => Verdict::Ready,
+            Err(problem) => Verdict::Problem(problem),
+        };
+        DayCheck {
+            day,
+            title: Some(puzzle.title().to_string()),
+            input,
+            verdict,
+        }
+    });
+    let unimplemented = registry
+        .unimplemented()
+        .into_iter()
+        .map(|day| (day, layout.input(data_root, day)))
+        .filter(|(_, input)| input.is_file())
+        .map(|(day, input)| DayCheck {
+            day,
+            title: None,
+            input,
+            verdict: Verdict::Unimplemented,
+        });
+    let mut checks = implemented.chain(unimplemented).collect::<Vec<_>>();
+    checks.sort_by_key(|check| check.day);
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::RunContext,
+        solver::{Answer, MultiSolver, Solver},
+    };
+
+    struct Stub;
+
+    impl MultiSolver for Stub {
+        type PartOne = Stub;
+        type PartTwo = Stub;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Stub"
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Stub
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            Stub
+        }
+    }
+
+    impl Solver for Stub {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Nothing")
+        }
+
+        fn solve(&self, _input: &str, _ctx: &RunContext) -> anyhow::Result<Answer> {
+            Ok(0.into())
+        }
+    }
+
+    #[test]
+    fn test_examine() -> std::io::Result<()> {
+        let data_root =
+            std::env::temp_dir().join(format!("aoc-2023-doctor-{}", std::process::id()));
+        let inputs = [
+            (1, "1abc2\n"),
+            (2, ""),
+            (3, "<!DOCTYPE html>\n<html lang=\"en-us\">\n"),
+            (
+                4,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            (5, "1 2 3"),
+            (9, "0 3 6 9\n"),
+        ];
+        for (day, input) in inputs {
+            let dir = data_root.join(format!("{:02}", day));
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join("input"), input)?;
+        }
+        let registry = (1..=6).fold(Registry::new(), |registry, day| {
+            registry.register(day, Stub)
+        });
+        let checks = examine(&registry, &data_root, &Layout::default());
+        fs::remove_dir_all(&data_root)?;
+
+        let verdicts = checks
+            .iter()
+            .map(|check| (check.day, check.verdict.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            verdicts,
+            vec![
+                (1, Verdict::Ready),
+                (2, Verdict::Problem(Problem::Empty)),
+                (3, Verdict::Problem(Problem::ErrorPage)),
+                (4, Verdict::Problem(Problem::ErrorPage)),
+                (5, Verdict::Problem(Problem::NoTrailingNewline)),
+                (6, Verdict::Problem(Problem::Missing)),
+                (9, Verdict::Unimplemented),
+            ]
+        );
+        assert_eq!(checks.iter().filter(|check| check.is_problem()).count(), 5);
+        assert_eq!(checks[0].title.as_deref(), Some("Stub"));
+        assert_eq!(checks[6].title, None);
+        assert_eq!(checks[5].input, data_root.join("06").join("input"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_gzipped_input() -> std::io::Result<()> {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("aoc-2023-doctor-{}.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(b"1 2 3")?;
+        fs::write(&path, encoder.finish()?)?;
+        // what's checked is the input once decompressed
+        let checked = check_input(&path);
+        fs::write(&path, "1 2 3\n")?;
+        let corrupt = check_input(&path);
+        fs::remove_file(&path)?;
+        assert_eq!(checked, Err(Problem::NoTrailingNewline));
+        assert!(
+            matches!(corrupt, Err(Problem::Unreadable(_))),
+            "{:?}",
+            corrupt
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_inputs_with_angle_brackets() -> std::io::Result<()> {
+        // some puzzles' inputs have comparisons in them, eg. 'a<2006:qkq'
+        let path = std::env::temp_dir().join(format!("aoc-2023-brackets-{}", std::process::id()));
+        fs::write(&path, "px{a<2006:qkq,m>2090:A,rfg}\n")?;
+        let checked = check_input(&path);
+        fs::remove_file(&path)?;
+        assert_eq!(checked, Ok(()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() {
+        let error_page = include_str!("fixtures/error_page.html");
+        assert!(looks_like_error_page(error_page));
+        assert_eq!(validate(error_page), Err(Problem::ErrorPage));
+        for text in [
+            "\u{feff}<!DOCTYPE html>\n<html>\n",
+            "HTTP/2 400\ncontent-type: text/html\n\n<!doctype html>\n",
+            "  <p>Not Found</p>\n",
+            "puzzle inputs differ by user.\n",
+        ] {
+            assert_eq!(validate(text), Err(Problem::ErrorPage), "{:?}", text);
+        }
+
+        // a '<' past the start is part of the puzzle
+        let input = "in{s<1351:px,qqz}\npx{a<2006:qkq,m>2090:A,rfg}\n\n{x=787,m=2655}\n";
+        assert!(!looks_like_error_page(input));
+        assert_eq!(validate(input), Ok(()));
+        assert_eq!(validate("a <!- b\n"), Ok(()));
+
+        assert_eq!(validate(" \n"), Err(Problem::Empty));
+        assert_eq!(validate("12\n"), Err(Problem::TooSmall(2)));
+        assert_eq!(validate("343\n"), Ok(()));
+        assert_eq!(
+            Problem::TooSmall(2).to_string(),
+            "is only 2 bytes, suspiciously small for an input"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Downloading puzzle inputs from adventofcode.com with the session cookie,
+//! for `aoc download` and `--download`. A response that isn't an input, eg.
+//! the "please log in" one sent once the cookie has expired, is reported as
+//! a problem with the session rather than saved.
+
+use anyhow::{anyhow, Context, Result};
+use std::{fs, path::Path};
+
+use crate::{
+    config::SESSION_VAR,
+    doctor::{self, Problem},
+    failure::FailureKind,
+    http::{Client, Response},
+};
+
+/// Whether `body` asks to log in, as adventofcode.com does when the session
+/// cookie has expired.
+pub fn logged_out(body: &str) -> bool {
+    body.to_ascii_lowercase().contains("please log in")
+}
+
+/// The error for a response with `status` that shows the session cookie
+/// wasn't taken.
+pub fn session_rejected(status: u16) -> anyhow::Error {
+    anyhow!(
+        "adventofcode.com didn't take the session cookie (status {}), it may have expired: log in again and copy the new one into {}",
+        status,
+        SESSION_VAR
+    )
+}
+
+/// The error for a command that needs the session cookie when there's none.
+pub fn no_session(doing: &str) -> anyhow::Error {
+    FailureKind::Usage.error(anyhow!(
+        "{} needs the adventofcode.com session cookie, set {} or session in aoc.toml",
+        doing,
+        SESSION_VAR
+    ))
+}
+
+pub fn input_url(year: u16, day: u8) -> String {
+    format!("https://adventofcode.com/{}/day/{}/input", year, day)
+}
+
+/// Day `day`'s input, erroring with what's likely wrong when the response
+/// isn't one.
+pub fn fetch(client: &dyn Client, year: u16, day: u8, session: &str) -> Result<String> {
+    let url = input_url(year, day);
+    let Response { status, body } = client.get(&url, session)?;
+    match status {
+        200 if !logged_out(&body) => match doctor::validate(&body) {
+            Ok(()) => Ok(body),
+            Err(problem @ Problem::TooSmall(_)) => Err(anyhow!(
+                "The input sent for day {} {}, try downloading it again",
+                day,
+                problem
+            )),
+            Err(_) => Err(session_rejected(status)),
+        },
+        200 | 400 | 500 => Err(session_rejected(status)),
+        404 => Err(anyhow!(
+            "There's no input for day {} of {} yet, it unlocks at midnight EST on December {}",
+            day,
+            year,
+            day
+        )),
+        status => Err(anyhow!("adventofcode.com answered {} for {}", status, url)),
+    }
+}
+
+/// Downloads day `day`'s input to `path`, creating its directory as needed.
+/// An input already at `path` is only replaced when `force` is set.
+pub fn download(
+    client: &dyn Client,
+    year: u16,
+    day: u8,
+    session: Option<&str>,
+    path: &Path,
+    force: bool,
+) -> Result<()> {
+    let Some(session) = session else {
+        return Err(no_session("Downloading an input"));
+    };
+    if path.exists() && !force {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "{} already exists, give --force to download it again",
+            path.display()
+        )));
+    }
+    let input = fetch(client, year, day, session)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+    }
+    fs::write(path, input).with_context(|| format!("Can't write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct Mock {
+        response: Response,
+        requests: RefCell<Vec<(String, String)>>,
+    }
+
+    impl Mock {
+        fn new(status: u16, body: &str) -> Self {
+            Self {
+                response: Response {
+                    status,
+                    body: body.to_string(),
+                },
+                requests: RefCell::default(),
+            }
+        }
+    }
+
+    impl Client for Mock {
+        fn get(&self, url: &str, session: &str) -> Result<Response> {
+            self.requests
+                .borrow_mut()
+                .push((url.to_string(), session.to_string()));
+            Ok(self.response.clone())
+        }
+
+        fn post(&self, _url: &str, _session: &str, _form: &[(&str, &str)]) -> Result<Response> {
+            unreachable!("downloading only GETs")
+        }
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("aoc-2023-download-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_download() -> Result<()> {
+        let dir = temp_dir("ok");
+        let path = dir.join("07/input");
+        let client = Mock::new(200, "32T3K 765\n");
+        download(&client, 2023, 7, Some("abc"), &path, false)?;
+        assert_eq!(fs::read_to_string(&path)?, "32T3K 765\n");
+        assert_eq!(
+            client.requests.take(),
+            [(
+                "https://adventofcode.com/2023/day/7/input".to_string(),
+                "abc".to_string()
+            )]
+        );
+
+        // an input already there is kept, unless forced
+        let error = download(&client, 2023, 7, Some("abc"), &path, false).unwrap_err();
+        assert_eq!(crate::failure::kind_of(&error), FailureKind::Usage);
+        assert!(error
+            .to_string()
+            .ends_with("already exists, give --force to download it again"));
+        assert!(client.requests.take().is_empty());
+        fs::write(&path, "old\n")?;
+        download(&client, 2023, 7, Some("abc"), &path, true)?;
+        assert_eq!(fs::read_to_string(&path)?, "32T3K 765\n");
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_problems() {
+        let dir = temp_dir("session");
+        let path = dir.join("07/input");
+        for client in [
+            Mock::new(
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            Mock::new(500, "Internal Server Error\n"),
+            Mock::new(200, "<!DOCTYPE html>\n<html lang=\"en-us\">\n"),
+            Mock::new(200, "\n"),
+        ] {
+            let error = download(&client, 2023, 7, Some("stale"), &path, false).unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("adventofcode.com didn't take the session cookie"),
+                "{}",
+                error
+            );
+            // nothing's saved
+            assert!(!dir.exists());
+        }
+    }
+
+    #[test]
+    fn test_other_failures() {
+        let path = temp_dir("other").join("input");
+        let error = |status, body| {
+            download(
+                &Mock::new(status, body),
+                2023,
+                25,
+                Some("abc"),
+                &path,
+                false,
+            )
+            .unwrap_err()
+            .to_string()
+        };
+        assert_eq!(
+            error(404, "Not Found\n"),
+            "There's no input for day 25 of 2023 yet, it unlocks at midnight EST on December 25"
+        );
+        assert_eq!(
+            error(503, ""),
+            "adventofcode.com answered 503 for https://adventofcode.com/2023/day/25/input"
+        );
+        assert_eq!(
+            error(200, "7\n"),
+            "The input sent for day 25 is only 1 byte, suspiciously small for an input, try downloading it again"
+        );
+
+        assert_eq!(
+            input_url(2022, 1),
+            "https://adventofcode.com/2022/day/1/input"
+        );
+
+        let client = Mock::new(200, "1\n");
+        let error = download(&client, 2023, 1, None, &path, false).unwrap_err();
+        assert_eq!(crate::failure::kind_of(&error), FailureKind::Usage);
+        assert!(error.to_string().contains("set AOC_SESSION"), "{}", error);
+        assert!(client.requests.take().is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+//! Telling the ways a run can fail apart, so scripts can tell a wrong answer
+//! from a missing input by `aoc`'s exit code alone.
+
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+/// Describes the exit codes, for the end of `--help`.
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Any other error, including several parts failing for different reasons
+  2  Bad usage, eg. an unknown option or options that can't be used together
+  3  An input is missing, or doesn't look like one
+  4  An input couldn't be parsed
+  5  An answer didn't match the expected one, or an example didn't check out
+  6  A part timed out
+  7  The day isn't implemented
+  8  A part got slower than its baseline allows, see --compare-baseline
+  9  A day or the run took longer than its budget, with --enforce-budget
+  10  An answer was submitted too soon after the last, see submit
+  130  Several days were interrupted with Ctrl-C before they all finished";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    Other,
+    Usage,
+    MissingInput,
+    Parse,
+    Mismatch,
+    TimedOut,
+    NotImplemented,
+    Regression,
+    OverBudget,
+    RateLimited,
+    Interrupted,
+}
+
+impl FailureKind {
+    /// The exit code for this kind of failure, see [`EXIT_CODES_HELP`].
+    pub fn exit_code(self) -> u8 {
+        match self {
+            FailureKind::Other => 1,
+            FailureKind::Usage => 2,
+            FailureKind::MissingInput => 3,
+            FailureKind::Parse => 4,
+            FailureKind::Mismatch => 5,
+            FailureKind::TimedOut => 6,
+            FailureKind::NotImplemented => 7,
+            FailureKind::Regression => 8,
+            FailureKind::OverBudget => 9,
+            FailureKind::RateLimited => 10,
+            // as for a process killed by SIGINT
+            FailureKind::Interrupted => 130,
+        }
+    }
+
+    /// Marks `error` as this kind of failure, see [`kind_of`].
+    pub fn error(self, error: anyhow::Error) -> anyhow::Error {
+        anyhow::Error::new(Failure { kind: self, error })
+    }
+
+    /// The kind shared by all of `kinds`, or [`FailureKind::Other`] when they
+    /// differ or there aren't any.
+    pub fn common(kinds: impl IntoIterator<Item = FailureKind>) -> FailureKind {
+        let mut kinds = kinds.into_iter();
+        let Some(first) = kinds.next() else {
+            return FailureKind::Other;
+        };
+        if kinds.all(|kind| kind == first) {
+            first
+        } else {
+            FailureKind::Other
+        }
+    }
+}
+
+/// An error marked with the kind of failure it is, displayed just as the
+/// error itself.
+#[derive(Debug)]
+pub struct Failure {
+    pub kind: FailureKind,
+    error: anyhow::Error,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl Error for Failure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// The kind of failure `error` was marked as anywhere in its chain,
+/// [`FailureKind::Other`] if it wasn't.
+pub fn kind_of(error: &anyhow::Error) -> FailureKind {
+    error
+        .chain()
+        .find_map(|error| error.downcast_ref::<Failure>())
+        .map_or(FailureKind::Other, |failure| failure.kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    fn test_kind_of() {
+        let error = FailureKind::Parse.error(anyhow!("Line 1, column 6: trailing whitespace"));
+        assert_eq!(error.to_string(), "Line 1, column 6: trailing whitespace");
+        assert_eq!(kind_of(&error), FailureKind::Parse);
+        let error = Err::<(), _>(error).context("Reading day 1").unwrap_err();
+        assert_eq!(kind_of(&error), FailureKind::Parse);
+        assert_eq!(kind_of(&anyhow!("unmarked")), FailureKind::Other);
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let kinds = [
+            FailureKind::Other,
+            FailureKind::Usage,
+            FailureKind::MissingInput,
+            FailureKind::Parse,
+            FailureKind::Mismatch,
+            FailureKind::TimedOut,
+            FailureKind::NotImplemented,
+            FailureKind::Regression,
+            FailureKind::OverBudget,
+            FailureKind::RateLimited,
+            FailureKind::Interrupted,
+        ];
+        let codes = kinds
+            .iter()
+            .map(|kind| kind.exit_code())
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(codes.len(), kinds.len());
+        assert!(!codes.contains(&0));
+        for kind in kinds {
+            let line = format!("  {}  ", kind.exit_code());
+            assert!(EXIT_CODES_HELP.contains(&line), "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn test_common() {
+        use FailureKind::*;
+        assert_eq!(FailureKind::common([Parse, Parse]), Parse);
+        assert_eq!(FailureKind::common([Parse, MissingInput]), Other);
+        assert_eq!(FailureKind::common([]), Other);
+    }
+}
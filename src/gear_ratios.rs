@@ -1,88 +1,147 @@
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    fs::read_to_string,
-    path::PathBuf,
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    context::RunContext,
+    metadata::{Difficulty, Metadata, RuntimeClass},
+    solver::{Answer, MultiSolver, Solver},
+    statement::puzzle_struct,
+    util::{grid::Grid, point::Point2},
 };
 
-use anyhow::{anyhow, Result};
-
-use crate::solver::{MultiSolver, Solver};
-
-///     --- Day 3: Gear Ratios ---
-///
-/// You and the Elf eventually reach a gondola lift station; he says the gondola lift will take you up to the water source, but this is as far as he can bring you. You go inside.
-///
-/// It doesn't take long to find the gondolas, but there seems to be a problem: they're not moving.
-///
-/// "Aaah!"
-///
-/// You turn around to see a slightly-greasy Elf with a wrench and a look of surprise. "Sorry, I wasn't expecting anyone! The gondola lift isn't working right now; it'll still be a while before I can fix it." You offer to help.
-///
-/// The engineer explains that an engine part seems to be missing from the engine, but nobody can figure out which one. If you can add up all the part numbers in the engine schematic, it should be easy to work out which part is missing.
-///
-/// The engine schematic (your puzzle input) consists of a visual representation of the engine. There are lots of numbers and symbols you don't really understand, but apparently any number adjacent to a symbol, even diagonally, is a "part number" and should be included in your sum. (Periods (.) do not count as a symbol.)
-///
-/// Here is an example engine schematic:
-/// ```
-/// 467..114..
-/// ...*......
-/// ..35..633.
-/// ......#...
-/// 617*......
-/// .....+.58.
-/// ..592.....
-/// ......755.
-/// ...$.*....
-/// .664.598..
-/// ```
-/// In this schematic, two numbers are not part numbers because they are not adjacent to a symbol: 114 (top right) and 58 (middle right). Every other number is adjacent to a symbol and so is a part number; their sum is 4361.
-///
-/// Of course, the actual engine schematic is much larger. What is the sum of all of the part numbers in the engine schematic?
-///
-///      --- Part Two ---
-///
-/// The engineer finds the missing part and installs it in the engine! As the engine springs to life, you jump in the closest gondola, finally ready to ascend to the water source.
-///
-/// You don't seem to be going very fast, though. Maybe something is still wrong? Fortunately, the gondola has a phone labeled "help", so you pick it up and the engineer answers.
-///
-/// Before you can explain the situation, she suggests that you look out the window. There stands the engineer, holding a phone in one hand and waving with the other. You're going so slowly that you haven't even left the station. You exit the gondola.
-///
-/// The missing part wasn't the only issue - one of the gears in the engine is wrong. A gear is any * symbol that is adjacent to exactly two part numbers. Its gear ratio is the result of multiplying those two numbers together.
-///
-/// This time, you need to find the gear ratio of every gear and add them all up so that the engineer can figure out which gear needs to be replaced.
-///
-/// Consider the same engine schematic again:
-/// ```
-/// 467..114..
-/// ...*......
-/// ..35..633.
-/// ......#...
-/// 617*......
-/// .....+.58.
-/// ..592.....
-/// ......755.
-/// ...$.*....
-/// .664.598..
-/// ```
-/// In this schematic, there are two gears. The first is in the top left; it has part numbers 467 and 35, so its gear ratio is 16345. The second gear is in the lower right; its gear ratio is 451490. (The * adjacent to 617 is not a gear because it is only adjacent to one part number.) Adding up all of the gear ratios produces 467835.
-///
-/// What is the sum of all of the gear ratios in your engine schematic?
-pub struct GearRatios;
+puzzle_struct! {
+    ///     --- Day 3: Gear Ratios ---
+    ///
+    /// You and the Elf eventually reach a gondola lift station; he says the gondola lift will take you up to the water source, but this is as far as he can bring you. You go inside.
+    ///
+    /// It doesn't take long to find the gondolas, but there seems to be a problem: they're not moving.
+    ///
+    /// "Aaah!"
+    ///
+    /// You turn around to see a slightly-greasy Elf with a wrench and a look of surprise. "Sorry, I wasn't expecting anyone! The gondola lift isn't working right now; it'll still be a while before I can fix it." You offer to help.
+    ///
+    /// The engineer explains that an engine part seems to be missing from the engine, but nobody can figure out which one. If you can add up all the part numbers in the engine schematic, it should be easy to work out which part is missing.
+    ///
+    /// The engine schematic (your puzzle input) consists of a visual representation of the engine. There are lots of numbers and symbols you don't really understand, but apparently any number adjacent to a symbol, even diagonally, is a "part number" and should be included in your sum. (Periods (.) do not count as a symbol.)
+    ///
+    /// Here is an example engine schematic:
+    /// ```
+    /// 467..114..
+    /// ...*......
+    /// ..35..633.
+    /// ......#...
+    /// 617*......
+    /// .....+.58.
+    /// ..592.....
+    /// ......755.
+    /// ...$.*....
+    /// .664.598..
+    /// ```
+    /// In this schematic, two numbers are not part numbers because they are not adjacent to a symbol: 114 (top right) and 58 (middle right). Every other number is adjacent to a symbol and so is a part number; their sum is 4361.
+    ///
+    /// Of course, the actual engine schematic is much larger. What is the sum of all of the part numbers in the engine schematic?
+    ///
+    ///      --- Part Two ---
+    ///
+    /// The engineer finds the missing part and installs it in the engine! As the engine springs to life, you jump in the closest gondola, finally ready to ascend to the water source.
+    ///
+    /// You don't seem to be going very fast, though. Maybe something is still wrong? Fortunately, the gondola has a phone labeled "help", so you pick it up and the engineer answers.
+    ///
+    /// Before you can explain the situation, she suggests that you look out the window. There stands the engineer, holding a phone in one hand and waving with the other. You're going so slowly that you haven't even left the station. You exit the gondola.
+    ///
+    /// The missing part wasn't the only issue - one of the gears in the engine is wrong. A gear is any * symbol that is adjacent to exactly two part numbers. Its gear ratio is the result of multiplying those two numbers together.
+    ///
+    /// This time, you need to find the gear ratio of every gear and add them all up so that the engineer can figure out which gear needs to be replaced.
+    ///
+    /// Consider the same engine schematic again:
+    /// ```
+    /// 467..114..
+    /// ...*......
+    /// ..35..633.
+    /// ......#...
+    /// 617*......
+    /// .....+.58.
+    /// ..592.....
+    /// ......755.
+    /// ...$.*....
+    /// .664.598..
+    /// ```
+    /// In this schematic, there are two gears. The first is in the top left; it has part numbers 467 and 35, so its gear ratio is 16345. The second gear is in the lower right; its gear ratio is 451490. (The * adjacent to 617 is not a gear because it is only adjacent to one part number.) Adding up all of the gear ratios produces 467835.
+    ///
+    /// What is the sum of all of the gear ratios in your engine schematic?
+    pub struct GearRatios;
+}
 pub struct PartOne;
 pub struct PartTwo;
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum SchematicComponent {
-    PartNumber(i32),
-    Symbol(char),
+const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+/// A number in the schematic, covering `start..end` of its row.
+struct SchematicNumber {
+    value: i32,
+    row: usize,
+    start: usize,
+    end: usize,
+}
+
+fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
+}
+
+fn find_numbers(schematic: &Grid<char>) -> Result<Vec<SchematicNumber>> {
+    let mut numbers = vec![];
+    for row in 0..schematic.rows() {
+        let cells = schematic.row(row).unwrap_or_default();
+        let mut col = 0;
+        while col < cells.len() {
+            if !cells[col].is_ascii_digit() {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            while col < cells.len() && cells[col].is_ascii_digit() {
+                col += 1;
+            }
+            let value = cells[start..col]
+                .iter()
+                .collect::<String>()
+                .parse::<i32>()?;
+            numbers.push(SchematicNumber {
+                value,
+                row,
+                start,
+                end: col,
+            });
+        }
+    }
+    Ok(numbers)
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct PositionalSchematicComponent {
-    pub component: SchematicComponent,
-    pub line: usize,
-    pub position: usize,
-    pub length: usize,
+/// Positions of the cells around a number, including diagonals, that match
+/// `predicate`.
+fn adjacent_cells(
+    schematic: &Grid<char>,
+    number: &SchematicNumber,
+    predicate: impl Fn(char) -> bool,
+) -> BTreeSet<Point2<i64>> {
+    (number.start..number.end)
+        .map(|col| Point2::from_row_col(number.row, col))
+        .flat_map(Point2::neighbours8)
+        .filter(|cell| schematic.get_point(*cell).is_some_and(|c| predicate(*c)))
+        .collect()
 }
 
 impl MultiSolver for GearRatios {
@@ -93,6 +152,25 @@ impl MultiSolver for GearRatios {
         "Day 3: Gear Ratios"
     }
 
+    fn input_hint(&self) -> Option<&str> {
+        Some("a grid of digits, symbols and '.' like '467..114..'")
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        Some(Self::STATEMENT)
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(3)
+    }
+
+    /// `#` is one of the symbols, so ` #` can't start a comment. Rows starting
+    /// with `#` would still be taken for comments, annotated examples for this
+    /// day should use another `--comment-prefix`.
+    fn inline_comments(&self) -> bool {
+        false
+    }
+
     fn get_part_one(&self) -> Self::PartOne {
         PartOne
     }
@@ -100,360 +178,224 @@ impl MultiSolver for GearRatios {
     fn get_part_two(&self) -> Self::PartTwo {
         PartTwo
     }
-}
 
-impl Solver for PartOne {
-    fn part_description(&self) -> (u32, &str) {
-        (1, "Sum of part numbers")
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            tags: &["grid", "parsing"],
+            difficulty: Some(Difficulty::Medium),
+            runtime: RuntimeClass::Fast,
+        }
     }
+}
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let mut schematic_components: BTreeSet<PositionalSchematicComponent> = BTreeSet::new();
-
-        let mut prev_line: Option<Vec<PositionalSchematicComponent>> = None;
-        for (line_num, line) in read_to_string(filepath)?.lines().enumerate() {
-            let mut current_line: Vec<PositionalSchematicComponent> = vec![];
-
-            let mut pos: usize = 0;
-            for possible_component in extract_schematic_line_parts(line) {
-                let len = possible_component.len();
-                if !possible_component.contains('.') {
-                    let comp = possible_component
-                        .parse::<i32>()
-                        .map_or(SchematicComponent::Symbol('*'), |n| {
-                            SchematicComponent::PartNumber(n)
-                        });
-                    current_line.push(PositionalSchematicComponent {
-                        component: comp,
-                        line: line_num,
-                        position: pos,
-                        length: len,
-                    });
-                }
-                pos += len;
-            }
+/// What's in an engine schematic, the part numbers and gears behind both
+/// parts' answers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Day3Analysis {
+    /// The numbers next to a symbol, in reading order.
+    pub part_numbers: Vec<PartNumber>,
+    /// The `*`s next to exactly two part numbers, in reading order.
+    pub gears: Vec<Gear>,
+}
 
-            for (line_pos, component) in current_line.iter().enumerate() {
-                match component.component {
-                    SchematicComponent::PartNumber(_) => {
-                        // look left, look right, can this number validate itself ?
-                        let mut valid = false;
-                        if line_pos > 0 {
-                            let prev_component = &current_line[line_pos - 1];
-                            match prev_component.component {
-                                SchematicComponent::Symbol(_) => {
-                                    if prev_component.position == component.position - 1 {
-                                        valid = true;
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-
-                        if line_pos < current_line.len() - 1 {
-                            let next_component = &current_line[line_pos + 1];
-                            match next_component.component {
-                                SchematicComponent::Symbol(_) => {
-                                    if next_component.position
-                                        == component.position + component.length
-                                    {
-                                        valid = true;
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-
-                        // look up to the previous line, can this number validate itself ?
-                        if let Some(prev_line) = &prev_line {
-                            for prev_component in prev_line {
-                                if prev_component.position
-                                    >= component.position.checked_sub(1).unwrap_or(0)
-                                    && prev_component.position
-                                        <= component.position + component.length
-                                {
-                                    // within range
-                                    match prev_component.component {
-                                        SchematicComponent::Symbol(_) => {
-                                            valid = true;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                        }
-
-                        if valid {
-                            schematic_components.insert(component.clone());
-                        }
-                    }
-                    SchematicComponent::Symbol(_) => {
-                        // look up to the previous line, can this symbol validate any numbers ?
-                        if let Some(prev_line) = &mut prev_line {
-                            for prev_component in prev_line.iter() {
-                                if !schematic_components.contains(prev_component)
-                                    && component.position
-                                        >= prev_component.position.checked_sub(1).unwrap_or(0)
-                                    && component.position
-                                        <= prev_component.position + prev_component.length
-                                {
-                                    // within range
-                                    match prev_component.component {
-                                        SchematicComponent::PartNumber(_) => {
-                                            schematic_components.insert(prev_component.clone());
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+/// A number next to a symbol, at the row and column of its first digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PartNumber {
+    pub value: i32,
+    pub row: usize,
+    pub col: usize,
+}
 
-            prev_line = Some(current_line);
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Gear {
+    pub row: usize,
+    pub col: usize,
+    /// The two numbers next to it, in reading order.
+    pub part_numbers: [i32; 2],
+    /// The two numbers multiplied together.
+    pub ratio: i64,
+}
 
-        let nums: Vec<i32> = schematic_components
-            .iter()
-            .fold(vec![], |mut acc, component| {
-                match component.component {
-                    SchematicComponent::PartNumber(n) => {
-                        acc.push(n);
-                    }
-                    _ => {}
-                }
-                acc
+/// Finds the part numbers and gears in the schematic `input`.
+pub fn analyze(input: &str) -> Result<Day3Analysis> {
+    let schematic: Grid<char> = input.parse()?;
+    let mut part_numbers = vec![];
+    let mut stars: BTreeMap<(usize, usize), Vec<i32>> = BTreeMap::new();
+    for number in find_numbers(&schematic)? {
+        if !adjacent_cells(&schematic, &number, is_symbol).is_empty() {
+            part_numbers.push(PartNumber {
+                value: number.value,
+                row: number.row,
+                col: number.start,
             });
-        Ok(nums.iter().sum::<i32>())
+        }
+        for star in adjacent_cells(&schematic, &number, |c| c == '*') {
+            if let Some(cell) = star.to_row_col() {
+                stars.entry(cell).or_default().push(number.value);
+            }
+        }
     }
+    let gears = stars
+        .into_iter()
+        .filter_map(|((row, col), numbers)| {
+            let part_numbers: [i32; 2] = numbers.try_into().ok()?;
+            Some(Gear {
+                row,
+                col,
+                part_numbers,
+                ratio: part_numbers.iter().map(|n| i64::from(*n)).product(),
+            })
+        })
+        .collect();
+    Ok(Day3Analysis {
+        part_numbers,
+        gears,
+    })
 }
 
-impl Solver for PartTwo {
+impl Solver for PartOne {
     fn part_description(&self) -> (u32, &str) {
-        (2, "Sum of gear ratios")
+        (1, "Sum of part numbers")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let mut gears: BTreeMap<(usize, usize), Vec<i32>> = BTreeMap::new();
-        let mut prev_line: Option<Vec<PositionalSchematicComponent>> = None;
-        for (line_num, line) in read_to_string(filepath)?.lines().enumerate() {
-            let mut current_line: Vec<PositionalSchematicComponent> = vec![];
-
-            let mut pos: usize = 0;
-            for possible_component in extract_schematic_line_parts(line) {
-                let len = possible_component.len();
-                if !possible_component.contains('.') {
-                    let comp = possible_component.parse::<i32>().map_or(
-                        SchematicComponent::Symbol(
-                            possible_component
-                                .chars()
-                                .nth(0)
-                                .ok_or(anyhow!("Single char symbol parsed as 0 size string!"))?,
-                        ),
-                        |n| SchematicComponent::PartNumber(n),
-                    );
-                    current_line.push(PositionalSchematicComponent {
-                        component: comp,
-                        line: line_num,
-                        position: pos,
-                        length: len,
-                    });
-                }
-                pos += len;
-            }
-
-            for (component_index, component) in current_line.iter().enumerate() {
-                match component.component {
-                    SchematicComponent::Symbol('*') => {
-                        // look left, look right, are the ratios present ?
-                        let left: Option<i32> = if component_index > 0 {
-                            let prev_component = &current_line[component_index - 1];
-                            match prev_component.component {
-                                SchematicComponent::PartNumber(n) => {
-                                    if prev_component.position + prev_component.length
-                                        == component.position
-                                    {
-                                        Some(n)
-                                    } else {
-                                        None
-                                    }
-                                }
-                                _ => None,
-                            }
-                        } else {
-                            None
-                        };
-
-                        let right: Option<i32> = if component_index < current_line.len() - 1 {
-                            let next_component = &current_line[component_index + 1];
-                            match next_component.component {
-                                SchematicComponent::PartNumber(n) => {
-                                    if next_component.position <= component.position + 1 {
-                                        Some(n)
-                                    } else {
-                                        None
-                                    }
-                                }
-                                _ => None,
-                            }
-                        } else {
-                            None
-                        };
-
-                        // look up to the previous line, are there ratios ?
-                        let ratios: Vec<i32> = {
-                            let mut ratios: Vec<i32> = vec![];
-                            if left.is_some() {
-                                ratios.push(left.unwrap());
-                            }
-                            if right.is_some() {
-                                ratios.push(right.unwrap());
-                            }
-                            if let Some(prev_line) = &prev_line {
-                                for prev_component in prev_line {
-                                    if prev_component.position + prev_component.length
-                                        >= component.position
-                                        && prev_component.position <= component.position + 1
-                                    {
-                                        // within range
-                                        match prev_component.component {
-                                            SchematicComponent::PartNumber(n) => {
-                                                ratios.push(n);
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                            ratios
-                        };
-                        gears.insert((line_num, component.position), ratios);
-                    }
-                    SchematicComponent::PartNumber(n) => {
-                        // look above for a gear that this is a ratio for
-                        if let Some(prev_line) = &prev_line {
-                            for prev_component in prev_line {
-                                if prev_component.position
-                                    >= component.position.checked_sub(1).unwrap_or(0)
-                                    && prev_component.position
-                                        <= component.position + component.length
-                                {
-                                    // within range
-                                    match prev_component.component {
-                                        SchematicComponent::Symbol('*') => {
-                                            gears
-                                                .get_mut(&(
-                                                    prev_component.line,
-                                                    prev_component.position,
-                                                ))
-                                                .map(|ratios| {
-                                                    ratios.push(n);
-                                                });
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            prev_line = Some(current_line);
-        }
-
-        let ratios = gears.iter().fold(vec![], |mut acc, (_, ratios)| {
-            if ratios.len() == 2 {
-                acc.push(ratios.iter().product::<i32>());
-            }
-            acc
-        });
+    fn solve(&self, input: &str, _ctx: &RunContext) -> Result<Answer> {
+        let sum = analyze(input)?
+            .part_numbers
+            .iter()
+            .map(|number| i64::from(number.value))
+            .sum::<i64>();
+        Ok(sum.into())
+    }
 
-        Ok(ratios.iter().sum::<i32>())
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 4361.into()))
     }
 }
 
-fn extract_schematic_line_parts(s: &str) -> Vec<&str> {
-    let mut parts: Vec<&str> = vec![];
-
-    #[derive(PartialEq, Eq)]
-    enum CharType {
-        Numeric(usize),
-        Dot(usize),
-        Symbol,
+impl Solver for PartTwo {
+    fn part_description(&self) -> (u32, &str) {
+        (2, "Sum of gear ratios")
     }
 
-    let mut last_char_type: Option<CharType> = None;
-    for (i, c) in s.chars().enumerate() {
-        let current_char_type = if c.is_numeric() {
-            CharType::Numeric(i)
-        } else if c == '.' {
-            CharType::Dot(i)
-        } else {
-            CharType::Symbol
-        };
-
-        last_char_type
-            .as_ref()
-            .map(|last_char_type| match last_char_type {
-                CharType::Numeric(start) => match current_char_type {
-                    CharType::Numeric(_) => {}
-                    _ => parts.push(&s[*start..i]),
-                },
-                CharType::Dot(start) => match current_char_type {
-                    CharType::Dot(_) => {}
-                    _ => parts.push(&s[*start..i]),
-                },
-                CharType::Symbol => {}
-            });
-
-        if current_char_type == CharType::Symbol {
-            parts.push(&s[i..=i]);
-        }
-
-        match current_char_type {
-            CharType::Numeric(_) => match last_char_type {
-                Some(CharType::Numeric(_)) => {}
-                _ => last_char_type = Some(current_char_type),
-            },
-            CharType::Dot(_) => match last_char_type {
-                Some(CharType::Dot(_)) => {}
-                _ => last_char_type = Some(current_char_type),
-            },
-            _ => last_char_type = Some(current_char_type),
-        }
+    fn solve(&self, input: &str, _ctx: &RunContext) -> Result<Answer> {
+        let sum = analyze(input)?
+            .gears
+            .iter()
+            .map(|gear| gear.ratio)
+            .sum::<i64>();
+        Ok(sum.into())
     }
 
-    if let Some(last_char_type) = last_char_type {
-        match last_char_type {
-            CharType::Numeric(start) => parts.push(&s[start..]),
-            CharType::Dot(start) => parts.push(&s[start..]),
-            _ => {}
-        }
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 467835.into()))
     }
-    parts
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::assert_part;
+
+    #[test]
+    fn test_part_one_example() {
+        assert_part!(GearRatios, 1, EXAMPLE, 4361);
+    }
 
+    #[test]
+    fn test_part_two_example() {
+        assert_part!(GearRatios, 2, EXAMPLE, 467835);
+    }
+
+    #[test]
+    fn test_analyze_example() -> Result<()> {
+        let analysis = analyze(EXAMPLE)?;
+        let part_numbers = analysis
+            .part_numbers
+            .iter()
+            .map(|number| (number.value, number.row, number.col))
+            .collect::<Vec<_>>();
+        // 114 and 58 aren't next to any symbol
+        assert_eq!(
+            part_numbers,
+            [
+                (467, 0, 0),
+                (35, 2, 2),
+                (633, 2, 6),
+                (617, 4, 0),
+                (592, 6, 2),
+                (755, 7, 6),
+                (664, 9, 1),
+                (598, 9, 5)
+            ]
+        );
+        // the * next to 617 alone isn't a gear
+        assert_eq!(
+            analysis.gears,
+            [
+                Gear {
+                    row: 1,
+                    col: 3,
+                    part_numbers: [467, 35],
+                    ratio: 16345
+                },
+                Gear {
+                    row: 8,
+                    col: 5,
+                    part_numbers: [755, 598],
+                    ratio: 451490
+                }
+            ]
+        );
+        let json = serde_json::to_value(&analysis)?;
+        assert_eq!(json["gears"][0]["ratio"], 16345);
+        assert_eq!(json["part_numbers"][0]["value"], 467);
+        Ok(())
+    }
+
+    // inputs `cargo fuzz run day03` found panicking
+    #[test]
+    fn test_fuzz_regressions() {
+        let input = "999999999*999999999\n";
+        assert_part!(GearRatios, 1, input, 1999999998_i64);
+        assert_part!(GearRatios, 2, input, 999999998000000001_i64);
+    }
+
+    // personal inputs aren't in every clone, see crate::input::real
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_one() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/03/input");
+        let filepath = crate::input::real(3);
         let solver = PartOne;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 539637);
+        assert_eq!(solution, 539637.into());
         Ok(())
     }
 
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_two() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/03/input");
+        let filepath = crate::input::real(3);
         let solver = PartTwo;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 82818007);
+        assert_eq!(solution, 82818007.into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_comments() -> anyhow::Result<()> {
+        use crate::{input::ParseMode, runner::prepare_input};
+
+        let prepare =
+            |input, prefix| prepare_input(input, &GearRatios, ParseMode::Strict, Some(prefix));
+        let annotated = format!("# the example from the statement\n{}", EXAMPLE);
+        let prepared = prepare(&annotated, "#")?;
+        assert_eq!(prepared, EXAMPLE);
+        assert_part!(GearRatios, 1, &prepared, 4361);
+        // the symbols are left alone, only whole lines are comments
+        assert_eq!(prepare("..#.. #..\n", "#")?, "..#.. #..\n");
+        // which rows starting with a symbol would be taken for
+        assert_eq!(prepare("#..1\n.2..\n", "#")?, ".2..\n");
+        assert_eq!(prepare("#..1\n// note\n", "//")?, "#..1\n");
         Ok(())
     }
 }
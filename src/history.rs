@@ -0,0 +1,442 @@
+//! A history of runs in SQLite, written with `--record-history` and read by
+//! `aoc history`, for seeing how a part's time has gone over many runs. It
+//! needs the `history` feature, as it builds SQLite in; without it
+//! [`History::open`] always errors.
+//!
+//! Answers are only kept as hashes, as in the timings log, see
+//! [`crate::timings`].
+
+use anyhow::Result;
+use chrono::DateTime;
+use std::time::Duration;
+
+use crate::{
+    report,
+    runner::{RunResult, Status},
+    solver::format_duration,
+};
+
+/// Where the history is kept, next to the baselines.
+pub const HISTORY_FILE: &str = ".aoc/history.sqlite";
+
+/// How many runs `aoc history` looks back over by default.
+pub const DEFAULT_RUNS: usize = 20;
+
+/// Brings a database from each version of the schema to the next, the
+/// database's `user_version` being how many have been applied.
+#[cfg_attr(not(feature = "history"), allow(dead_code))]
+const MIGRATIONS: &[&str] = &[
+    "\
+CREATE TABLE runs (
+    run_id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    git_sha TEXT
+);
+CREATE TABLE results (
+    run_id INTEGER NOT NULL REFERENCES runs (run_id),
+    day INTEGER NOT NULL,
+    part INTEGER NOT NULL,
+    duration_ms REAL,
+    answer_hash TEXT,
+    status TEXT NOT NULL
+);
+CREATE INDEX results_by_day ON results (day, part, run_id);
+",
+    "\
+-- the event each run was of, runs from before it was kept are all 2023's
+ALTER TABLE runs ADD COLUMN year INTEGER NOT NULL DEFAULT 2023;
+",
+];
+
+/// One part's result in one run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub run_id: i64,
+    /// When the run was, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub git_sha: Option<String>,
+    pub part: u32,
+    /// `None` when the part wasn't solved.
+    pub duration: Option<Duration>,
+    /// See [`status_name`].
+    pub status: String,
+}
+
+/// How a status is kept in the history.
+pub fn status_name(status: &Status) -> &'static str {
+    match status {
+        Status::Solved => "solved",
+        Status::Failed(_) => "failed",
+        Status::TimedOut(_) => "timed out",
+        Status::Skipped(_) => "skipped",
+    }
+}
+
+/// The open history database.
+#[cfg(feature = "history")]
+pub struct History {
+    connection: rusqlite::Connection,
+}
+
+#[cfg(feature = "history")]
+impl History {
+    /// Opens the history at `path`, creating it if need be, and brings its
+    /// schema up to date.
+    pub fn open(path: &std::path::Path) -> Result<Self> {
+        use anyhow::Context;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Can't create {}", dir.display()))?;
+        }
+        let connection = rusqlite::Connection::open(path)
+            .with_context(|| format!("Can't open the history in {}", path.display()))?;
+        Self::migrated(connection)
+            .with_context(|| format!("Can't update the history in {}", path.display()))
+    }
+
+    /// A history kept in memory only, for tests.
+    pub fn in_memory() -> Result<Self> {
+        Self::migrated(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn migrated(mut connection: rusqlite::Connection) -> Result<Self> {
+        let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > MIGRATIONS.len() {
+            return Err(anyhow::anyhow!(
+                "It was written by a newer version, with schema version {}",
+                version
+            ));
+        }
+        let transaction = connection.transaction()?;
+        for migration in &MIGRATIONS[version..] {
+            transaction.execute_batch(migration)?;
+        }
+        transaction.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        transaction.commit()?;
+        Ok(Self { connection })
+    }
+
+    /// Adds a run of `year`'s `results` made at `timestamp`, in seconds since
+    /// the Unix epoch, returning its id.
+    pub fn record(&mut self, results: &[RunResult], year: u16, timestamp: i64) -> Result<i64> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO runs (timestamp, git_sha, year) VALUES (?1, ?2, ?3)",
+            rusqlite::params![timestamp, report::build_sha(), year],
+        )?;
+        let run_id = transaction.last_insert_rowid();
+        {
+            let mut insert = transaction.prepare(
+                "INSERT INTO results (run_id, day, part, duration_ms, answer_hash, status)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for result in results {
+                let duration_ms = result
+                    .timing
+                    .filter(|_| result.status == Status::Solved)
+                    .map(|timing| timing.total.as_secs_f64() * 1000.0);
+                let answer_hash = result
+                    .answer
+                    .as_ref()
+                    .map(|answer| crate::input::hash(&answer.to_string()));
+                insert.execute(rusqlite::params![
+                    run_id,
+                    result.day,
+                    result.part,
+                    duration_ms,
+                    answer_hash,
+                    status_name(&result.status)
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(run_id)
+    }
+
+    /// `year`'s day's results, or only `part`'s, in the last `runs` runs that
+    /// had any, oldest first.
+    pub fn entries(
+        &self,
+        year: u16,
+        day: u8,
+        part: Option<u32>,
+        runs: usize,
+    ) -> Result<Vec<Entry>> {
+        let mut select = self.connection.prepare(
+            "SELECT results.run_id, runs.timestamp, runs.git_sha, results.part,
+                    results.duration_ms, results.status
+             FROM results JOIN runs USING (run_id)
+             WHERE results.day = ?1 AND (?2 IS NULL OR results.part = ?2)
+               AND results.run_id IN (
+                 SELECT DISTINCT run_id FROM results JOIN runs USING (run_id)
+                 WHERE runs.year = ?4 AND day = ?1 AND (?2 IS NULL OR part = ?2)
+                 ORDER BY run_id DESC LIMIT ?3
+               )
+             ORDER BY results.run_id, results.part",
+        )?;
+        let entries = select
+            .query_map(rusqlite::params![day, part, runs as i64, year], |row| {
+                Ok(Entry {
+                    run_id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    git_sha: row.get(2)?,
+                    part: row.get(3)?,
+                    duration: row
+                        .get::<_, Option<f64>>(4)?
+                        .map(|millis| Duration::from_secs_f64(millis.max(0.0) / 1000.0)),
+                    status: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+}
+
+/// Stands in for the history in builds without the `history` feature, it
+/// can't be opened.
+#[cfg(not(feature = "history"))]
+pub struct History {
+    never: std::convert::Infallible,
+}
+
+#[cfg(not(feature = "history"))]
+impl History {
+    pub fn open(_path: &std::path::Path) -> Result<Self> {
+        Err(crate::failure::FailureKind::Usage.error(anyhow::anyhow!(
+            "This build can't keep a history, build it with the history feature"
+        )))
+    }
+
+    pub fn record(&mut self, _results: &[RunResult], _year: u16, _timestamp: i64) -> Result<i64> {
+        match self.never {}
+    }
+
+    pub fn entries(
+        &self,
+        _year: u16,
+        _day: u8,
+        _part: Option<u32>,
+        _runs: usize,
+    ) -> Result<Vec<Entry>> {
+        match self.never {}
+    }
+}
+
+/// The columns of [`table`].
+pub const COLUMNS: [&str; 6] = ["Run", "When", "Commit", "Part", "Time", "Status"];
+
+/// The entries as a markdown table, a row per part per run.
+pub fn table(entries: &[Entry]) -> String {
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.run_id.to_string(),
+                DateTime::from_timestamp(entry.timestamp, 0)
+                    .map(|when| when.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default(),
+                entry
+                    .git_sha
+                    .as_deref()
+                    .map(|sha| sha.chars().take(7).collect())
+                    .unwrap_or_default(),
+                entry.part.to_string(),
+                entry.duration.map(format_duration).unwrap_or_default(),
+                entry.status.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    report::markdown(COLUMNS, [true, false, false, true, true, false], &rows)
+}
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The durations as a line of bars, the shortest the lowest and the longest
+/// the highest, with a gap for a run that has none.
+pub fn sparkline(durations: &[Option<Duration>]) -> String {
+    let known = durations.iter().flatten();
+    let (Some(min), Some(max)) = (known.clone().min(), known.max()) else {
+        return " ".repeat(durations.len());
+    };
+    let range = (*max - *min).as_secs_f64();
+    durations
+        .iter()
+        .map(|duration| match duration {
+            None => ' ',
+            Some(_) if range == 0.0 => BARS[0],
+            Some(duration) => {
+                let height = (*duration - *min).as_secs_f64() / range;
+                BARS[(height * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Each part's [`sparkline`] over the entries, with its first and last
+/// times, a line per part.
+pub fn trends(entries: &[Entry]) -> Vec<String> {
+    let mut parts = entries.iter().map(|entry| entry.part).collect::<Vec<_>>();
+    parts.sort_unstable();
+    parts.dedup();
+    parts
+        .into_iter()
+        .map(|part| {
+            let durations = entries
+                .iter()
+                .filter(|entry| entry.part == part)
+                .map(|entry| entry.duration)
+                .collect::<Vec<_>>();
+            let known = durations.iter().flatten().collect::<Vec<_>>();
+            let span = match (known.first(), known.last()) {
+                (Some(first), Some(last)) if known.len() > 1 => format!(
+                    " {} → {}",
+                    format_duration(**first),
+                    format_duration(**last)
+                ),
+                _ => String::new(),
+            };
+            format!("Part {}: {}{}", part, sparkline(&durations), span)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(run_id: i64, part: u32, millis: Option<u64>) -> Entry {
+        Entry {
+            run_id,
+            timestamp: 1_701_925_200,
+            git_sha: Some("0123456789abcdef".to_string()),
+            part,
+            duration: millis.map(Duration::from_millis),
+            status: if millis.is_some() { "solved" } else { "failed" }.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let millis = |values: &[Option<u64>]| {
+            values
+                .iter()
+                .map(|value| value.map(Duration::from_millis))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sparkline(&millis(&[Some(10), Some(80), None, Some(45), Some(10)])),
+            "▁█ ▅▁"
+        );
+        assert_eq!(sparkline(&millis(&[Some(5), Some(5)])), "▁▁");
+        assert_eq!(sparkline(&millis(&[None])), " ");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_table() {
+        let entries = [entry(1, 1, Some(12)), entry(2, 1, None)];
+        assert_eq!(
+            table(&entries),
+            "\
+| Run | When             | Commit  | Part |   Time | Status |
+| --: | :--------------- | :------ | ---: | -----: | :----- |
+|   1 | 2023-12-07 05:00 | 0123456 |    1 | 12.0ms | solved |
+|   2 | 2023-12-07 05:00 | 0123456 |    1 |        | failed |
+"
+        );
+        assert_eq!(
+            trends(&[
+                entry(1, 1, Some(12)),
+                entry(2, 1, Some(4)),
+                entry(1, 2, None)
+            ]),
+            ["Part 1: █▁ 12.0ms → 4.0ms", "Part 2:  "]
+        );
+    }
+
+    #[cfg(feature = "history")]
+    mod database {
+        use super::*;
+        use crate::solver::Timing;
+
+        fn result(day: u8, part: u32, millis: u64) -> RunResult {
+            RunResult {
+                day,
+                part,
+                title: format!("Day {}", day),
+                description: "Answer".to_string(),
+                answer: Some(6440.into()),
+                timing: Some(Timing {
+                    parse: None,
+                    total: Duration::from_millis(millis),
+                }),
+                peak_memory: None,
+                implementation: None,
+                status: Status::Solved,
+                failure: None,
+                truncated: None,
+            }
+        }
+
+        #[test]
+        fn test_record_and_query() -> Result<()> {
+            let mut history = History::in_memory()?;
+            for run in 0..25 {
+                let mut results = vec![result(5, 1, 10 + run), result(7, 1, 3)];
+                if run % 2 == 0 {
+                    results.push(result(5, 2, 100 - run));
+                }
+                history.record(&results, 2023, 1_701_925_200 + run as i64)?;
+            }
+            // another event's run, which none of 2023's days show
+            history.record(&[result(5, 1, 1)], 2022, 1_701_925_300)?;
+
+            let part2 = history.entries(2023, 5, Some(2), DEFAULT_RUNS)?;
+            // the part only ran in 13 of the runs
+            assert_eq!(part2.len(), 13);
+            assert_eq!(part2[0].run_id, 1);
+            assert_eq!(part2[12].duration, Some(Duration::from_millis(76)));
+
+            let day = history.entries(2023, 5, None, 3)?;
+            assert_eq!(
+                day.iter()
+                    .map(|entry| (entry.run_id, entry.part))
+                    .collect::<Vec<_>>(),
+                [(23, 1), (23, 2), (24, 1), (25, 1), (25, 2)]
+            );
+            assert_eq!(day[4].status, "solved");
+            assert!(history.entries(2023, 6, None, DEFAULT_RUNS)?.is_empty());
+            let other = history.entries(2022, 5, None, DEFAULT_RUNS)?;
+            assert_eq!(other.len(), 1);
+            assert_eq!(other[0].run_id, 26);
+
+            // the answer is only kept as a hash
+            let hash: String = history.connection.query_row(
+                "SELECT answer_hash FROM results LIMIT 1",
+                [],
+                |row| row.get(0),
+            )?;
+            assert_eq!(hash, crate::input::hash("6440"));
+            Ok(())
+        }
+
+        #[test]
+        fn test_migrations() -> Result<()> {
+            let history = History::in_memory()?;
+            let version: usize =
+                history
+                    .connection
+                    .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            assert_eq!(version, MIGRATIONS.len());
+            // opening it again finds nothing left to do
+            let again = History::migrated(history.connection)?;
+            again
+                .connection
+                .pragma_update(None, "user_version", MIGRATIONS.len() + 1)?;
+            let newer = History::migrated(again.connection).err().unwrap();
+            assert!(newer.to_string().contains("newer version"), "{}", newer);
+            Ok(())
+        }
+    }
+}
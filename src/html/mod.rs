@@ -0,0 +1,305 @@
+//! A self-contained HTML report of a run, for `--report`, to share progress:
+//! a row per day with each part's answer and time as a bar, failures that
+//! expand to their details, and what the run was made from.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::{fs, path::Path, time::Duration};
+
+use crate::{
+    input, report,
+    runner::{RunResult, Status},
+    solver::format_duration,
+    style,
+};
+
+/// The page, with the `{{name}}` placeholders that [`render`] fills in. Its
+/// styles are inline so the file stands on its own.
+const TEMPLATE: &str = include_str!("report.html");
+
+/// What a run was made from, shown under the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    pub date: String,
+    /// `git describe` of the commit the binary was built from, when known.
+    pub version: Option<String>,
+    /// Each day's input hash, see [`input::hash`].
+    pub inputs: Vec<(u8, String)>,
+}
+
+impl Metadata {
+    /// The metadata of a run made now, from `inputs`. Inputs that can't be
+    /// read, eg. stdin, are left out.
+    pub fn new(inputs: &[(u8, &Path)]) -> Self {
+        Self {
+            date: Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+            version: report::build_version().map(str::to_string),
+            inputs: inputs
+                .iter()
+                .filter_map(|(day, path)| Some((*day, input::hash(&input::read(path).ok()?))))
+                .collect(),
+        }
+    }
+}
+
+/// Renders the report of `results`, with answers masked when they're hidden,
+/// see [`style::answers_hidden`].
+pub fn render(results: &[RunResult], metadata: &Metadata) -> String {
+    let longest = results
+        .iter()
+        .filter_map(|result| result.timing)
+        .map(|timing| timing.total)
+        .max()
+        .unwrap_or_default();
+    let mut days = results.iter().map(|result| result.day).collect::<Vec<u8>>();
+    days.dedup();
+    let rows = days
+        .into_iter()
+        .map(|day| {
+            let day_results = results
+                .iter()
+                .filter(|result| result.day == day)
+                .collect::<Vec<_>>();
+            let title = day_results
+                .first()
+                .map(|result| result.title.as_str())
+                .unwrap_or_default();
+            let part = |part: u32| {
+                day_results
+                    .iter()
+                    .find(|result| result.part == part)
+                    .map(|result| part_cell(result, longest))
+                    .unwrap_or_else(|| "<td></td>".to_string())
+            };
+            format!(
+                "<tr><td class=\"day\">{}</td><td>{}</td>{}{}</tr>",
+                day,
+                escape(title),
+                part(1),
+                part(2)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let solved = results
+        .iter()
+        .filter(|result| result.status == Status::Solved)
+        .count();
+    let inputs = metadata
+        .inputs
+        .iter()
+        .map(|(day, hash)| {
+            format!(
+                "<dt>Day {} input</dt><dd class=\"input\"><code>{}</code></dd>",
+                day,
+                escape(hash)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    fill(TEMPLATE, |name| match name {
+        "summary" => Some(format!("{} of {} part(s) solved", solved, results.len())),
+        "rows" => Some(rows.clone()),
+        "date" => Some(escape(&metadata.date)),
+        "version" => Some(escape(metadata.version.as_deref().unwrap_or("unknown"))),
+        "inputs" => Some(inputs.clone()),
+        _ => None,
+    })
+}
+
+/// Writes the report of `results` to `path`, see [`render`].
+pub fn write(path: &Path, results: &[RunResult], metadata: &Metadata) -> Result<()> {
+    fs::write(path, render(results, metadata))
+        .with_context(|| format!("Can't write the report to {}", path.display()))
+}
+
+/// A part's cell: its answer and time, or why it has neither.
+fn part_cell(result: &RunResult, longest: Duration) -> String {
+    let details = |class: &str, label: &str, reason: &str| {
+        format!(
+            "<td class=\"{}\"><details><summary>{}</summary><pre>{}</pre></details></td>",
+            class,
+            label,
+            escape(reason)
+        )
+    };
+    match &result.status {
+        Status::Solved => {
+            let answer = result
+                .answer
+                .as_ref()
+                .map(style::answer_text)
+                .unwrap_or_default();
+            let time = result
+                .timing
+                .map(|timing| {
+                    let width = if longest.is_zero() {
+                        0.0
+                    } else {
+                        timing.total.as_secs_f64() / longest.as_secs_f64() * 100.0
+                    };
+                    format!(
+                        "<div class=\"time\"><div class=\"track\"><div class=\"bar\" style=\"width: {:.1}%\"></div></div><span>{}</span></div>",
+                        width,
+                        format_duration(timing.total)
+                    )
+                })
+                .unwrap_or_default();
+            format!(
+                "<td class=\"solved\"><span class=\"answer\">{}</span>{}</td>",
+                escape(&answer),
+                time
+            )
+        }
+        Status::Failed(reason) => details("failed", "failed", reason),
+        Status::TimedOut(reason) => details("timed-out", "timed out", reason),
+        Status::Skipped(reason) => format!(
+            "<td class=\"skipped\" title=\"{}\">{}</td>",
+            escape(reason),
+            if result.is_interrupted() {
+                "interrupted"
+            } else {
+                "skipped"
+            }
+        ),
+    }
+}
+
+/// Replaces each `{{name}}` in `template` with `value(name)` in one pass, so
+/// a value can't bring in placeholders of its own. Unknown names are left.
+fn fill(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        filled += &rest[..start];
+        match value(&rest[start + 2..end]) {
+            Some(value) => filled += &value,
+            None => filled += &rest[start..end + 2],
+        }
+        rest = &rest[end + 2..];
+    }
+    filled + rest
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Timing;
+    use scraper::{Html, Selector};
+
+    fn result(day: u8, part: u32, status: Status, millis: Option<u64>) -> RunResult {
+        RunResult {
+            day,
+            part,
+            title: format!("Day {}: <Puzzle & Co>", day),
+            description: "Answer".to_string(),
+            answer: millis.map(|millis| (millis as i64 * 10).into()),
+            timing: millis.map(|millis| Timing {
+                parse: None,
+                total: Duration::from_millis(millis),
+            }),
+            peak_memory: None,
+            implementation: None,
+            status,
+            failure: None,
+            truncated: None,
+        }
+    }
+
+    fn select<'a>(document: &'a Html, selector: &str) -> Vec<scraper::ElementRef<'a>> {
+        document
+            .select(&Selector::parse(selector).unwrap())
+            .collect()
+    }
+
+    fn text(element: &scraper::ElementRef) -> String {
+        element.text().collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let results = [
+            result(1, 1, Status::Solved, Some(40)),
+            result(1, 2, Status::Solved, Some(10)),
+            result(3, 1, Status::Solved, Some(5)),
+            result(
+                3,
+                2,
+                Status::Failed("<bad> input on line 3".to_string()),
+                None,
+            ),
+            result(5, 1, Status::Skipped("no input".to_string()), None),
+        ];
+        let metadata = Metadata {
+            date: "2023-12-07 05:00 UTC".to_string(),
+            version: Some("v1.2-3-gabc".to_string()),
+            inputs: vec![(1, "0123456789abcdef".to_string())],
+        };
+        let document = Html::parse_document(&render(&results, &metadata));
+
+        let rows = select(&document, "table.days tbody tr");
+        assert_eq!(rows.len(), 3);
+        let first = select(&document, "table.days tbody tr:first-child td")
+            .iter()
+            .map(|td| text(td).trim().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(first[..2], ["1", "Day 1: <Puzzle & Co>"]);
+        // bars are relative to the slowest part
+        let widths = select(&document, ".bar")
+            .iter()
+            .map(|bar| bar.value().attr("style").unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            widths,
+            ["width: 100.0%", "width: 25.0%", "width: 12.5%"].map(str::to_string)
+        );
+        assert_eq!(
+            select(&document, "td.solved .answer")
+                .iter()
+                .map(text)
+                .collect::<Vec<_>>(),
+            ["400", "100", "50"]
+        );
+        let failed = select(&document, "td.failed details");
+        assert_eq!(failed.len(), 1);
+        assert_eq!(
+            text(&select(&document, "td.failed details pre")[0]),
+            "<bad> input on line 3"
+        );
+        assert_eq!(text(&select(&document, "td.skipped")[0]), "skipped");
+
+        assert_eq!(text(&select(&document, "dd.version")[0]), "v1.2-3-gabc");
+        assert_eq!(
+            text(&select(&document, "dd.date")[0]),
+            "2023-12-07 05:00 UTC"
+        );
+        assert_eq!(
+            text(&select(&document, "dd.input code")[0]),
+            "0123456789abcdef"
+        );
+        assert_eq!(
+            text(&select(&document, "p.summary")[0]),
+            "3 of 5 part(s) solved"
+        );
+        // nothing to fetch, so the file works anywhere
+        assert!(select(&document, "link, script[src], img").is_empty());
+        assert_eq!(select(&document, "head style").len(), 1);
+    }
+
+    #[test]
+    fn test_fill() {
+        let value = |name: &str| (name == "a").then(|| "{{b}}".to_string());
+        assert_eq!(fill("x {{a}} {{b}} {{a", value), "x {{b}} {{b}} {{a");
+        assert_eq!(fill("{{}}", value), "{{}}");
+    }
+}
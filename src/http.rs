@@ -0,0 +1,593 @@
+//! The one way out to the network. Requests to adventofcode.com go through
+//! [`Polite`], which keeps to its automation guidelines: it says what it is
+//! in the User-Agent, never asks for the same URL more than once every
+//! [`MIN_INTERVAL`], even across runs, backs off and retries when the server
+//! is struggling, and answers GETs from a cache on disk where it can, for
+//! no longer than [`LEADERBOARD_MAX_AGE`] for private leaderboards.
+//! Nothing else uses ureq, so none of that can be skipped by accident.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::input;
+
+/// Sent with every request, as adventofcode.com asks tools to say what they
+/// are and where to find out more.
+pub const USER_AGENT: &str = concat!(
+    "aoc-2023/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    " by ",
+    env!("CARGO_PKG_AUTHORS"),
+    ")"
+);
+
+/// How long to wait for adventofcode.com before giving up on it.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The least time between two requests for the same URL.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How many times a request that failed with a [`TRANSIENT`] status is
+/// tried again.
+pub const MAX_RETRIES: u32 = 3;
+
+/// How long to wait before the first retry, doubling for each after it.
+pub const BACKOFF: Duration = Duration::from_secs(2);
+
+/// The statuses of the server being down for a moment. 500 isn't one, it's
+/// what adventofcode.com answers a bad session cookie with.
+pub const TRANSIENT: [u16; 3] = [502, 503, 504];
+
+/// How long a private leaderboard is answered from the cache, as
+/// adventofcode.com asks they're fetched no more often than that.
+pub const LEADERBOARD_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// Where [`Polite`] keeps the times of its last requests and its cache,
+/// relative to the current directory like the history and baselines.
+pub const HTTP_DIR: &str = ".aoc/http";
+
+const LAST_REQUESTS_FILE: &str = "last-requests.json";
+
+const CACHE_DIR: &str = "cache";
+
+/// Private leaderboards are cached apart from the days' pages and the
+/// others, so forgetting those doesn't fetch them sooner than
+/// [`LEADERBOARD_MAX_AGE`].
+const LEADERBOARD_CACHE_DIR: &str = "leaderboards";
+
+/// A cached response's body and when it was fetched, in ms since the epoch.
+#[derive(Debug, Serialize, Deserialize)]
+struct Cached {
+    fetched: u64,
+    body: String,
+}
+
+/// What came back for a request, whatever its status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Where requests to adventofcode.com go, so tests can stand in for the
+/// network.
+pub trait Client {
+    /// GETs `url` with `session` as the session cookie.
+    fn get(&self, url: &str, session: &str) -> Result<Response>;
+
+    /// POSTs `form` to `url` with `session` as the session cookie.
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    Empty,
+    Form(Vec<(String, String)>),
+    Json(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    /// The adventofcode.com session cookie.
+    pub session: Option<String>,
+    pub body: Body,
+}
+
+/// Sends requests as they are, see [`Ureq`].
+pub trait Transport {
+    /// Sends `request`, only erroring when there's no response at all.
+    fn send(&self, request: &Request) -> Result<Response>;
+}
+
+/// Sends requests over HTTP.
+pub struct Ureq {
+    pub timeout: Duration,
+}
+
+impl Transport for Ureq {
+    fn send(&self, request: &Request) -> Result<Response> {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(self.timeout)
+            .user_agent(USER_AGENT)
+            .build();
+        let mut call = match request.method {
+            Method::Get => agent.get(&request.url),
+            Method::Post => agent.post(&request.url),
+        };
+        if let Some(session) = &request.session {
+            call = call.set("Cookie", &format!("session={}", session));
+        }
+        let response = match &request.body {
+            Body::Empty => call.call(),
+            Body::Form(form) => call.send_form(
+                &form
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect::<Vec<_>>(),
+            ),
+            Body::Json(json) => call
+                .set("Content-Type", "application/json")
+                .send_string(json),
+        };
+        let response = match response {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(anyhow!("{}", e)),
+        };
+        let status = response.status();
+        let body = response.into_string().context("Can't read the response")?;
+        Ok(Response { status, body })
+    }
+}
+
+/// The time, and waiting for it to pass, so tests needn't wait.
+pub trait Clock {
+    /// The time since the Unix epoch.
+    fn now(&self) -> Duration;
+
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// A [`Client`] keeping to adventofcode.com's automation guidelines, see the
+/// module docs. Its state is kept in `dir`.
+pub struct Polite<T: Transport, C: Clock> {
+    transport: T,
+    clock: C,
+    dir: PathBuf,
+}
+
+impl Polite<Ureq, SystemClock> {
+    /// The client for adventofcode.com, keeping its state in [`HTTP_DIR`].
+    pub fn adventofcode() -> Self {
+        Polite::new(Ureq { timeout: TIMEOUT }, SystemClock, HTTP_DIR)
+    }
+}
+
+impl<T: Transport, C: Clock> Polite<T, C> {
+    pub fn new(transport: T, clock: C, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            transport,
+            clock,
+            dir: dir.into(),
+        }
+    }
+
+    /// Sends `request`, or answers it from the cache. A successful GET is
+    /// cached by the session and URL, under the day the URL is about, or
+    /// until it's [`LEADERBOARD_MAX_AGE`] old for a leaderboard. A POST
+    /// about a day, ie. an answer, can change what its pages say, eg. unlock
+    /// part 2, and the calendar, so it drops the day's cached responses and
+    /// those not about any day.
+    pub fn send(&self, request: Request) -> Result<Response> {
+        let cached = match request.method {
+            Method::Get => Some(self.cache_path(&request)),
+            Method::Post => None,
+        };
+        if let Some(path) = &cached {
+            if let Some(body) = self.cached(path, max_age(&request.url))? {
+                return Ok(Response { status: 200, body });
+            }
+        }
+
+        let mut retries = 0;
+        let response = loop {
+            self.throttle(&request.url)?;
+            let response = self
+                .transport
+                .send(&request)
+                .map_err(|e| anyhow!("Can't reach {}: {}", host(&request.url), e))?;
+            if !TRANSIENT.contains(&response.status) || retries == MAX_RETRIES {
+                break response;
+            }
+            self.clock.sleep(BACKOFF * 2_u32.pow(retries));
+            retries += 1;
+        };
+
+        match (cached, day_of(&request.url)) {
+            (Some(path), _) if response.status == 200 => {
+                let cached = Cached {
+                    fetched: self.clock.now().as_millis() as u64,
+                    body: response.body.clone(),
+                };
+                write(&path, &serde_json::to_string(&cached)?)?
+            }
+            (None, Some(day)) => {
+                self.forget(Some(day))?;
+                // eg. the calendar's stars
+                self.forget(None)?;
+            }
+            _ => {}
+        }
+        Ok(response)
+    }
+
+    /// Drops the cached responses about `day`, or those not about any day
+    /// for `None`, so they're asked for again, eg. the puzzle page once part
+    /// 1 has been solved on the website.
+    pub fn forget(&self, day: Option<u8>) -> Result<()> {
+        let dir = self.day_cache(day);
+        match fs::remove_dir_all(&dir) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Can't clear {}", dir.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The body cached at `path`, unless it's older than `max_age`. One that
+    /// can't be read as a cached response, eg. from an older version, is
+    /// fetched again.
+    fn cached(&self, path: &Path, max_age: Option<Duration>) -> Result<Option<String>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Can't read {}", path.display())),
+        };
+        let Ok(cached) = serde_json::from_str::<Cached>(&text) else {
+            return Ok(None);
+        };
+        let age = self
+            .clock
+            .now()
+            .saturating_sub(Duration::from_millis(cached.fetched));
+        Ok(match max_age {
+            Some(max_age) if age >= max_age => None,
+            _ => Some(cached.body),
+        })
+    }
+
+    /// Waits until [`MIN_INTERVAL`] has passed since the last request for
+    /// `url`, then notes the time of this one.
+    fn throttle(&self, url: &str) -> Result<()> {
+        let path = self.dir.join(LAST_REQUESTS_FILE);
+        let mut last = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str::<BTreeMap<String, u64>>(&text)
+                .with_context(|| format!("Bad last requests file {}", path.display()))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).with_context(|| format!("Can't read {}", path.display())),
+        };
+        if let Some(&at) = last.get(url) {
+            let next = Duration::from_millis(at) + MIN_INTERVAL;
+            let now = self.clock.now();
+            if next > now {
+                self.clock.sleep(next - now);
+            }
+        }
+        last.insert(url.to_string(), self.clock.now().as_millis() as u64);
+        write(&path, &serde_json::to_string_pretty(&last)?)
+    }
+
+    fn day_cache(&self, day: Option<u8>) -> PathBuf {
+        let day = match day {
+            Some(day) => format!("{:02}", day),
+            None => "other".to_string(),
+        };
+        self.dir.join(CACHE_DIR).join(day)
+    }
+
+    fn cache_path(&self, request: &Request) -> PathBuf {
+        let key = format!(
+            "{}\n{}",
+            request.session.as_deref().unwrap_or_default(),
+            request.url
+        );
+        let dir = if is_leaderboard(&request.url) {
+            self.dir.join(CACHE_DIR).join(LEADERBOARD_CACHE_DIR)
+        } else {
+            self.day_cache(day_of(&request.url))
+        };
+        dir.join(input::hash(&key))
+    }
+}
+
+impl<T: Transport, C: Clock> Client for Polite<T, C> {
+    fn get(&self, url: &str, session: &str) -> Result<Response> {
+        self.send(Request {
+            method: Method::Get,
+            url: url.to_string(),
+            session: Some(session.to_string()),
+            body: Body::Empty,
+        })
+    }
+
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response> {
+        self.send(Request {
+            method: Method::Post,
+            url: url.to_string(),
+            session: Some(session.to_string()),
+            body: Body::Form(
+                form.iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            ),
+        })
+    }
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+    }
+    fs::write(path, contents).with_context(|| format!("Can't write {}", path.display()))
+}
+
+/// The day an adventofcode.com URL is about, eg. 7 for `/2023/day/7/input`.
+fn day_of(url: &str) -> Option<u8> {
+    let (_, rest) = url.split_once("/day/")?;
+    rest.split('/').next()?.parse().ok()
+}
+
+/// Whether `url` is a private leaderboard's, eg.
+/// `/2023/leaderboard/private/view/12345.json`.
+fn is_leaderboard(url: &str) -> bool {
+    url.contains("/leaderboard/private/view/")
+}
+
+/// How long the response to `url` is good for, forever if `None`.
+fn max_age(url: &str) -> Option<Duration> {
+    is_leaderboard(url).then_some(LEADERBOARD_MAX_AGE)
+}
+
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    /// Answers with each of its statuses in turn, noting when it was asked.
+    struct Mock<'a> {
+        statuses: RefCell<Vec<u16>>,
+        clock: &'a Fake,
+        sent: RefCell<Vec<(Method, String, Duration)>>,
+    }
+
+    impl<'a> Mock<'a> {
+        fn new(clock: &'a Fake, statuses: &[u16]) -> Self {
+            Self {
+                statuses: RefCell::new(statuses.iter().rev().copied().collect()),
+                clock,
+                sent: RefCell::default(),
+            }
+        }
+    }
+
+    impl Transport for &Mock<'_> {
+        fn send(&self, request: &Request) -> Result<Response> {
+            self.sent
+                .borrow_mut()
+                .push((request.method, request.url.clone(), self.clock.now()));
+            let status = self.statuses.borrow_mut().pop().unwrap_or(200);
+            Ok(Response {
+                status,
+                body: format!("{} {}", status, request.url),
+            })
+        }
+    }
+
+    /// A clock that only moves when slept on.
+    #[derive(Default)]
+    struct Fake(Cell<Duration>);
+
+    impl Fake {
+        fn advance(&self, duration: Duration) {
+            self.0.set(self.0.get() + duration);
+        }
+    }
+
+    impl Clock for &Fake {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.advance(duration);
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("aoc-2023-http-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    const INPUT: &str = "https://adventofcode.com/2023/day/7/input";
+    const ANSWER: &str = "https://adventofcode.com/2023/day/7/answer";
+    const CALENDAR: &str = "https://adventofcode.com/2023";
+    const LEADERBOARD: &str = "https://adventofcode.com/2023/leaderboard/private/view/12345.json";
+
+    #[test]
+    fn test_throttling() -> Result<()> {
+        let dir = temp_dir("throttle");
+        let clock = Fake::default();
+        let transport = Mock::new(&clock, &[]);
+        let client = Polite::new(&transport, &clock, &dir);
+        client.post(ANSWER, "abc", &[("level", "1")])?;
+        client.post(ANSWER, "abc", &[("level", "1")])?;
+        // another URL needn't wait
+        client.get(INPUT, "abc")?;
+        clock.advance(Duration::from_secs(2));
+        // nor does one asked long enough after
+        clock.advance(MIN_INTERVAL);
+        client.post(ANSWER, "abc", &[("level", "1")])?;
+        // the times are kept across runs
+        let client = Polite::new(&transport, &clock, &dir);
+        client.post(ANSWER, "abc", &[("level", "1")])?;
+        let times = transport
+            .sent
+            .take()
+            .into_iter()
+            .map(|(_, _, at)| at.as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(times, [0, 5, 5, 12, 17]);
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_retries() -> Result<()> {
+        let dir = temp_dir("retry");
+        let clock = Fake::default();
+        let transport = Mock::new(&clock, &[503, 502, 200]);
+        let client = Polite::new(&transport, &clock, &dir);
+        let response = client.post(ANSWER, "abc", &[])?;
+        assert_eq!(response.status, 200);
+        let times = transport
+            .sent
+            .take()
+            .into_iter()
+            .map(|(_, _, at)| at.as_secs())
+            .collect::<Vec<_>>();
+        // backing off 2s then 4s, but never sooner than the interval
+        assert_eq!(times, [0, 5, 10]);
+
+        // giving up after the last retry
+        let transport = Mock::new(&clock, &[503; 5]);
+        let client = Polite::new(&transport, &clock, &dir);
+        assert_eq!(client.post(ANSWER, "abc", &[])?.status, 503);
+        assert_eq!(transport.sent.take().len(), 1 + MAX_RETRIES as usize);
+
+        // 500 is a bad session cookie, not worth retrying
+        let transport = Mock::new(&clock, &[500]);
+        let client = Polite::new(&transport, &clock, &dir);
+        assert_eq!(client.get(INPUT, "abc")?.status, 500);
+        assert_eq!(transport.sent.take().len(), 1);
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache() -> Result<()> {
+        let dir = temp_dir("cache");
+        let clock = Fake::default();
+        let transport = Mock::new(&clock, &[404]);
+        let client = Polite::new(&transport, &clock, &dir);
+        // failures aren't cached
+        assert_eq!(client.get(INPUT, "abc")?.status, 404);
+        let fetched = client.get(INPUT, "abc")?;
+        assert_eq!(fetched.status, 200);
+        assert_eq!(client.get(INPUT, "abc")?, fetched);
+        assert_eq!(transport.sent.take().len(), 2);
+        assert!(dir.join("cache/07").is_dir());
+
+        // each session has its own inputs
+        client.get(INPUT, "def")?;
+        assert_eq!(transport.sent.take().len(), 1);
+
+        // an answer can unlock more of the day and add to the calendar, so
+        // they're fetched again
+        client.get(CALENDAR, "abc")?;
+        assert!(dir.join("cache/other").is_dir());
+        transport.sent.take();
+        client.post(ANSWER, "abc", &[])?;
+        client.get(INPUT, "abc")?;
+        client.get(CALENDAR, "abc")?;
+        let sent = transport.sent.take();
+        assert_eq!(sent.len(), 3);
+        assert_eq!((sent[1].0, sent[1].1.as_str()), (Method::Get, INPUT));
+        assert_eq!((sent[2].0, sent[2].1.as_str()), (Method::Get, CALENDAR));
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaderboard_cache() -> Result<()> {
+        let dir = temp_dir("leaderboard");
+        let clock = Fake::default();
+        let transport = Mock::new(&clock, &[]);
+        let client = Polite::new(&transport, &clock, &dir);
+        client.get(LEADERBOARD, "abc")?;
+        // answered from the cache until it's old enough
+        clock.advance(LEADERBOARD_MAX_AGE - Duration::from_secs(1));
+        client.get(LEADERBOARD, "abc")?;
+        // nor does an answer make it fetched sooner
+        client.post(ANSWER, "abc", &[])?;
+        client.get(LEADERBOARD, "abc")?;
+        assert_eq!(transport.sent.take().len(), 2);
+        clock.advance(Duration::from_secs(1));
+        client.get(LEADERBOARD, "abc")?;
+        let sent = transport.sent.take();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, LEADERBOARD);
+
+        // other pages don't go stale
+        client.get(INPUT, "abc")?;
+        clock.advance(LEADERBOARD_MAX_AGE * 100);
+        client.get(INPUT, "abc")?;
+        assert_eq!(transport.sent.take().len(), 1);
+
+        // nor does an entry that isn't a cached response break anything
+        let entry = fs::read_dir(dir.join("cache/07"))?.next().unwrap()?.path();
+        fs::write(&entry, "32T3K 765\n")?;
+        assert_eq!(client.get(INPUT, "abc")?.body, format!("200 {}", INPUT));
+        assert_eq!(transport.sent.take().len(), 1);
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_urls() {
+        assert_eq!(day_of(INPUT), Some(7));
+        assert_eq!(day_of("https://adventofcode.com/2023/day/25"), Some(25));
+        assert_eq!(day_of("https://adventofcode.com/2023"), None);
+        assert_eq!(max_age(LEADERBOARD), Some(LEADERBOARD_MAX_AGE));
+        assert_eq!(max_age(INPUT), None);
+        assert_eq!(host(INPUT), "adventofcode.com");
+        assert!(USER_AGENT.starts_with("aoc-2023/"));
+        assert!(USER_AGENT.contains("github.com/JehtBlack/aoc-2023"));
+    }
+}
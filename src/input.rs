@@ -0,0 +1,337 @@
+//! Reading an input, gzipped or not, and tidying it up before a day's parser
+//! sees it. Downloaded inputs are clean, but hand-made ones often have stray
+//! blank lines or trailing spaces, which each day would otherwise deal with
+//! differently.
+
+use anyhow::{anyhow, Result};
+use flate2::read::MultiGzDecoder;
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// The first bytes of every gzip stream.
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The extension of a gzipped input.
+pub const GZIP_EXTENSION: &str = "gz";
+
+/// `path` with `.gz` on the end, where a gzipped copy of it would be.
+pub fn gzipped(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path);
+    name.push(".");
+    name.push(GZIP_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// The contents of an input read from `path`, decompressed when it's gzipped,
+/// going by its `.gz` extension or the gzip magic bytes, and as they are
+/// otherwise.
+pub fn decompress(path: &Path, bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    let compressed =
+        path.extension().is_some_and(|ext| ext == GZIP_EXTENSION) || bytes.starts_with(&GZIP_MAGIC);
+    if !compressed {
+        return Ok(bytes);
+    }
+    let mut decompressed = vec![];
+    MultiGzDecoder::new(bytes.as_slice())
+        .read_to_end(&mut decompressed)
+        .map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("can't decompress it as gzip: {}", e),
+            )
+        })?;
+    Ok(decompressed)
+}
+
+/// [`decompress`] for an input that has to be text.
+pub fn decode(path: &Path, bytes: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(decompress(path, bytes)?).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })
+}
+
+/// Reads the input file at `path`, decompressing it if it's gzipped.
+pub fn read(path: &Path) -> io::Result<String> {
+    decode(path, fs::read(path)?)
+}
+
+/// How forgiving to be of stray whitespace in an input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// The input has to be as clean as a downloaded one, the first blank line
+    /// or trailing whitespace is an error.
+    #[default]
+    Strict,
+    /// Blank lines are skipped and trailing whitespace trimmed, for inputs
+    /// written by hand.
+    Lenient,
+}
+
+/// What comments in an input start with, unless `--comment-prefix` says
+/// otherwise.
+pub const DEFAULT_COMMENT_PREFIX: &str = "#";
+
+/// Drops the lines whose first non-space characters are `prefix`, and with
+/// `inline` cuts each other line short at a space followed by `prefix`, eg.
+/// `12 34 # the overlap case`. For annotated examples only, real inputs have no
+/// comments and `#` is part of some puzzles' inputs, so this is opt in.
+pub fn strip_comments<'a>(input: &'a str, prefix: &str, inline: bool) -> Cow<'a, str> {
+    let marker = format!(" {}", prefix);
+    let is_comment = |line: &str| line.trim_start().starts_with(prefix);
+    let trailing = |line: &str| inline.then(|| line.find(&marker)).flatten();
+    if !input
+        .lines()
+        .any(|line| is_comment(line) || trailing(line).is_some())
+    {
+        return Cow::Borrowed(input);
+    }
+    let mut stripped = String::with_capacity(input.len());
+    for line in input.split_inclusive('\n').filter(|line| !is_comment(line)) {
+        match trailing(line) {
+            Some(start) => {
+                let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                stripped.push_str(line[..start].trim_end());
+                stripped.push_str(ending);
+            }
+            None => stripped.push_str(line),
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+/// Checks or tidies `input` according to `mode`. Where blank lines separate
+/// `sections` of the input, eg. day 5's maps, single blank lines between them
+/// are kept and only the extra ones count as stray.
+pub fn prepare(input: &str, mode: ParseMode, sections: bool) -> Result<Cow<'_, str>> {
+    match mode {
+        ParseMode::Strict => check(input, sections).map(|_| Cow::Borrowed(input)),
+        ParseMode::Lenient => Ok(Cow::Owned(tidy(input, sections))),
+    }
+}
+
+/// The first `lines` lines of `input`, each with its line ending, for solving
+/// part of an input to narrow down where a parser goes wrong. `None` when the
+/// input has no more lines than that.
+pub fn first_lines(input: &str, lines: usize) -> Option<&str> {
+    let end = input
+        .match_indices('\n')
+        .nth(lines.checked_sub(1)?)
+        .map(|(index, _)| index + 1)?;
+    (end < input.len()).then(|| &input[..end])
+}
+
+/// Identifies an input without keeping it, to tell whether a day's input
+/// changed since a run. FNV-1a, as the hash has to be the same whichever
+/// build computes it.
+pub fn hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Where `aoc` would look for the day's real input, in the data root from
+/// `AOC_DATA_DIR` or `aoc.toml` as usual, for the tests of the days' real
+/// answers behind the `real-inputs` feature. Inputs are personal, so they
+/// aren't in every clone.
+#[cfg(all(test, feature = "real-inputs"))]
+pub(crate) fn real(day: u8) -> PathBuf {
+    use crate::config::{Overrides, Settings};
+
+    let settings = Settings::from_env(&Overrides::default()).expect("bad settings");
+    settings.layout.input(&settings.data_root(), day)
+}
+
+/// Errors with the line and column of the first stray whitespace.
+fn check(input: &str, sections: bool) -> Result<()> {
+    let lines = input.lines().collect::<Vec<_>>();
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_end();
+        if trimmed.len() != line.len() {
+            return Err(anyhow!(
+                "Line {}, column {}: trailing whitespace, which only lenient parsing allows",
+                index + 1,
+                trimmed.chars().count() + 1
+            ));
+        }
+        let separator = sections
+            && index > 0
+            && !lines[index - 1].is_empty()
+            && lines.get(index + 1).is_some_and(|next| !next.is_empty());
+        if line.is_empty() && !separator {
+            return Err(anyhow!(
+                "Line {}, column 1: blank line, which only lenient parsing allows",
+                index + 1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Trims every line's trailing whitespace and drops the blank lines, or all
+/// but one between each of the `sections`.
+fn tidy(input: &str, sections: bool) -> String {
+    let mut tidied = String::with_capacity(input.len());
+    let mut gap = false;
+    for line in input.lines().map(str::trim_end) {
+        if line.is_empty() {
+            gap = sections && !tidied.is_empty();
+            continue;
+        }
+        if gap {
+            tidied.push('\n');
+            gap = false;
+        }
+        tidied.push_str(line);
+        tidied.push('\n');
+    }
+    tidied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = "\n12 34  \n\n\n56\t\n78\n\n";
+
+    #[test]
+    fn test_strict_accepts_clean_input() -> Result<()> {
+        let clean = "12 34\n56\n";
+        assert!(matches!(
+            prepare(clean, ParseMode::Strict, false)?,
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(prepare("a\n\nb\n", ParseMode::Strict, true)?, "a\n\nb\n");
+        assert_eq!(prepare("", ParseMode::Strict, false)?, "");
+        // Windows line endings aren't trailing whitespace
+        assert_eq!(
+            prepare("a\r\nb\r\n", ParseMode::Strict, false)?,
+            "a\r\nb\r\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_reports_first_offence() {
+        let error = |input, sections| {
+            prepare(input, ParseMode::Strict, sections)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(error(MESSY, false).starts_with("Line 1, column 1: blank line"));
+        assert!(error("12 34  \n56\n", false).starts_with("Line 1, column 6: trailing whitespace"));
+        assert!(error("a\nb\n\n", false).starts_with("Line 3, column 1: blank line"));
+        assert!(error("a\n\nb\n", false).starts_with("Line 2, column 1: blank line"));
+        // sections are separated by exactly one blank line
+        assert!(error("a\n\n\nb\n", true).starts_with("Line 2, column 1: blank line"));
+        assert!(error("\na\n\nb\n", true).starts_with("Line 1, column 1: blank line"));
+        assert!(error("a\n\nb\n\n", true).starts_with("Line 4, column 1: blank line"));
+        assert!(error("a\n  \nb\n", true).starts_with("Line 2, column 1: trailing whitespace"));
+    }
+
+    fn gzip(text: &str) -> Vec<u8> {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_decode_gzip() -> Result<()> {
+        let plain = Path::new("03/input");
+        let named = Path::new("03/input.gz");
+        assert_eq!(decode(plain, b"1 2\n".to_vec())?, "1 2\n");
+        // gzipped either by name or by content
+        assert_eq!(decode(named, gzip("1 2\n"))?, "1 2\n");
+        assert_eq!(decode(plain, gzip("1 2\n"))?, "1 2\n");
+        // streams gzipped one after the other read as one
+        let concatenated = [gzip("1 2\n"), gzip("3 4\n")].concat();
+        assert_eq!(decode(plain, concatenated)?, "1 2\n3 4\n");
+
+        let error = decode(named, b"1 2\n".to_vec()).unwrap_err();
+        assert!(
+            error.to_string().starts_with("can't decompress it as gzip"),
+            "{}",
+            error
+        );
+        let truncated = gzip("1 2\n")[..12].to_vec();
+        assert!(decode(plain, truncated).is_err());
+        assert!(decode(plain, vec![b'1', 0xff, b'\n']).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gzipped_path() {
+        assert_eq!(gzipped(Path::new("03/input")), Path::new("03/input.gz"));
+        assert_eq!(
+            gzipped(Path::new("inputs/day03.txt")),
+            Path::new("inputs/day03.txt.gz")
+        );
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let annotated = "# the overlap case\n12 34 # both ranges\n  # indented\n56\n";
+        assert_eq!(strip_comments(annotated, "#", true), "12 34\n56\n");
+        // only whole lines without inline comments
+        assert_eq!(
+            strip_comments(annotated, "#", false),
+            "12 34 # both ranges\n56\n"
+        );
+        assert_eq!(
+            strip_comments("a // note\r\n// b\r\nc#d\n", "//", true),
+            "a\r\nc#d\n"
+        );
+        // a # in the middle of a line isn't a comment
+        assert!(matches!(
+            strip_comments("...#..\n.#.\n", "#", true),
+            Cow::Borrowed("...#..\n.#.\n")
+        ));
+        // though a line starting with one is
+        assert_eq!(strip_comments("#..\n.#.\n", "#", false), ".#.\n");
+    }
+
+    #[test]
+    fn test_first_lines() {
+        assert_eq!(first_lines("a\nb\nc\n", 2), Some("a\nb\n"));
+        assert_eq!(first_lines("a\r\nb\r\nc", 1), Some("a\r\n"));
+        // nothing to cut from an input that short
+        assert_eq!(first_lines("a\nb\n", 2), None);
+        assert_eq!(first_lines("a\nb", 2), None);
+        assert_eq!(first_lines("", 1), None);
+        assert_eq!(first_lines("a\n", 0), None);
+    }
+
+    #[test]
+    fn test_hash() {
+        // FNV-1a's published test vectors
+        assert_eq!(hash(""), "cbf29ce484222325");
+        assert_eq!(hash("a"), "af63dc4c8601ec8c");
+        assert_ne!(hash("1 2\n"), hash("1 2"));
+    }
+
+    #[test]
+    fn test_lenient_tidies() -> Result<()> {
+        assert_eq!(
+            prepare(MESSY, ParseMode::Lenient, false)?,
+            "12 34\n56\n78\n"
+        );
+        assert_eq!(
+            prepare(MESSY, ParseMode::Lenient, true)?,
+            "12 34\n\n56\n78\n"
+        );
+        assert_eq!(prepare("a", ParseMode::Lenient, false)?, "a\n");
+        assert_eq!(prepare(" \n\n", ParseMode::Lenient, true)?, "");
+        Ok(())
+    }
+}
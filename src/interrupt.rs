@@ -0,0 +1,97 @@
+//! Stopping a run of several days on Ctrl-C while keeping the results of the
+//! days that already finished, see [`crate::runner::run_days`].
+
+use anyhow::Result;
+use std::{
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+use crate::failure::FailureKind;
+
+/// Why a part that never got to run, or was abandoned, was skipped.
+pub const INTERRUPTED: &str = "interrupted";
+
+/// Set once a run has been interrupted, shared by everything running for it.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+    at: Arc<OnceLock<Instant>>,
+    /// How long parts already running get to finish once interrupted, they're
+    /// waited for however long they take without one.
+    grace_period: Option<Duration>,
+}
+
+impl Interrupt {
+    pub fn new(grace_period: Option<Duration>) -> Self {
+        Self {
+            at: Arc::default(),
+            grace_period,
+        }
+    }
+
+    /// An interrupt triggered by Ctrl-C, or by the process being sent SIGINT.
+    /// A second Ctrl-C exits straight away, for when waiting is no good.
+    ///
+    /// Only one handler can be installed in a process, so this can only be
+    /// called once.
+    pub fn on_ctrl_c(grace_period: Option<Duration>) -> Result<Self> {
+        let interrupt = Self::new(grace_period);
+        let handler = interrupt.clone();
+        ctrlc::set_handler(move || {
+            if handler.is_triggered() {
+                std::process::exit(FailureKind::Interrupted.exit_code().into());
+            }
+            handler.trigger();
+        })?;
+        Ok(interrupt)
+    }
+
+    pub fn trigger(&self) {
+        self.at.get_or_init(Instant::now);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.at.get().is_some()
+    }
+
+    /// Whether parts still running should be given up on, once the grace
+    /// period after the interrupt is over.
+    pub fn is_past_grace_period(&self) -> bool {
+        match (self.at.get(), self.grace_period) {
+            (Some(at), Some(grace_period)) => at.elapsed() >= grace_period,
+            _ => false,
+        }
+    }
+
+    /// Whether running parts can be given up on at all, see
+    /// [`Interrupt::is_past_grace_period`].
+    pub fn has_grace_period(&self) -> bool {
+        self.grace_period.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grace_period() {
+        let interrupt = Interrupt::new(Some(Duration::from_millis(20)));
+        let shared = interrupt.clone();
+        assert!(!interrupt.is_triggered());
+        assert!(!interrupt.is_past_grace_period());
+        shared.trigger();
+        assert!(interrupt.is_triggered());
+        assert!(!interrupt.is_past_grace_period());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(interrupt.is_past_grace_period());
+        // triggering again doesn't restart the grace period
+        shared.trigger();
+        assert!(interrupt.is_past_grace_period());
+
+        // without one, running parts are waited for
+        let waiting = Interrupt::new(None);
+        waiting.trigger();
+        assert!(waiting.is_triggered() && !waiting.is_past_grace_period());
+    }
+}
@@ -0,0 +1,255 @@
+//! Where each day's input is under the data root, repos lay them out in all
+//! sorts of ways, eg. `03/input`, `input03.txt` or `inputs/day03.txt`.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::input;
+
+/// What each day's input is called in the nested layout, unless
+/// `--input-name` says otherwise.
+pub const DEFAULT_INPUT_NAME: &str = "input";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Layout {
+    /// A directory per day holding its input, named by the pattern, eg.
+    /// `03/input`.
+    Nested(String),
+    /// Every input in the data root itself, eg. `input03.txt`.
+    Flat,
+    /// A path template, eg. `inputs/day{day:02}.txt`.
+    Template(String),
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::Nested(DEFAULT_INPUT_NAME.to_string())
+    }
+}
+
+/// Renders a template, replacing `{day:02}` with the two digit day and `{day}`
+/// with the day as it is, so `day{day:02}.txt` is `day03.txt` for day 3.
+pub fn render(template: &str, day: u8) -> String {
+    template
+        .replace("{day:02}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+}
+
+impl Layout {
+    /// The nested layout with its inputs named by `input_name`, any other
+    /// layout names its inputs itself.
+    pub fn with_input_name(self, input_name: &str) -> Self {
+        match self {
+            Layout::Nested(_) => Layout::Nested(input_name.to_string()),
+            layout => layout,
+        }
+    }
+
+    /// The template each day's input path under the data root is rendered from.
+    pub fn template(&self) -> String {
+        match self {
+            Layout::Nested(input_name) => format!("{{day:02}}/{}", input_name),
+            Layout::Flat => "input{day:02}.txt".to_string(),
+            Layout::Template(template) => template.clone(),
+        }
+    }
+
+    /// Where `day`'s input is under `data_root`, or where a gzipped copy of
+    /// it is, eg. `03/input.gz`, when there's only that.
+    pub fn input(&self, data_root: &Path, day: u8) -> PathBuf {
+        let path = data_root.join(render(&self.template(), day));
+        let compressed = input::gzipped(&path);
+        if !path.exists() && compressed.is_file() {
+            return compressed;
+        }
+        path
+    }
+
+    /// The template `day`'s `n`th example path is rendered from, next to its
+    /// input: `example` for the first and eg. `example2` for the second in the
+    /// day's own directory, when the layout gives it one, otherwise the
+    /// input's name with it appended, eg. `input{day:02}-example.txt`.
+    pub fn example_template(&self, n: u32) -> String {
+        let name = match n {
+            1 => "example".to_string(),
+            n => format!("example{}", n),
+        };
+        let template = self.template();
+        let (dir, file) = match template.rsplit_once('/') {
+            Some((dir, file)) => (format!("{}/", dir), file),
+            None => (String::new(), template.as_str()),
+        };
+        if dir.contains("{day") {
+            return format!("{}{}", dir, name);
+        }
+        match file.rsplit_once('.') {
+            Some((stem, extension)) => format!("{}{}-{}.{}", dir, stem, name, extension),
+            None => format!("{}{}-{}", dir, file, name),
+        }
+    }
+
+    /// Where `day`'s `n`th example is under `data_root`, see
+    /// [`Layout::example_template`].
+    pub fn example(&self, data_root: &Path, day: u8, n: u32) -> PathBuf {
+        data_root.join(render(&self.example_template(n), day))
+    }
+
+    /// The layout and the paths it looks for, for telling users where an input
+    /// was expected.
+    pub fn describe(&self) -> String {
+        match self {
+            Layout::Template(template) => format!("the layout {}", template),
+            layout => format!("the {} layout, {}", layout, layout.template()),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "nested" => Ok(Layout::default()),
+            "flat" => Ok(Layout::Flat),
+            template if template.contains("{day") => Ok(Layout::Template(template.to_string())),
+            _ => Err(anyhow!(
+                "'{}' isn't a layout, expected nested, flat or a template with {{day}} or {{day:02}} in it",
+                s
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Layout {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Layout::Nested(_) => write!(f, "nested"),
+            Layout::Flat => write!(f, "flat"),
+            Layout::Template(template) => write!(f, "{}", template),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        assert_eq!(render("day{day:02}.txt", 3), "day03.txt");
+        assert_eq!(render("day{day}.txt", 3), "day3.txt");
+        assert_eq!(render("{day}/{day:02}", 25), "25/25");
+        assert_eq!(render("input", 3), "input");
+    }
+
+    #[test]
+    fn test_layout_paths() -> Result<()> {
+        let root = Path::new("data");
+        let path = |layout: &str, day| -> Result<PathBuf> {
+            Ok(layout.parse::<Layout>()?.input(root, day))
+        };
+        assert_eq!(path("nested", 7)?, Path::new("data/07/input"));
+        assert_eq!(path("flat", 7)?, Path::new("data/input07.txt"));
+        assert_eq!(
+            path("inputs/day{day:02}.txt", 7)?,
+            Path::new("data/inputs/day07.txt")
+        );
+        assert_eq!(
+            path("{day:02}/input.txt", 12)?,
+            Path::new("data/12/input.txt")
+        );
+        assert_eq!(Layout::default().input(root, 1), Path::new("data/01/input"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_gzipped_input() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("aoc-2023-layout-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("03"))?;
+        std::fs::create_dir_all(root.join("04"))?;
+        std::fs::write(root.join("03").join("input.gz"), "")?;
+        std::fs::write(root.join("04").join("input.gz"), "")?;
+        std::fs::write(root.join("04").join("input"), "")?;
+        let layout = Layout::default();
+        let (only_gzipped, both, neither) = (
+            layout.input(&root, 3),
+            layout.input(&root, 4),
+            layout.input(&root, 5),
+        );
+        std::fs::remove_dir_all(&root)?;
+
+        assert_eq!(only_gzipped, root.join("03").join("input.gz"));
+        // the plain input wins, and is where a missing one is looked for
+        assert_eq!(both, root.join("04").join("input"));
+        assert_eq!(neither, root.join("05").join("input"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_example_paths() -> Result<()> {
+        let root = Path::new("data");
+        let example = |layout: &str, n| -> Result<PathBuf> {
+            Ok(layout.parse::<Layout>()?.example(root, 7, n))
+        };
+        assert_eq!(example("nested", 1)?, Path::new("data/07/example"));
+        assert_eq!(example("nested", 2)?, Path::new("data/07/example2"));
+        assert_eq!(example("flat", 1)?, Path::new("data/input07-example.txt"));
+        assert_eq!(
+            example("inputs/day{day:02}.txt", 2)?,
+            Path::new("data/inputs/day07-example2.txt")
+        );
+        assert_eq!(example("{day}", 1)?, Path::new("data/7-example"));
+        assert_eq!(
+            example("{day:02}/input.txt", 1)?,
+            Path::new("data/07/example")
+        );
+        // the input's name doesn't matter with a directory per day
+        assert_eq!(
+            Layout::default()
+                .with_input_name("day{day}.txt")
+                .example(root, 7, 1),
+            Path::new("data/07/example")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_name() {
+        let nested = Layout::default().with_input_name("day{day}.txt");
+        assert_eq!(
+            nested.input(Path::new("data"), 3),
+            Path::new("data/03/day3.txt")
+        );
+        // only the nested layout has an input name
+        assert_eq!(Layout::Flat.with_input_name("day{day}.txt"), Layout::Flat);
+    }
+
+    #[test]
+    fn test_parse_layout() {
+        assert_eq!("nested".parse::<Layout>().unwrap(), Layout::default());
+        assert!("tree".parse::<Layout>().is_err());
+        assert!("inputs/day.txt".parse::<Layout>().is_err());
+        assert_eq!(
+            Layout::Flat.describe(),
+            "the flat layout, input{day:02}.txt"
+        );
+        assert_eq!(
+            "{day}.in".parse::<Layout>().unwrap().describe(),
+            "the layout {day}.in"
+        );
+    }
+}
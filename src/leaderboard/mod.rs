@@ -0,0 +1,245 @@
+//! Private leaderboards, for `aoc leaderboard`, from the JSON adventofcode.com
+//! serves them as. It asks that they're fetched no more than once every 15
+//! minutes, which [`crate::http::Polite`]'s cache sees to.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{download, http::Client, registry::LAST_DAY, style};
+
+pub fn leaderboard_url(year: u16, id: u64) -> String {
+    format!(
+        "https://adventofcode.com/{}/leaderboard/private/view/{}.json",
+        year, id
+    )
+}
+
+/// A private leaderboard as adventofcode.com serves it, with only what's
+/// shown of it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Leaderboard {
+    pub owner_id: u64,
+    pub event: String,
+    /// By their ids.
+    pub members: BTreeMap<u64, Member>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// `None` for those who keep their name to themselves.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub local_score: u64,
+    #[serde(default)]
+    pub stars: u32,
+    /// The stars got on each day, by day then part.
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Star {
+    /// When it was got, in seconds since the epoch.
+    pub get_star_ts: i64,
+}
+
+impl Member {
+    /// The name adventofcode.com shows for them.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    /// Their stars on day `day`.
+    pub fn day_stars(&self, day: u8) -> usize {
+        self.completion_day_level.get(&day).map_or(0, BTreeMap::len)
+    }
+}
+
+/// The JSON of private leaderboard `id`, as it's sent.
+pub fn fetch(client: &dyn Client, year: u16, id: u64, session: Option<&str>) -> Result<String> {
+    let Some(session) = session else {
+        return Err(download::no_session("Showing a leaderboard"));
+    };
+    let url = leaderboard_url(year, id);
+    let response = client.get(&url, session)?;
+    match response.status {
+        // anything else is sent to the page listing the leaderboards
+        200 if response.body.trim_start().starts_with('{') => Ok(response.body),
+        200 => Err(anyhow!(
+            "adventofcode.com didn't send leaderboard {}: check the id, and that the session cookie is current and its account is a member",
+            id
+        )),
+        400 | 500 => Err(download::session_rejected(response.status)),
+        status => Err(anyhow!("adventofcode.com answered {} for {}", status, url)),
+    }
+}
+
+pub fn parse(json: &str) -> Result<Leaderboard> {
+    serde_json::from_str(json).context("Bad leaderboard JSON")
+}
+
+/// The members by local score, then stars, with a column of their stars
+/// for each day: ★ for both, ☆ for part 1 only.
+pub fn render(leaderboard: &Leaderboard) -> String {
+    let mut members = leaderboard.members.values().collect::<Vec<_>>();
+    members.sort_by(|a, b| {
+        (b.local_score, b.stars)
+            .cmp(&(a.local_score, a.stars))
+            .then(a.id.cmp(&b.id))
+    });
+
+    let mut text = String::new();
+    // the days' numbers, tens above units
+    let indent = " ".repeat(11);
+    let tens = (1..=LAST_DAY)
+        .map(|day| match day / 10 {
+            0 => ' ',
+            tens => char::from(b'0' + tens),
+        })
+        .collect::<String>();
+    let units = (1..=LAST_DAY)
+        .map(|day| char::from(b'0' + day % 10))
+        .collect::<String>();
+    let _ = writeln!(text, "{}{}", indent, tens.trim_end());
+    let _ = writeln!(text, "{}{}", indent, units);
+
+    for member in &members {
+        // those on the same score share a rank
+        let rank = 1 + members
+            .iter()
+            .filter(|other| other.local_score > member.local_score)
+            .count();
+        let days = (1..=LAST_DAY)
+            .map(|day| {
+                match member.day_stars(day) {
+                    0 => style::dim("·"),
+                    1 => style::warning("☆"),
+                    _ => style::success("★"),
+                }
+                .to_string()
+            })
+            .collect::<String>();
+        let _ = writeln!(
+            text,
+            "{:>3}) {:>5} {} {}",
+            rank,
+            member.local_score,
+            days,
+            member.display_name()
+        );
+    }
+    let _ = writeln!(text, "{}", style::dim("★ both stars, ☆ part 1 only"));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Response;
+
+    const LEADERBOARD: &str = include_str!("fixtures/leaderboard.json");
+
+    struct Mock(Response);
+
+    impl Client for Mock {
+        fn get(&self, _url: &str, _session: &str) -> Result<Response> {
+            Ok(self.0.clone())
+        }
+
+        fn post(&self, _url: &str, _session: &str, _form: &[(&str, &str)]) -> Result<Response> {
+            unreachable!("leaderboards are only fetched")
+        }
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let leaderboard = parse(LEADERBOARD)?;
+        assert_eq!(leaderboard.owner_id, 1001);
+        assert_eq!(leaderboard.event, "2023");
+        assert_eq!(leaderboard.members.len(), 4);
+        let owner = &leaderboard.members[&1001];
+        assert_eq!(owner.display_name(), "JehtBlack");
+        assert_eq!((owner.local_score, owner.stars), (118, 13));
+        assert_eq!((owner.day_stars(6), owner.day_stars(7)), (2, 1));
+        assert_eq!(
+            owner.completion_day_level[&7][&1],
+            Star {
+                get_star_ts: 1701937212
+            }
+        );
+        assert_eq!(
+            leaderboard.members[&2002].display_name(),
+            "(anonymous user #2002)"
+        );
+
+        // those with no stars can be missing what they haven't got
+        let leaderboard = parse(r#"{"owner_id":1,"event":"2023","members":{"5":{"id":5}}}"#)?;
+        let member = &leaderboard.members[&5];
+        assert_eq!((member.local_score, member.stars), (0, 0));
+        assert_eq!(member.day_stars(1), 0);
+        assert!(parse("<!DOCTYPE html>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let text = render(&parse(LEADERBOARD)?);
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "                    1111111111222222",
+                "           1234567890123456789012345",
+                "  1)   126 ★★★★★★★·················· (anonymous user #2002)",
+                "  2)   118 ★★★★★★☆·················· JehtBlack",
+                "  3)    18 ★·······☆················ late starter",
+                "  4)     0 ························· Quiet Quokka",
+                "★ both stars, ☆ part 1 only",
+            ]
+        );
+
+        // a leaderboard with nobody on it yet
+        let empty = parse(r#"{"owner_id":1,"event":"2023","members":{}}"#)?;
+        assert_eq!(render(&empty).lines().count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch() -> Result<()> {
+        let fetch = |status, body: &str| {
+            fetch(
+                &Mock(Response {
+                    status,
+                    body: body.to_string(),
+                }),
+                2023,
+                12345,
+                Some("abc"),
+            )
+        };
+        assert_eq!(fetch(200, LEADERBOARD)?, LEADERBOARD);
+        assert!(fetch(200, "<!DOCTYPE html>")
+            .unwrap_err()
+            .to_string()
+            .starts_with("adventofcode.com didn't send leaderboard 12345"));
+        assert!(fetch(500, "")
+            .unwrap_err()
+            .to_string()
+            .starts_with("adventofcode.com didn't take the session cookie"));
+        assert_eq!(
+            leaderboard_url(2023, 12345),
+            "https://adventofcode.com/2023/leaderboard/private/view/12345.json"
+        );
+        assert_eq!(
+            leaderboard_url(2022, 1),
+            "https://adventofcode.com/2022/leaderboard/private/view/1.json"
+        );
+        Ok(())
+    }
+}
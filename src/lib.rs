@@ -0,0 +1,68 @@
+//! Advent of Code 2023 solutions, exposed as a library so the `aoc-2023`
+//! binary, benchmarks and tests can share the same solvers. Days with an
+//! `analyze` function are public too, for what's behind their answers, eg.
+//! `gear_ratios::analyze`'s part numbers and gears.
+
+pub mod answers;
+pub mod badge;
+pub mod baseline;
+pub mod batch;
+pub mod bench;
+pub mod budget;
+pub mod calendar;
+pub mod clipboard;
+pub mod config;
+pub mod context;
+pub mod doctor;
+pub mod download;
+pub mod failure;
+pub mod history;
+pub mod html;
+pub mod http;
+pub mod input;
+pub mod interrupt;
+pub mod layout;
+pub mod leaderboard;
+pub mod mem;
+pub mod metadata;
+pub mod mismatch;
+pub mod notify;
+pub mod outcomes;
+pub mod picker;
+pub mod progress;
+pub mod puzzle_page;
+pub mod readme;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod schedule;
+pub mod solver;
+pub mod statement;
+pub mod status;
+pub mod style;
+pub mod submit;
+#[cfg(test)]
+mod testutil;
+pub mod timings;
+pub mod util;
+pub mod validate;
+pub mod verify;
+pub mod watch;
+
+#[cfg(feature = "day07")]
+pub mod camel_cards;
+#[cfg(feature = "day02")]
+mod cube_conundrum;
+#[cfg(feature = "day03")]
+pub mod gear_ratios;
+#[cfg(feature = "day05")]
+mod if_you_give_a_seed_a_fertilizer;
+#[cfg(feature = "day04")]
+pub mod scratchcards;
+#[cfg(feature = "slow-test-day")]
+mod slow_test_day;
+#[cfg(feature = "day01")]
+mod trebuchet;
+#[cfg(feature = "day06")]
+mod wait_for_it;
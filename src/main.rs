@@ -1,20 +1,62 @@
 use core::fmt;
-use std::path::PathBuf;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use anyhow::{anyhow, Result};
-use clap::{Parser, ValueEnum};
+use answers::Answers;
+use anyhow::{anyhow, Context, Result};
+use aoc_2023::{
+    answers, badge, baseline, batch, bench, budget, calendar, clipboard, config, context, doctor,
+    download, failure, history, html, http, input, interrupt, layout, leaderboard, mem, metadata,
+    mismatch, notify, outcomes, picker, progress, puzzle_page, readme, registry, report, runner,
+    schedule, solver, statement, status, style, submit, timings,
+    util::{
+        generate,
+        ranges::{Span, SpanSet},
+    },
+    verify, watch,
+};
+use baseline::{Baseline, BaselineOptions, PartTime};
+use bench::{BenchOptions, BenchRecord};
+use budget::Budgets;
+use calendar::Timezone;
+use chrono::{DateTime, Local, Utc};
+use clap::{
+    builder::{NonEmptyStringValueParser, PossibleValuesParser},
+    error::{ContextKind, ContextValue, ErrorKind},
+    ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum,
+};
+use clap_complete::Shell;
+use config::{ConfigFile, Overrides, Settings};
+use context::Verbosity;
 use dotenv::dotenv;
-use solver::{MultiSolver, Solver};
-
-pub mod solver;
-
-mod camel_cards;
-mod cube_conundrum;
-mod gear_ratios;
-mod if_you_give_a_seed_a_fertilizer;
-mod scratchcards;
-mod trebuchet;
-mod wait_for_it;
+use failure::FailureKind;
+use input::ParseMode;
+use interrupt::Interrupt;
+use layout::Layout;
+use lazy_static::lazy_static;
+use metadata::DayFilter;
+use outcomes::{Check, Outcome, Outcomes};
+use progress::{no_progress, ProgressBars};
+use registry::{DayNameError, Registry};
+use report::TableFormat;
+use runner::{PartOptions, RunResult, ScheduleOptions, Status, Summary};
+use schedule::Estimates;
+use solver::{format_duration, Answer, Puzzle};
+use submit::{
+    log::{Decision, Submission},
+    Verdict,
+};
+use timings::TimingRecord;
+use tracing::info;
+use tracing_subscriber::EnvFilter;
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, ValueEnum)]
 enum Part {
@@ -26,69 +68,142 @@ enum Part {
     All,
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, ValueEnum)]
-enum DayTitles {
-    All,
-    Trebuchet,
-    CubeConundrum,
-    GearRatios,
-    Scratchcards,
-    IfYouGiveASeedAFertilizer,
-    WaitForIt,
-    CamelCards,
+lazy_static! {
+    static ref REGISTRY: Registry = Registry::builtin();
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 enum Day {
+    All,
     Numeric(u8),
-    Name(DayTitles),
+    /// Several days, in order and without duplicates.
+    Selection(Vec<u8>),
+    /// Whichever day it is, see [`Day::resolve_today`].
+    Today,
+}
+
+impl Day {
+    /// Resolves [`Day::Today`] to the puzzle that's out at `now`, which has to
+    /// be in `registry`. Other days are left as they are.
+    fn resolve_today(
+        self,
+        registry: &Registry,
+        now: DateTime<Utc>,
+        timezone: Timezone,
+    ) -> Result<Day> {
+        let Day::Today = self else {
+            return Ok(self);
+        };
+        let day = calendar::puzzle_day(calendar::date_at(now, timezone))?;
+        if registry.get(day).is_none() {
+            return Err(FailureKind::NotImplemented
+                .error(anyhow!("Today is day {}, which isn't implemented yet", day)));
+        }
+        Ok(Day::Numeric(day))
+    }
+
+    fn resolve(self, settings: &Settings) -> Result<Day> {
+        self.resolve_today(&REGISTRY, Utc::now(), settings.timezone)
+    }
 }
 
 impl fmt::Display for Day {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Day::All => write!(f, "All"),
+            Day::Today => write!(f, "Today"),
             Day::Numeric(n) => write!(f, "Day {}", n),
-            Day::Name(d) => write!(f, "{:?}", d),
+            Day::Selection(days) => {
+                let days = days.iter().map(u8::to_string).collect::<Vec<_>>();
+                write!(f, "Days {}", days.join(", "))
+            }
         }
     }
 }
 
-fn valid_day(s: &str) -> Result<Day, clap::Error> {
-    match s.parse::<u8>() {
-        Ok(n) => {
-            if n == 0 || n > 25 {
-                return Err(clap::Error::raw(
-                    clap::error::ErrorKind::InvalidValue,
-                    format!("\n{}", day_values_error()),
-                ));
-            }
-            Ok(Day::Numeric(n))
-        }
-        Err(_) => {
-            let possible = DayTitles::from_str(s, true);
-            match possible {
-                Ok(d) => Ok(Day::Name(d)),
-                Err(_) => Err(clap::Error::raw(
-                    clap::error::ErrorKind::InvalidValue,
-                    format!("\n{}", day_values_error()),
-                )),
+fn valid_day(s: &str) -> Result<Day, String> {
+    if s.eq_ignore_ascii_case("all") {
+        return Ok(Day::All);
+    }
+    if s.eq_ignore_ascii_case("today") {
+        return Ok(Day::Today);
+    }
+    if s.eq_ignore_ascii_case("latest") {
+        return latest_day(&REGISTRY).map(Day::Numeric);
+    }
+    match REGISTRY.select(s) {
+        Ok(days) => match days.as_slice() {
+            [day] => Ok(Day::Numeric(*day)),
+            _ => Ok(Day::Selection(days)),
+        },
+        // suggestions are more use than the whole list
+        Err(e) => match e.downcast_ref::<DayNameError>() {
+            Some(DayNameError::DidYouMean { .. } | DayNameError::Ambiguous { .. }) => {
+                Err(e.to_string())
             }
-        }
+            _ => Err(format!("{}\n{}", e, day_values_error())),
+        },
     }
 }
 
+/// The highest implemented day for "latest", whatever the date.
+fn latest_day(registry: &Registry) -> Result<u8, String> {
+    registry.latest().ok_or_else(|| {
+        "latest is the highest implemented day, but this build has none, build it with a day's feature such as day07".to_string()
+    })
+}
+
+/// The accepted day selectors, generated from the registered puzzles so they
+/// only list the days compiled in.
 fn possible_day_values() -> Vec<String> {
-    vec!["1..24".to_string()]
-        .into_iter()
-        .chain(
-            DayTitles::value_variants()
-                .iter()
-                .map(|d| format!("{}", d.to_possible_value().unwrap().get_name()))
-                .collect::<Vec<String>>(),
-        )
+    let days = REGISTRY
+        .days()
+        .map(|(day, _)| Span::new(day as u64, day as u64 + 1))
+        .collect::<SpanSet>();
+    days.spans()
+        .iter()
+        .map(|span| match span.len() {
+            1 => span.start.to_string(),
+            _ => format!("{}-{}", span.start, span.end - 1),
+        })
+        .chain(["all", "today", "latest"].map(str::to_string))
+        .chain(REGISTRY.names().map(|(_, name)| name))
         .collect::<Vec<String>>()
 }
 
+/// What tab completion offers for a day, every day on its own rather than the
+/// ranges [`possible_day_values`] shows.
+fn completion_day_values() -> Vec<String> {
+    REGISTRY
+        .days()
+        .map(|(day, _)| day.to_string())
+        .chain(["all", "today", "latest"].map(str::to_string))
+        .chain(REGISTRY.names().map(|(_, name)| name))
+        .collect()
+}
+
+/// The completion script for `shell`. The day arguments are parsed by
+/// [`valid_day`], which clap can't see into, so the registry's days are given
+/// to the generator as their possible values.
+fn completions(shell: Shell) -> String {
+    let days = completion_day_values();
+    let with_days = |command: clap::Command| {
+        // in place, mut_arg would move the day after the other positionals
+        command.mut_args(|arg| match arg.get_id().as_str() {
+            "day" => arg.value_parser(PossibleValuesParser::new(days.clone())),
+            _ => arg,
+        })
+    };
+    let mut command = with_days(Cli::command())
+        .mut_subcommand("run", with_days)
+        .mut_subcommand("bench", with_days)
+        .mut_subcommand("verify", with_days);
+    let name = command.get_name().to_string();
+    let mut script = vec![];
+    clap_complete::generate(shell, &mut command, name, &mut script);
+    String::from_utf8_lossy(&script).into_owned()
+}
+
 fn possible_day_values_string(join_str: &str) -> String {
     possible_day_values().join(join_str)
 }
@@ -98,81 +213,3722 @@ fn day_values_error() -> String {
 }
 
 fn day_values_help() -> String {
-    format!("Possible values:\n- {}", possible_day_values_string("\n- "))
+    format!(
+        "Possible values, several days can be given as a comma separated list such as 1-3,7:\n- {}",
+        possible_day_values_string("\n- ")
+    )
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// Human readable lines
+    Text,
+    /// One JSON object per event
+    Json,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ColorWhen {
+    /// Only when stdout and stderr are terminals and NO_COLOR isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+/// How a run of several days is printed.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Each day's parts under its title as it finishes
+    #[default]
+    Plain,
+    /// A GitHub flavoured markdown table with a row per day
+    Markdown,
+    /// The markdown table's columns as CSV
+    Csv,
+}
+
+/// How `verify` reports the examples it checked.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+enum VerifyFormat {
+    /// A line per day as it's checked
+    #[default]
+    Plain,
+    /// JUnit XML, a test case per part
+    Junit,
+    /// Test Anything Protocol, a test per part
+    Tap,
+}
+
+impl OutputFormat {
+    fn table(self) -> Option<TableFormat> {
+        match self {
+            OutputFormat::Plain => None,
+            OutputFormat::Markdown => Some(TableFormat::Markdown),
+            OutputFormat::Csv => Some(TableFormat::Csv),
+        }
+    }
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    solver::parse_duration(s).map_err(|e| e.to_string())
 }
 
 #[derive(Parser)]
-#[command(author, about, version)]
+#[command(
+    author,
+    about,
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    after_long_help = format!("{}\n\n{}", config::ENVIRONMENT_HELP, failure::EXIT_CODES_HELP)
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    run: RunArgs,
+    /// Format of the diagnostics written to stderr, their verbosity is set with RUST_LOG
+    #[arg(long, value_enum, default_value_t = LogFormat::Text, global = true)]
+    log_format: LogFormat,
+    /// When to colour answers, failures and timings
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorWhen::Auto, global = true)]
+    color: ColorWhen,
+    /// Mask answers in everything printed for people to read, eg. when streaming, leaving only how long they are. --json and --output still have them
+    #[arg(long, global = true)]
+    hide_answers: bool,
+    /// The data root to look for inputs in, overriding $AOC_DATA_DIR
+    #[arg(long, value_name = "DIR", global = true)]
+    data_dir: Option<PathBuf>,
+    /// The event year, for adventofcode.com and the year's directory in the data root, overriding
+    /// $AOC_YEAR
+    #[arg(long, global = true)]
+    year: Option<u16>,
+    /// Where each day's input is in the data root: nested, flat or a template like
+    /// inputs/day{day:02}.txt, overriding $AOC_LAYOUT
+    #[arg(long, global = true)]
+    layout: Option<Layout>,
+    /// What each day's input file is called in the nested layout, eg. day{day:02}.txt,
+    /// overriding $AOC_INPUT_NAME
+    #[arg(long, value_name = "PATTERN", value_parser = NonEmptyStringValueParser::new(), global = true)]
+    input_name: Option<String>,
+    /// The profile in aoc.toml to use, for its session and data directory, overriding $AOC_PROFILE
+    #[arg(long, value_name = "NAME", global = true)]
+    profile: Option<String>,
+}
+
+impl Cli {
+    fn overrides(&self) -> Overrides {
+        Overrides {
+            data_dir: self.data_dir.clone(),
+            year: self.year,
+            layout: self.layout.clone(),
+            input_name: self.input_name.clone(),
+            profile: self.profile.clone(),
+        }
+    }
+}
+
+/// Without a subcommand the arguments are taken as `run`'s, so `aoc 7 part1`
+/// is the same as `aoc run 7 part1`.
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one or more days, the default when no subcommand is given
+    Run(Box<RunArgs>),
+    /// Time a day's parts over several runs and report statistics
+    Bench(BenchArgs),
+    /// Solve a day again each time its input, or example, changes
+    Watch(WatchArgs),
+    /// Check days against the examples from their puzzle statements
+    Verify(VerifyArgs),
+    /// Download a day's puzzle input into the data directory
+    Download(DownloadArgs),
+    /// Download a day's puzzle description as markdown into the data directory, for --describe
+    FetchPuzzle(FetchPuzzleArgs),
+    /// Save the example in a day's puzzle description as its example input, for --example
+    FetchExample(FetchExampleArgs),
+    /// Show the stars on adventofcode.com against the days implemented here and the answers recorded
+    Status(StatusArgs),
+    /// Show a private leaderboard, fetched no more than once every 15 minutes
+    Leaderboard(LeaderboardArgs),
+    /// Wait for the next puzzle to come out, then download its input
+    Await(AwaitArgs),
+    /// Submit an answer for one part of a day
+    Submit(SubmitArgs),
+    /// Generate the module for a new day
+    New(NewArgs),
+    /// List the implemented days with their parts, tags, difficulty, runtime class and whether their input is present
+    List(ListArgs),
+    /// Print a completion script for the shell, including the implemented days' names
+    Completions(CompletionsArgs),
+    /// Check every implemented day's input is present and looks like a real one, before a long run
+    Doctor(DoctorArgs),
+    /// Run the jobs listed in a TOML file, each a day and part with its input and optionally the answer expected, and report them as a table
+    Batch(BatchArgs),
+    /// Work with a --timings-log file
+    Timings(TimingsArgs),
+    /// Keep reports of progress up to date
+    Report(ReportArgs),
+    /// List the profiles in aoc.toml, one per adventofcode.com account, or pick the default
+    Profile(ProfileArgs),
+    /// Print a day's times over the runs kept with --record-history, with a sparkline per part
+    History(HistoryArgs),
+    /// Print a large made-up input for a day, for stress testing and benchmarks
+    #[command(hide = true)]
+    Gen(GenArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(value_parser = valid_day, help = day_values_help())]
+    day: Option<Day>,
+    /// The part to solve, part1, part2 or all (the default), followed by the input file, a quoted glob pattern such as "inputs/*.txt" to solve each matching input, - to read it from stdin, or the data directory when running all days. The input defaults to <DAY>/input under the data root, or the data root itself, see --data-dir
+    #[arg(value_names = ["PART", "INPUT"], num_args = 1..=2)]
+    part_and_input: Vec<PathBuf>,
+    #[arg(skip)]
+    part: Option<Part>,
+    #[arg(skip)]
+    input: Option<PathBuf>,
+    /// Check every implemented day against the examples from its puzzle statement
+    #[arg(long, exclusive = true)]
+    self_test: bool,
+    /// List the implemented days with their tags, difficulty and runtime class
+    #[arg(long)]
+    list: bool,
+    /// Print the puzzle statement for the day instead of solving it, only for the part if one is given
+    #[arg(long, requires = "day", conflicts_with = "list")]
+    describe: bool,
+    /// Only parse INPUT for the day, reporting what it contains and any malformed lines, instead of solving it
+    #[arg(long, value_name = "INPUT", requires = "day", conflicts_with_all = ["part_and_input", "list", "describe"])]
+    parse_only: Option<PathBuf>,
+    /// Leave out days marked as slow when running or listing all days
+    #[arg(long)]
+    skip_slow: bool,
+    /// Only run or list days with this tag, may be given more than once
+    #[arg(long, value_name = "TAG")]
+    only_tag: Vec<String>,
+    /// Leave a day out of a run of several days, reporting it as skipped. Give a number or name, more than once or as --skip 5,23
+    #[arg(long, value_name = "DAY", value_delimiter = ',', value_parser = skipped_day, conflicts_with = "list")]
+    skip: Vec<u8>,
+    /// Solve only the first N lines of the input, after it's checked or tidied, to narrow down where a parser goes wrong
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["bench", "save_baseline", "compare_baseline"])]
+    limit_lines: Option<u32>,
+    /// Don't report how long each part took
+    #[arg(long)]
+    no_timing: bool,
+    /// Fail without a day rather than asking which one to run, which only happens in a terminal anyway
+    #[arg(long)]
+    no_interactive: bool,
+    /// Solve part 2 from this input instead, eg. an example that only applies to part 2, when both parts of a single day run
+    #[arg(long, value_name = "INPUT", requires = "day", conflicts_with_all = ["list", "describe", "parse_only", "paste", "bench", "compare_impls", "cross_check"])]
+    input2: Option<PathBuf>,
+    /// Record the parts' answers in answers.toml in the data root once they're right, ie. solved and matching any --answer, keeping any recorded already
+    #[arg(long, conflicts_with_all = ["example", "limit_lines", "input2", "bench", "compare_impls", "cross_check", "list", "describe", "parse_only"])]
+    record: bool,
+    /// Keep the parts' times and statuses in the history in .aoc, for the history command. Needs the history feature
+    #[arg(long, conflicts_with_all = ["example", "limit_lines", "bench", "list", "describe", "parse_only"])]
+    record_history: bool,
+    /// Post a summary of the run, the days run, stars, time and any failures, to a Discord or Slack webhook at URL, or the one set as AOC_NOTIFY_WEBHOOK or notify_webhook in aoc.toml. A webhook that can't be reached is only warned about
+    #[arg(long, value_name = "URL", num_args = 0..=1, conflicts_with_all = ["bench", "list", "describe", "parse_only"])]
+    notify_webhook: Option<Option<String>>,
+    /// Solve the input copied to the clipboard, rather than one saved to a file
+    #[arg(long, conflicts_with_all = ["example", "list", "describe", "parse_only"])]
+    paste: bool,
+    /// Also save the pasted input where the day's input is looked for, so later runs can do without the clipboard
+    #[arg(long, requires = "paste")]
+    save: bool,
+    /// Download the day's input first if it isn't saved yet, needs the session cookie set as AOC_SESSION or session in aoc.toml
+    #[arg(long, conflicts_with_all = ["example", "paste", "list", "describe"])]
+    download: bool,
+    /// Solve the part(s) N times, discarding the first run as a warm-up, and report timing statistics
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+    bench: Option<u32>,
+    /// Bench even when a single run exceeds the sanity limit
+    #[arg(long, requires = "bench")]
+    force: bool,
+    /// Print the results as a JSON array with one object per part, or the listing or bench statistics as JSON, and nothing else on stdout
+    #[arg(long, conflicts_with_all = ["describe", "parse_only"])]
+    json: bool,
+    /// How to print a run of several days, a table has a row per day with the Day, Title, Part 1, Part 2 and Time columns and the summary goes to stderr
+    #[arg(long, value_enum, default_value_t, conflicts_with_all = ["json", "bench"])]
+    format: OutputFormat,
+    /// Also write the results to FILE as JSON, a record per part with when it ran and the commit the binary was built from
+    #[arg(long, value_name = "FILE", conflicts_with = "bench")]
+    output: Option<PathBuf>,
+    /// Add to the --output file as JSON Lines, a line per part, instead of replacing it
+    #[arg(long, requires = "output")]
+    append: bool,
+    /// Also write a report of the run to FILE as a self-contained HTML page, with each part's answer and time, the details of any failures and the input hashes
+    #[arg(long, value_name = "FILE", conflicts_with = "bench")]
+    report: Option<PathBuf>,
+    /// Append a JSON line per solved part to FILE with its time and hashes of its answer and input, for tracking performance in CI, see the timings command
+    #[arg(long, value_name = "FILE")]
+    timings_log: Option<PathBuf>,
+    /// How long all the parts run may take to solve together, eg. 1s, not counting reading or parsing inputs. Days can have budgets of their own in a [budgets] table in aoc.toml
+    #[arg(long, value_name = "DURATION", value_parser = parse_timeout, conflicts_with = "bench")]
+    budget: Option<Duration>,
+    /// Fail when a day or the run took longer than its budget, rather than only warning
+    #[arg(long, conflicts_with = "bench")]
+    enforce_budget: bool,
+    /// Run the named implementation of each part instead of its default
+    #[arg(long = "impl", value_name = "NAME", conflicts_with_all = ["compare_impls", "cross_check"])]
+    implementation: Option<String>,
+    /// Run every implementation of each part and fail if their answers disagree
+    #[arg(long, conflicts_with = "bench")]
+    compare_impls: bool,
+    /// Like --compare-impls, for checking a fast implementation against a brute force: inputs too big for the brute force are refused rather than left to run for minutes
+    #[arg(long, conflicts_with_all = ["bench", "compare_impls"])]
+    cross_check: bool,
+    /// Stop the all-days run at the first day that fails
+    #[arg(long)]
+    fail_fast: bool,
+    /// Number of days to run at once when running all days, starting those that took longest before first when the baseline or --output file from an earlier run says which they are
+    #[arg(long, short, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    jobs: u32,
+    /// Give up on any part still running after this long, eg. 30s or 500ms
+    #[arg(long, value_name = "DURATION", value_parser = parse_timeout, conflicts_with = "bench")]
+    timeout: Option<Duration>,
+    /// When running several days, how long the days still running get to finish after Ctrl-C before they're given up on, eg. 5s. They're waited for without it, a second Ctrl-C stops at once
+    #[arg(long, value_name = "DURATION", value_parser = parse_timeout)]
+    grace_period: Option<Duration>,
+    /// Show a progress bar per day on stderr, only when it is a terminal
+    #[arg(long)]
+    progress: bool,
+    /// Have the solvers show their work on stderr, eg. each scratchcard's matches, and everything they can with -vv
+    #[arg(long, short, action = ArgAction::Count, conflicts_with = "bench")]
+    verbose: u8,
+    /// Solve the day's example, data/<DAY>/example, or data/<DAY>/example<N> when N is given, instead of its input. Layouts without a directory per day add -example<N> to the input's name instead. Running several days skips those without one
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+    example: Option<u32>,
+    /// The expected answer, an integer or text, exiting non-zero when the day's differs. When running both parts give one per part, as --answer A --answer B or --answer A,B
+    #[arg(long, value_name = "ANSWER", value_delimiter = ',', value_parser = expected_answer, conflicts_with_all = ["bench", "compare_impls", "cross_check"])]
+    answer: Vec<Answer>,
+    /// Record each part's time as the baseline NAME when running several days, updating it if it exists, for --compare-baseline to compare later runs with
+    #[arg(long, value_name = "NAME", value_parser = baseline_name)]
+    save_baseline: Option<String>,
+    /// Compare each part's time with the baseline NAME when running several days, failing if any got slower than --regression-threshold allows. With --bench N each part is timed as the best of N runs
+    #[arg(long, value_name = "NAME", value_parser = baseline_name)]
+    compare_baseline: Option<String>,
+    /// How much slower than its baseline a part may get, in percent, before it counts as a regression. Slowdowns of under 1ms never do, as that's noise
+    #[arg(long, value_name = "PERCENT", default_value_t = baseline::DEFAULT_THRESHOLD, value_parser = regression_threshold, requires = "compare_baseline")]
+    regression_threshold: f64,
+    #[command(flatten)]
+    parsing: ParseArgs,
+    #[command(flatten)]
+    rerun: RerunArgs,
+}
+
+/// How strictly inputs are checked before a day's parser sees them.
+#[derive(Args)]
+struct ParseArgs {
+    /// Fail on the first blank line or trailing whitespace in the input, the default
+    #[arg(long, conflicts_with = "lenient")]
+    strict: bool,
+    /// Skip blank lines and trim trailing whitespace in the input, for inputs written by hand
+    #[arg(long)]
+    lenient: bool,
+    /// Strip comment lines, and comments after a space at the end of a line, from the input before it's checked, for annotated examples. Day 3 only has whole line comments as # is one of its symbols
+    #[arg(long)]
+    allow_comments: bool,
+    /// What comments start with
+    #[arg(long, value_name = "PREFIX", default_value = input::DEFAULT_COMMENT_PREFIX, value_parser = comment_prefix, requires = "allow_comments")]
+    comment_prefix: String,
+}
+
+/// Going back to the days that failed last time, see [`Outcomes`].
+#[derive(Args)]
+struct RerunArgs {
+    /// Only the days that had a part fail the last time they were checked, out of those selected or every day
+    #[arg(long)]
+    rerun_failed: bool,
+    /// Forget which days failed before checking them again
+    #[arg(long, conflicts_with = "rerun_failed")]
+    reset: bool,
+}
+
+impl RerunArgs {
+    /// Forgets the outcomes if asked to, then picks out the days that failed
+    /// their last `check` from `days`, or from every day when it's empty.
+    /// `None` when not rerunning failures.
+    fn select(
+        &self,
+        check: Check,
+        days: &[u8],
+        data_root: &Path,
+        layout: &Layout,
+    ) -> Result<Option<Vec<u8>>> {
+        if self.reset {
+            Outcomes::reset(data_root)?;
+        }
+        if !self.rerun_failed {
+            return Ok(None);
+        }
+        let failed = Outcomes::load(data_root).failed_days(check, |day| {
+            input::read(&layout.input(data_root, day))
+                .ok()
+                .map(|data| input::hash(&data))
+        });
+        Ok(Some(
+            failed
+                .into_iter()
+                .filter(|day| days.is_empty() || days.contains(day))
+                .collect(),
+        ))
+    }
+}
+
+impl ParseArgs {
+    fn mode(&self) -> ParseMode {
+        if self.lenient {
+            ParseMode::Lenient
+        } else {
+            ParseMode::Strict
+        }
+    }
+
+    /// What comments start with, `None` unless they're allowed.
+    fn comments(&self) -> Option<String> {
+        self.allow_comments.then(|| self.comment_prefix.clone())
+    }
+}
+
+#[derive(Args)]
+struct WatchArgs {
     #[arg(value_parser = valid_day, help = day_values_help())]
     day: Day,
-    #[arg(value_enum)]
+    /// The part to solve, both by default
+    #[arg(value_enum, default_value_t = Part::All)]
     part: Part,
-    input: PathBuf,
+    /// Solve and watch the day's example, or example N, instead of its input
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+    example: Option<u32>,
+    #[command(flatten)]
+    parsing: ParseArgs,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    #[arg(value_parser = valid_day, help = day_values_help())]
+    day: Day,
+    /// The part to bench, part1, part2 or all (the default), followed by the input file, which defaults to data/<DAY>/input
+    #[arg(value_names = ["PART", "INPUT"], num_args = 1..=2)]
+    part_and_input: Vec<PathBuf>,
+    #[arg(skip)]
+    part: Option<Part>,
+    #[arg(skip)]
+    input: Option<PathBuf>,
+    /// Solve each part N times, the first run is a warm-up and is discarded
+    #[arg(long, short = 'n', value_name = "N", default_value_t = 10, value_parser = clap::value_parser!(u32).range(2..))]
+    iterations: u32,
+    /// Bench even when a single run exceeds the sanity limit
+    #[arg(long)]
+    force: bool,
+    /// Print the statistics as JSON
+    #[arg(long)]
+    json: bool,
+    /// Bench the named implementation of each part instead of its default
+    #[arg(long = "impl", value_name = "NAME")]
+    implementation: Option<String>,
+    /// Append a JSON line per part to FILE with its median time and hashes of its answer and input, see the timings command
+    #[arg(long, value_name = "FILE")]
+    timings_log: Option<PathBuf>,
+    #[command(flatten)]
+    parsing: ParseArgs,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// The days to check, every implemented day by default
+    #[arg(value_parser = valid_day)]
+    day: Option<Day>,
+    /// The data directory with the inputs and answers.toml to check them against, the data root by default, see --data-dir
+    #[arg(value_name = "DATA_DIR")]
+    dir: Option<PathBuf>,
+    #[command(flatten)]
+    rerun: RerunArgs,
+    /// How to report the checks, junit and tap go to stdout in place of the lines per day unless written to a file with --output
+    #[arg(long, value_enum, default_value_t)]
+    format: VerifyFormat,
+    /// Write the junit or tap report to FILE
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Also solve the inputs of parts with no answer in answers.toml yet, and record the answers of those whose example passed
+    #[arg(long)]
+    record: bool,
+}
+
+fn baseline_name(s: &str) -> Result<String, String> {
+    baseline::file(Path::new(baseline::BASELINE_DIR), s)
+        .map(|_| s.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn regression_threshold(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(percent) if percent >= 0.0 && percent.is_finite() => Ok(percent),
+        _ => Err(format!("'{}' isn't a percentage, eg. 10 or 2.5", s)),
+    }
+}
+
+fn density(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(density) if (0.0..=1.0).contains(&density) => Ok(density),
+        _ => Err(format!("'{}' isn't a density from 0 to 1, eg. 0.3", s)),
+    }
+}
+
+fn skipped_day(s: &str) -> Result<u8, String> {
+    match REGISTRY.select(s).map_err(|e| e.to_string())?.as_slice() {
+        [day] => Ok(*day),
+        _ => Err(format!("'{}' isn't a single day", s)),
+    }
+}
+
+fn comment_prefix(s: &str) -> Result<String, String> {
+    if s.is_empty() || s.chars().any(char::is_whitespace) {
+        return Err("a comment prefix can't be empty or have spaces in it".to_string());
+    }
+    Ok(s.to_string())
+}
+
+fn expected_answer(s: &str) -> Result<Answer, String> {
+    s.parse().map_err(|e: anyhow::Error| e.to_string())
+}
+
+fn event_day(s: &str) -> Result<u8, String> {
+    match s.parse::<u8>() {
+        Ok(day) if (1..=registry::LAST_DAY).contains(&day) => Ok(day),
+        _ => Err(format!("expected a day from 1 to {}", registry::LAST_DAY)),
+    }
+}
+
+#[derive(Args)]
+struct DownloadArgs {
+    #[arg(value_parser = event_day)]
+    day: u8,
+    /// Download the input again even though it's saved already
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct FetchPuzzleArgs {
+    #[arg(value_parser = event_day)]
+    day: u8,
+    /// Fetch the page again rather than use the copy cached in .aoc/http, eg. once part 1 has been solved on the website
+    #[arg(long)]
+    refresh: bool,
+}
+
+#[derive(Args)]
+struct FetchExampleArgs {
+    #[arg(value_parser = event_day)]
+    day: u8,
+    /// Also save the first example only in part 2's description as example2
+    #[arg(long)]
+    part2: bool,
+    /// Replace examples already there
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct AwaitArgs {
+    /// Download the new day's input into the data directory once it's out
+    #[arg(long)]
+    download: bool,
+    /// Generate the new day's module once it's out, titled from its puzzle page
+    #[arg(long)]
+    new: bool,
+}
+
+#[derive(Args)]
+struct LeaderboardArgs {
+    /// The leaderboard's id, the number at the end of its URL
+    id: u64,
+    /// Print the leaderboard's JSON as adventofcode.com sends it, for scripts
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct StatusArgs {
+    /// Fetch the calendar again rather than use the copy cached in .aoc/http
+    #[arg(long)]
+    refresh: bool,
+    /// Don't fetch the calendar, show the stars recorded in answers.toml
+    #[arg(long, conflicts_with = "refresh")]
+    offline: bool,
+}
+
+#[derive(Args)]
+struct SubmitArgs {
+    #[arg(value_parser = event_day)]
+    day: u8,
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: u32,
+    /// The input to solve the part with for its answer, the day's input under the data root by default
+    #[arg(conflicts_with = "value")]
+    input: Option<PathBuf>,
+    /// Submit this answer rather than solving the part for it
+    #[arg(long, value_name = "ANSWER")]
+    value: Option<String>,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    #[arg(value_parser = event_day)]
+    day: u8,
+    /// The puzzle's title, eg. "Haunted Wasteland"
+    #[arg(long)]
+    title: String,
+}
+
+#[derive(Args)]
+struct CompletionsArgs {
+    shell: Shell,
+}
+
+#[derive(Args)]
+struct DoctorArgs {
+    /// The data directory to check, the data root by default, see --data-dir
+    data_dir: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct BatchArgs {
+    /// The jobs file, a [[job]] table per job with its day, part, input relative to the file and optionally the expected answer
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+    /// Number of jobs to run at once
+    #[arg(long, short, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    jobs: u32,
+    /// Give up on any job still running after this long, eg. 30s or 500ms
+    #[arg(long, value_name = "DURATION", value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+    #[command(flatten)]
+    parsing: ParseArgs,
+}
+
+#[derive(Args)]
+struct TimingsArgs {
+    #[command(subcommand)]
+    command: TimingsCommand,
+}
+
+#[derive(Subcommand)]
+enum TimingsCommand {
+    /// Print each part's last time, its best and the change from the time before
+    Summarize {
+        /// The timings log, as written with --timings-log
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    #[command(subcommand)]
+    command: ReportCommand,
+}
+
+#[derive(Subcommand)]
+enum ReportCommand {
+    /// Write a table of the days, their stars from answers.toml and their best times from a timings log into a README, between <!-- BEGIN aoc-2023 results --> and <!-- END aoc-2023 results --> lines
+    Readme {
+        /// The file to write the table into
+        #[arg(long, value_name = "FILE", default_value = "README.md")]
+        file: PathBuf,
+        /// The timings log to take each part's best time from, as written with --timings-log
+        #[arg(long, value_name = "FILE")]
+        timings: Option<PathBuf>,
+    },
+    /// Write the stars recorded in answers.toml as a shields.io endpoint badge, eg. 14/50 ⭐
+    Badge {
+        /// The file to write the badge's JSON to, rather than printing it
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Only count the one day's stars, out of its two
+        #[arg(long, value_parser = event_day)]
+        day: Option<u8>,
+    },
+}
+
+#[derive(Args)]
+struct ProfileArgs {
+    #[command(subcommand)]
+    command: ProfileCommand,
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// List the profiles with their data directories and session files, marking the one in use
+    List,
+    /// Use a profile when none is picked with --profile or $AOC_PROFILE, setting default_profile in aoc.toml
+    SetDefault {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
+#[derive(Args)]
+struct HistoryArgs {
+    #[arg(value_parser = event_day)]
+    day: u8,
+    /// Only the one part, both by default
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+    /// How many of the latest runs to show
+    #[arg(long, value_name = "N", default_value_t = history::DEFAULT_RUNS as u32, value_parser = clap::value_parser!(u32).range(1..))]
+    last: u32,
+}
+
+#[derive(Args)]
+struct GenArgs {
+    #[arg(value_parser = event_day)]
+    day: u8,
+    /// About how many lines the input has
+    #[arg(long, value_name = "N", default_value_t = 1000)]
+    lines: usize,
+    /// The seed of the random numbers, the same seed giving the same input
+    #[arg(long, value_name = "S", default_value_t = 0)]
+    seed: u64,
+    /// The share of spelled-out digits in day 1's input, or of symbols in day 3's, from 0 to 1
+    #[arg(long, value_name = "D", default_value_t = generate::DEFAULT_DENSITY, value_parser = density)]
+    density: f64,
+}
+
+#[derive(Args)]
+struct ListArgs {
+    /// Leave out days marked as slow
+    #[arg(long)]
+    skip_slow: bool,
+    /// Only list days with this tag, may be given more than once
+    #[arg(long, value_name = "TAG")]
+    only_tag: Vec<String>,
+    /// Print the listing as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// The first value is the part if it names one, which means an input file
+/// called eg. `part1` has to be given as `./part1`.
+fn split_part_and_input(
+    values: Vec<PathBuf>,
+) -> Result<(Option<Part>, Option<PathBuf>), clap::Error> {
+    let mut values = values.into_iter();
+    let mut first = values.next();
+    let part = first
+        .as_ref()
+        .and_then(|value| value.to_str())
+        .and_then(|value| Part::from_str(value, true).ok());
+    if part.is_some() {
+        first = values.next();
+    }
+    if values.next().is_some() {
+        return Err(Cli::command().error(
+            ErrorKind::InvalidValue,
+            format!(
+                "'{}' isn't a part, expected one of part1, part2 or all",
+                first.as_deref().unwrap_or(Path::new("")).display()
+            ),
+        ));
+    }
+    Ok((part, first))
+}
+
+impl Cli {
+    /// Parses the command line, then tells the optional part apart from the
+    /// input in the arguments after the day.
+    fn try_parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut cli = Self::try_parse_from(args)?;
+        match &mut cli.command {
+            None => cli.run.split_part_and_input(Self::command())?,
+            Some(Command::Run(args)) => {
+                // built, so its usage has the binary's name in it
+                let mut command = Self::command();
+                command.build();
+                let run = command
+                    .find_subcommand("run")
+                    .cloned()
+                    .expect("run is a subcommand");
+                args.split_part_and_input(run)?
+            }
+            Some(Command::Bench(args)) => {
+                (args.part, args.input) =
+                    split_part_and_input(std::mem::take(&mut args.part_and_input))?;
+            }
+            Some(_) => {}
+        }
+        Ok(cli)
+    }
+}
+
+impl RunArgs {
+    /// Also checks what clap can't: a day is needed unless one can be picked
+    /// interactively, see [`picker::available`].
+    fn split_part_and_input(&mut self, mut command: clap::Command) -> Result<(), clap::Error> {
+        let needs_day = !self.self_test && !self.list && !self.rerun.rerun_failed;
+        if self.day.is_none() && needs_day && (self.no_interactive || !picker::available()) {
+            return Err(command.error(
+                ErrorKind::MissingRequiredArgument,
+                "the following required arguments were not provided:\n  <DAY>",
+            ));
+        }
+        (self.part, self.input) = split_part_and_input(std::mem::take(&mut self.part_and_input))?;
+        if self.describe && self.input.is_some() {
+            return Err(command.error(
+                ErrorKind::ArgumentConflict,
+                "--describe only takes a day and optionally a part, not an input",
+            ));
+        }
+        Ok(())
+    }
+}
+
+struct RunOptions {
+    /// The event being run, see [`Settings::event_year`].
+    year: u16,
+    show_timing: bool,
+    bench: Option<BenchOptions>,
+    json: bool,
+    timeout: Option<Duration>,
+    progress: bool,
+    implementation: Option<String>,
+    compare_impls: bool,
+    /// The most steps an implementation may take with `compare_impls`, for
+    /// `--cross-check`, see [`runner::cross_check`].
+    cross_check: Option<u64>,
+    /// Which example to solve in place of each day's input.
+    example: Option<u32>,
+    /// The answers the parts must give, in part order, if any.
+    answers: Vec<Answer>,
+    format: OutputFormat,
+    /// Where to write the results too, and whether to append to it.
+    output: Option<(PathBuf, bool)>,
+    /// Where to write the HTML report to, see [`html`].
+    report: Option<PathBuf>,
+    /// The log to append each part's time to, see [`timings`].
+    timings_log: Option<PathBuf>,
+    budgets: Budgets,
+    /// Whether running over a budget fails the run, see `--enforce-budget`.
+    enforce_budget: bool,
+    verbosity: Verbosity,
+    parse_mode: ParseMode,
+    /// See [`PartOptions::comments`].
+    comments: Option<String>,
+    /// See [`PartOptions::limit_lines`].
+    limit_lines: Option<usize>,
+    /// Part 2's input when it isn't part 1's, see `--input2`.
+    input2: Option<PathBuf>,
+    baseline: BaselineOptions,
+    /// The data root to remember how each part did in for `--rerun-failed`,
+    /// `None` for runs not worth remembering, eg. of examples.
+    outcomes: Option<PathBuf>,
+    /// The data root to record the answers in with `--record`, see
+    /// [`answers::record`].
+    record: Option<PathBuf>,
+    /// Whether to keep the run in the history, see [`history`].
+    record_history: bool,
+    /// The webhook to post a summary of the run to, see [`notify`].
+    notify_webhook: Option<String>,
+}
+
+/// Exit code when nothing failed but at least one part hit the `--timeout`.
+fn timed_out() -> ExitCode {
+    ExitCode::from(FailureKind::TimedOut.exit_code())
+}
+
+/// What the failed parts among `results` have in common, see
+/// [`FailureKind::common`].
+fn failure_kind(results: &[RunResult]) -> FailureKind {
+    FailureKind::common(results.iter().filter_map(|result| result.failure))
+}
+
+fn not_implemented(day: u8) -> anyhow::Error {
+    FailureKind::NotImplemented.error(anyhow!("Day {} not implemented", day))
+}
+
+/// [`runner::prepare_input`] for commands that solve or parse an input
+/// themselves, naming it in any error.
+fn prepare_input<'a>(
+    data: &'a str,
+    input: &Path,
+    puzzle: &dyn Puzzle,
+    parse_mode: ParseMode,
+    comments: Option<&str>,
+) -> Result<Cow<'a, str>> {
+    runner::prepare_input(data, puzzle, parse_mode, comments)
+        .map_err(|e| FailureKind::Parse.error(anyhow!("{}: {}", runner::input_name(input), e)))
 }
 
-fn run_day<P1: Solver, P2: Solver>(
-    day_solver: Box<dyn MultiSolver<PartOne = P1, PartTwo = P2>>,
+impl Part {
+    fn numbers(&self) -> &'static [u32] {
+        match self {
+            Part::Part1 => &[1],
+            Part::Part2 => &[2],
+            Part::All => &[1, 2],
+        }
+    }
+
+    /// The part numbered `part`, or both when `None`.
+    fn from_number(part: Option<u32>) -> Self {
+        match part {
+            Some(1) => Part::Part1,
+            Some(2) => Part::Part2,
+            _ => Part::All,
+        }
+    }
+}
+
+fn bench_day(
+    day: u8,
+    puzzle: &dyn Puzzle,
     part: Part,
-    input: &PathBuf,
+    input: &Path,
+    bench_options: &BenchOptions,
+    options: &RunOptions,
 ) -> Result<()> {
-    match part {
-        Part::Part1 => day_solver
-            .get_part_one()
-            .run(input, Some(day_solver.get_puzzle_title()))?,
-        Part::Part2 => day_solver
-            .get_part_two()
-            .run(input, Some(day_solver.get_puzzle_title()))?,
-        Part::All => day_solver.run_all(input)?,
-    }
-    Ok(())
-}
-
-fn find_runner(day: u8, part: Part, filepath: &PathBuf) -> Result<()> {
-    match day {
-        1 => run_day(Box::new(trebuchet::Trebuchet), part, filepath),
-        2 => run_day(Box::new(cube_conundrum::CubeConundrum), part, filepath),
-        3 => run_day(Box::new(gear_ratios::GearRatios), part, filepath),
-        4 => run_day(Box::new(scratchcards::Scratchcards), part, filepath),
-        5 => run_day(
-            Box::new(if_you_give_a_seed_a_fertilizer::IfYouGiveASeedAFertilizer),
+    runner::check_day(day, puzzle)?;
+    // read once up front so file IO isn't part of the measurements
+    let data = runner::load_input(input)?;
+    let input_hash = input::hash(&data);
+    let data = prepare_input(
+        &data,
+        input,
+        puzzle,
+        options.parse_mode,
+        options.comments.as_deref(),
+    )?;
+    let json = options.json;
+    if !json {
+        println!("{}", puzzle.title());
+    }
+    let mut records = vec![];
+    for part in part.numbers() {
+        let solver = runner::find_implementation(puzzle, *part, options.implementation.as_deref())?;
+        let (answer, stats) = bench::bench(solver.as_ref(), &data, bench_options)?;
+        let (part, desc) = solver.part_description();
+        records.push(TimingRecord::new(
+            day,
             part,
-            filepath,
-        ),
-        6 => run_day(Box::new(wait_for_it::WaitForIt), part, filepath),
-        7 => run_day(Box::new(camel_cards::CamelCards), part, filepath),
-        _ => Err(anyhow!("Day {} not implemented", day)),
+            stats.median,
+            &answer,
+            Some(input_hash.clone()),
+        ));
+        if json {
+            let record = BenchRecord::new(day, part, answer, &stats);
+            println!("{}", serde_json::to_string(&record)?);
+        } else {
+            println!("[Part {}] {}: {}", part, desc, style::answer(&answer));
+            println!("    {}", stats);
+        }
+    }
+    if let Some(path) = &options.timings_log {
+        timings::append(path, &records)?;
     }
+    Ok(())
 }
 
-fn main() -> Result<()> {
-    dotenv().ok();
-    let cli = Cli::parse();
-    println!(
-        "User requested solution for {} (part: {:?})",
-        cli.day, cli.part
-    );
-
-    match cli.day {
-        Day::Numeric(n) => find_runner(n, cli.part, &cli.input)?,
-        Day::Name(DayTitles::All) => {
-            // run all days, input path is expected to be the base path
-            // containing numbered directories (eg. 01, 02, 03, etc.)
-            // with each containing the input file for that day called input with no extension
-            for day in 1..=24 {
-                let mut path = PathBuf::from(&cli.input);
-                path.push(format!("{:02}", day));
-                path.push("input");
-                find_runner(day, cli.part, &path)?;
+/// Prints the results for one day under its puzzle title. Days that were
+/// skipped entirely print nothing, they're listed in the summary instead.
+fn print_day(results: &[RunResult], show_timing: bool) {
+    if results
+        .iter()
+        .all(|result| matches!(result.status, Status::Skipped(_)))
+    {
+        return;
+    }
+    match results.first() {
+        Some(first) if !first.title.is_empty() => println!("{}", style::heading(&first.title)),
+        Some(first) => println!("{}", style::heading(format!("Day {}", first.day))),
+        None => return,
+    }
+    if let Some(lines) = results.iter().find_map(|result| result.truncated) {
+        println!("{}", truncated(lines));
+    }
+    for result in results {
+        let prefix = match &result.implementation {
+            Some(name) => format!("[Part {}, {}] {}", result.part, name, result.description),
+            None => format!("[Part {}] {}", result.part, result.description),
+        };
+        let mut details = vec![];
+        if let Some(timing) = result.timing.filter(|_| show_timing) {
+            details.push(timing.to_string());
+        }
+        if let Some(bytes) = result.peak_memory.filter(|_| show_timing) {
+            details.push(format!("peak {}", mem::format_bytes(bytes)));
+        }
+        match (&result.status, &result.answer) {
+            (Status::Solved, Some(answer)) if !details.is_empty() => println!(
+                "{}: {} {}",
+                prefix,
+                style::answer(answer),
+                style::dim(format!("({})", details.join(", ")))
+            ),
+            (Status::Solved, Some(answer)) => println!("{}: {}", prefix, style::answer(answer)),
+            (Status::Failed(e), _) => {
+                println!("{}: {}", prefix, style::failure(format!("failed: {}", e)))
             }
+            (Status::TimedOut(reason), _) => println!(
+                "{}: {}",
+                prefix,
+                style::warning(format!("timed out, {}", reason))
+            ),
+            _ => {}
+        }
+    }
+}
+
+fn run_single_day(
+    registry: &Registry,
+    day: u8,
+    part: Part,
+    input: &Path,
+    options: &RunOptions,
+) -> Result<ExitCode> {
+    if let Some(bench_options) = &options.bench {
+        let puzzle = registry.get(day).ok_or_else(|| not_implemented(day))?;
+        bench_day(day, puzzle, part, input, bench_options, options)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let bar = options
+        .progress
+        .then(ProgressBars::for_stderr)
+        .flatten()
+        .zip(registry.get(day))
+        .map(|(bars, puzzle)| bars.day_bar(puzzle.title()));
+    let part_options = PartOptions {
+        timeout: options.timeout,
+        progress: match &bar {
+            Some(bar) => Arc::new(bar.clone()),
+            None => no_progress(),
+        },
+        implementation: options.implementation.clone(),
+        parse_mode: options.parse_mode,
+        comments: options.comments.clone(),
+        limit_lines: options.limit_lines,
+        interrupt: None,
+        verbosity: options.verbosity,
+    };
+    let results = if options.compare_impls {
+        compare_day(
+            registry,
+            day,
+            part,
+            input,
+            &part_options,
+            options.cross_check,
+        )?
+    } else if let Some(input2) = &options.input2 {
+        if runner::is_stdin(input) && runner::is_stdin(input2) {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "Only one of the parts can read its input from stdin"
+            )));
         }
-        Day::Name(DayTitles::Trebuchet) => find_runner(1, cli.part, &cli.input)?,
-        Day::Name(DayTitles::CubeConundrum) => find_runner(2, cli.part, &cli.input)?,
-        Day::Name(DayTitles::GearRatios) => find_runner(3, cli.part, &cli.input)?,
-        Day::Name(DayTitles::Scratchcards) => find_runner(4, cli.part, &cli.input)?,
-        Day::Name(DayTitles::IfYouGiveASeedAFertilizer) => find_runner(5, cli.part, &cli.input)?,
-        Day::Name(DayTitles::WaitForIt) => find_runner(6, cli.part, &cli.input)?,
-        Day::Name(DayTitles::CamelCards) => find_runner(7, cli.part, &cli.input)?,
+        let mut results = runner::run_day(registry, day, &[1], input, &part_options);
+        results.extend(runner::run_day(registry, day, &[2], input2, &part_options));
+        results
+    } else {
+        runner::run_day(registry, day, part.numbers(), input, &part_options)
     };
+    if let Some(bar) = bar {
+        bar.finish_and_clear();
+    }
+    if let Some((path, append)) = &options.output {
+        report::write_results(path, &results, *append)?;
+    }
+    if let Some(path) = &options.report {
+        html::write(path, &results, &html::Metadata::new(&[(day, input)]))?;
+    }
+    if let Some(path) = &options.timings_log {
+        timings::append(path, &timings::records(&results, |_| input_hash(input)))?;
+    }
+    if options.record_history {
+        record_history(&results, options.year)?;
+    }
+    if let Some(url) = &options.notify_webhook {
+        notify_webhook(url, options.year, &results);
+    }
+    let from_file = !(runner::is_stdin(input) || runner::is_clipboard(input));
+    if let (Some(state_root), true) = (&options.outcomes, from_file) {
+        let outcomes = run_outcomes(&results, |_| Some(input.to_path_buf()), &options.answers);
+        remember(state_root, &outcomes)?;
+    }
+    if let [result] = results.as_slice() {
+        // a lone part that failed has nothing else worth printing around it,
+        // except for scripts reading the JSON
+        if let (Status::Failed(e), false) = (&result.status, options.json) {
+            let kind = result.failure.unwrap_or(FailureKind::Other);
+            return Err(kind.error(anyhow!("{}", e)));
+        }
+    }
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        print_day(&results, options.show_timing);
+        if let Some(input2) = &options.input2 {
+            println!(
+                "{}",
+                style::dim(format!(
+                    "Part 1 read {}, part 2 read {}",
+                    runner::input_name(input),
+                    runner::input_name(input2)
+                ))
+            );
+        }
+    }
+    if results.iter().any(RunResult::is_failure) {
+        return Err(failure_kind(&results).error(anyhow!("Day {} failed", day)));
+    }
+    check_answers(&results, &options.answers)?;
+    if let Some(data_root) = &options.record {
+        record_answers(data_root, &results)?;
+    }
+    check_budgets(&results, options)?;
+    if results
+        .iter()
+        .any(|result| matches!(result.status, Status::TimedOut(_)))
+    {
+        return Ok(timed_out());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Compares each solved part with its expected answer, if any were given,
+/// reporting every mismatch before erroring.
+fn check_answers(results: &[RunResult], expected: &[Answer]) -> Result<()> {
+    let mut mismatches = 0;
+    for (result, answer_expected) in results.iter().zip(expected) {
+        let Some(answer) = &result.answer else {
+            continue;
+        };
+        if answer == answer_expected {
+            continue;
+        }
+        eprintln!(
+            "[Part {}] {}",
+            result.part,
+            style::failure("doesn't match the expected answer")
+        );
+        let other_part = results
+            .iter()
+            .position(|other| other.part != result.part)
+            .and_then(|index| expected.get(index));
+        for line in mismatch::explain(answer_expected, answer, other_part) {
+            eprintln!("  {}", line);
+        }
+        mismatches += 1;
+    }
+    if mismatches > 0 {
+        return Err(FailureKind::Mismatch.error(anyhow!("{} answer(s) didn't match", mismatches)));
+    }
     Ok(())
 }
+
+/// Runs every implementation of the selected parts, erroring if any disagree,
+/// or if any would take more than `max_steps` when it's given.
+fn compare_day(
+    registry: &Registry,
+    day: u8,
+    part: Part,
+    input: &Path,
+    part_options: &PartOptions,
+    max_steps: Option<u64>,
+) -> Result<Vec<RunResult>> {
+    let puzzle = registry.get(day).ok_or_else(|| not_implemented(day))?;
+    let data = runner::load_input(input)?;
+    let mut results = vec![];
+    for part in part.numbers() {
+        results.extend(match max_steps {
+            Some(max_steps) => {
+                runner::cross_check(day, puzzle, *part, &data, part_options, max_steps)?
+            }
+            None => runner::compare_implementations(day, puzzle, *part, &data, part_options)?,
+        });
+    }
+    Ok(results)
+}
+
+/// Runs every implemented day with its input from the data root, see
+/// [`runner::all_days`]. Failing days are reported in a summary at the end
+/// instead of stopping the run, unless `fail_fast` is set.
+fn run_all_days(
+    registry: &Registry,
+    data_root: &Path,
+    layout: &Layout,
+    part: Part,
+    options: &RunOptions,
+    schedule: &ScheduleOptions,
+    filter: &DayFilter,
+) -> Result<ExitCode> {
+    let unimplemented = registry.unimplemented();
+    if !unimplemented.is_empty() {
+        let days = unimplemented
+            .iter()
+            .map(|day| day.to_string())
+            .collect::<Vec<String>>();
+        info!("Skipping unimplemented days: {}", days.join(", "));
+    }
+    let mut entries = runner::all_days(registry, data_root, layout, filter);
+    if let Some(n) = options.example {
+        // days without the example are skipped like those without an input
+        for (day, input) in &mut entries {
+            *input = layout.example(data_root, *day, n);
+        }
+    }
+    for (day, input) in entries.iter().filter(|(_, input)| !input.is_file()) {
+        info!("Skipping day {}, {} doesn't exist", day, input.display());
+    }
+
+    // the answers are to the real inputs, so say nothing about the examples
+    let answers = match options.example {
+        Some(_) => None,
+        None => Answers::load(data_root)?,
+    };
+
+    let start = Instant::now();
+    let results = runner::run_days(registry, &entries, part.numbers(), schedule, |results| {
+        if !options.json && options.format == OutputFormat::Plain {
+            print_day(results, options.show_timing)
+        }
+    })?;
+    let wall_clock = start.elapsed();
+    if let Some((path, append)) = &options.output {
+        report::write_results(path, &results, *append)?;
+    }
+    if let Some(path) = &options.report {
+        let inputs = entries
+            .iter()
+            .filter(|(day, _)| results.iter().any(|result| result.day == *day))
+            .map(|(day, input)| (*day, input.as_path()))
+            .collect::<Vec<_>>();
+        html::write(path, &results, &html::Metadata::new(&inputs))?;
+    }
+    if let Some(path) = &options.timings_log {
+        let input_of = |day| entries.iter().find(|(entry, _)| *entry == day);
+        let records = timings::records(&results, |day| {
+            input_of(day).and_then(|(_, input)| input_hash(input))
+        });
+        timings::append(path, &records)?;
+    }
+    if options.record_history {
+        record_history(&results, options.year)?;
+    }
+    if let Some(url) = &options.notify_webhook {
+        notify_webhook(url, options.year, &results);
+    }
+
+    let summary = Summary::from_results(&results);
+    if options.json {
+        // stdout is only the JSON, so the summary moves to stderr
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        eprintln!("{}", summary);
+    } else if let Some(format) = options.format.table() {
+        // likewise for the table, so it can be redirected straight to a file
+        print!("{}", report::table(&results, format, options.show_timing));
+        eprintln!("{}", summary);
+        if options.show_timing {
+            eprintln!("{}", total_times(summary.solve_time, wall_clock));
+        }
+    } else {
+        if !results.is_empty() {
+            println!();
+            print!(
+                "{}",
+                report::summary_table(
+                    &results,
+                    answers.as_ref(),
+                    &options.budgets,
+                    options.show_timing
+                )
+            );
+            if let Some(answers) = &answers {
+                println!("{}/50 stars", report::stars(&results, answers));
+            }
+            if let Some(overall) = options.budgets.overall {
+                let all = results.iter().collect::<Vec<_>>();
+                println!(
+                    "Budget: {}",
+                    budget::cell(&all, Some(overall), options.show_timing)
+                );
+            }
+            println!();
+        }
+        println!("{}", summary);
+        if options.show_timing {
+            println!("{}", total_times(summary.solve_time, wall_clock));
+        }
+    }
+    if !summary.skipped.is_empty() && options.example.is_none() {
+        eprintln!(
+            "{}",
+            style::dim(format!(
+                "Inputs are looked for under {} with {}",
+                data_root.display(),
+                layout.describe()
+            ))
+        );
+    }
+    if let Some(state_root) = &options.outcomes {
+        let input = |day| {
+            entries
+                .iter()
+                .find(|(entry, _)| *entry == day)
+                .map(|(_, input)| input.clone())
+        };
+        remember(state_root, &run_outcomes(&results, input, &[]))?;
+    }
+    if let Some(data_root) = &options.record {
+        record_answers(data_root, &results)?;
+    }
+    let regressed = if options.baseline.is_empty() {
+        0
+    } else {
+        let times = part_times(registry, &entries, &results, options)?;
+        let to_stderr = options.json || options.format != OutputFormat::Plain;
+        check_baselines(&times, &options.baseline, to_stderr)?
+    };
+    if !summary.interrupted.is_empty() {
+        return Err(FailureKind::Interrupted.error(anyhow!(
+            "Interrupted, {} day(s) didn't finish",
+            summary.interrupted.len()
+        )));
+    }
+    if !summary.failed.is_empty() {
+        let error = anyhow!("{} day(s) failed", summary.failed.len());
+        return Err(failure_kind(&results).error(error));
+    }
+    if regressed > 0 {
+        return Err(FailureKind::Regression.error(anyhow!(
+            "{} part(s) got slower than the baseline allows",
+            regressed
+        )));
+    }
+    check_budgets(&results, options)?;
+    if !summary.timed_out.is_empty() {
+        return Ok(timed_out());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Each solved part's time for a baseline: the best of `--bench` runs when
+/// given, as a single run is too noisy to compare, otherwise the run's own.
+fn part_times(
+    registry: &Registry,
+    entries: &[(u8, PathBuf)],
+    results: &[RunResult],
+    options: &RunOptions,
+) -> Result<Vec<PartTime>> {
+    let mut times = vec![];
+    for result in results
+        .iter()
+        .filter(|result| result.status == Status::Solved)
+    {
+        let Some((day, input)) = entries.iter().find(|(day, _)| *day == result.day) else {
+            continue;
+        };
+        let data = runner::load_input(input)?;
+        let (duration, samples) = match &options.bench {
+            Some(bench_options) => {
+                let puzzle = registry.get(*day).ok_or_else(|| not_implemented(*day))?;
+                let solver = runner::find_implementation(puzzle, result.part, None)?;
+                let prepared = prepare_input(
+                    &data,
+                    input,
+                    puzzle,
+                    options.parse_mode,
+                    options.comments.as_deref(),
+                )?;
+                info!(
+                    "Timing day {} part {} over {} runs",
+                    day, result.part, bench_options.iterations
+                );
+                let (_, stats) = bench::bench(solver.as_ref(), &prepared, bench_options)?;
+                (stats.min, stats.samples)
+            }
+            None => (
+                result.timing.map(|timing| timing.total).unwrap_or_default(),
+                1,
+            ),
+        };
+        times.push(PartTime {
+            year: options.year,
+            day: *day,
+            part: result.part,
+            input_hash: input::hash(&data),
+            version: report::build_version().map(str::to_string),
+            duration,
+            samples,
+        });
+    }
+    Ok(times)
+}
+
+/// How long each day took before, for a parallel run to start the slowest
+/// first: from the baseline being compared with or saved to, or else the
+/// results file about to be written. Without either the days run in order.
+fn timing_estimates(options: &RunOptions) -> Estimates {
+    let baseline = options
+        .baseline
+        .compare
+        .as_ref()
+        .or(options.baseline.save.as_ref());
+    if let Some(name) = baseline {
+        match Baseline::load(Path::new(baseline::BASELINE_DIR), name) {
+            Ok(baseline) => return schedule::from_baseline(&baseline, options.year),
+            Err(e) => info!("Not ordering days by the baseline: {}", e),
+        }
+    }
+    match &options.output {
+        Some((path, _)) if path.is_file() => {
+            schedule::from_results_file(path).unwrap_or_else(|e| {
+                info!("Not ordering days by earlier results: {:#}", e);
+                Estimates::new()
+            })
+        }
+        _ => Estimates::new(),
+    }
+}
+
+/// Compares `times` with the baseline to compare with and saves them as the
+/// one to save, in that order so a baseline can be compared with and then
+/// updated in one run. Returns how many parts regressed.
+fn check_baselines(
+    times: &[PartTime],
+    options: &BaselineOptions,
+    to_stderr: bool,
+) -> Result<usize> {
+    let dir = Path::new(baseline::BASELINE_DIR);
+    let mut regressed = 0;
+    if let Some(name) = &options.compare {
+        let deltas = Baseline::load(dir, name)?.compare(times)?;
+        regressed = deltas
+            .iter()
+            .filter(|delta| delta.regressed(options.threshold))
+            .count();
+        let report = format!(
+            "{}\n{}",
+            style::heading(format!("Compared with baseline '{}':", name)),
+            baseline::table(&deltas, options.threshold)
+        );
+        if to_stderr {
+            eprint!("{}", report);
+        } else {
+            print!("{}", report);
+        }
+    }
+    if let Some(name) = &options.save {
+        let mut saved = Baseline::load_or_default(dir, name)?;
+        saved.update(times);
+        saved.save(dir, name)?;
+        eprintln!(
+            "{}",
+            style::dim(format!(
+                "Saved {} part time(s) as baseline '{}'",
+                times.len(),
+                name
+            ))
+        );
+    }
+    Ok(regressed)
+}
+
+/// Warns about each day, and the run, that took longer to solve than its
+/// budget, failing with `--enforce-budget` if any did.
+fn check_budgets(results: &[RunResult], options: &RunOptions) -> Result<()> {
+    let overruns = budget::overruns(results, &options.budgets);
+    for overrun in &overruns {
+        eprintln!("{}", style::warning(overrun));
+    }
+    if options.enforce_budget && !overruns.is_empty() {
+        return Err(FailureKind::OverBudget.error(anyhow!("{} budget(s) exceeded", overruns.len())));
+    }
+    Ok(())
+}
+
+fn total_times(solve_time: Duration, wall_clock: Duration) -> String {
+    format!(
+        "Total solve time: {}\nWall-clock time: {}",
+        style::dim(format_duration(solve_time)),
+        style::dim(format_duration(wall_clock))
+    )
+}
+
+/// Lists the implemented days, marking whether each one's input is in the
+/// data directory. Building the listing also constructs every part, without
+/// solving anything.
+fn list_days(
+    registry: &Registry,
+    data_root: &Path,
+    layout: &Layout,
+    filter: &DayFilter,
+    json: bool,
+) -> Result<()> {
+    let days = registry
+        .info(data_root, layout)
+        .filter(|info| filter.matches(info.day, &info.metadata))
+        .collect::<Vec<_>>();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&days)?);
+        return Ok(());
+    }
+    for info in days {
+        let difficulty = info
+            .metadata
+            .difficulty
+            .map(|difficulty| difficulty.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{} {:>2} {:<32} {:<6} {:<4} {}",
+            if info.has_input {
+                style::success("✓")
+            } else {
+                style::failure("✗")
+            },
+            info.day,
+            info.name,
+            difficulty,
+            info.metadata.runtime,
+            info.metadata.tags.join(", ")
+        );
+        println!("     {}", info.title);
+        for (part, description) in info.parts.iter().enumerate() {
+            println!("     part {}: {}", part + 1, description);
+        }
+    }
+    Ok(())
+}
+
+/// Prints a table of every implemented day's input and whether it's ready to
+/// be solved, see [`doctor::examine`], failing if any of them isn't.
+fn check_data(registry: &Registry, data_root: &Path, layout: &Layout) -> Result<ExitCode> {
+    let checks = doctor::examine(registry, data_root, layout);
+    let rows = checks
+        .iter()
+        .map(|check| {
+            (
+                check.day.to_string(),
+                check.title.clone().unwrap_or_else(|| "-".to_string()),
+                check.input.display().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let title_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0).max(5);
+    let input_width = rows.iter().map(|row| row.2.len()).max().unwrap_or(0).max(5);
+    println!(
+        "{}",
+        style::heading(format!(
+            "{:>3}  {:<title_width$}  {:<input_width$}  Status",
+            "Day", "Title", "Input"
+        ))
+    );
+    for (check, (day, title, input)) in checks.iter().zip(&rows) {
+        let verdict = match check.verdict {
+            doctor::Verdict::Ready => style::success(check.verdict.to_string()),
+            doctor::Verdict::Problem(_) => style::failure(check.verdict.to_string()),
+            doctor::Verdict::Unimplemented => style::warning(check.verdict.to_string()),
+        };
+        println!(
+            "{:>3}  {:<title_width$}  {:<input_width$}  {}",
+            day, title, input, verdict
+        );
+    }
+
+    let problems = checks.iter().filter(|check| check.is_problem()).count();
+    if problems > 0 {
+        return Err(FailureKind::MissingInput.error(anyhow!(
+            "Fail: {} day(s) have a problem with their input, inputs are looked for under {} with {}",
+            problems,
+            data_root.display(),
+            layout.describe()
+        )));
+    }
+    let ready = checks
+        .iter()
+        .filter(|check| check.verdict == doctor::Verdict::Ready)
+        .count();
+    println!(
+        "{}",
+        style::success(format!("Pass: {} day(s) ready to run", ready))
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Prints the day's puzzle statement, the one fetched with `fetch-puzzle` if
+/// there is one, otherwise the one quoted in the day's doc comment.
+fn describe_day(registry: &Registry, day: Day, part: Option<Part>, data_root: &Path) -> Result<()> {
+    let Day::Numeric(day) = day else {
+        return Err(
+            FailureKind::Usage.error(anyhow!("--describe needs a single day to be selected"))
+        );
+    };
+    let cached = puzzle_page::path(data_root, day);
+    let text = if cached.is_file() {
+        fs::read_to_string(&cached).with_context(|| format!("Can't read {}", cached.display()))?
+    } else {
+        let puzzle = registry.get(day).ok_or_else(|| not_implemented(day))?;
+        let text = puzzle
+            .statement()
+            .ok_or_else(|| anyhow!("Day {} has no puzzle statement", day))?;
+        statement::render(text)
+    };
+    match part.unwrap_or(Part::All) {
+        Part::All => println!("{}", text.trim_end()),
+        part => {
+            for part in part.numbers() {
+                let text = statement::split_part(&text, *part)
+                    .ok_or_else(|| anyhow!("Day {} has no statement for part {}", day, part))?;
+                println!("{}", text);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Saves the example in the day's puzzle description next to its input, and
+/// with `part2` the one only in part 2's as its second example, see
+/// [`puzzle_page::examples`]. Examples already there are only replaced with
+/// `force`.
+fn fetch_example(day: u8, part2: bool, force: bool, settings: &Settings) -> Result<()> {
+    let data_root = settings.data_root();
+    let paths = [
+        settings.layout.example(&data_root, day, 1),
+        settings.layout.example(&data_root, day, 2),
+    ];
+    let wanted = if part2 { &paths[..] } else { &paths[..1] };
+    if let Some(path) = wanted.iter().find(|path| path.exists() && !force) {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "{} already exists, give --force to replace it",
+            path.display()
+        )));
+    }
+    let page = puzzle_page::fetch(
+        &http::Polite::adventofcode(),
+        settings.event_year(),
+        day,
+        settings.session.as_deref(),
+    )?;
+    let examples = puzzle_page::examples(&page)?;
+    let mut chosen = vec![(1, examples.part1)];
+    if part2 {
+        match examples.part2 {
+            Some(blocks) if !blocks.is_empty() => chosen.push((2, blocks)),
+            Some(_) => return Err(anyhow!("Part 2's description has no example of its own")),
+            None => {
+                return Err(anyhow!(
+                    "Part 2's description isn't in the page yet, it's shown once part 1 is solved: if it's been solved on the website, run fetch-puzzle {} --refresh first",
+                    day
+                ))
+            }
+        }
+    }
+    for ((part, blocks), path) in chosen.into_iter().zip(&paths) {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+        }
+        fs::write(path, &blocks[0]).with_context(|| format!("Can't write {}", path.display()))?;
+        eprintln!(
+            "{}",
+            style::dim(format!(
+                "Saved part {}'s example to {}",
+                part,
+                path.display()
+            ))
+        );
+        if blocks.len() > 1 {
+            eprintln!(
+                "{}",
+                style::warning(format!(
+                    "Part {}'s description has {} blocks that could be the example, the first was taken: check {} is the right one",
+                    part,
+                    blocks.len(),
+                    path.display()
+                ))
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Prints the stars of each day against the days implemented and the answers
+/// recorded, see [`status`]. The stars come from the calendar on
+/// adventofcode.com unless `offline`, there's no session cookie or it can't
+/// be fetched, when they're those recorded in answers.toml.
+fn show_status(
+    registry: &Registry,
+    refresh: bool,
+    offline: bool,
+    settings: &Settings,
+) -> Result<()> {
+    let answers = Answers::load(&settings.data_root())?.unwrap_or_default();
+    let implemented = registry.days().map(|(day, _)| day).collect::<Vec<_>>();
+    let calendar = match settings.session.as_deref() {
+        Some(session) if !offline => match fetch_stars(session, refresh, settings) {
+            Ok(calendar) => Some(calendar),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    style::warning(format!(
+                        "Can't fetch the calendar ({:#}), showing the stars recorded in {}",
+                        e,
+                        answers::ANSWERS_FILE
+                    ))
+                );
+                None
+            }
+        },
+        _ => None,
+    };
+    let days = status::days(calendar.as_ref(), &answers, &implemented);
+    print!("{}", status::render(&days, calendar.is_some()));
+    Ok(())
+}
+
+/// Each day's stars on the calendar on adventofcode.com.
+fn fetch_stars(session: &str, refresh: bool, settings: &Settings) -> Result<BTreeMap<u8, u8>> {
+    let client = http::Polite::adventofcode();
+    if refresh {
+        client.forget(None)?;
+    }
+    let page = status::fetch_calendar(&client, settings.event_year(), session)?;
+    status::parse_calendar(&page)
+}
+
+/// Prints private leaderboard `id`, or its JSON as it's sent with `json`, see
+/// [`leaderboard`].
+fn show_leaderboard(id: u64, json: bool, settings: &Settings) -> Result<()> {
+    let body = leaderboard::fetch(
+        &http::Polite::adventofcode(),
+        settings.event_year(),
+        id,
+        settings.session.as_deref(),
+    )?;
+    if json {
+        println!("{}", body.trim_end());
+    } else {
+        print!("{}", leaderboard::render(&leaderboard::parse(&body)?));
+    }
+    Ok(())
+}
+
+/// The profiles in `file`, a line each with where its data is kept and its
+/// session file, marking the one `overrides` and the environment pick.
+fn list_profiles(file: &ConfigFile, overrides: &Overrides) -> String {
+    if file.profiles.is_empty() {
+        return format!(
+            "There are no profiles in {}, add one as a [profiles.<NAME>] table\n",
+            config::CONFIG_FILE
+        );
+    }
+    let env = |name: &str| std::env::var(name).ok();
+    let active = file.profile_name(overrides, env);
+    let data_dir = file.data_dir(overrides, env);
+    let width = file
+        .profiles
+        .keys()
+        .map(|name| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut text = String::new();
+    for (name, profile) in &file.profiles {
+        let mark = if active.as_deref() == Some(name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        let data = data_dir.join(profile.data_subdir.as_deref().unwrap_or(Path::new(name)));
+        let session = match &profile.session_file {
+            Some(path) => format!(", session in {}", path.display()),
+            None => String::new(),
+        };
+        let default = if file.default_profile.as_deref() == Some(name.as_str()) {
+            style::dim(" (default)").to_string()
+        } else {
+            String::new()
+        };
+        text.push_str(&format!(
+            "{} {:width$}  {}{}{}\n",
+            mark,
+            name,
+            data.display(),
+            session,
+            default,
+            width = width
+        ));
+    }
+    if active.is_none() {
+        text.push_str(&format!(
+            "{}\n",
+            style::dim("None is in use, pick one with --profile or set a default")
+        ));
+    }
+    text
+}
+
+/// Fetches the day's puzzle description into the data root, see
+/// [`puzzle_page`].
+fn fetch_puzzle(day: u8, refresh: bool, settings: &Settings) -> Result<()> {
+    let client = http::Polite::adventofcode();
+    if refresh {
+        client.forget(Some(day))?;
+    }
+    let page = puzzle_page::fetch(
+        &client,
+        settings.event_year(),
+        day,
+        settings.session.as_deref(),
+    )?;
+    let markdown = puzzle_page::markdown(&page)?;
+    let path = puzzle_page::path(&settings.data_root(), day);
+    if !puzzle_page::save(&path, &markdown)? {
+        eprintln!(
+            "{}",
+            style::dim(format!(
+                "Kept {}, it has more of the puzzle than was fetched",
+                path.display()
+            ))
+        );
+        return Ok(());
+    }
+    let parts = match puzzle_page::parts(&markdown) {
+        1 => "part 1, once that's solved fetch it again with --refresh for part 2",
+        _ => "both parts",
+    };
+    eprintln!(
+        "{}",
+        style::dim(format!(
+            "Saved day {}'s puzzle to {} ({})",
+            day,
+            path.display(),
+            parts
+        ))
+    );
+    Ok(())
+}
+
+/// Marks what follows as coming from a cut down input, see `--limit-lines`.
+fn truncated(lines: usize) -> String {
+    style::warning(format!(
+        "(from only the first {} line(s) of the input)",
+        lines
+    ))
+    .to_string()
+}
+
+fn parse_only(
+    registry: &Registry,
+    day: Day,
+    input: &Path,
+    parse_mode: ParseMode,
+    comments: Option<&str>,
+    limit_lines: Option<usize>,
+) -> Result<()> {
+    let Day::Numeric(day) = day else {
+        return Err(
+            FailureKind::Usage.error(anyhow!("--parse-only needs a single day to be selected"))
+        );
+    };
+    let puzzle = registry.get(day).ok_or_else(|| not_implemented(day))?;
+    let data = runner::load_input(input)?;
+    let data = prepare_input(&data, input, puzzle, parse_mode, comments)?;
+    let first_lines =
+        limit_lines.and_then(|lines| input::first_lines(&data, lines).map(|first| (first, lines)));
+    let stats = puzzle
+        .parse_stats(first_lines.map_or(&data, |(first, _)| first))
+        .ok_or_else(|| anyhow!("Day {} doesn't support --parse-only", day))?;
+    println!("{}", puzzle.title());
+    if let Some((_, lines)) = first_lines {
+        println!("{}", truncated(lines));
+    }
+    println!("{}", stats);
+    if !stats.is_valid() {
+        return Err(FailureKind::Parse.error(anyhow!(
+            "{} has {} malformed line(s)",
+            runner::input_name(input),
+            stats.malformed.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the examples of the given days, every day when empty.
+fn self_test(
+    registry: &Registry,
+    days: &[u8],
+    data_root: &Path,
+    layout: &Layout,
+    format: VerifyFormat,
+    output: Option<&Path>,
+    record: bool,
+) -> Result<()> {
+    if output.is_some() && format == VerifyFormat::Plain {
+        return Err(FailureKind::Usage.error(anyhow!("--output needs --format junit or tap")));
+    }
+    let mut cases = verify::run(registry, days);
+    // the inputs are checked against the answers recorded for them, and with
+    // --record solved for the parts without one
+    let answers = Answers::load(data_root)?.unwrap_or_default();
+    let example_failed = |day, part| {
+        cases.iter().any(|case: &verify::Case| {
+            case.day == day && case.part == part && case.verdict != verify::Verdict::Passed
+        })
+    };
+    let checked =
+        verify::check_answers(registry, days, data_root, layout, &answers, |day, part| {
+            record && !example_failed(day, part)
+        });
+    cases.extend(checked.cases);
+    let unrecorded = checked.unrecorded;
+    let report = match format {
+        VerifyFormat::Plain => None,
+        VerifyFormat::Junit => Some(verify::junit(&cases)),
+        VerifyFormat::Tap => Some(verify::tap(&cases)),
+    };
+    // the report is all that goes to stdout when it isn't written to a file
+    let print_days = report.is_none() || output.is_some();
+    let mut failures = vec![];
+    for (day, _) in registry.days() {
+        if !days.is_empty() && !days.contains(&day) {
+            continue;
+        }
+        let day_cases = cases
+            .iter()
+            .filter(|case| case.day == day)
+            .collect::<Vec<_>>();
+        if let Some(error) = verify::day_error(&day_cases) {
+            if print_days {
+                println!("Day {}: {}", day, style::failure("FAILED"));
+                for case in &day_cases {
+                    let explained = verify::explain(case, &cases);
+                    if !explained.is_empty() {
+                        println!("  [Part {}] {}", case.part, style::failure("wrong answer"));
+                    }
+                    for line in explained {
+                        println!("    {}", line);
+                    }
+                }
+            }
+            failures.push(error.to_string());
+        } else if print_days {
+            let inputs = day_cases.iter().filter(|case| case.input).count();
+            let passed = if inputs == 0 {
+                format!("{} example(s) passed", day_cases.len())
+            } else {
+                format!(
+                    "{} example(s) and {} recorded answer(s) passed",
+                    day_cases.len() - inputs,
+                    inputs
+                )
+            };
+            println!("Day {}: {}", day, style::success(passed));
+        }
+    }
+    match (report, output) {
+        (Some(report), Some(path)) => verify::write(path, &report)?,
+        (Some(report), None) => print!("{}", report),
+        (None, _) => {}
+    }
+    let outcomes = cases
+        .iter()
+        .map(|case| Outcome {
+            check: Check::Verify,
+            day: case.day,
+            part: case.part,
+            failed: case.verdict != verify::Verdict::Passed,
+            input_hash: None,
+        })
+        .collect::<Vec<_>>();
+    remember(data_root, &outcomes)?;
+    if record {
+        record_answers(data_root, &unrecorded)?;
+    }
+
+    if !failures.is_empty() {
+        return Err(FailureKind::Mismatch.error(anyhow!(
+            "{} day(s) failed self-test:\n{}",
+            failures.len(),
+            failures.join("\n")
+        )));
+    }
+    Ok(())
+}
+
+/// Records the solved parts' answers in the data root for `--record`, saying
+/// which were added and warning about any recorded differently already.
+fn record_answers(data_root: &Path, results: &[RunResult]) -> Result<()> {
+    let solved = results
+        .iter()
+        .filter(|result| result.status == Status::Solved)
+        .filter_map(|result| Some((result.day, result.part, result.answer.clone()?)))
+        .collect::<Vec<_>>();
+    let recorded = answers::record(data_root, registry::YEAR, &solved)?;
+    for (day, part, known) in &recorded.conflicting {
+        eprintln!(
+            "{}",
+            style::warning(format!(
+                "Day {} part {} is recorded as {} already, keeping that",
+                day,
+                part,
+                style::answer_text(known)
+            ))
+        );
+    }
+    if !recorded.added.is_empty() {
+        eprintln!(
+            "{}",
+            style::dim(format!(
+                "Recorded {} answer(s) in {}",
+                recorded.added.len(),
+                data_root.join(answers::ANSWERS_FILE).display()
+            ))
+        );
+    }
+    Ok(())
+}
+
+/// Keeps `year`'s `results` in the history for `aoc history`, as one run.
+fn record_history(results: &[RunResult], year: u16) -> Result<()> {
+    let mut history = history::History::open(Path::new(history::HISTORY_FILE))?;
+    history.record(results, year, Utc::now().timestamp())?;
+    Ok(())
+}
+
+/// Posts a summary of `results` to the webhook at `url`, only warning when it
+/// can't, so a webhook that's down never changes how the run exits.
+fn notify_webhook(url: &str, year: u16, results: &[RunResult]) {
+    if let Err(e) = notify::notify(&notify::Http, url, year, results) {
+        eprintln!("{}", style::warning(format!("{:#}", e)));
+    }
+}
+
+/// Records `outcomes` in the data root for `--rerun-failed`.
+fn remember(data_root: &Path, outcomes: &[Outcome]) -> Result<()> {
+    let mut remembered = Outcomes::load(data_root);
+    remembered.record(outcomes);
+    remembered.save(data_root)
+}
+
+/// How each part that ran did, a part that gave an answer other than the
+/// expected one among `answers` counts as failed too.
+fn run_outcomes(
+    results: &[RunResult],
+    input: impl Fn(u8) -> Option<PathBuf>,
+    answers: &[Answer],
+) -> Vec<Outcome> {
+    results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| !matches!(result.status, Status::Skipped(_)))
+        .map(|(index, result)| {
+            let mismatched = answers
+                .get(index)
+                .is_some_and(|expected| result.answer.as_ref() != Some(expected));
+            Outcome {
+                check: Check::Run,
+                day: result.day,
+                part: result.part,
+                failed: result.status != Status::Solved || mismatched,
+                input_hash: input(result.day)
+                    .and_then(|path| input::read(&path).ok())
+                    .map(|data| input::hash(&data)),
+            }
+        })
+        .collect()
+}
+
+/// Identifies the input at `path` for a log, `None` when it can't be read
+/// again, eg. stdin.
+fn input_hash(path: &Path) -> Option<String> {
+    input::read(path).ok().map(|data| input::hash(&data))
+}
+
+/// Sends diagnostics to stderr so they never mix with the answers on stdout.
+/// Only warnings are shown unless RUST_LOG asks for more, eg. RUST_LOG=debug.
+fn init_tracing(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+fn run(mut cli: RunArgs, settings: &Settings) -> Result<ExitCode> {
+    cli.day = cli.day.map(|day| day.resolve(settings)).transpose()?;
+    let data_root = settings.data_root();
+    if cli.self_test {
+        self_test(
+            &REGISTRY,
+            &[],
+            &data_root,
+            &settings.layout,
+            VerifyFormat::Plain,
+            None,
+            false,
+        )?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    // several days read their inputs from a data directory given as INPUT
+    let state_root = match (&cli.day, &cli.input) {
+        (Some(Day::Numeric(_)), _) | (_, None) => data_root.clone(),
+        (_, Some(dir)) => dir.clone(),
+    };
+    let selected = match &cli.day {
+        Some(Day::Numeric(day)) => vec![*day],
+        Some(Day::Selection(days)) => days.clone(),
+        _ => vec![],
+    };
+    if let Some(failed) = cli
+        .rerun
+        .select(Check::Run, &selected, &state_root, &settings.layout)?
+    {
+        if failed.is_empty() {
+            println!("No days failed last time, there's nothing to rerun");
+            return Ok(ExitCode::SUCCESS);
+        }
+        cli.day = Some(match cli.day {
+            Some(Day::Numeric(day)) => Day::Numeric(day),
+            _ => Day::Selection(failed),
+        });
+    }
+    let filter = DayFilter {
+        skip_slow: cli.skip_slow,
+        only_tags: cli.only_tag,
+        only_days: match &cli.day {
+            Some(Day::Selection(days)) => days.clone(),
+            _ => vec![],
+        },
+    };
+    if cli.list {
+        list_days(&REGISTRY, &data_root, &settings.layout, &filter, cli.json)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(input) = &cli.parse_only {
+        let day = cli.day.expect("clap requires a day with --parse-only");
+        parse_only(
+            &REGISTRY,
+            day,
+            input,
+            cli.parsing.mode(),
+            cli.parsing.comments().as_deref(),
+            cli.limit_lines.map(|lines| lines as usize),
+        )?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if cli.describe {
+        let day = cli.day.expect("clap requires a day with --describe");
+        describe_day(&REGISTRY, day, cli.part, &data_root)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // without a day there's someone at a terminal to ask, see `RunArgs`
+    let (day, part) = match cli.day {
+        Some(day) => (day, cli.part.unwrap_or(Part::All)),
+        None => match picker::pick(&REGISTRY, &data_root, &settings.layout)? {
+            Some(pick) => (Day::Numeric(pick.day), Part::from_number(pick.part)),
+            None => return Ok(ExitCode::SUCCESS),
+        },
+    };
+    if matches!(day, Day::Numeric(_)) && !(filter.is_empty() && cli.skip.is_empty()) {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--skip-slow, --only-tag and --skip only apply when running several days"
+        )));
+    }
+    let options = RunOptions {
+        year: settings.event_year(),
+        show_timing: !cli.no_timing,
+        bench: cli.bench.map(|iterations| BenchOptions {
+            iterations: iterations as usize,
+            force: cli.force,
+            max_single_run: bench::MAX_SINGLE_RUN,
+        }),
+        json: cli.json,
+        timeout: cli.timeout,
+        progress: cli.progress,
+        implementation: cli.implementation,
+        compare_impls: cli.compare_impls || cli.cross_check,
+        cross_check: cli.cross_check.then_some(runner::CROSS_CHECK_STEPS),
+        example: cli.example,
+        answers: cli.answer,
+        format: cli.format,
+        output: cli.output.map(|path| (path, cli.append)),
+        report: cli.report,
+        timings_log: cli.timings_log,
+        budgets: Budgets {
+            overall: cli.budget,
+            days: settings.budgets.clone(),
+        },
+        enforce_budget: cli.enforce_budget,
+        verbosity: Verbosity::from_count(cli.verbose),
+        parse_mode: cli.parsing.mode(),
+        comments: cli.parsing.comments(),
+        limit_lines: cli.limit_lines.map(|lines| lines as usize),
+        input2: cli.input2.clone(),
+        baseline: BaselineOptions {
+            save: cli.save_baseline,
+            compare: cli.compare_baseline,
+            threshold: cli.regression_threshold,
+        },
+        outcomes: (cli.example.is_none()
+            && cli.bench.is_none()
+            && cli.limit_lines.is_none()
+            && cli.input2.is_none())
+        .then(|| state_root.clone()),
+        record: cli.record.then(|| state_root.clone()),
+        record_history: cli.record_history,
+        notify_webhook: match cli.notify_webhook {
+            Some(url) => Some(url.or_else(|| settings.notify_webhook.clone()).ok_or_else(
+                || {
+                    FailureKind::Usage.error(anyhow!(
+                        "--notify-webhook needs a URL, or one set as {} or notify_webhook in {}",
+                        config::NOTIFY_WEBHOOK_VAR,
+                        config::CONFIG_FILE
+                    ))
+                },
+            )?),
+            None => None,
+        },
+    };
+    if options.enforce_budget && options.budgets.is_empty() {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--enforce-budget needs --budget or a [budgets] table in {}",
+            config::CONFIG_FILE
+        )));
+    }
+    if options.format != OutputFormat::Plain && matches!(day, Day::Numeric(_)) {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--format only applies when running several days, use --json for a single day"
+        )));
+    }
+    if cli.grace_period.is_some() && matches!(day, Day::Numeric(_)) {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--grace-period only applies when running several days"
+        )));
+    }
+    if !options.baseline.is_empty() && matches!(day, Day::Numeric(_)) {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--save-baseline and --compare-baseline only apply when running several days"
+        )));
+    }
+    if options.bench.is_some() && !matches!(day, Day::Numeric(_)) && options.baseline.is_empty() {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--bench needs a single day to be selected, unless timing several for a baseline"
+        )));
+    }
+    if (options.implementation.is_some() || options.compare_impls)
+        && !matches!(day, Day::Numeric(_))
+    {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--impl, --compare-impls and --cross-check need a single day to be selected"
+        )));
+    }
+    if options.input2.is_some() {
+        if !matches!(day, Day::Numeric(_)) {
+            return Err(
+                FailureKind::Usage.error(anyhow!("--input2 needs a single day to be selected"))
+            );
+        }
+        if part != Part::All {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "--input2 only applies when both parts run, give the input for a single part as INPUT"
+            )));
+        }
+    }
+    if cli.paste && !matches!(day, Day::Numeric(_)) {
+        return Err(FailureKind::Usage.error(anyhow!("--paste needs a single day to be selected")));
+    }
+    if cli.download && !matches!(day, Day::Numeric(_)) {
+        return Err(
+            FailureKind::Usage.error(anyhow!("--download needs a single day to be selected"))
+        );
+    }
+    if !options.answers.is_empty() {
+        if !matches!(day, Day::Numeric(_)) {
+            return Err(
+                FailureKind::Usage.error(anyhow!("--answer needs a single day to be selected"))
+            );
+        }
+        if options.answers.len() != part.numbers().len() {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "Got {} --answer value(s) for {} part(s), give one per part",
+                options.answers.len(),
+                part.numbers().len()
+            )));
+        }
+    }
+    info!("User requested solution for {} (part: {:?})", day, part);
+
+    let day = match day {
+        Day::Numeric(n) => n,
+        Day::Today => unreachable!("today was resolved to its day above"),
+        // a selection runs like all days, with the filter picking out its days
+        Day::All | Day::Selection(_) => {
+            let schedule = ScheduleOptions {
+                jobs: cli.jobs as usize,
+                fail_fast: cli.fail_fast,
+                part: PartOptions {
+                    timeout: options.timeout,
+                    parse_mode: options.parse_mode,
+                    comments: options.comments.clone(),
+                    limit_lines: options.limit_lines,
+                    interrupt: Some(Interrupt::on_ctrl_c(cli.grace_period)?),
+                    verbosity: options.verbosity,
+                    ..Default::default()
+                },
+                progress: options.progress.then(ProgressBars::for_stderr).flatten(),
+                skip: cli.skip,
+                estimates: if cli.jobs > 1 {
+                    timing_estimates(&options)
+                } else {
+                    Estimates::new()
+                },
+            };
+            let data_root = cli.input.unwrap_or(data_root);
+            if runner::is_stdin(&data_root) {
+                return Err(FailureKind::Usage.error(anyhow!(
+                    "Several days can't read from stdin, each needs its own input file under a data directory"
+                )));
+            }
+            return run_all_days(
+                &REGISTRY,
+                &data_root,
+                &settings.layout,
+                part,
+                &options,
+                &schedule,
+                &filter,
+            );
+        }
+    };
+    let input = match (options.example, cli.input) {
+        (Some(_), Some(input)) => {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "--example picks the input itself, so {} can't be given too",
+                input.display()
+            )))
+        }
+        (None, Some(input)) if cli.paste => {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "--paste reads the input off the clipboard, so {} can't be given too",
+                input.display()
+            )))
+        }
+        (None, Some(input)) if cli.download => {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "--download saves the day's input where it's looked for, so {} can't be given too",
+                input.display()
+            )))
+        }
+        (None, Some(input)) if runner::is_pattern(&input) => {
+            return run_matching_inputs(&REGISTRY, day, part, &input, &options)
+        }
+        (Some(n), None) => runner::resolve_example(day, n, &data_root, &settings.layout)?,
+        (None, None) if cli.paste => pasted_input(day, cli.save, &data_root, &settings.layout)?,
+        (None, None) if cli.download => {
+            let path = settings.layout.input(&data_root, day);
+            if !path.exists() {
+                download_input(day, &path, false, settings)?;
+            }
+            path
+        }
+        (None, input) => {
+            runner::resolve_input(input.as_deref(), day, &data_root, &settings.layout)?
+        }
+    };
+    run_single_day(&REGISTRY, day, part, &input, &options)
+}
+
+/// Solves `day` against every input matching the glob `pattern`, eg. inputs
+/// shared by friends, printing a row per input. An input that fails only
+/// fails its own cells, the rest are still solved.
+fn run_matching_inputs(
+    registry: &Registry,
+    day: u8,
+    part: Part,
+    pattern: &Path,
+    options: &RunOptions,
+) -> Result<ExitCode> {
+    if options.bench.is_some()
+        || options.json
+        || options.compare_impls
+        || !options.answers.is_empty()
+        || options.output.is_some()
+        || options.report.is_some()
+        || options.timings_log.is_some()
+        || options.record.is_some()
+        || options.record_history
+        || options.notify_webhook.is_some()
+        || options.budgets.overall.is_some()
+        || options.enforce_budget
+        || options.input2.is_some()
+    {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "--bench, --json, --compare-impls, --cross-check, --answer, --output, --report, --timings-log, --record, --record-history, --notify-webhook, --budget, --enforce-budget and --input2 need a single input, not a pattern"
+        )));
+    }
+    let inputs = runner::expand_pattern(pattern)?;
+    let results = runner::run_inputs(
+        registry,
+        day,
+        part.numbers(),
+        &inputs,
+        &PartOptions {
+            timeout: options.timeout,
+            implementation: options.implementation.clone(),
+            parse_mode: options.parse_mode,
+            comments: options.comments.clone(),
+            limit_lines: options.limit_lines,
+            verbosity: options.verbosity,
+            ..Default::default()
+        },
+    );
+    print!("{}", report::inputs_table(&results, options.show_timing));
+    for (input, results) in &results {
+        for result in results {
+            if let Status::Failed(e) = &result.status {
+                println!(
+                    "{}",
+                    style::failure(format!("{} part {}: {}", input.display(), result.part, e))
+                );
+            }
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|(_, results)| results.iter().any(RunResult::is_failure))
+        .count();
+    let results = results
+        .into_iter()
+        .flat_map(|(_, results)| results)
+        .collect::<Vec<_>>();
+    if failed > 0 {
+        let error = anyhow!("{} of {} input(s) failed", failed, inputs.len());
+        return Err(failure_kind(&results).error(error));
+    }
+    if results
+        .iter()
+        .any(|result| matches!(result.status, Status::TimedOut(_)))
+    {
+        return Ok(timed_out());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The input for `--paste`, which is read off the clipboard up front when it's
+/// to be saved, see [`clipboard::save`].
+fn pasted_input(day: u8, save: bool, data_root: &Path, layout: &Layout) -> Result<PathBuf> {
+    if !save {
+        return Ok(PathBuf::from(runner::CLIPBOARD_INPUT));
+    }
+    let path = layout.input(data_root, day);
+    clipboard::save(&path, &clipboard::read()?)?;
+    eprintln!(
+        "{}",
+        style::dim(format!("Saved the pasted input to {}", path.display()))
+    );
+    Ok(path)
+}
+
+/// Downloads `day`'s input from adventofcode.com to `path`.
+fn download_input(day: u8, path: &Path, force: bool, settings: &Settings) -> Result<()> {
+    download::download(
+        &http::Polite::adventofcode(),
+        settings.event_year(),
+        day,
+        settings.session.as_deref(),
+        path,
+        force,
+    )?;
+    eprintln!(
+        "{}",
+        style::dim(format!("Saved day {}'s input to {}", day, path.display()))
+    );
+    Ok(())
+}
+
+/// Waits for the next puzzle of the event to come out, counting down on a
+/// terminal, then downloads its input with `download` and generates its
+/// module with `new`.
+fn await_puzzle(download: bool, new: bool, settings: &Settings) -> Result<()> {
+    let year = settings.event_year();
+    let Some((day, at)) = calendar::next_unlock(Utc::now(), year) else {
+        return Err(FailureKind::Usage.error(anyhow!("All of {}'s puzzles are out", year)));
+    };
+    // rather than find out once it's out
+    if (download || new) && settings.session.is_none() {
+        return Err(download::no_session("Fetching the new day"));
+    }
+    if new && REGISTRY.get(day).is_some() {
+        return Err(anyhow!("Day {} is already implemented", day));
+    }
+    eprintln!(
+        "Day {} comes out at {}",
+        day,
+        at.with_timezone(&Local).format("%H:%M:%S on %-d %B")
+    );
+    let term = console::Term::stderr();
+    calendar::wait_until(http::SystemClock, at, |left| {
+        if term.is_term() {
+            term.clear_line().ok();
+            let left = calendar::format_countdown(left);
+            eprint!("{}", style::dim(format!("Day {} in {}", day, left)));
+        }
+    });
+    if term.is_term() {
+        term.clear_line().ok();
+    }
+    eprintln!(
+        "{}",
+        style::success(format!(
+            "Day {} is out: {}",
+            day,
+            puzzle_page::puzzle_url(year, day)
+        ))
+    );
+    if download {
+        let path = settings.layout.input(&settings.data_root(), day);
+        download_input(day, &path, false, settings)?;
+    }
+    if new {
+        let client = http::Polite::adventofcode();
+        let page = puzzle_page::fetch(&client, year, day, settings.session.as_deref())?;
+        let markdown = puzzle_page::markdown(&page)?;
+        puzzle_page::save(&puzzle_page::path(&settings.data_root(), day), &markdown)?;
+        let title = puzzle_page::title(&markdown)
+            .ok_or_else(|| anyhow!("Can't find day {}'s title on its puzzle page", day))?;
+        new_day(day, title, settings)?;
+    }
+    Ok(())
+}
+
+/// Submits the answer to a part, solving the part for it unless it's given,
+/// and records it in the data root once it's right. A wrong answer fails as
+/// a mismatch.
+fn submit_answer(registry: &Registry, args: SubmitArgs, settings: &Settings) -> Result<ExitCode> {
+    let SubmitArgs {
+        day, part, input, ..
+    } = args;
+    if settings.session.is_none() {
+        // before spending any time on solving
+        return Err(download::no_session("Submitting an answer"));
+    }
+    let data_root = settings.data_root();
+    let log_path = submit::log::path(&data_root, day);
+    let known = submit::log::known(&submit::log::load(&log_path)?, part);
+    if let Some(correct) = &known.correct {
+        return Err(FailureKind::Usage.error(anyhow!(
+            "Day {} part {} is solved already, with {}, see {}",
+            day,
+            part,
+            style::answer_text(correct),
+            log_path.display()
+        )));
+    }
+    let answer = match args.value {
+        Some(value) => value.parse::<Answer>()?,
+        None => {
+            let input = runner::resolve_input(input.as_deref(), day, &data_root, &settings.layout)?;
+            let mut results =
+                runner::run_day(registry, day, &[part], &input, &PartOptions::default());
+            let result = results.remove(0);
+            match (result.status, result.answer) {
+                (Status::Solved, Some(answer)) => answer,
+                (Status::Solved, None) => {
+                    return Err(anyhow!("Day {} part {} gave no answer", day, part))
+                }
+                (
+                    Status::Failed(message) | Status::TimedOut(message) | Status::Skipped(message),
+                    _,
+                ) => {
+                    return Err(result.failure.unwrap_or(FailureKind::Other).error(anyhow!(
+                        "Day {} part {} failed: {}",
+                        day,
+                        part,
+                        message
+                    )))
+                }
+            }
+        }
+    };
+    match submit::log::check(&known, &answer) {
+        Decision::Submit => {}
+        Decision::Warn(why) => eprintln!(
+            "{}",
+            style::warning(format!("{}, submitting it anyway", why))
+        ),
+        Decision::Refuse(why) => {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "Not submitting it: {}, see {}",
+                why,
+                log_path.display()
+            )))
+        }
+    }
+    eprintln!(
+        "{}",
+        style::dim(format!(
+            "Submitting {} for day {} part {}",
+            style::answer_text(&answer),
+            day,
+            part
+        ))
+    );
+    let verdict = submit::submit(
+        &http::Polite::adventofcode(),
+        settings.event_year(),
+        day,
+        part,
+        &answer,
+        settings.session.as_deref(),
+    )?;
+    submit::log::append(
+        &log_path,
+        Submission {
+            part,
+            answer: answer.clone(),
+            verdict: verdict.into(),
+            timestamp: Utc::now().timestamp(),
+        },
+    )?;
+    match verdict {
+        Verdict::Correct => {
+            println!("{}", style::success(verdict));
+            let recorded =
+                answers::record(&data_root, settings.event_year(), &[(day, part, answer)])?;
+            if !recorded.added.is_empty() {
+                eprintln!(
+                    "{}",
+                    style::dim(format!(
+                        "Recorded the answer in {}",
+                        data_root.join(answers::ANSWERS_FILE).display()
+                    ))
+                );
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Verdict::AlreadyCompleted => {
+            println!("{}", verdict);
+            Ok(ExitCode::SUCCESS)
+        }
+        Verdict::RateLimited(_) => Err(FailureKind::RateLimited.error(anyhow!("{}", verdict))),
+        _ => Err(FailureKind::Mismatch.error(anyhow!(
+            "{} isn't the answer to day {} part {}: {}",
+            style::answer_text(&answer),
+            day,
+            part,
+            verdict
+        ))),
+    }
+}
+
+/// Clears the screen and solves the day each time its input changes. Failures
+/// are printed like any other result, so fixing the input picks up again.
+fn watch_day(args: WatchArgs, settings: &Settings) -> Result<ExitCode> {
+    let Day::Numeric(day) = args.day.clone().resolve(settings)? else {
+        return Err(FailureKind::Usage.error(anyhow!("watch needs a single day to be selected")));
+    };
+    let data_root = settings.data_root();
+    // not resolved, it's fine for the file to only turn up once watching
+    let input = match args.example {
+        Some(n) => settings.layout.example(&data_root, day, n),
+        None => settings.layout.input(&data_root, day),
+    };
+    let term = console::Term::stdout();
+    watch::watch(std::slice::from_ref(&input), || {
+        if term.is_term() {
+            term.clear_screen().ok();
+        }
+        println!(
+            "{}",
+            style::dim(format!("Watching {}, Ctrl-C to stop", input.display()))
+        );
+        let results = runner::run_day(
+            &REGISTRY,
+            day,
+            args.part.numbers(),
+            &input,
+            &PartOptions {
+                parse_mode: args.parsing.mode(),
+                comments: args.parsing.comments(),
+                ..Default::default()
+            },
+        );
+        print_day(&results, true);
+    })?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn new_day(day: u8, title: &str, settings: &Settings) -> Result<()> {
+    if REGISTRY.get(day).is_some() {
+        return Err(anyhow!("Day {} is already implemented", day));
+    }
+    let scaffold = aoc_2023::scaffold::generate(
+        Path::new("."),
+        &settings.data_root(),
+        &settings.layout,
+        day,
+        title,
+    )?;
+    for file in &scaffold.files {
+        println!("Created {}", file.display());
+    }
+    println!(
+        "\nTo build and register it, add these lines to {}",
+        scaffold.wiring
+    );
+    Ok(())
+}
+
+fn bench(args: BenchArgs, settings: &Settings) -> Result<ExitCode> {
+    let Day::Numeric(day) = args.day.clone().resolve(settings)? else {
+        return Err(FailureKind::Usage.error(anyhow!("bench needs a single day to be selected")));
+    };
+    let input = runner::resolve_input(
+        args.input.as_deref(),
+        day,
+        &settings.data_root(),
+        &settings.layout,
+    )?;
+    let options = RunOptions {
+        year: settings.event_year(),
+        show_timing: true,
+        bench: Some(BenchOptions {
+            iterations: args.iterations as usize,
+            force: args.force,
+            max_single_run: bench::MAX_SINGLE_RUN,
+        }),
+        json: args.json,
+        timeout: None,
+        progress: false,
+        implementation: args.implementation,
+        compare_impls: false,
+        cross_check: None,
+        example: None,
+        answers: vec![],
+        format: OutputFormat::Plain,
+        output: None,
+        report: None,
+        timings_log: args.timings_log,
+        budgets: Budgets::default(),
+        enforce_budget: false,
+        verbosity: Verbosity::Quiet,
+        parse_mode: args.parsing.mode(),
+        comments: args.parsing.comments(),
+        limit_lines: None,
+        input2: None,
+        baseline: BaselineOptions::default(),
+        outcomes: None,
+        record: None,
+        record_history: false,
+        notify_webhook: None,
+    };
+    run_single_day(
+        &REGISTRY,
+        day,
+        args.part.unwrap_or(Part::All),
+        &input,
+        &options,
+    )
+}
+
+fn run_batch(args: BatchArgs) -> Result<ExitCode> {
+    let jobs = batch::load(&args.file)?;
+    let results = batch::run(
+        &REGISTRY,
+        &jobs,
+        args.jobs as usize,
+        &PartOptions {
+            timeout: args.timeout,
+            parse_mode: args.parsing.mode(),
+            comments: args.parsing.comments(),
+            ..Default::default()
+        },
+    )?;
+    print!("{}", batch::table(&results));
+    for (number, job_result) in (1..).zip(&results) {
+        if let Status::Failed(message) = &job_result.result.status {
+            println!("{}", style::failure(format!("Job {}: {}", number, message)));
+        }
+    }
+
+    let count = |verdict| {
+        results
+            .iter()
+            .filter(|job_result| job_result.verdict() == verdict)
+            .count()
+    };
+    let (errors, mismatches) = (
+        count(batch::Verdict::Error),
+        count(batch::Verdict::Mismatch),
+    );
+    if errors > 0 {
+        let failed = results
+            .iter()
+            .map(|job_result| job_result.result.clone())
+            .collect::<Vec<_>>();
+        return Err(failure_kind(&failed).error(anyhow!(
+            "{} job(s) failed and {} didn't give the expected answer",
+            errors,
+            mismatches
+        )));
+    }
+    if mismatches > 0 {
+        return Err(FailureKind::Mismatch.error(anyhow!(
+            "{} job(s) didn't give the expected answer",
+            mismatches
+        )));
+    }
+    println!(
+        "{}",
+        style::success(format!(
+            "Pass: {} job(s) solved, {} with the expected answer",
+            results.len(),
+            count(batch::Verdict::Passed)
+        ))
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    dotenv().ok();
+    let cli = match Cli::try_parse_args(std::env::args_os()) {
+        Ok(cli) => cli,
+        Err(e) => return usage_error(e),
+    };
+    style::set_enabled(match cli.color {
+        ColorWhen::Auto => style::auto_enabled(),
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+    });
+    style::set_answers_hidden(cli.hide_answers);
+    init_tracing(cli.log_format);
+    // reported like returning the error from main would, in colour
+    dispatch(cli).unwrap_or_else(|e| {
+        eprintln!("{} {:?}", style::failure("Error:"), e);
+        ExitCode::from(failure::kind_of(&e).exit_code())
+    })
+}
+
+/// Reports a command line clap rejected, telling a day that just isn't
+/// implemented apart from bad usage by its exit code.
+fn usage_error(e: clap::Error) -> ExitCode {
+    let not_implemented = e.kind() == ErrorKind::ValueValidation
+        && matches!(
+            e.get(ContextKind::InvalidArg),
+            Some(ContextValue::String(arg)) if arg == "[DAY]"
+        )
+        && matches!(
+            e.get(ContextKind::InvalidValue),
+            Some(ContextValue::String(value))
+                if value.parse::<u8>().is_ok_and(|day| (1..=registry::LAST_DAY).contains(&day))
+        );
+    let _ = e.print();
+    if !e.use_stderr() {
+        // --help and --version
+        return ExitCode::SUCCESS;
+    }
+    ExitCode::from(if not_implemented {
+        FailureKind::NotImplemented.exit_code()
+    } else {
+        FailureKind::Usage.exit_code()
+    })
+}
+
+fn dispatch(cli: Cli) -> Result<ExitCode> {
+    // before the settings, which can't be had while the profile in use is
+    // broken
+    if let Some(Command::Profile(ProfileArgs { command })) = &cli.command {
+        let path = Path::new(config::CONFIG_FILE);
+        return match command {
+            ProfileCommand::List => {
+                print!(
+                    "{}",
+                    list_profiles(&ConfigFile::load(path)?, &cli.overrides())
+                );
+                Ok(ExitCode::SUCCESS)
+            }
+            ProfileCommand::SetDefault { name } => {
+                config::set_default_profile(path, name)?;
+                println!("{} is the default profile now", name);
+                Ok(ExitCode::SUCCESS)
+            }
+        };
+    }
+    let settings = Settings::from_env(&cli.overrides())?;
+    let solves = match &cli.command {
+        None
+        | Some(
+            Command::Run(_)
+            | Command::Bench(_)
+            | Command::Watch(_)
+            | Command::Verify(_)
+            | Command::Batch(_),
+        ) => true,
+        Some(Command::Submit(args)) => args.value.is_none(),
+        _ => false,
+    };
+    if solves {
+        runner::check_year(settings.event_year())?;
+    }
+    match cli.command {
+        None => run(cli.run, &settings),
+        Some(Command::Run(args)) => run(*args, &settings),
+        Some(Command::Bench(args)) => bench(args, &settings),
+        Some(Command::Watch(args)) => watch_day(args, &settings),
+        Some(Command::Verify(args)) => {
+            let mut days = match args.day.map(|day| day.resolve(&settings)).transpose()? {
+                None | Some(Day::All) => vec![],
+                Some(Day::Numeric(day)) => vec![day],
+                Some(Day::Selection(days)) => days,
+                Some(Day::Today) => unreachable!("today was resolved to its day"),
+            };
+            let data_root = args.dir.unwrap_or_else(|| settings.data_root());
+            if let Some(failed) =
+                args.rerun
+                    .select(Check::Verify, &days, &data_root, &settings.layout)?
+            {
+                if failed.is_empty() {
+                    println!("No days failed last time, there's nothing to verify again");
+                    return Ok(ExitCode::SUCCESS);
+                }
+                days = failed;
+            }
+            self_test(
+                &REGISTRY,
+                &days,
+                &data_root,
+                &settings.layout,
+                args.format,
+                args.output.as_deref(),
+                args.record,
+            )?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::List(args)) => {
+            let filter = DayFilter {
+                skip_slow: args.skip_slow,
+                only_tags: args.only_tag,
+                ..Default::default()
+            };
+            list_days(
+                &REGISTRY,
+                &settings.data_root(),
+                &settings.layout,
+                &filter,
+                args.json,
+            )?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Download(DownloadArgs { day, force })) => {
+            let path = settings.layout.input(&settings.data_root(), day);
+            download_input(day, &path, force, &settings)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::FetchPuzzle(FetchPuzzleArgs { day, refresh })) => {
+            fetch_puzzle(day, refresh, &settings)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::FetchExample(FetchExampleArgs { day, part2, force })) => {
+            fetch_example(day, part2, force, &settings)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Status(StatusArgs { refresh, offline })) => {
+            show_status(&REGISTRY, refresh, offline, &settings)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Await(AwaitArgs { download, new })) => {
+            await_puzzle(download, new, &settings)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Leaderboard(LeaderboardArgs { id, json })) => {
+            show_leaderboard(id, json, &settings)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Submit(args)) => submit_answer(&REGISTRY, args, &settings),
+        Some(Command::New(NewArgs { day, title })) => {
+            new_day(day, &title, &settings)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Completions(CompletionsArgs { shell })) => {
+            print!("{}", completions(shell));
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Doctor(DoctorArgs { data_dir })) => {
+            let data_root = data_dir.unwrap_or_else(|| settings.data_root());
+            check_data(&REGISTRY, &data_root, &settings.layout)
+        }
+        Some(Command::Batch(args)) => run_batch(args),
+        Some(Command::Timings(TimingsArgs {
+            command: TimingsCommand::Summarize { path },
+        })) => {
+            print!(
+                "{}",
+                timings::table(&timings::trends(
+                    &timings::load(&path)?,
+                    settings.event_year()
+                ))
+            );
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Report(ReportArgs {
+            command: ReportCommand::Readme { file, timings },
+        })) => {
+            let answers = Answers::load(&settings.data_root())?.unwrap_or_default();
+            let trends = match timings {
+                Some(path) => timings::trends(&timings::load(&path)?, settings.event_year()),
+                None => vec![],
+            };
+            let days = REGISTRY
+                .days()
+                .map(|(day, puzzle)| (day, puzzle.title()))
+                .collect::<Vec<_>>();
+            let table = readme::table(&days, &answers, &trends);
+            if readme::update(&file, &table)? {
+                println!("Updated the table in {}", file.display());
+            } else {
+                println!("The table in {} is up to date", file.display());
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Report(ReportArgs {
+            command: ReportCommand::Badge { output, day },
+        })) => {
+            let answers = Answers::load(&settings.data_root())?.unwrap_or_default();
+            let badge = match day {
+                Some(day) => badge::day(&answers, settings.event_year(), day),
+                None => badge::event(&answers, settings.event_year()),
+            };
+            match output {
+                Some(path) => {
+                    badge.write(&path)?;
+                    println!("Wrote {} to {}", badge.message, path.display());
+                }
+                None => println!("{}", badge.to_json()),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Profile(_)) => unreachable!("profiles are seen to before the settings"),
+        Some(Command::History(HistoryArgs { day, part, last })) => {
+            let entries = history::History::open(Path::new(history::HISTORY_FILE))?.entries(
+                settings.event_year(),
+                day,
+                part,
+                last as usize,
+            )?;
+            if entries.is_empty() {
+                println!(
+                    "No runs of day {} in the history yet, run it with --record-history",
+                    day
+                );
+                return Ok(ExitCode::SUCCESS);
+            }
+            print!("{}", history::table(&entries));
+            println!();
+            for line in history::trends(&entries) {
+                println!("{}", line);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Gen(GenArgs {
+            day,
+            lines,
+            seed,
+            density,
+        })) => {
+            let input = generate::for_day(day, lines, density, seed)
+                .map_err(|e| FailureKind::Usage.error(e))?;
+            print!("{}", input);
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "all" is the only day that's valid whichever days are compiled in
+    fn parse_cli(args: &str) -> Result<Cli, clap::Error> {
+        Cli::try_parse_args(["aoc"].into_iter().chain(args.split_whitespace()))
+    }
+
+    /// The arguments for running days, whether or not `run` was given.
+    fn parse(args: &str) -> Result<RunArgs, clap::Error> {
+        match parse_cli(args)? {
+            Cli {
+                command: None, run, ..
+            } => Ok(run),
+            Cli {
+                command: Some(Command::Run(run)),
+                ..
+            } => Ok(*run),
+            _ => panic!("{} isn't a run", args),
+        }
+    }
+
+    #[test]
+    fn test_part_defaults_to_all() -> Result<()> {
+        let cli = parse("all data")?;
+        assert_eq!(cli.day, Some(Day::All));
+        assert_eq!(cli.part, None);
+        assert_eq!(cli.input, Some(PathBuf::from("data")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_explicit_part() -> Result<()> {
+        let cli = parse("all part2 data")?;
+        assert_eq!(cli.part, Some(Part::Part2));
+        assert_eq!(cli.input, Some(PathBuf::from("data")));
+        let cli = parse("all all data")?;
+        assert_eq!((cli.day, cli.part), (Some(Day::All), Some(Part::All)));
+        assert_eq!(cli.input, Some(PathBuf::from("data")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_is_optional() -> Result<()> {
+        let cli = parse("all part1")?;
+        assert_eq!((cli.part, cli.input), (Some(Part::Part1), None));
+        let cli = parse("all")?;
+        assert_eq!((cli.part, cli.input), (None, None));
+        // a day is only asked for when there's a terminal, never for scripts
+        for args in ["--no-interactive", "run --no-interactive"] {
+            assert_eq!(
+                parse(args).err().map(|e| e.kind()),
+                Some(ErrorKind::MissingRequiredArgument),
+                "{}",
+                args
+            );
+        }
+        assert_eq!(parse("--no-interactive --list")?.day, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_part() {
+        let error = parse("all part3 data").err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+        assert!(error.to_string().contains("'part3' isn't a part"));
+        assert!(parse("all part1 a b").is_err());
+    }
+
+    #[test]
+    fn test_describe() -> Result<()> {
+        let cli = parse("all --describe")?;
+        assert_eq!(cli.part, None);
+        let cli = parse("all part1 --describe")?;
+        assert_eq!(cli.part, Some(Part::Part1));
+        assert!(parse("all part1 data --describe").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_example() -> Result<()> {
+        assert_eq!(parse("all")?.example, None);
+        assert_eq!(parse("all --example")?.example, Some(1));
+        assert_eq!(parse("all part2 --example 2")?.example, Some(2));
+        assert!(parse_cli("all --example 0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_subcommand_matches_default() -> Result<()> {
+        for args in [
+            "all part2 data --no-timing",
+            "run all part2 data --no-timing",
+        ] {
+            let run = parse(args)?;
+            assert_eq!((run.day, run.part), (Some(Day::All), Some(Part::Part2)));
+            assert_eq!(run.input, Some(PathBuf::from("data")));
+            assert!(run.no_timing);
+        }
+        assert!(parse_cli("run all data --log-format json").is_ok());
+        // a subcommand's options can't be mixed with the default run's
+        assert!(parse_cli("all data list").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bench_subcommand() -> Result<()> {
+        let Some(Command::Bench(args)) = parse_cli("bench all part1 data -n 5 --json")?.command
+        else {
+            panic!("expected bench");
+        };
+        assert_eq!((args.day, args.part), (Day::All, Some(Part::Part1)));
+        assert_eq!(args.input, Some(PathBuf::from("data")));
+        assert_eq!(args.iterations, 5);
+        assert!(args.json);
+        assert!(parse_cli("bench all -n 1").is_err());
+        assert!(parse_cli("bench").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_subcommands() -> Result<()> {
+        assert!(matches!(
+            parse_cli("verify")?.command,
+            Some(Command::Verify(VerifyArgs { day: None, .. }))
+        ));
+        let Some(Command::Verify(args)) = parse_cli("verify all data")?.command else {
+            panic!("not parsed as verify");
+        };
+        assert_eq!(args.dir, Some(PathBuf::from("data")));
+        assert!(matches!(
+            parse_cli("list --skip-slow")?.command,
+            Some(Command::List(ListArgs {
+                skip_slow: true,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse_cli("submit 25 2 input.txt")?.command,
+            Some(Command::Submit(SubmitArgs {
+                day: 25,
+                part: 2,
+                input: Some(_),
+                value: None,
+            }))
+        ));
+        assert!(matches!(
+            parse_cli("submit 25 1 --value 1234")?.command,
+            Some(Command::Submit(SubmitArgs {
+                input: None,
+                value: Some(_),
+                ..
+            }))
+        ));
+        assert!(parse_cli("submit 25 1 input.txt --value 1234").is_err());
+        assert!(parse_cli("download 26").is_err());
+        assert!(matches!(
+            parse_cli("fetch-puzzle 6")?.command,
+            Some(Command::FetchPuzzle(FetchPuzzleArgs {
+                day: 6,
+                refresh: false
+            }))
+        ));
+        assert!(matches!(
+            parse_cli("fetch-example 6 --part2")?.command,
+            Some(Command::FetchExample(FetchExampleArgs {
+                day: 6,
+                part2: true,
+                force: false
+            }))
+        ));
+        assert!(matches!(
+            parse_cli("status --offline")?.command,
+            Some(Command::Status(StatusArgs {
+                refresh: false,
+                offline: true
+            }))
+        ));
+        assert!(parse_cli("status --offline --refresh").is_err());
+        assert!(matches!(
+            parse_cli("await --download")?.command,
+            Some(Command::Await(AwaitArgs {
+                download: true,
+                new: false
+            }))
+        ));
+        assert!(matches!(
+            parse_cli("leaderboard 12345 --json")?.command,
+            Some(Command::Leaderboard(LeaderboardArgs {
+                id: 12345,
+                json: true
+            }))
+        ));
+        assert!(parse_cli("leaderboard").is_err());
+        assert!(parse_cli("submit 1 3 1234").is_err());
+        assert!(parse_cli("new 8 Haunted").is_err());
+        assert!(matches!(
+            parse_cli("watch all part2 --example 2")?.command,
+            Some(Command::Watch(WatchArgs {
+                day: Day::All,
+                part: Part::Part2,
+                example: Some(2),
+                ..
+            }))
+        ));
+        assert_eq!(
+            parse_cli("watch all --lenient")?
+                .command
+                .map(|command| match command {
+                    Command::Watch(args) => args.parsing.mode(),
+                    _ => unreachable!(),
+                }),
+            Some(ParseMode::Lenient)
+        );
+        assert_eq!(parse_cli("all")?.run.parsing.mode(), ParseMode::Strict);
+        assert_eq!(
+            parse_cli("all --strict")?.run.parsing.mode(),
+            ParseMode::Strict
+        );
+        assert_eq!(
+            parse_cli("all --lenient")?.run.parsing.mode(),
+            ParseMode::Lenient
+        );
+        assert!(parse_cli("all --strict --lenient").is_err());
+        assert!(matches!(
+            parse_cli("watch all")?.command,
+            Some(Command::Watch(WatchArgs {
+                part: Part::All,
+                example: None,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse_cli("completions zsh")?.command,
+            Some(Command::Completions(CompletionsArgs { shell: Shell::Zsh }))
+        ));
+        assert!(parse_cli("completions tcsh").is_err());
+        assert!(matches!(
+            parse_cli("profile list")?.command,
+            Some(Command::Profile(ProfileArgs {
+                command: ProfileCommand::List
+            }))
+        ));
+        assert!(matches!(
+            parse_cli("profile set-default work")?.command,
+            Some(Command::Profile(ProfileArgs {
+                command: ProfileCommand::SetDefault { name }
+            })) if name == "work"
+        ));
+        assert!(parse_cli("profile set-default").is_err());
+        assert!(matches!(
+            parse_cli("new 8 --title Haunted")?.command,
+            Some(Command::New(NewArgs { day: 8, .. }))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_flags() -> Result<()> {
+        let cli = parse_cli("all --data-dir shared --year 2022")?;
+        let overrides = cli.overrides();
+        assert_eq!(overrides.data_dir, Some(PathBuf::from("shared")));
+        assert_eq!(overrides.year, Some(2022));
+        let cli = parse_cli("list --data-dir shared")?;
+        assert_eq!(cli.overrides().data_dir, Some(PathBuf::from("shared")));
+        assert!(parse_cli("all --year twenty").is_err());
+        let cli = parse_cli("submit 7 1 --profile work")?;
+        assert_eq!(cli.overrides().profile.as_deref(), Some("work"));
+        assert_eq!(parse_cli("all")?.overrides().profile, None);
+        Ok(())
+    }
+
+    #[cfg(all(feature = "day02", feature = "day07"))]
+    #[test]
+    fn test_day_names() {
+        assert_eq!(valid_day("camel-cards"), Ok(Day::Numeric(7)));
+        assert_eq!(valid_day("CamelCards"), Ok(Day::Numeric(7)));
+        assert_eq!(valid_day("cube"), Ok(Day::Numeric(2)));
+        let error = valid_day("camel-crads").unwrap_err();
+        assert!(error.ends_with("did you mean 'camel-cards'?"), "{}", error);
+        assert!(!error.contains("possible values"), "{}", error);
+        let error = valid_day("c").unwrap_err();
+        assert!(
+            error.contains("'cube-conundrum', 'camel-cards'"),
+            "{}",
+            error
+        );
+        let error = valid_day("zzz").unwrap_err();
+        assert!(error.contains("[possible values: "), "{}", error);
+    }
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().expect("a valid RFC 3339 timestamp")
+    }
+
+    #[test]
+    fn test_latest() -> Result<()> {
+        // the highest day compiled in, not the calendar's
+        assert_eq!(
+            valid_day("latest").ok(),
+            REGISTRY.latest().map(Day::Numeric)
+        );
+        assert_eq!(valid_day("Latest"), valid_day("latest"));
+        let error = latest_day(&Registry::new()).unwrap_err();
+        assert!(
+            error.starts_with("latest is the highest implemented day, but this build has none"),
+            "{}",
+            error
+        );
+        assert!(possible_day_values().contains(&"latest".to_string()));
+        Ok(())
+    }
+
+    #[cfg(feature = "day07")]
+    #[test]
+    fn test_parse_latest() -> Result<()> {
+        // `aoc latest` alone runs both parts on the day's default input
+        let args = parse("latest")?;
+        assert_eq!(args.day, REGISTRY.latest().map(Day::Numeric));
+        assert_eq!(args.part, None);
+        assert_eq!(args.input, None);
+        let args = parse("latest part2 input.txt")?;
+        assert_eq!(args.part, Some(Part::Part2));
+        assert_eq!(args.input, Some(PathBuf::from("input.txt")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_today() -> Result<()> {
+        assert_eq!(valid_day("today"), Ok(Day::Today));
+        assert_eq!(valid_day("Today"), Ok(Day::Today));
+        // other days are left alone whatever the date
+        let summer = at("2023-07-01T12:00:00Z");
+        assert_eq!(
+            Day::All.resolve_today(&REGISTRY, summer, Timezone::Est)?,
+            Day::All
+        );
+        let error = Day::Today
+            .resolve_today(&REGISTRY, summer, Timezone::Est)
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with("Today is 1 July"),
+            "{}",
+            error
+        );
+        // a bad selector, not a failed run
+        assert_eq!(failure::kind_of(&error).exit_code(), 2);
+        // day 20 isn't done
+        let error = Day::Today
+            .resolve_today(&REGISTRY, at("2023-12-20T12:00:00Z"), Timezone::Est)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Today is day 20, which isn't implemented yet"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "day07")]
+    #[test]
+    fn test_today_in_december() -> Result<()> {
+        let released = at("2023-12-07T05:00:00Z");
+        assert_eq!(
+            Day::Today.resolve_today(&REGISTRY, released, Timezone::Est)?,
+            Day::Numeric(7)
+        );
+        // an hour earlier in EST it's still the 6th
+        let earlier =
+            Day::Today.resolve_today(&REGISTRY, at("2023-12-07T04:00:00Z"), Timezone::Est);
+        assert_eq!(
+            earlier.ok(),
+            cfg!(feature = "day06").then_some(Day::Numeric(6))
+        );
+        // and `aoc today` alone runs both parts on the day's default input
+        let args = parse("today")?;
+        assert_eq!(args.day, Some(Day::Today));
+        assert_eq!(args.part, None);
+        assert_eq!(args.input, None);
+        Ok(())
+    }
+
+    #[cfg(all(feature = "day05", feature = "day07"))]
+    #[test]
+    fn test_parse_skip() -> Result<()> {
+        let args = parse("all all data --skip 7 --skip if-you-give-a-seed-a-fertilizer,7")?;
+        assert_eq!(args.skip, vec![7, 5, 7]);
+        assert_eq!(args.input, Some(PathBuf::from("data")));
+        // skips name implemented days, one at a time
+        assert!(parse("all --skip 25").is_err());
+        assert!(parse("all --skip 5-7").is_err());
+        assert!(parse("all --skip all").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_paste() -> Result<()> {
+        let args = parse("all --paste --save")?;
+        assert!(args.paste && args.save);
+        // saving only applies to a pasted input
+        assert!(parse("all --save").is_err());
+        assert!(parse("all --paste --example").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_download() -> Result<()> {
+        let Some(Command::Download(args)) = parse_cli("download 7 --force")?.command else {
+            panic!("not parsed as download");
+        };
+        assert_eq!((args.day, args.force), (7, true));
+        assert!(parse_cli("download 26").is_err());
+
+        assert!(parse("all --download")?.download);
+        assert!(parse("all --download --paste").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_batch() -> Result<()> {
+        let Some(Command::Batch(args)) = parse_cli("batch jobs.toml -j 4 --lenient")?.command
+        else {
+            panic!("not parsed as batch");
+        };
+        assert_eq!(args.file, PathBuf::from("jobs.toml"));
+        assert_eq!(args.jobs, 4);
+        assert_eq!(args.parsing.mode(), ParseMode::Lenient);
+        assert!(parse_cli("batch").is_err());
+        assert!(parse_cli("batch jobs.toml -j 0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_timings() -> Result<()> {
+        let Some(Command::Timings(TimingsArgs {
+            command: TimingsCommand::Summarize { path },
+        })) = parse_cli("timings summarize timings.jsonl")?.command
+        else {
+            panic!("not parsed as timings summarize");
+        };
+        assert_eq!(path, PathBuf::from("timings.jsonl"));
+        assert!(parse_cli("timings").is_err());
+        assert_eq!(
+            parse_cli("all --timings-log timings.jsonl")?
+                .run
+                .timings_log,
+            Some(PathBuf::from("timings.jsonl"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_report() -> Result<()> {
+        let Some(Command::Report(ReportArgs {
+            command: ReportCommand::Readme { file, timings },
+        })) = parse_cli("report readme")?.command
+        else {
+            panic!("not parsed as report readme");
+        };
+        assert_eq!((file, timings), (PathBuf::from("README.md"), None));
+        let Some(Command::Report(ReportArgs {
+            command: ReportCommand::Readme { file, timings },
+        })) = parse_cli("report readme --file docs/index.md --timings t.jsonl")?.command
+        else {
+            panic!("not parsed as report readme");
+        };
+        assert_eq!(file, PathBuf::from("docs/index.md"));
+        assert_eq!(timings, Some(PathBuf::from("t.jsonl")));
+        let Some(Command::Report(ReportArgs {
+            command: ReportCommand::Badge { output, day },
+        })) = parse_cli("report badge --output badge.json --day 7")?.command
+        else {
+            panic!("not parsed as report badge");
+        };
+        assert_eq!((output, day), (Some(PathBuf::from("badge.json")), Some(7)));
+        assert!(parse_cli("report badge --day 26").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_notify_webhook() -> Result<()> {
+        assert_eq!(parse("all")?.notify_webhook, None);
+        assert_eq!(
+            parse("all all --notify-webhook")?.notify_webhook,
+            Some(None)
+        );
+        assert_eq!(
+            parse("all all data --notify-webhook https://example.com/hook")?.notify_webhook,
+            Some(Some("https://example.com/hook".to_string()))
+        );
+        assert!(parse("all --notify-webhook --bench 3").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_history() -> Result<()> {
+        assert!(parse("all --record-history")?.record_history);
+        assert!(parse("all --record-history --bench 3").is_err());
+        let Some(Command::History(HistoryArgs { day, part, last })) =
+            parse_cli("history 7")?.command
+        else {
+            panic!("not parsed as history");
+        };
+        assert_eq!((day, part, last), (7, None, history::DEFAULT_RUNS as u32));
+        let Some(Command::History(HistoryArgs { day, part, last })) =
+            parse_cli("history 25 2 --last 5")?.command
+        else {
+            panic!("not parsed as history");
+        };
+        assert_eq!((day, part, last), (25, Some(2), 5));
+        assert!(parse_cli("history 7 3").is_err());
+        assert!(parse_cli("history 7 --last 0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen() -> Result<()> {
+        let Some(Command::Gen(args)) = parse_cli("gen 4")?.command else {
+            panic!("not parsed as gen");
+        };
+        assert_eq!(
+            (args.day, args.lines, args.seed, args.density),
+            (4, 1000, 0, generate::DEFAULT_DENSITY)
+        );
+        let Some(Command::Gen(args)) =
+            parse_cli("gen 1 --lines 50 --seed 9 --density 0.8")?.command
+        else {
+            panic!("not parsed as gen");
+        };
+        assert_eq!(
+            (args.day, args.lines, args.seed, args.density),
+            (1, 50, 9, 0.8)
+        );
+        assert!(parse_cli("gen 1 --density 1.5").is_err());
+        assert!(parse_cli("gen 26").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_record() -> Result<()> {
+        assert!(parse("all --record")?.record);
+        assert!(parse("all --record --example").is_err());
+        assert!(parse("all --record --limit-lines 3").is_err());
+        let Some(Command::Verify(args)) = parse_cli("verify --record")?.command else {
+            panic!("not parsed as verify");
+        };
+        assert!(args.record);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_verbose() -> Result<()> {
+        assert_eq!(parse("all")?.verbose, 0);
+        assert_eq!(parse("all -v")?.verbose, 1);
+        assert_eq!(parse("all -vv")?.verbose, 2);
+        assert_eq!(parse("all --verbose --verbose")?.verbose, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_budget() -> Result<()> {
+        let args = parse("all --budget 1s --enforce-budget")?;
+        assert_eq!(args.budget, Some(Duration::from_secs(1)));
+        assert!(args.enforce_budget);
+        assert!(parse("all --budget soon").is_err());
+        assert!(parse("all --budget 1s --bench 3").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_input2() -> Result<()> {
+        let args = parse("all --example --input2 data/01/example2")?;
+        assert_eq!(args.input2, Some(PathBuf::from("data/01/example2")));
+        assert!(parse("--input2 example2").is_err());
+        assert!(parse("all --input2 example2 --bench 3").is_err());
+        assert!(parse("all --input2 example2 --compare-impls").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_cross_check() -> Result<()> {
+        assert!(parse("all --cross-check")?.cross_check);
+        assert!(parse("all --cross-check --compare-impls").is_err());
+        assert!(parse("all --cross-check --impl brute").is_err());
+        assert!(parse("all --cross-check --bench 3").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_comments() -> Result<()> {
+        assert_eq!(parse("all")?.parsing.comments(), None);
+        assert_eq!(
+            parse("all --allow-comments")?.parsing.comments().as_deref(),
+            Some("#")
+        );
+        let args = parse("all --allow-comments --comment-prefix //")?;
+        assert_eq!(args.parsing.comments().as_deref(), Some("//"));
+        // a prefix means nothing without comments being allowed
+        assert!(parse("all --comment-prefix //").is_err());
+        assert!(parse("all --allow-comments --comment-prefix").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_limit_lines() -> Result<()> {
+        assert_eq!(
+            parse("all part2 data --limit-lines 40")?.limit_lines,
+            Some(40)
+        );
+        assert!(parse("all --limit-lines 0").is_err());
+        // timings of part of an input would be misleading
+        assert!(parse("all --limit-lines 40 --bench 5").is_err());
+        assert!(parse("all --limit-lines 40 --save-baseline main").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rerun_failed() -> Result<()> {
+        // no day needed to rerun the ones that failed
+        let args = parse("--no-interactive --rerun-failed")?;
+        assert!(args.rerun.rerun_failed);
+        assert_eq!(args.day, None);
+        assert!(parse("all --reset")?.rerun.reset);
+        assert!(parse("all --rerun-failed --reset").is_err());
+        match parse_cli("verify --rerun-failed")?.command {
+            Some(Command::Verify(args)) => assert!(args.rerun.rerun_failed),
+            _ => panic!("verify --rerun-failed isn't a verify"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_baselines() -> Result<()> {
+        let args = parse("all --save-baseline main --compare-baseline v1.2")?;
+        assert_eq!(args.save_baseline.as_deref(), Some("main"));
+        assert_eq!(args.compare_baseline.as_deref(), Some("v1.2"));
+        assert_eq!(args.regression_threshold, baseline::DEFAULT_THRESHOLD);
+        let args = parse("all --compare-baseline main --regression-threshold 2.5")?;
+        assert_eq!(args.regression_threshold, 2.5);
+        assert!(parse("all --save-baseline ../main").is_err());
+        assert!(parse("all --compare-baseline main --regression-threshold -1").is_err());
+        // the threshold is only for comparing
+        assert!(parse("all --regression-threshold 5").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_completions() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let script = completions(shell);
+            assert!(script.contains("completions"), "{}", shell);
+            assert!(script.contains("all"), "{}", shell);
+        }
+    }
+
+    #[cfg(feature = "day03")]
+    #[test]
+    fn test_completions_offer_day_names() {
+        let script = completions(Shell::Bash);
+        assert!(script.contains("gear-ratios"));
+    }
+
+    #[test]
+    fn test_help_lists_subcommands() {
+        let help = Cli::command().render_help().to_string();
+        for command in [
+            "run",
+            "bench",
+            "watch",
+            "verify",
+            "download",
+            "submit",
+            "new",
+            "list",
+            "completions",
+            "doctor",
+        ] {
+            assert!(help.contains(&format!("  {} ", command)), "{}", help);
+        }
+    }
+}
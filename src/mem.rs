@@ -0,0 +1,141 @@
+//! Peak memory tracking for solver runs, only compiled in with the
+//! `mem-stats` feature. Without it [`measure`] just calls the closure and
+//! reports nothing, so there is no overhead on allocations.
+//!
+//! The counters are process wide, so with several days running at once
+//! (`--jobs`) a part's peak includes whatever the other threads had allocated
+//! at the time.
+
+#[cfg(feature = "mem-stats")]
+mod tracking {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    pub struct TrackingAllocator;
+
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    #[global_allocator]
+    static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+    fn allocated(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn freed(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for TrackingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            freed(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                freed(layout.size());
+                allocated(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    /// Resets the peak to what is allocated right now and returns that as the
+    /// baseline to measure from.
+    pub fn reset_peak() -> usize {
+        let current = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(current, Ordering::Relaxed);
+        current
+    }
+
+    pub fn peak() -> usize {
+        PEAK.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs `f`, returning its result along with the peak number of bytes
+/// allocated above what was already allocated when it started. The peak is
+/// always `None` without the `mem-stats` feature.
+#[cfg(feature = "mem-stats")]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<u64>) {
+    let baseline = tracking::reset_peak();
+    let result = f();
+    let peak = tracking::peak().saturating_sub(baseline);
+    (result, Some(peak as u64))
+}
+
+#[cfg(not(feature = "mem-stats"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<u64>) {
+    (f(), None)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[0])
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0MiB");
+    }
+
+    #[cfg(feature = "mem-stats")]
+    #[test]
+    fn test_measure_tracks_peak() {
+        const SIZE: usize = 8 * 1024 * 1024;
+        let (_, heavy) = measure(|| {
+            let buffer = vec![1u8; SIZE];
+            buffer.iter().map(|b| *b as u64).sum::<u64>()
+        });
+        let heavy = heavy.unwrap();
+        assert!(heavy >= SIZE as u64);
+
+        // the previous peak must not carry over into the next measurement
+        let (_, light) = measure(|| 1 + 1);
+        assert!(light.unwrap() < heavy);
+    }
+
+    #[cfg(not(feature = "mem-stats"))]
+    #[test]
+    fn test_measure_disabled() {
+        assert_eq!(measure(|| 7), (7, None));
+    }
+}
@@ -0,0 +1,151 @@
+use core::fmt;
+use serde::Serialize;
+
+/// Roughly how hard a puzzle was to solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+/// How long a day takes on a real input, `Slow` days can be left out of an
+/// all-days run with `--skip-slow`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuntimeClass {
+    #[default]
+    Fast,
+    Slow,
+}
+
+impl fmt::Display for RuntimeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeClass::Fast => write!(f, "fast"),
+            RuntimeClass::Slow => write!(f, "slow"),
+        }
+    }
+}
+
+/// Descriptive information about a day, used to list and filter the days.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Metadata {
+    /// What kind of puzzle it is, eg. "grid", "graph" or "parsing".
+    pub tags: &'static [&'static str],
+    pub difficulty: Option<Difficulty>,
+    pub runtime: RuntimeClass,
+}
+
+impl Metadata {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .iter()
+            .any(|candidate| candidate.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Which days an all-days run or listing should include.
+#[derive(Debug, Clone, Default)]
+pub struct DayFilter {
+    pub skip_slow: bool,
+    /// Keep only days with at least one of these tags, every day when empty.
+    pub only_tags: Vec<String>,
+    /// Keep only these days, every day when empty.
+    pub only_days: Vec<u8>,
+}
+
+impl DayFilter {
+    pub fn is_empty(&self) -> bool {
+        !self.skip_slow && self.only_tags.is_empty() && self.only_days.is_empty()
+    }
+
+    pub fn matches(&self, day: u8, metadata: &Metadata) -> bool {
+        if !self.only_days.is_empty() && !self.only_days.contains(&day) {
+            return false;
+        }
+        if self.skip_slow && metadata.runtime == RuntimeClass::Slow {
+            return false;
+        }
+        self.only_tags.is_empty() || self.only_tags.iter().any(|tag| metadata.has_tag(tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: Metadata = Metadata {
+        tags: &["grid", "parsing"],
+        difficulty: Some(Difficulty::Medium),
+        runtime: RuntimeClass::Fast,
+    };
+    const SLOW_GRAPH: Metadata = Metadata {
+        tags: &["graph"],
+        difficulty: Some(Difficulty::Hard),
+        runtime: RuntimeClass::Slow,
+    };
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = DayFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(1, &GRID));
+        assert!(filter.matches(1, &SLOW_GRAPH));
+        assert!(filter.matches(1, &Metadata::default()));
+    }
+
+    #[test]
+    fn test_skip_slow() {
+        let filter = DayFilter {
+            skip_slow: true,
+            ..Default::default()
+        };
+        assert!(filter.matches(1, &GRID));
+        assert!(!filter.matches(1, &SLOW_GRAPH));
+        // days without metadata are assumed to be fast
+        assert!(filter.matches(1, &Metadata::default()));
+    }
+
+    #[test]
+    fn test_only_tags() {
+        let filter = DayFilter {
+            only_tags: vec!["Grid".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.matches(1, &GRID));
+        assert!(!filter.matches(1, &SLOW_GRAPH));
+        assert!(!filter.matches(1, &Metadata::default()));
+
+        let filter = DayFilter {
+            only_tags: vec!["graph".to_string(), "grid".to_string()],
+            skip_slow: true,
+            ..Default::default()
+        };
+        assert!(filter.matches(1, &GRID));
+        assert!(!filter.matches(1, &SLOW_GRAPH));
+    }
+
+    #[test]
+    fn test_only_days() {
+        let filter = DayFilter {
+            only_days: vec![3, 5],
+            ..Default::default()
+        };
+        assert!(!filter.is_empty());
+        assert!(filter.matches(3, &GRID));
+        assert!(filter.matches(5, &SLOW_GRAPH));
+        assert!(!filter.matches(4, &GRID));
+    }
+}
@@ -0,0 +1,208 @@
+//! Explaining a wrong answer, for `--answer` and `verify`: the expected and
+//! actual answers lined up with the differences marked, and hints at the
+//! usual reasons an answer is nearly right.
+
+use std::fmt;
+
+use crate::{solver::Answer, style};
+
+/// A likely reason for a wrong answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// The answer is the other part's expected one.
+    OtherPart,
+    /// One more or one less than expected, eg. an off by one in a range.
+    OffByOne,
+    /// The expected answer wrapped around to fit in an `i32`.
+    TruncatedToI32,
+    WrongSign,
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Hint::OtherPart => "it's the other part's answer, did you run the wrong part?",
+            Hint::OffByOne => "it's off by one",
+            Hint::TruncatedToI32 => {
+                "it's the expected answer truncated to 32 bits, is something overflowing an i32?"
+            }
+            Hint::WrongSign => "it has the wrong sign",
+        })
+    }
+}
+
+/// The hints for getting `actual` instead of `expected`, given the other
+/// part's expected answer if it's known.
+pub fn hints(expected: &Answer, actual: &Answer, other_part: Option<&Answer>) -> Vec<Hint> {
+    let mut hints = vec![];
+    if other_part == Some(actual) && actual != expected {
+        hints.push(Hint::OtherPart);
+    }
+    if let (Answer::Integer(expected), Answer::Integer(actual)) = (expected, actual) {
+        let (expected, actual) = (*expected, *actual);
+        if expected.abs_diff(actual) == 1 {
+            hints.push(Hint::OffByOne);
+        }
+        if i32::try_from(expected).is_err() && actual == expected as i32 as i64 {
+            hints.push(Hint::TruncatedToI32);
+        }
+        if expected != 0 && expected.checked_neg() == Some(actual) {
+            hints.push(Hint::WrongSign);
+        }
+    }
+    hints
+}
+
+/// The expected and actual answers lined up, numbers by their last digit and
+/// text by its first character, with the characters that differ marked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    expected: Vec<char>,
+    actual: Vec<char>,
+}
+
+impl Diff {
+    pub fn new(expected: &Answer, actual: &Answer) -> Self {
+        let numbers = matches!((expected, actual), (Answer::Integer(_), Answer::Integer(_)));
+        let (expected, actual) = (expected.to_string(), actual.to_string());
+        let width = expected.chars().count().max(actual.chars().count());
+        let pad = |text: String| {
+            let padding = " ".repeat(width - text.chars().count());
+            let padded = if numbers {
+                padding + &text
+            } else {
+                text + &padding
+            };
+            padded.chars().collect::<Vec<_>>()
+        };
+        Self {
+            expected: pad(expected),
+            actual: pad(actual),
+        }
+    }
+
+    fn differs(&self, index: usize) -> bool {
+        self.expected[index] != self.actual[index]
+    }
+
+    /// The answers on a line each, the actual one's differences coloured
+    /// when styling is on, and the differences marked on a third line.
+    pub fn lines(&self) -> [String; 3] {
+        let actual = self
+            .actual
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                if self.differs(index) {
+                    style::failure(c).to_string()
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect::<String>();
+        let marker = (0..self.actual.len())
+            .map(|index| if self.differs(index) { '^' } else { ' ' })
+            .collect::<String>();
+        [
+            format!("expected: {}", self.expected.iter().collect::<String>()),
+            format!("actual:   {}", actual),
+            format!("          {}", marker).trim_end().to_string(),
+        ]
+    }
+}
+
+/// How a wrong answer is explained, a line each, indented under the line
+/// saying it didn't match. With answers hidden only the hints are given.
+pub fn explain(expected: &Answer, actual: &Answer, other_part: Option<&Answer>) -> Vec<String> {
+    let mut lines = vec![];
+    if !style::answers_hidden() {
+        lines.extend(Diff::new(expected, actual).lines());
+    }
+    lines.extend(
+        hints(expected, actual, other_part)
+            .into_iter()
+            .map(|hint| format!("hint: {}", hint)),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints_for(expected: i64, actual: i64) -> Vec<Hint> {
+        hints(&expected.into(), &actual.into(), None)
+    }
+
+    #[test]
+    fn test_other_part() {
+        let other = Answer::from(467835);
+        assert_eq!(
+            hints(&4361.into(), &467835.into(), Some(&other)),
+            [Hint::OtherPart]
+        );
+        assert_eq!(
+            hints(&4361.into(), &4362.into(), Some(&other)),
+            [Hint::OffByOne]
+        );
+        // both parts having the same answer isn't a hint at anything
+        assert!(hints(&5.into(), &5.into(), Some(&5.into())).is_empty());
+    }
+
+    #[test]
+    fn test_off_by_one() {
+        assert_eq!(hints_for(54321, 54322), [Hint::OffByOne]);
+        assert_eq!(hints_for(54321, 54320), [Hint::OffByOne]);
+        assert!(hints_for(54321, 54323).is_empty());
+        assert!(hints_for(i64::MIN, i64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_truncated_to_i32() {
+        let expected = 5_000_000_000;
+        assert_eq!(
+            hints_for(expected, expected as i32 as i64),
+            [Hint::TruncatedToI32]
+        );
+        // an answer that fits in an i32 can't have been truncated
+        assert!(hints_for(70_000, 4464).is_empty());
+    }
+
+    #[test]
+    fn test_wrong_sign() {
+        assert_eq!(hints_for(-12, 12), [Hint::WrongSign]);
+        assert_eq!(hints_for(12, -12), [Hint::WrongSign]);
+        assert!(hints_for(0, 0).is_empty());
+        assert!(hints_for(i64::MIN, i64::MIN).is_empty());
+        // -1 for 1 is two off, so only the sign is wrong
+        assert_eq!(hints_for(1, -1), [Hint::WrongSign]);
+        assert_eq!(hints_for(0, -1), [Hint::OffByOne]);
+    }
+
+    #[test]
+    fn test_text_answers() {
+        assert!(hints(&"EFGH".into(), &"EFGI".into(), None).is_empty());
+        assert_eq!(
+            hints(&"EFGH".into(), &"ABCD".into(), Some(&"ABCD".into())),
+            [Hint::OtherPart]
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(
+            Diff::new(&54321.into(), &54322.into()).lines(),
+            ["expected: 54321", "actual:   54322", "              ^"]
+        );
+        // numbers line up by their last digit
+        assert_eq!(
+            Diff::new(&1.into(), &5905.into()).lines(),
+            ["expected:    1", "actual:   5905", "          ^^^^"]
+        );
+        // text by its first character
+        assert_eq!(
+            Diff::new(&"EFGH".into(), &"EFG".into()).lines(),
+            ["expected: EFGH", "actual:   EFG ", "             ^"]
+        );
+    }
+}
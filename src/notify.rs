@@ -0,0 +1,187 @@
+//! Posting a short summary of a run to a chat webhook, for
+//! `--notify-webhook`. The payload has the message as both Discord's
+//! `content` and Slack's `text`, each ignoring the other's, so the one
+//! payload works with either.
+
+use anyhow::{anyhow, Result};
+use serde_json::json;
+use std::time::Duration;
+
+use crate::{
+    http::{Body, Method, Request, Transport, Ureq},
+    runner::{RunResult, Status, Summary},
+    solver::format_duration,
+};
+
+/// How long to wait for the webhook before giving up on it.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a payload gets sent, so tests can stand in for the network.
+pub trait Webhook {
+    /// Posts `body`, a JSON document, to `url`.
+    fn post(&self, url: &str, body: &str) -> Result<()>;
+}
+
+/// Sends payloads over HTTP.
+pub struct Http;
+
+impl Webhook for Http {
+    fn post(&self, url: &str, body: &str) -> Result<()> {
+        let request = Request {
+            method: Method::Post,
+            url: url.to_string(),
+            session: None,
+            body: Body::Json(body.to_string()),
+        };
+        let response = Ureq { timeout: TIMEOUT }
+            .send(&request)
+            .map_err(|e| anyhow!("Can't post to the webhook: {}", e))?;
+        if response.status >= 400 {
+            return Err(anyhow!(
+                "Can't post to the webhook: it answered {}",
+                response.status
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A few lines about `year`'s run: how many days ran, the stars, ie. the
+/// parts solved, and the time spent solving, then a line per failure or
+/// timeout.
+pub fn message(year: u16, results: &[RunResult]) -> String {
+    let summary = Summary::from_results(results);
+    let days = summary.succeeded.len() + summary.failed.len() + summary.timed_out.len();
+    let stars = results
+        .iter()
+        .filter(|result| result.status == Status::Solved)
+        .count();
+    let mut lines = vec![format!(
+        "AoC {}: {} day(s) run, {} ⭐ in {}",
+        year,
+        days,
+        stars,
+        format_duration(summary.solve_time)
+    )];
+    let reasons = |problem: &str, days: &[(u8, Vec<String>)]| {
+        days.iter()
+            .flat_map(|(day, reasons)| {
+                reasons.iter().map(move |reason| {
+                    // only the first line of each, to keep it compact
+                    let reason = reason.lines().next().unwrap_or_default();
+                    format!("Day {} {}: {}", day, problem, reason)
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    lines.extend(reasons("failed", &summary.failed));
+    lines.extend(reasons("timed out", &summary.timed_out));
+    lines.join("\n")
+}
+
+/// The JSON to post for `results`, see [`message`].
+pub fn payload(year: u16, results: &[RunResult]) -> String {
+    let message = message(year, results);
+    json!({ "content": message, "text": message }).to_string()
+}
+
+/// Posts the summary of `results` to `url`.
+pub fn notify(webhook: &dyn Webhook, url: &str, year: u16, results: &[RunResult]) -> Result<()> {
+    webhook.post(url, &payload(year, results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Timing;
+    use std::cell::RefCell;
+
+    fn result(day: u8, part: u32, millis: u64, status: Status) -> RunResult {
+        RunResult {
+            day,
+            part,
+            title: format!("Day {}", day),
+            description: "Answer".to_string(),
+            answer: (status == Status::Solved).then(|| 1.into()),
+            timing: Some(Timing {
+                parse: None,
+                total: Duration::from_millis(millis),
+            }),
+            peak_memory: None,
+            implementation: None,
+            status,
+            failure: None,
+            truncated: None,
+        }
+    }
+
+    fn results() -> Vec<RunResult> {
+        vec![
+            result(1, 1, 2, Status::Solved),
+            result(1, 2, 3, Status::Solved),
+            result(3, 1, 5, Status::Solved),
+            result(
+                3,
+                2,
+                1,
+                Status::Failed("Expected 467835 but got 4361\nmore detail".to_string()),
+            ),
+            result(5, 1, 0, Status::TimedOut("Gave up after 1.00s".to_string())),
+            result(6, 1, 0, Status::Skipped("No input".to_string())),
+        ]
+    }
+
+    #[derive(Default)]
+    struct Mock {
+        posted: RefCell<Vec<(String, String)>>,
+        fail: bool,
+    }
+
+    impl Webhook for Mock {
+        fn post(&self, url: &str, body: &str) -> Result<()> {
+            if self.fail {
+                return Err(anyhow!("Connection refused"));
+            }
+            self.posted
+                .borrow_mut()
+                .push((url.to_string(), body.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(
+            message(2023, &results()),
+            "\
+AoC 2023: 3 day(s) run, 3 ⭐ in 11.0ms
+Day 3 failed: [Part 2] Expected 467835 but got 4361
+Day 5 timed out: [Part 1] Gave up after 1.00s"
+        );
+        assert_eq!(message(2022, &[]), "AoC 2022: 0 day(s) run, 0 ⭐ in 0.0µs");
+    }
+
+    #[test]
+    fn test_payload() {
+        let results = &results()[..2];
+        let payload: serde_json::Value = serde_json::from_str(&payload(2023, results)).unwrap();
+        let message = "AoC 2023: 1 day(s) run, 2 ⭐ in 5.0ms";
+        assert_eq!(payload, json!({ "content": message, "text": message }));
+    }
+
+    #[test]
+    fn test_notify() {
+        let webhook = Mock::default();
+        notify(&webhook, "https://example.com/hook", 2023, &results()).unwrap();
+        let posted = webhook.posted.take();
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0].0, "https://example.com/hook");
+        assert_eq!(posted[0].1, payload(2023, &results()));
+
+        let failing = Mock {
+            fail: true,
+            ..Default::default()
+        };
+        assert!(notify(&failing, "https://example.com/hook", 2023, &results()).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Remembering which parts failed last time, so `--rerun-failed` can go
+//! straight back to them after a run or verify pass over every day.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, io::ErrorKind, path::Path};
+use tracing::warn;
+
+/// The file in the data root the outcomes are kept in.
+pub const STATE_FILE: &str = ".aoc-outcomes.json";
+
+/// What produced an outcome, as the examples checked by `verify` passing says
+/// nothing about a day's real input and the other way around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    Run,
+    Verify,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub check: Check,
+    pub day: u8,
+    pub part: u32,
+    pub failed: bool,
+    /// The input the part ran on, see [`crate::input::hash`]. `None` for
+    /// examples, which are built in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+}
+
+/// The last outcome of every part that's been run or verified, in day and
+/// part order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outcomes {
+    pub parts: Vec<Outcome>,
+}
+
+impl Outcomes {
+    /// The outcomes remembered in `data_root`, none when there's no state
+    /// file or it can't be made sense of, as it only saves some typing.
+    pub fn load(data_root: &Path) -> Self {
+        let path = data_root.join(STATE_FILE);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("Ignoring {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            warn!(
+                "Ignoring {}, which isn't a list of outcomes: {}",
+                path.display(),
+                e
+            );
+            Self::default()
+        })
+    }
+
+    /// Saves the outcomes in `data_root`, if it exists, there's nothing worth
+    /// remembering about a run whose inputs are missing anyway.
+    pub fn save(&self, data_root: &Path) -> Result<()> {
+        if !data_root.is_dir() {
+            return Ok(());
+        }
+        let path = data_root.join(STATE_FILE);
+        let text = serde_json::to_string_pretty(self)? + "\n";
+        fs::write(&path, text).with_context(|| format!("Can't save {}", path.display()))
+    }
+
+    /// Forgets every outcome remembered in `data_root`.
+    pub fn reset(data_root: &Path) -> Result<()> {
+        let path = data_root.join(STATE_FILE);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Can't remove {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Replaces the outcomes of the parts in `outcomes`, keeping those of any
+    /// other parts.
+    pub fn record(&mut self, outcomes: &[Outcome]) {
+        for outcome in outcomes {
+            let key = (outcome.check, outcome.day, outcome.part);
+            match self
+                .parts
+                .iter_mut()
+                .find(|saved| (saved.check, saved.day, saved.part) == key)
+            {
+                Some(saved) => *saved = outcome.clone(),
+                None => self.parts.push(outcome.clone()),
+            }
+        }
+        self.parts
+            .sort_by_key(|outcome| (outcome.day, outcome.part));
+    }
+
+    /// The days with a part that failed its last `check`, in day order.
+    /// Failures on an input that has changed since, going by `input_hash` of
+    /// the day's current input, are stale and left out.
+    pub fn failed_days(&self, check: Check, input_hash: impl Fn(u8) -> Option<String>) -> Vec<u8> {
+        let mut days = self
+            .parts
+            .iter()
+            .filter(|outcome| outcome.check == check && outcome.failed)
+            .filter(|outcome| match &outcome.input_hash {
+                Some(hash) => input_hash(outcome.day).as_ref() == Some(hash),
+                None => true,
+            })
+            .map(|outcome| outcome.day)
+            .collect::<Vec<_>>();
+        days.dedup();
+        days
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(check: Check, day: u8, part: u32, failed: bool, input: Option<&str>) -> Outcome {
+        Outcome {
+            check,
+            day,
+            part,
+            failed,
+            input_hash: input.map(crate::input::hash),
+        }
+    }
+
+    #[test]
+    fn test_failed_days() {
+        let mut outcomes = Outcomes::default();
+        outcomes.record(&[
+            outcome(Check::Verify, 2, 1, false, None),
+            outcome(Check::Verify, 2, 2, true, None),
+            outcome(Check::Verify, 5, 1, true, None),
+            outcome(Check::Run, 3, 1, true, Some("three")),
+            outcome(Check::Run, 4, 2, true, Some("four")),
+            outcome(Check::Run, 6, 1, false, Some("six")),
+        ]);
+        let current = |day| {
+            Some(crate::input::hash(if day == 4 {
+                "changed"
+            } else {
+                "three"
+            }))
+        };
+        assert_eq!(outcomes.failed_days(Check::Verify, current), vec![2, 5]);
+        // day 4's input changed since it failed
+        assert_eq!(outcomes.failed_days(Check::Run, current), vec![3]);
+
+        // a pass replaces the failure
+        outcomes.record(&[outcome(Check::Verify, 5, 1, false, None)]);
+        assert_eq!(outcomes.failed_days(Check::Verify, current), vec![2]);
+        assert_eq!(outcomes.parts.len(), 6);
+    }
+
+    #[test]
+    fn test_save_load_and_reset() -> Result<()> {
+        let data_root =
+            std::env::temp_dir().join(format!("aoc-2023-outcomes-{}", std::process::id()));
+        fs::create_dir_all(&data_root)?;
+        assert_eq!(Outcomes::load(&data_root), Outcomes::default());
+
+        let mut outcomes = Outcomes::default();
+        outcomes.record(&[outcome(Check::Run, 7, 2, true, Some("input"))]);
+        outcomes.save(&data_root)?;
+        let loaded = Outcomes::load(&data_root);
+        Outcomes::reset(&data_root)?;
+        let reset = Outcomes::load(&data_root);
+        // a broken state file is as good as none
+        fs::write(data_root.join(STATE_FILE), "{\"parts\": 7}")?;
+        let broken = Outcomes::load(&data_root);
+        Outcomes::reset(&data_root)?;
+        Outcomes::reset(&data_root)?;
+        fs::remove_dir_all(&data_root)?;
+
+        assert_eq!(loaded, outcomes);
+        assert_eq!(reset, Outcomes::default());
+        assert_eq!(broken, Outcomes::default());
+        Ok(())
+    }
+}
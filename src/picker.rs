@@ -0,0 +1,144 @@
+//! Picking a day and part from a menu, for when `aoc` is run in a terminal
+//! without saying which day to solve.
+
+use anyhow::Result;
+use dialoguer::{
+    theme::{ColorfulTheme, SimpleTheme, Theme},
+    Select,
+};
+use std::{
+    io::{stdin, stdout, IsTerminal},
+    path::Path,
+};
+
+use crate::{
+    layout::Layout,
+    registry::{DayInfo, Registry},
+    style,
+};
+
+/// Whether there's someone at a terminal to ask, rather than a script.
+pub fn available() -> bool {
+    stdin().is_terminal() && stdout().is_terminal()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pick {
+    pub day: u8,
+    /// The part to solve, `None` for both.
+    pub part: Option<u32>,
+}
+
+/// How a day is listed, with a note when its input is missing as running it
+/// would only fail.
+pub fn day_label(info: &DayInfo) -> String {
+    if info.has_input {
+        info.title.clone()
+    } else {
+        format!("{} (no input at {})", info.title, info.input.display())
+    }
+}
+
+/// The parts a day can be run for, both first when it has more than one.
+pub fn part_choices(info: &DayInfo) -> Vec<(Option<u32>, String)> {
+    let parts = info
+        .parts
+        .iter()
+        .zip(1..)
+        .map(|(description, part)| (Some(part), format!("Part {}: {}", part, description)));
+    match info.parts.len() {
+        0 | 1 => parts.collect(),
+        _ => std::iter::once((None, "Both parts".to_string()))
+            .chain(parts)
+            .collect(),
+    }
+}
+
+/// The day the menu starts on: the latest one with an input, as that's most
+/// likely the one being worked on, or the latest one when none have.
+pub fn default_day(days: &[DayInfo]) -> usize {
+    days.iter()
+        .rposition(|info| info.has_input)
+        .unwrap_or(days.len().saturating_sub(1))
+}
+
+/// Asks for a day then a part, `None` when the user backs out with Esc or q.
+pub fn pick(registry: &Registry, data_root: &Path, layout: &Layout) -> Result<Option<Pick>> {
+    let days = registry.info(data_root, layout).collect::<Vec<_>>();
+    let theme: Box<dyn Theme> = if style::enabled() {
+        Box::new(ColorfulTheme::default())
+    } else {
+        Box::new(SimpleTheme)
+    };
+    let Some(index) = Select::with_theme(theme.as_ref())
+        .with_prompt("Which day? (Esc to quit)")
+        .items(&days.iter().map(day_label).collect::<Vec<_>>())
+        .default(default_day(&days))
+        .max_length(10)
+        .interact_opt()?
+    else {
+        return Ok(None);
+    };
+    let info = &days[index];
+    let parts = part_choices(info);
+    let Some(part) = Select::with_theme(theme.as_ref())
+        .with_prompt(format!("Which part of {}?", info.title))
+        .items(&parts.iter().map(|(_, label)| label).collect::<Vec<_>>())
+        .default(0)
+        .interact_opt()?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(Pick {
+        day: info.day,
+        part: parts[part].0,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Metadata;
+    use std::path::PathBuf;
+
+    fn info(day: u8, parts: &[&str], has_input: bool) -> DayInfo {
+        DayInfo {
+            day,
+            name: format!("day-{}", day),
+            title: format!("Day {}: Test", day),
+            parts: parts.iter().map(|part| part.to_string()).collect(),
+            metadata: Metadata::default(),
+            input: PathBuf::from(format!("data/{:02}/input", day)),
+            has_input,
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(day_label(&info(3, &[], true)), "Day 3: Test");
+        assert_eq!(
+            day_label(&info(4, &[], false)),
+            "Day 4: Test (no input at data/04/input)"
+        );
+        let both = part_choices(&info(3, &["Sum", "Product"], true));
+        assert_eq!(
+            both,
+            vec![
+                (None, "Both parts".to_string()),
+                (Some(1), "Part 1: Sum".to_string()),
+                (Some(2), "Part 2: Product".to_string()),
+            ]
+        );
+        // nothing to choose between for a day with only one part so far
+        let one = part_choices(&info(3, &["Sum"], true));
+        assert_eq!(one, vec![(Some(1), "Part 1: Sum".to_string())]);
+    }
+
+    #[test]
+    fn test_default_day() {
+        let days = [info(1, &[], true), info(2, &[], true), info(3, &[], false)];
+        assert_eq!(default_day(&days), 1);
+        assert_eq!(default_day(&days[2..]), 0);
+        assert_eq!(default_day(&[]), 0);
+    }
+}
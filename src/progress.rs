@@ -0,0 +1,66 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::{io::IsTerminal, sync::Arc};
+
+/// Lets a solver report how far through its work it is, see
+/// [`crate::context::RunContext::progress`]. Calls are cheap but not free, so
+/// tight loops should report in batches rather than per item.
+pub trait Progress: Send + Sync {
+    fn set_length(&self, length: u64);
+    fn inc(&self, delta: u64);
+    fn set_message(&self, message: &str);
+}
+
+/// Discards all progress, used unless `--progress` is given.
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn set_length(&self, _length: u64) {}
+    fn inc(&self, _delta: u64) {}
+    fn set_message(&self, _message: &str) {}
+}
+
+impl Progress for ProgressBar {
+    fn set_length(&self, length: u64) {
+        ProgressBar::set_length(self, length);
+        self.set_position(0);
+    }
+
+    fn inc(&self, delta: u64) {
+        ProgressBar::inc(self, delta);
+    }
+
+    fn set_message(&self, message: &str) {
+        ProgressBar::set_message(self, message.to_string());
+    }
+}
+
+pub fn no_progress() -> Arc<dyn Progress> {
+    Arc::new(NoProgress)
+}
+
+/// One progress bar per day, drawn on stderr so stdout stays parseable.
+pub struct ProgressBars {
+    multi: MultiProgress,
+}
+
+impl ProgressBars {
+    /// Bars are only drawn to a terminal, `None` when stderr is redirected.
+    pub fn for_stderr() -> Option<Self> {
+        if !std::io::stderr().is_terminal() {
+            return None;
+        }
+        Some(Self {
+            multi: MultiProgress::with_draw_target(ProgressDrawTarget::stderr()),
+        })
+    }
+
+    pub fn day_bar(&self, title: &str) -> ProgressBar {
+        let style = ProgressStyle::with_template("{prefix:.bold} {wide_bar} {pos}/{len} {msg}")
+            .expect("progress template is valid");
+        self.multi.add(
+            ProgressBar::new(0)
+                .with_style(style)
+                .with_prefix(title.to_string()),
+        )
+    }
+}
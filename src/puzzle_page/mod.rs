@@ -0,0 +1,439 @@
+//! Puzzle descriptions fetched from adventofcode.com with the session cookie
+//! for `aoc fetch-puzzle`, kept as markdown next to the day's input for
+//! reading offline. `--describe` prefers them to the statements quoted in the
+//! days' doc comments. `aoc fetch-example` takes the examples from them.
+
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{download, http::Client, statement::PART_TWO_HEADING};
+
+/// What the parts' descriptions are wrapped in on the puzzle page.
+const ARTICLE: &str = "<article class=\"day-desc\">";
+
+/// What a block of code, such as an example, is wrapped in.
+const CODE_BLOCK: &str = "<pre><code>";
+
+/// Where the description of `day` is kept, next to its examples.
+pub fn path(data_root: &Path, day: u8) -> PathBuf {
+    data_root.join(format!("{:02}", day)).join("puzzle.md")
+}
+
+pub fn puzzle_url(year: u16, day: u8) -> String {
+    format!("https://adventofcode.com/{}/day/{}", year, day)
+}
+
+/// The puzzle page of day `day`.
+pub fn fetch(client: &dyn Client, year: u16, day: u8, session: Option<&str>) -> Result<String> {
+    let Some(session) = session else {
+        return Err(download::no_session("Fetching a puzzle"));
+    };
+    let url = puzzle_url(year, day);
+    let response = client.get(&url, session)?;
+    match response.status {
+        200 => Ok(response.body),
+        400 | 500 => Err(download::session_rejected(response.status)),
+        404 => Err(anyhow!(
+            "Day {} of {} isn't unlocked yet, it unlocks at midnight EST on December {}",
+            day,
+            year,
+            day
+        )),
+        status => Err(anyhow!("adventofcode.com answered {} for {}", status, url)),
+    }
+}
+
+/// The descriptions in a puzzle page as markdown, part 2's only once part 1
+/// is solved.
+pub fn markdown(html: &str) -> Result<String> {
+    let parts = articles(html)
+        .into_iter()
+        .map(to_markdown)
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        return Err(anyhow!("There's no puzzle description in the page"));
+    }
+    Ok(parts.join("\n"))
+}
+
+/// How many parts' descriptions `markdown` has.
+pub fn parts(markdown: &str) -> usize {
+    if markdown.contains(PART_TWO_HEADING) {
+        2
+    } else {
+        1
+    }
+}
+
+/// The puzzle's title, from the heading `markdown` starts with, eg. "Wait
+/// For It" from `## --- Day 6: Wait For It ---`.
+pub fn title(markdown: &str) -> Option<&str> {
+    let heading = markdown.lines().next()?.strip_prefix("## --- Day ")?;
+    let (_, title) = heading.split_once(": ")?;
+    Some(title.trim_end_matches('-').trim())
+}
+
+/// Keeps `markdown` at `path`, creating its directory as needed, unless
+/// what's kept there already has more parts. Part 2 only shows up once part
+/// 1 is solved, so fetching again after that adds it. Returns whether it was
+/// written.
+pub fn save(path: &Path, markdown: &str) -> Result<bool> {
+    if let Ok(cached) = fs::read_to_string(path) {
+        if parts(&cached) > parts(markdown) {
+            return Ok(false);
+        }
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+    }
+    fs::write(path, markdown).with_context(|| format!("Can't write {}", path.display()))?;
+    Ok(true)
+}
+
+/// The blocks of code in a puzzle page that could be its examples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Examples {
+    /// Those in part 1's description, in order, the first being taken as
+    /// its example.
+    pub part1: Vec<String>,
+    /// Those only in part 2's description, leaving out the ones it repeats
+    /// from part 1. `None` until part 1 is solved and part 2 is shown.
+    pub part2: Option<Vec<String>>,
+}
+
+/// The example candidates in a puzzle page, each block's text as it's shown,
+/// without the highlighting.
+pub fn examples(html: &str) -> Result<Examples> {
+    let articles = articles(html);
+    let Some(part1) = articles.first() else {
+        return Err(anyhow!("There's no puzzle description in the page"));
+    };
+    let part1 = code_blocks(part1);
+    if part1.is_empty() {
+        return Err(anyhow!("Part 1's description has no example in it"));
+    }
+    let part2 = articles.get(1).map(|part2| {
+        code_blocks(part2)
+            .into_iter()
+            .filter(|block| !part1.contains(block))
+            .collect()
+    });
+    Ok(Examples { part1, part2 })
+}
+
+/// The text of each `<pre><code>` block in a description, ending in a
+/// newline like an input.
+fn code_blocks(article: &str) -> Vec<String> {
+    let mut blocks = vec![];
+    let mut rest = article;
+    while let Some(start) = rest.find(CODE_BLOCK) {
+        let block = &rest[start + CODE_BLOCK.len()..];
+        let end = block.find("</code></pre>").unwrap_or(block.len());
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in block[..end].chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' => in_tag = false,
+                c if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        let mut text = decode_entities(&text);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        blocks.push(text);
+        rest = &block[end..];
+    }
+    blocks
+}
+
+/// The HTML inside each of the page's descriptions.
+fn articles(html: &str) -> Vec<&str> {
+    let mut articles = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find(ARTICLE) {
+        let article = &rest[start + ARTICLE.len()..];
+        let end = article.find("</article>").unwrap_or(article.len());
+        articles.push(&article[..end]);
+        rest = &article[end..];
+    }
+    articles
+}
+
+/// A light conversion of a description's HTML to markdown, covering what
+/// the descriptions use: headings, paragraphs, lists, code blocks, inline
+/// code, emphasis (shown in bold, as it's how the page highlights) and
+/// links.
+fn to_markdown(html: &str) -> String {
+    let mut markdown = String::new();
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (text, tag) = match rest.find('<') {
+            Some(0) => {
+                let end = rest.find('>').unwrap_or(rest.len());
+                let tag = &rest[1..end];
+                rest = rest.get(end + 1..).unwrap_or_default();
+                ("", Some(tag))
+            }
+            Some(start) => {
+                let text = &rest[..start];
+                rest = &rest[start..];
+                (text, None)
+            }
+            None => (std::mem::take(&mut rest), None),
+        };
+        let Some(tag) = tag else {
+            let text = decode_entities(text);
+            if in_pre {
+                markdown.push_str(&text);
+            } else if !text.trim().is_empty() || !(markdown.is_empty() || markdown.ends_with('\n'))
+            {
+                markdown.push_str(&text.replace('\n', " "));
+            }
+            continue;
+        };
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        match (name, closing) {
+            ("h2", false) => markdown.push_str("## "),
+            ("h2" | "p", true) => markdown.push_str("\n\n"),
+            ("pre", false) => {
+                markdown.push_str("```\n");
+                in_pre = true;
+            }
+            ("pre", true) => {
+                if !markdown.ends_with('\n') {
+                    markdown.push('\n');
+                }
+                markdown.push_str("```\n\n");
+                in_pre = false;
+            }
+            ("code", _) if !in_pre => {
+                markdown.push('`');
+                in_code = !closing;
+            }
+            ("em", _) if !in_pre && !in_code => markdown.push_str("**"),
+            ("li", false) => markdown.push_str("- "),
+            ("li" | "ul", true) => markdown.push('\n'),
+            ("a", false) if !in_pre => {
+                markdown.push('[');
+                links.push(href(tag));
+            }
+            ("a", true) if !in_pre => {
+                let href = links.pop().flatten().unwrap_or_default();
+                markdown.push_str(&format!("]({})", href));
+            }
+            _ => {}
+        }
+    }
+    let mut tidied = String::new();
+    for line in markdown.lines().map(str::trim_end) {
+        if line.is_empty() && (tidied.is_empty() || tidied.ends_with("\n\n")) {
+            continue;
+        }
+        tidied.push_str(line);
+        tidied.push('\n');
+    }
+    tidied.trim_end().to_string() + "\n"
+}
+
+/// The target of a link tag, made absolute when it's on adventofcode.com.
+fn href(tag: &str) -> Option<String> {
+    let (_, rest) = tag.split_once("href=\"")?;
+    let (href, _) = rest.split_once('"')?;
+    let href = decode_entities(href);
+    Some(match href.strip_prefix('/') {
+        Some(path) => format!("https://adventofcode.com/{}", path),
+        None => href,
+    })
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Response;
+
+    const PART_ONE: &str = include_str!("fixtures/part_one.html");
+    const BOTH_PARTS: &str = include_str!("fixtures/both_parts.html");
+
+    struct Mock(Response);
+
+    impl Client for Mock {
+        fn get(&self, _url: &str, _session: &str) -> Result<Response> {
+            Ok(self.0.clone())
+        }
+
+        fn post(&self, _url: &str, _session: &str, _form: &[(&str, &str)]) -> Result<Response> {
+            unreachable!("fetching puzzles only GETs")
+        }
+    }
+
+    #[test]
+    fn test_markdown() -> Result<()> {
+        let one = markdown(PART_ONE)?;
+        assert!(
+            one.starts_with(
+                "## --- Day 6: Wait For It ---\n\nThe ferry quickly brings you across Island Island."
+            ),
+            "{}",
+            one
+        );
+        assert!(one.contains(
+            "the **time** allowed for each race and also the best **distance** ever recorded"
+        ));
+        assert!(one.contains(
+            "For example:\n\n```\nTime:      7  15   30\nDistance:  9  40  200\n```\n\nThis document describes three races:\n\n- The first race lasts 7 milliseconds."
+        ));
+        assert!(one.contains(
+            "- The third race lasts 30 milliseconds. The record distance in this race is 200 millimeters.\n\nSince the current record for this race is `9` millimeters, there are actually `4` different ways"
+        ));
+        assert!(one.ends_with("**what do you get if you multiply these numbers together?**\n"));
+        // nothing from outside the descriptions
+        assert!(!one.contains("Answer:"));
+        assert!(!one.contains("Advent of Code"));
+        assert_eq!(parts(&one), 1);
+        assert_eq!(title(&one), Some("Wait For It"));
+
+        let both = markdown(BOTH_PARTS)?;
+        assert!(both.starts_with(&one));
+        assert!(both.contains("\n## --- Part Two ---\n\nAs the race is about to start"));
+        assert!(both.contains("very bad [kerning](https://en.wikipedia.org/wiki/Kerning)."));
+        assert!(both.contains("the race lasts for **`71530` milliseconds**"));
+        assert!(!both.contains("Your puzzle answer was"));
+        assert_eq!(parts(&both), 2);
+
+        assert!(markdown("<html><body>Oops</body></html>").is_err());
+        assert_eq!(title("Oops"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_examples() -> Result<()> {
+        let example = "Time:      7  15   30\nDistance:  9  40  200\n";
+        assert_eq!(
+            examples(PART_ONE)?,
+            Examples {
+                part1: vec![example.to_string()],
+                part2: None,
+            }
+        );
+        // part 2 repeats part 1's, which is left out
+        assert_eq!(
+            examples(BOTH_PARTS)?,
+            Examples {
+                part1: vec![example.to_string()],
+                part2: Some(vec!["Time:      71530\nDistance:  940200\n".to_string()]),
+            }
+        );
+
+        // every block is a candidate, highlighting and entities undone
+        let page = format!(
+            "{}<p>For example:</p><pre><code>a -&gt; <em>b</em>\n</code></pre><p>Or:</p><pre><code>x</code></pre></article>\
+             {}<p>Again:</p><pre><code>x</code></pre></article>",
+            ARTICLE, ARTICLE
+        );
+        assert_eq!(
+            examples(&page)?,
+            Examples {
+                part1: vec!["a -> b\n".to_string(), "x\n".to_string()],
+                part2: Some(vec![]),
+            }
+        );
+
+        let error = |html: &str| examples(html).unwrap_err().to_string();
+        assert_eq!(
+            error("<html><body>Oops</body></html>"),
+            "There's no puzzle description in the page"
+        );
+        assert_eq!(
+            error(&format!("{}<p>No code here</p></article>", ARTICLE)),
+            "Part 1's description has no example in it"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        assert_eq!(
+            to_markdown(
+                "<p>See <a href=\"/2023/about\">about</a> &amp; <code>a &lt; b</code>.</p>\n<pre><code>x <em>y</em>\n</code></pre>"
+            ),
+            "See [about](https://adventofcode.com/2023/about) & `a < b`.\n\n```\nx y\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_save() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-puzzle-{}", std::process::id()));
+        let path = path(&dir, 6);
+        assert!(path.ends_with("06/puzzle.md"));
+        let one = markdown(PART_ONE)?;
+        let both = markdown(BOTH_PARTS)?;
+        assert!(save(&path, &one)?);
+        assert!(save(&path, &both)?);
+        assert_eq!(fs::read_to_string(&path)?, both);
+        // part 2 is never lost
+        assert!(!save(&path, &one)?);
+        assert_eq!(fs::read_to_string(&path)?, both);
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch() -> Result<()> {
+        let page = |status, body: &str| {
+            fetch(
+                &Mock(Response {
+                    status,
+                    body: body.to_string(),
+                }),
+                2023,
+                6,
+                Some("abc"),
+            )
+        };
+        assert_eq!(page(200, PART_ONE)?, PART_ONE);
+        assert_eq!(puzzle_url(2024, 6), "https://adventofcode.com/2024/day/6");
+        assert!(page(400, "")
+            .unwrap_err()
+            .to_string()
+            .starts_with("adventofcode.com didn't take the session cookie"));
+        assert!(page(404, "")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Day 6 of 2023 isn't unlocked yet"));
+        assert!(fetch(
+            &Mock(Response {
+                status: 200,
+                body: String::new()
+            }),
+            2023,
+            6,
+            None
+        )
+        .is_err());
+        Ok(())
+    }
+}
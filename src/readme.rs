@@ -0,0 +1,180 @@
+//! The table of days, stars and times kept in a README, for `aoc report
+//! readme`. It's made from the answers file and a timings log and goes
+//! between two marker comments, so the rest of the file is left as it is:
+//!
+//! ```markdown
+//! <!-- BEGIN aoc-2023 results -->
+//! <!-- END aoc-2023 results -->
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use std::{collections::BTreeSet, fs, path::Path, time::Duration};
+
+use crate::{answers::Answers, report, solver::format_duration, timings::Trend};
+
+pub const BEGIN_MARKER: &str = "<!-- BEGIN aoc-2023 results -->";
+pub const END_MARKER: &str = "<!-- END aoc-2023 results -->";
+
+/// The columns of [`table`].
+pub const COLUMNS: [&str; 5] = ["Day", "Title", "Part 1", "Part 2", "Best time"];
+
+const STAR: &str = "⭐";
+
+/// A row per day among `days`, by day and title, and those with answers: a
+/// star for each part with a recorded answer, and the sum of the parts' best
+/// times in `trends`.
+pub fn table(days: &[(u8, &str)], answers: &Answers, trends: &[Trend]) -> String {
+    let numbers = days
+        .iter()
+        .map(|(day, _)| *day)
+        .chain((1..=25).filter(|day| (1..=2).any(|part| answers.get(*day, part).is_some())))
+        .collect::<BTreeSet<_>>();
+    let rows = numbers
+        .into_iter()
+        .map(|day| {
+            let title = days
+                .iter()
+                .find(|(number, _)| *number == day)
+                .map(|(_, title)| report::name(title))
+                .unwrap_or_default();
+            let star = |part| {
+                if answers.get(day, part).is_some() {
+                    STAR.to_string()
+                } else {
+                    String::new()
+                }
+            };
+            let best = trends
+                .iter()
+                .filter(|trend| trend.day == day)
+                .map(|trend| trend.best)
+                .sum::<Duration>();
+            [
+                day.to_string(),
+                title.to_string(),
+                star(1),
+                star(2),
+                if best.is_zero() {
+                    String::new()
+                } else {
+                    format_duration(best)
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+    report::markdown(COLUMNS, [true, false, false, false, true], &rows)
+}
+
+/// `text` with whatever was between its markers replaced by `table`. It's an
+/// error for either marker to be missing, or for them to be the wrong way
+/// round, rather than guessing where the table goes.
+pub fn splice(text: &str, table: &str) -> Result<String> {
+    let missing = |marker: &str| {
+        anyhow!(
+            "There's no {} marker, add {} and {} on lines of their own where the table should go",
+            marker,
+            BEGIN_MARKER,
+            END_MARKER
+        )
+    };
+    let begin = text
+        .find(BEGIN_MARKER)
+        .ok_or_else(|| missing(BEGIN_MARKER))?
+        + BEGIN_MARKER.len();
+    let end = text[begin..]
+        .find(END_MARKER)
+        .map(|end| begin + end)
+        .ok_or_else(|| missing(END_MARKER))?;
+    Ok(format!("{}\n{}{}", &text[..begin], table, &text[end..]))
+}
+
+/// Splices `table` into the file at `path`, see [`splice`], returning
+/// whether it changed.
+pub fn update(path: &Path, table: &str) -> Result<bool> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+    let updated =
+        splice(&text, table).with_context(|| format!("Can't update {}", path.display()))?;
+    if updated == text {
+        return Ok(false);
+    }
+    fs::write(path, updated).with_context(|| format!("Can't write {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trend(day: u8, part: u32, best_ms: u64) -> Trend {
+        Trend {
+            day,
+            part,
+            runs: 3,
+            last: Duration::from_millis(best_ms * 2),
+            best: Duration::from_millis(best_ms),
+            previous: None,
+        }
+    }
+
+    #[test]
+    fn test_table() -> Result<()> {
+        let answers = Answers::parse(
+            "[1]\npart1 = 142\npart2 = 281\n\n[4]\npart1 = 13\n\n[9]\npart1 = 114\n",
+        )?;
+        let days = [
+            (1, "Day 1: Trebuchet?!"),
+            (4, "Day 4: Scratchcards"),
+            (7, "Day 7: Camel Cards"),
+        ];
+        let trends = [trend(1, 1, 2), trend(1, 2, 3), trend(4, 1, 1)];
+        assert_eq!(
+            table(&days, &answers, &trends),
+            "\
+| Day | Title        | Part 1 | Part 2 | Best time |
+| --: | :----------- | :----- | :----- | --------: |
+|   1 | Trebuchet?!  | ⭐      | ⭐      |     5.0ms |
+|   4 | Scratchcards | ⭐      |        |     1.0ms |
+|   7 | Camel Cards  |        |        |           |
+|   9 |              | ⭐      |        |           |
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_splice() -> Result<()> {
+        let text = format!(
+            "# Advent of Code 2023\n\n{}\nan old table\n{}\n\nThe rest.\n",
+            BEGIN_MARKER, END_MARKER
+        );
+        let spliced = splice(&text, "| new |\n")?;
+        assert_eq!(
+            spliced,
+            format!(
+                "# Advent of Code 2023\n\n{}\n| new |\n{}\n\nThe rest.\n",
+                BEGIN_MARKER, END_MARKER
+            )
+        );
+        // splicing again changes nothing
+        assert_eq!(splice(&spliced, "| new |\n")?, spliced);
+        // nor does an empty section need anything between the markers
+        let empty = format!("{}{}", BEGIN_MARKER, END_MARKER);
+        assert_eq!(
+            splice(&empty, "| new |\n")?,
+            format!("{}\n| new |\n{}", BEGIN_MARKER, END_MARKER)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_splice_without_markers() {
+        let error = |text: &str| splice(text, "| new |\n").unwrap_err().to_string();
+        assert!(error("# README\n").starts_with(&format!("There's no {} marker", BEGIN_MARKER)));
+        assert!(error(&format!("{}\n", BEGIN_MARKER))
+            .starts_with(&format!("There's no {} marker", END_MARKER)));
+        // an end before the beginning doesn't count
+        assert!(error(&format!("{}\n{}\n", END_MARKER, BEGIN_MARKER))
+            .starts_with(&format!("There's no {} marker", END_MARKER)));
+    }
+}
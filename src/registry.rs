@@ -0,0 +1,469 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{layout::Layout, metadata::Metadata, solver::Puzzle};
+
+/// Advent of Code runs from the 1st to the 25th of December.
+pub const LAST_DAY: u8 = 25;
+
+/// The event the registered days are solutions to.
+pub const YEAR: u16 = 2023;
+
+/// What listing the days reports about each one, built without solving
+/// anything.
+#[derive(Debug, Clone, Serialize)]
+pub struct DayInfo {
+    pub day: u8,
+    pub name: String,
+    pub title: String,
+    /// The description of each part, in part order.
+    pub parts: Vec<String>,
+    #[serde(flatten)]
+    pub metadata: Metadata,
+    /// Where the day's input is expected under the data root.
+    pub input: PathBuf,
+    pub has_input: bool,
+}
+
+/// The set of puzzles that can be run, keyed by day number.
+#[derive(Default)]
+pub struct Registry {
+    puzzles: BTreeMap<u8, Box<dyn Puzzle>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every day compiled into this crate, each is behind its own `dayNN`
+    /// feature.
+    pub fn builtin() -> Self {
+        let registry = Self::new();
+        #[cfg(feature = "day01")]
+        let registry = registry.register(1, crate::trebuchet::Trebuchet);
+        #[cfg(feature = "day02")]
+        let registry = registry.register(2, crate::cube_conundrum::CubeConundrum);
+        #[cfg(feature = "day03")]
+        let registry = registry.register(3, crate::gear_ratios::GearRatios);
+        #[cfg(feature = "day04")]
+        let registry = registry.register(4, crate::scratchcards::Scratchcards);
+        #[cfg(feature = "day05")]
+        let registry = registry.register(
+            5,
+            crate::if_you_give_a_seed_a_fertilizer::IfYouGiveASeedAFertilizer,
+        );
+        #[cfg(feature = "day06")]
+        let registry = registry.register(6, crate::wait_for_it::WaitForIt);
+        #[cfg(feature = "day07")]
+        let registry = registry.register(7, crate::camel_cards::CamelCards);
+        #[cfg(feature = "slow-test-day")]
+        let registry =
+            registry.register(crate::slow_test_day::DAY, crate::slow_test_day::SlowTestDay);
+        registry
+    }
+
+    pub fn register(mut self, day: u8, puzzle: impl Puzzle + 'static) -> Self {
+        self.puzzles.insert(day, Box::new(puzzle));
+        self
+    }
+
+    pub fn get(&self, day: u8) -> Option<&dyn Puzzle> {
+        self.puzzles.get(&day).map(|puzzle| puzzle.as_ref())
+    }
+
+    /// Looks up a day by its command line name, see [`kebab_name`], ignoring
+    /// case and separators so `CubeConundrum` and `cube_conundrum` work too.
+    pub fn find_by_name(&self, name: &str) -> Option<u8> {
+        let name = squash(name);
+        self.names()
+            .find(|(_, candidate)| squash(candidate) == name)
+            .map(|(day, _)| day)
+    }
+
+    /// Looks up a day by name like [`Registry::find_by_name`], also accepting
+    /// the start of a name when only one day's name starts that way. When
+    /// nothing matches the error suggests the closest names, if any are close.
+    pub fn resolve_name(&self, name: &str) -> Result<u8, DayNameError> {
+        if let Some(day) = self.find_by_name(name) {
+            return Ok(day);
+        }
+        let squashed = squash(name);
+        let starting = self
+            .names()
+            .filter(|(_, candidate)| {
+                !squashed.is_empty() && squash(candidate).starts_with(&squashed)
+            })
+            .collect::<Vec<_>>();
+        match starting.as_slice() {
+            [(day, _)] => return Ok(*day),
+            [_, ..] => {
+                return Err(DayNameError::Ambiguous {
+                    name: name.to_string(),
+                    candidates: starting
+                        .into_iter()
+                        .map(|(_, candidate)| candidate)
+                        .collect(),
+                })
+            }
+            [] => {}
+        }
+
+        // too short to tell a typo from a different name
+        if squashed.len() < MIN_FUZZY_LEN {
+            return Err(DayNameError::Unknown(name.to_string()));
+        }
+        let distances = self
+            .names()
+            .map(|(_, candidate)| {
+                let full = squash(&candidate);
+                // a typo in the start of a name, eg. `geer` for gear-ratios
+                let start = full
+                    .chars()
+                    .take(squashed.chars().count())
+                    .collect::<String>();
+                let distance = strsim::levenshtein(&squashed, &full)
+                    .min(strsim::levenshtein(&squashed, &start));
+                (distance, candidate)
+            })
+            .filter(|(distance, _)| *distance <= (squashed.len() / 4).max(1))
+            .collect::<Vec<_>>();
+        let closest = distances.iter().map(|(distance, _)| *distance).min();
+        let suggestions = distances
+            .into_iter()
+            .filter(|(distance, _)| Some(*distance) == closest)
+            .map(|(_, candidate)| candidate)
+            .collect::<Vec<_>>();
+        if suggestions.is_empty() {
+            Err(DayNameError::Unknown(name.to_string()))
+        } else {
+            Err(DayNameError::DidYouMean {
+                name: name.to_string(),
+                suggestions,
+            })
+        }
+    }
+
+    /// Command line names of the registered puzzles in day order.
+    pub fn names(&self) -> impl Iterator<Item = (u8, String)> + '_ {
+        self.days()
+            .map(|(day, puzzle)| (day, kebab_name(puzzle.title())))
+    }
+
+    /// The registered days picked out by a comma separated list of day
+    /// numbers, inclusive ranges such as `1-3`, names and `all`, in day order
+    /// without duplicates. A range only picks the days in it that are
+    /// registered, but every other entry has to name a registered day.
+    pub fn select(&self, selection: &str) -> Result<Vec<u8>> {
+        let mut days = BTreeSet::new();
+        for entry in selection.split(',').map(str::trim) {
+            if entry.eq_ignore_ascii_case("all") {
+                days.extend(self.puzzles.keys());
+                continue;
+            }
+            if let Some((first, last)) = parse_range(entry) {
+                if first > last {
+                    return Err(anyhow!("Day range {} is backwards", entry));
+                }
+                let in_range = self
+                    .puzzles
+                    .range(first..=last)
+                    .map(|(day, _)| *day)
+                    .collect::<Vec<_>>();
+                if in_range.is_empty() {
+                    return Err(anyhow!("No days in {} are implemented", entry));
+                }
+                days.extend(in_range);
+                continue;
+            }
+            let day = match entry.parse::<u8>() {
+                Ok(day) if self.puzzles.contains_key(&day) => day,
+                Ok(_) => return Err(DayNameError::Unknown(entry.to_string()).into()),
+                Err(_) => self.resolve_name(entry)?,
+            };
+            days.insert(day);
+        }
+        Ok(days.into_iter().collect())
+    }
+
+    /// Information about every registered day in day order, with inputs
+    /// looked for under `data_root` where `layout` puts them.
+    pub fn info<'a>(
+        &'a self,
+        data_root: &'a Path,
+        layout: &'a Layout,
+    ) -> impl Iterator<Item = DayInfo> + 'a {
+        self.days().map(move |(day, puzzle)| {
+            let day = puzzle.day_number().unwrap_or(day);
+            let input = layout.input(data_root, day);
+            DayInfo {
+                day,
+                name: kebab_name(puzzle.title()),
+                title: puzzle.title().to_string(),
+                parts: [1, 2]
+                    .into_iter()
+                    .filter_map(|part| puzzle.solver(part))
+                    .map(|solver| solver.part_description().1.to_string())
+                    .collect(),
+                metadata: puzzle.metadata(),
+                has_input: input.is_file(),
+                input,
+            }
+        })
+    }
+
+    /// Days of the event that have no registered puzzle.
+    pub fn unimplemented(&self) -> Vec<u8> {
+        (1..=LAST_DAY)
+            .filter(|day| !self.puzzles.contains_key(day))
+            .collect()
+    }
+
+    /// The highest registered day, `None` when no days are registered.
+    pub fn latest(&self) -> Option<u8> {
+        self.puzzles.keys().next_back().copied()
+    }
+
+    /// Registered puzzles in day order.
+    pub fn days(&self) -> impl Iterator<Item = (u8, &dyn Puzzle)> {
+        self.puzzles
+            .iter()
+            .map(|(day, puzzle)| (*day, puzzle.as_ref()))
+    }
+}
+
+/// `first-last` if both ends are day numbers, names like "gear-ratios" are
+/// left alone.
+fn parse_range(entry: &str) -> Option<(u8, u8)> {
+    let (first, last) = entry.split_once('-')?;
+    Some((first.trim().parse().ok()?, last.trim().parse().ok()?))
+}
+
+/// Turns a puzzle title such as "Day 5: If You Give A Seed A Fertilizer" into
+/// the name used to select it on the command line, eg.
+/// "if-you-give-a-seed-a-fertilizer".
+pub fn kebab_name(title: &str) -> String {
+    let title = match title.split_once(':') {
+        Some((prefix, rest)) if prefix.starts_with("Day ") => rest,
+        _ => title,
+    };
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// Shortest name, ignoring separators, that typos are looked for in.
+const MIN_FUZZY_LEN: usize = 3;
+
+/// Lower case letters and digits only, for comparing names.
+fn squash(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Why a day couldn't be found by name, see [`Registry::resolve_name`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DayNameError {
+    #[error("'{0}' isn't an implemented day")]
+    Unknown(String),
+    #[error(
+        "'{name}' isn't an implemented day, did you mean {}?",
+        quoted(suggestions, " or ")
+    )]
+    DidYouMean {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    #[error("'{name}' could be any of {}", quoted(candidates, ", "))]
+    Ambiguous {
+        name: String,
+        candidates: Vec<String>,
+    },
+}
+
+fn quoted(names: &[String], separator: &str) -> String {
+    names
+        .iter()
+        .map(|name| format!("'{}'", name))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kebab_name() {
+        assert_eq!(kebab_name("Day 1: Trebuchet?!"), "trebuchet");
+        assert_eq!(kebab_name("Day 6: Wait for It"), "wait-for-it");
+        assert_eq!(
+            kebab_name("Day 5: If You Give A Seed A Fertilizer"),
+            "if-you-give-a-seed-a-fertilizer"
+        );
+        assert_eq!(kebab_name("Gear Ratios"), "gear-ratios");
+    }
+
+    #[test]
+    #[cfg(all(feature = "day03", feature = "day07"))]
+    fn test_find_by_name() {
+        let registry = Registry::builtin();
+        assert_eq!(registry.find_by_name("gear-ratios"), Some(3));
+        assert_eq!(registry.find_by_name("Camel-Cards"), Some(7));
+        assert_eq!(registry.find_by_name("gear"), None);
+        assert_eq!(registry.find_by_name("GearRatios"), Some(3));
+        assert_eq!(registry.find_by_name("gear_ratios"), Some(3));
+        assert_eq!(registry.find_by_name("Camel Cards"), Some(7));
+    }
+
+    #[test]
+    #[cfg(all(feature = "day02", feature = "day03", feature = "day07"))]
+    fn test_resolve_name() {
+        let registry = Registry::builtin();
+        // exact, ignoring case and separators
+        assert_eq!(registry.resolve_name("CubeConundrum"), Ok(2));
+        // a prefix only one name has
+        assert_eq!(registry.resolve_name("gear"), Ok(3));
+        assert_eq!(registry.resolve_name("camel-c"), Ok(7));
+        // typos
+        assert_eq!(
+            registry.resolve_name("cubeconundrm"),
+            Err(DayNameError::DidYouMean {
+                name: "cubeconundrm".to_string(),
+                suggestions: vec!["cube-conundrum".to_string()],
+            })
+        );
+        assert_eq!(
+            registry.resolve_name("geer").unwrap_err().to_string(),
+            "'geer' isn't an implemented day, did you mean 'gear-ratios'?"
+        );
+        // ambiguous prefixes list every day they could be
+        let error = registry.resolve_name("c").unwrap_err();
+        assert!(matches!(&error, DayNameError::Ambiguous { candidates, .. }
+                if candidates.contains(&"cube-conundrum".to_string())
+                    && candidates.contains(&"camel-cards".to_string())));
+        assert_eq!(
+            error.to_string(),
+            "'c' could be any of 'cube-conundrum', 'camel-cards'"
+        );
+        // hopeless
+        for name in ["xyz", "haunted-wasteland", "", "-", "q"] {
+            assert_eq!(
+                registry.resolve_name(name),
+                Err(DayNameError::Unknown(name.to_string())),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_unimplemented() {
+        let registry = Registry::new()
+            .register(1, crate::trebuchet::Trebuchet)
+            .register(25, crate::trebuchet::Trebuchet);
+        assert_eq!(registry.unimplemented(), (2..=24).collect::<Vec<u8>>());
+        assert_eq!(registry.latest(), Some(25));
+        assert_eq!(Registry::new().latest(), None);
+    }
+
+    #[cfg(feature = "day01")]
+    fn registry_of(days: &[u8]) -> Registry {
+        days.iter().fold(Registry::new(), |registry, day| {
+            registry.register(*day, crate::trebuchet::Trebuchet)
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_select_lists_and_ranges() -> Result<()> {
+        let registry = registry_of(&[1, 2, 3, 5, 7]);
+        assert_eq!(registry.select("1-3,7")?, vec![1, 2, 3, 7]);
+        assert_eq!(registry.select("7,3,7")?, vec![3, 7]);
+        // overlapping ranges, and a range with gaps only picks registered days
+        assert_eq!(registry.select("1-3, 2-6")?, vec![1, 2, 3, 5]);
+        assert_eq!(registry.select("5-5")?, vec![5]);
+        assert_eq!(registry.select("all,3")?, vec![1, 2, 3, 5, 7]);
+        assert_eq!(registry.select("trebuchet,7")?, vec![1, 7]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_select_invalid() {
+        let registry = registry_of(&[1, 2, 3, 5, 7]);
+        for selection in ["4", "1,x", "3-1", "8-10", "", "1,,2", "1-", "-3", "26"] {
+            assert!(registry.select(selection).is_err(), "{}", selection);
+        }
+        assert_eq!(
+            registry.select("1,4").unwrap_err().to_string(),
+            "'4' isn't an implemented day"
+        );
+    }
+
+    #[test]
+    fn test_builtin_info() {
+        let registry = Registry::builtin();
+        let data_root = Path::new("no-such-data-root");
+        for info in registry.info(data_root, &Layout::default()) {
+            assert_eq!(info.parts.len(), 2, "{}", info.title);
+            assert!(info.parts.iter().all(|part| !part.is_empty()));
+            assert_eq!(registry.find_by_name(&info.name), Some(info.day));
+            assert_eq!(info.input, Layout::default().input(data_root, info.day));
+            assert!(!info.has_input);
+        }
+        assert_eq!(
+            registry.info(data_root, &Layout::default()).count(),
+            registry.days().count()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day07")]
+    fn test_info_json() -> Result<()> {
+        let registry = Registry::new().register(7, crate::camel_cards::CamelCards);
+        let info = registry
+            .info(Path::new("data"), &Layout::default())
+            .next()
+            .unwrap();
+        let json = serde_json::to_value(&info)?;
+        assert_eq!(json["day"], 7);
+        assert_eq!(json["name"], "camel-cards");
+        assert_eq!(json["parts"][1], "Total winnings");
+        assert_eq!(json["difficulty"], "medium");
+        assert_eq!(json["runtime"], "fast");
+        assert_eq!(
+            json["has_input"],
+            std::path::Path::new("data/07/input").is_file()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_builtin_days_declare_their_number() {
+        for (day, puzzle) in Registry::builtin().days() {
+            assert_eq!(puzzle.day_number(), Some(day), "{}", puzzle.title());
+        }
+    }
+
+    #[test]
+    fn test_builtin_matches_features() {
+        let days = Registry::builtin()
+            .days()
+            .map(|(day, _)| day)
+            .collect::<Vec<_>>();
+        assert_eq!(days.contains(&1), cfg!(feature = "day01"));
+        assert_eq!(days.contains(&5), cfg!(feature = "day05"));
+        assert_eq!(days.contains(&7), cfg!(feature = "day07"));
+    }
+}
@@ -0,0 +1,606 @@
+//! Reports of a run: tables summarising several days, one row per day, and
+//! the result files written by `--output`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    answers::Answers,
+    budget::{self, Budgets},
+    runner::{RunResult, Status},
+    solver::format_duration,
+    style,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TableFormat {
+    /// A GitHub flavoured markdown table, padded so it lines up as text too.
+    Markdown,
+    Csv,
+}
+
+/// The columns, always all of them and in this order so scripts can rely on
+/// it. Parts that weren't run are left empty, as is the time without timing.
+pub const COLUMNS: [&str; 5] = ["Day", "Title", "Part 1", "Part 2", "Time"];
+
+/// Whether each column is right aligned in markdown, the numeric ones are.
+const RIGHT_ALIGNED: [bool; 5] = [true, false, true, true, true];
+
+/// The table's cells, a row per day in the order the days appear in
+/// `results`.
+pub fn rows(results: &[RunResult], show_timing: bool) -> Vec<[String; 5]> {
+    let mut days = results.iter().map(|result| result.day).collect::<Vec<u8>>();
+    days.dedup();
+    days.into_iter()
+        .map(|day| {
+            let day_results = results
+                .iter()
+                .filter(|result| result.day == day)
+                .collect::<Vec<_>>();
+            let part = |part: u32| {
+                day_results
+                    .iter()
+                    .find(|result| result.part == part)
+                    .map(|result| cell(result))
+                    .unwrap_or_default()
+            };
+            let time = day_results
+                .iter()
+                .filter_map(|result| result.timing)
+                .map(|timing| timing.total)
+                .sum::<Duration>();
+            [
+                day.to_string(),
+                day_results
+                    .first()
+                    .map(|result| name(&result.title).to_string())
+                    .unwrap_or_default(),
+                part(1),
+                part(2),
+                if show_timing && !time.is_zero() {
+                    format_duration(time)
+                } else {
+                    String::new()
+                },
+            ]
+        })
+        .collect()
+}
+
+/// The puzzle's name without its "Day N: " prefix, the day has a column.
+pub(crate) fn name(title: &str) -> &str {
+    match title.split_once(": ") {
+        Some((day, name)) if day.starts_with("Day ") => name,
+        _ => title,
+    }
+}
+
+fn cell(result: &RunResult) -> String {
+    match (&result.status, &result.answer) {
+        (Status::Solved, Some(answer)) => style::answer_text(answer),
+        (Status::Solved, None) => String::new(),
+        (Status::Failed(_), _) => "failed".to_string(),
+        (Status::TimedOut(_), _) => "timed out".to_string(),
+        (Status::Skipped(_), _) if result.is_interrupted() => "interrupted".to_string(),
+        (Status::Skipped(_), _) => "skipped".to_string(),
+    }
+}
+
+/// Renders the run as a table, ending with a newline.
+pub fn table(results: &[RunResult], format: TableFormat, show_timing: bool) -> String {
+    let rows = rows(results, show_timing);
+    match format {
+        TableFormat::Markdown => markdown(COLUMNS, RIGHT_ALIGNED, &rows),
+        TableFormat::Csv => csv(&rows),
+    }
+}
+
+/// The columns of [`summary_table`], the table's with how each day went.
+pub const SUMMARY_COLUMNS: [&str; 6] = ["Day", "Title", "Part 1", "Part 2", "Time", "Status"];
+
+/// [`SUMMARY_COLUMNS`] with each day's budget, when some days have one.
+pub const SUMMARY_BUDGET_COLUMNS: [&str; 7] = [
+    "Day", "Title", "Part 1", "Part 2", "Time", "Budget", "Status",
+];
+
+/// How a day went as a whole, for the end of a run of every day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayStatus {
+    /// Every part that ran gave the answer in the answers file.
+    Verified,
+    /// A part gave an answer other than the one in the answers file.
+    Wrong,
+    /// Nothing ran, eg. for want of an input.
+    Skipped,
+    /// A part failed or timed out.
+    Error,
+    /// Solved, but with no known answer to check against.
+    Unverified,
+}
+
+impl DayStatus {
+    /// How one day's `results` went, checked against `answers` if there are
+    /// any. A wrong answer is worse than skipping, an error worse still.
+    pub fn of(results: &[&RunResult], answers: Option<&Answers>) -> Self {
+        let known =
+            |result: &RunResult| answers.and_then(|answers| answers.get(result.day, result.part));
+        if results
+            .iter()
+            .any(|result| matches!(result.status, Status::Failed(_) | Status::TimedOut(_)))
+        {
+            return DayStatus::Error;
+        }
+        let solved = results
+            .iter()
+            .filter(|result| result.status == Status::Solved)
+            .collect::<Vec<_>>();
+        if solved.iter().any(|result| {
+            known(result).is_some_and(|answer| result.answer.as_ref() != Some(answer))
+        }) {
+            DayStatus::Wrong
+        } else if solved.is_empty() {
+            DayStatus::Skipped
+        } else if solved.iter().all(|result| known(result).is_some()) {
+            DayStatus::Verified
+        } else {
+            DayStatus::Unverified
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            DayStatus::Verified => "✓",
+            DayStatus::Wrong => "✗",
+            DayStatus::Skipped => "–",
+            DayStatus::Error => "!",
+            DayStatus::Unverified => "?",
+        }
+    }
+}
+
+/// Renders the run as the table with a status per day, see [`DayStatus`],
+/// for the end of a run of every day. Only the days that ran have a row.
+pub fn summary_table(
+    results: &[RunResult],
+    answers: Option<&Answers>,
+    budgets: &Budgets,
+    show_timing: bool,
+) -> String {
+    let mut days = results.iter().map(|result| result.day).collect::<Vec<u8>>();
+    days.dedup();
+    let rows = rows(results, show_timing)
+        .into_iter()
+        .zip(days)
+        .map(|(row, day)| {
+            let day_results = results
+                .iter()
+                .filter(|result| result.day == day)
+                .collect::<Vec<_>>();
+            let [day_cell, title, part1, part2, time] = row;
+            let budget = budget::cell(&day_results, budgets.day(day), show_timing);
+            let status = DayStatus::of(&day_results, answers).symbol().to_string();
+            [day_cell, title, part1, part2, time, budget, status]
+        })
+        .collect::<Vec<_>>();
+    if budgets.days.is_empty() {
+        let rows = rows
+            .into_iter()
+            .map(|[day, title, part1, part2, time, _, status]| {
+                [day, title, part1, part2, time, status]
+            })
+            .collect::<Vec<_>>();
+        markdown(
+            SUMMARY_COLUMNS,
+            [true, false, true, true, true, false],
+            &rows,
+        )
+    } else {
+        markdown(
+            SUMMARY_BUDGET_COLUMNS,
+            [true, false, true, true, true, true, false],
+            &rows,
+        )
+    }
+}
+
+/// How many stars the run earned: a part each that gave the answer in
+/// `answers`, out of the 50 there are.
+pub fn stars(results: &[RunResult], answers: &Answers) -> usize {
+    results
+        .iter()
+        .filter(|result| {
+            result.status == Status::Solved
+                && result.answer.is_some()
+                && result.answer.as_ref() == answers.get(result.day, result.part)
+        })
+        .count()
+}
+
+/// The columns of [`inputs_table`], for one day run against several inputs.
+pub const INPUT_COLUMNS: [&str; 4] = ["Input", "Part 1", "Part 2", "Time"];
+
+/// Renders one day's results against several inputs as a markdown table, a
+/// row per input, with a part that couldn't be solved as a failed cell.
+pub fn inputs_table(results: &[(PathBuf, Vec<RunResult>)], show_timing: bool) -> String {
+    let rows = results
+        .iter()
+        .map(|(input, results)| {
+            let part = |part: u32| {
+                results
+                    .iter()
+                    .find(|result| result.part == part)
+                    .map(cell)
+                    .unwrap_or_default()
+            };
+            let time = results
+                .iter()
+                .filter_map(|result| result.timing)
+                .map(|timing| timing.total)
+                .sum::<Duration>();
+            [
+                input.display().to_string(),
+                part(1),
+                part(2),
+                if show_timing && !time.is_zero() {
+                    format_duration(time)
+                } else {
+                    String::new()
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+    markdown(INPUT_COLUMNS, [false, true, true, true], &rows)
+}
+
+/// Renders `rows` under `columns` as a GitHub flavoured markdown table,
+/// padded so it lines up as text too.
+pub fn markdown<const N: usize>(
+    columns: [&str; N],
+    right_aligned: [bool; N],
+    rows: &[[String; N]],
+) -> String {
+    let escaped = rows
+        .iter()
+        .map(|row| row.clone().map(|cell| cell.replace('|', "\\|")))
+        .collect::<Vec<_>>();
+    let widths: [usize; N] = std::array::from_fn(|column| {
+        escaped
+            .iter()
+            .map(|row| row[column].chars().count())
+            .chain([columns[column].len(), 3])
+            .max()
+            .unwrap_or_default()
+    });
+    let line = |cells: [String; N]| {
+        let cells = cells
+            .iter()
+            .enumerate()
+            .map(|(column, cell)| {
+                let width = widths[column];
+                if right_aligned[column] {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut table = line(columns.map(str::to_string));
+    table += &line(std::array::from_fn(|column| {
+        let dashes = "-".repeat(widths[column] - 1);
+        if right_aligned[column] {
+            format!("{}:", dashes)
+        } else {
+            format!(":{}", dashes)
+        }
+    }));
+    for row in escaped {
+        table += &line(row);
+    }
+    table
+}
+
+fn csv(rows: &[[String; 5]]) -> String {
+    std::iter::once(COLUMNS.map(str::to_string))
+        .chain(rows.iter().cloned())
+        .map(|row| {
+            let fields = row.iter().map(|field| csv_field(field)).collect::<Vec<_>>();
+            format!("{}\n", fields.join(","))
+        })
+        .collect()
+}
+
+/// Quotes a field if it has to be, doubling any quotes inside it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One part's result as written to a results file, with when and by which
+/// build it was produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// When the results were written, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// `git describe` of the commit the binary was built from, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(flatten)]
+    pub result: RunResult,
+}
+
+/// The commit the binary was built from, see `build.rs`.
+pub fn build_version() -> Option<&'static str> {
+    option_env!("AOC_GIT_DESCRIBE")
+}
+
+/// The full hash of the commit the binary was built from, see `build.rs`.
+pub fn build_sha() -> Option<&'static str> {
+    option_env!("AOC_GIT_SHA")
+}
+
+pub fn records(results: &[RunResult]) -> Vec<Record> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default();
+    results
+        .iter()
+        .map(|result| Record {
+            timestamp,
+            version: build_version().map(str::to_string),
+            result: result.clone(),
+        })
+        .collect()
+}
+
+/// Writes a record per part to `path`, replacing it with a JSON array, or
+/// with `append` adding a line per record to it as JSON Lines.
+pub fn write_results(path: &Path, results: &[RunResult], append: bool) -> Result<()> {
+    let records = records(results);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .with_context(|| format!("Can't write results to {}", path.display()))?;
+    let text = if append {
+        records
+            .iter()
+            .map(|record| Ok(format!("{}\n", serde_json::to_string(record)?)))
+            .collect::<Result<String>>()?
+    } else {
+        format!("{}\n", serde_json::to_string_pretty(&records)?)
+    };
+    file.write_all(text.as_bytes())
+        .with_context(|| format!("Can't write results to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Timing;
+
+    fn result(day: u8, title: &str, part: u32, status: Status, answer: Option<i64>) -> RunResult {
+        RunResult {
+            day,
+            part,
+            title: title.to_string(),
+            description: "Answer".to_string(),
+            answer: answer.map(Into::into),
+            timing: answer.map(|_| Timing {
+                parse: None,
+                total: Duration::from_millis(2),
+            }),
+            peak_memory: None,
+            implementation: None,
+            status,
+            failure: None,
+            truncated: None,
+        }
+    }
+
+    fn results() -> Vec<RunResult> {
+        vec![
+            result(1, "Day 1: Trebuchet?!", 1, Status::Solved, Some(142)),
+            result(1, "Day 1: Trebuchet?!", 2, Status::Solved, Some(281)),
+            result(
+                3,
+                "Day 3: Pipes | Commas, \"Quotes\"",
+                1,
+                Status::Solved,
+                Some(4361),
+            ),
+            result(
+                3,
+                "Day 3: Pipes | Commas, \"Quotes\"",
+                2,
+                Status::Failed("bad".to_string()),
+                None,
+            ),
+            result(
+                5,
+                "Day 5: Skipped",
+                1,
+                Status::Skipped("no input".to_string()),
+                None,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_rows() {
+        let rows = rows(&results(), true);
+        assert_eq!(
+            rows[0],
+            ["1", "Trebuchet?!", "142", "281", "4.0ms"].map(str::to_string)
+        );
+        assert_eq!(rows[1][2..4], ["4361", "failed"].map(str::to_string));
+        assert_eq!(rows[1][4], "2.0ms");
+        assert_eq!(
+            rows[2][1..],
+            ["Skipped", "skipped", "", ""].map(str::to_string)
+        );
+
+        let untimed = super::rows(&results(), false);
+        assert!(untimed.iter().all(|row| row[4].is_empty()));
+    }
+
+    #[test]
+    fn test_markdown() {
+        let table = table(&results(), TableFormat::Markdown, true);
+        assert_eq!(
+            table,
+            "\
+| Day | Title                     |  Part 1 | Part 2 |  Time |
+| --: | :------------------------ | ------: | -----: | ----: |
+|   1 | Trebuchet?!               |     142 |    281 | 4.0ms |
+|   3 | Pipes \\| Commas, \"Quotes\" |    4361 | failed | 2.0ms |
+|   5 | Skipped                   | skipped |        |       |
+"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let table = table(&results(), TableFormat::Csv, false);
+        assert_eq!(
+            table,
+            "\
+Day,Title,Part 1,Part 2,Time
+1,Trebuchet?!,142,281,
+3,\"Pipes | Commas, \"\"Quotes\"\"\",4361,failed,
+5,Skipped,skipped,,
+"
+        );
+    }
+
+    #[test]
+    fn test_summary_table() -> Result<()> {
+        let mut results = results();
+        results.extend([
+            result(6, "Day 6: Wait For It", 1, Status::Solved, Some(280)),
+            result(7, "Day 7: Camel Cards", 1, Status::Solved, Some(6440)),
+        ]);
+        let answers = Answers::parse("[1]\npart1 = 142\npart2 = 281\n\n[6]\npart1 = 288\n")?;
+        assert_eq!(
+            summary_table(&results, Some(&answers), &Budgets::default(), true),
+            "\
+| Day | Title                     |  Part 1 | Part 2 |  Time | Status |
+| --: | :------------------------ | ------: | -----: | ----: | :----- |
+|   1 | Trebuchet?!               |     142 |    281 | 4.0ms | ✓      |
+|   3 | Pipes \\| Commas, \"Quotes\" |    4361 | failed | 2.0ms | !      |
+|   5 | Skipped                   | skipped |        |       | –      |
+|   6 | Wait For It               |     280 |        | 2.0ms | ✗      |
+|   7 | Camel Cards               |    6440 |        | 2.0ms | ?      |
+"
+        );
+        assert_eq!(stars(&results, &answers), 2);
+        // without an answers file nothing can be verified, or be wrong
+        let unchecked = summary_table(&results, None, &Budgets::default(), false);
+        let statuses = unchecked
+            .lines()
+            .skip(2)
+            .map(|line| {
+                line.trim_end_matches(" |")
+                    .rsplit("| ")
+                    .next()
+                    .unwrap()
+                    .trim()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, ["?", "!", "–", "?", "?"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary_table_budgets() {
+        let budgets = Budgets {
+            overall: None,
+            days: [(1, Duration::from_millis(3)), (3, Duration::from_millis(5))].into(),
+        };
+        assert_eq!(
+            summary_table(&results(), None, &budgets, true),
+            "\
+| Day | Title                     |  Part 1 | Part 2 |  Time |          Budget | Status |
+| --: | :------------------------ | ------: | -----: | ----: | --------------: | :----- |
+|   1 | Trebuchet?!               |     142 |    281 | 4.0ms | 4.0ms / 3.0ms ✗ | ?      |
+|   3 | Pipes \\| Commas, \"Quotes\" |    4361 | failed | 2.0ms |   2.0ms / 5.0ms | !      |
+|   5 | Skipped                   | skipped |        |       |                 | –      |
+"
+        );
+    }
+
+    #[test]
+    fn test_write_results() -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("aoc-2023-results-{}.json", std::process::id()));
+        let results = results();
+        write_results(&path, &results, false)?;
+        let written = std::fs::read_to_string(&path);
+        std::fs::remove_file(&path)?;
+        write_results(&path, &results[..2], true)?;
+        write_results(&path, &results[2..], true)?;
+        let appended = std::fs::read_to_string(&path);
+        std::fs::remove_file(&path)?;
+
+        let records: Vec<Record> = serde_json::from_str(&written?)?;
+        assert_eq!(
+            records
+                .into_iter()
+                .map(|record| record.result)
+                .collect::<Vec<_>>(),
+            results
+        );
+        // JSON Lines, one per part across both runs
+        let appended = appended?;
+        assert!(appended.starts_with("{\"timestamp\":"), "{}", appended);
+        let lines = appended
+            .lines()
+            .map(serde_json::from_str::<Record>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(lines.len(), results.len());
+        assert_eq!(lines[4].result, results[4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_inputs_table() {
+        let results = results();
+        let by_input = vec![
+            (PathBuf::from("alice.txt"), results[..2].to_vec()),
+            (PathBuf::from("bob.txt"), results[2..4].to_vec()),
+        ];
+        assert_eq!(
+            inputs_table(&by_input, true),
+            "\
+| Input     | Part 1 | Part 2 |  Time |
+| :-------- | -----: | -----: | ----: |
+| alice.txt |    142 |    281 | 4.0ms |
+| bob.txt   |   4361 | failed | 2.0ms |
+"
+        );
+        assert!(inputs_table(&by_input, false).contains("|    281 |      |\n"));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            table(&[], TableFormat::Csv, true),
+            "Day,Title,Part 1,Part 2,Time\n"
+        );
+        assert_eq!(table(&[], TableFormat::Markdown, true).lines().count(), 2);
+    }
+}
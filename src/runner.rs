@@ -0,0 +1,2194 @@
+use anyhow::{anyhow, Result};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    borrow::Cow,
+    io::{self, Read},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::{info_span, Span};
+
+use crate::{
+    clipboard,
+    context::{RunContext, Verbosity},
+    doctor,
+    failure::{self, FailureKind},
+    input::{self, ParseMode},
+    interrupt::{Interrupt, INTERRUPTED},
+    layout::Layout,
+    mem,
+    metadata::DayFilter,
+    progress::{no_progress, Progress, ProgressBars},
+    registry::{self, Registry},
+    schedule::{self, Estimates},
+    solver::{format_duration, Answer, Puzzle, Solver, Timing},
+    style,
+};
+
+/// Serialized as a `status` of "solved", "failed", "timed_out" or "skipped",
+/// with the `reason` alongside for anything but "solved".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub enum Status {
+    Solved,
+    Failed(String),
+    TimedOut(String),
+    Skipped(String),
+}
+
+/// The outcome of running a single part of a day.
+///
+/// This is the record shared by every machine readable output, its JSON field
+/// names are stable: `day`, `part`, `title`, `description`, `answer` (a number
+/// or string, `null` when unsolved), `duration_ms` and `parse_ms` (only present
+/// when the part was solved, `parse_ms` only when the solver timed its parsing),
+/// `peak_bytes` (only with the `mem-stats` feature, see [`mem::measure`]),
+/// `implementation` (only for parts with more than one, see
+/// [`crate::solver::MultiSolver::alternatives`]), `status` and `reason` (see
+/// [`Status`]) and `failure` (only for failed parts, why they failed, see
+/// [`FailureKind`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunResult {
+    pub day: u8,
+    pub part: u32,
+    pub title: String,
+    pub description: String,
+    pub answer: Option<Answer>,
+    #[serde(flatten)]
+    pub timing: Option<Timing>,
+    #[serde(
+        rename = "peak_bytes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub peak_memory: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implementation: Option<String>,
+    #[serde(flatten)]
+    pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<FailureKind>,
+    /// How many lines the input was cut down to with
+    /// [`PartOptions::limit_lines`], when it had more.
+    #[serde(
+        rename = "truncated_to_lines",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub truncated: Option<usize>,
+}
+
+impl RunResult {
+    fn unsolved(day: u8, puzzle: Option<&dyn Puzzle>, part: u32, status: Status) -> Self {
+        let description = puzzle
+            .and_then(|puzzle| puzzle.solver(part))
+            .map(|solver| solver.part_description().1.to_string())
+            .unwrap_or_default();
+        Self {
+            day,
+            title: puzzle
+                .map(|puzzle| puzzle.title().to_string())
+                .unwrap_or_default(),
+            part,
+            description,
+            answer: None,
+            timing: None,
+            peak_memory: None,
+            implementation: None,
+            status,
+            failure: None,
+            truncated: None,
+        }
+    }
+
+    fn failed(
+        day: u8,
+        puzzle: Option<&dyn Puzzle>,
+        part: u32,
+        kind: FailureKind,
+        message: String,
+    ) -> Self {
+        Self {
+            failure: Some(kind),
+            ..Self::unsolved(day, puzzle, part, Status::Failed(message))
+        }
+    }
+
+    /// A part that never ran because the run was interrupted first.
+    pub fn interrupted(day: u8, puzzle: Option<&dyn Puzzle>, part: u32) -> Self {
+        Self::unsolved(day, puzzle, part, Status::Skipped(INTERRUPTED.to_string()))
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        matches!(&self.status, Status::Skipped(reason) if reason == INTERRUPTED)
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self.status, Status::Failed(_))
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn solve_catching_panics(
+    solver: &dyn Solver,
+    input: &str,
+    ctx: &RunContext,
+) -> Result<(Answer, Timing)> {
+    catch_unwind(AssertUnwindSafe(|| solver.solve_timed_with(input, ctx)))
+        .unwrap_or_else(|payload| Err(anyhow!("panicked: {}", panic_message(payload))))
+}
+
+/// How a part solved on a worker thread ended, see [`solve_on_worker`].
+enum Worker {
+    Finished(Result<(Answer, Timing)>),
+    TimedOut,
+    /// Given up on once the grace period after an interrupt was over.
+    Interrupted,
+}
+
+/// How often a worker is checked on while waiting for it, to notice an
+/// interrupt.
+const INTERRUPT_POLL: Duration = Duration::from_millis(20);
+
+/// Solves on a worker thread, giving up once `timeout` has passed or
+/// `interrupt`'s grace period is over.
+///
+/// Threads can't be killed, so this is best effort: when giving up the run
+/// context is cancelled and the worker is left detached. Solvers that check
+/// [`RunContext::is_cancelled`] stop soon after, any others keep running in
+/// the background until they finish or the process exits.
+fn solve_on_worker(
+    solver: Box<dyn Solver>,
+    input: &str,
+    ctx: Arc<RunContext>,
+    timeout: Option<Duration>,
+    interrupt: Option<&Interrupt>,
+) -> Worker {
+    let (sender, receiver) = mpsc::channel();
+    let worker_ctx = Arc::clone(&ctx);
+    let input = input.to_string();
+    let span = Span::current();
+    thread::spawn(move || {
+        let solved = span.in_scope(|| solve_catching_panics(solver.as_ref(), &input, &worker_ctx));
+        // the receiver is gone if the runner already gave up
+        let _ = sender.send(solved);
+    });
+
+    let start = Instant::now();
+    loop {
+        let mut wait = timeout.map_or(Duration::MAX, |timeout| {
+            timeout.saturating_sub(start.elapsed())
+        });
+        if interrupt.is_some() {
+            wait = wait.min(INTERRUPT_POLL);
+        }
+        match receiver.recv_timeout(wait) {
+            Ok(solved) => return Worker::Finished(solved),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Worker::Finished(Err(anyhow!("the worker solving it went away")))
+            }
+        }
+        let given_up = if timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+            Worker::TimedOut
+        } else if interrupt.is_some_and(Interrupt::is_past_grace_period) {
+            Worker::Interrupted
+        } else {
+            continue;
+        };
+        ctx.cancel();
+        return given_up;
+    }
+}
+
+/// How each part of a day is run.
+#[derive(Clone)]
+pub struct PartOptions {
+    /// Longest each part may run for, see [`run_part`].
+    pub timeout: Option<Duration>,
+    /// Handed to the solvers through their [`RunContext`].
+    pub progress: Arc<dyn Progress>,
+    /// Which implementation of each part to run, the default when `None`.
+    pub implementation: Option<String>,
+    /// How the input is checked or tidied before solving, see
+    /// [`input::prepare`].
+    pub parse_mode: ParseMode,
+    /// What comments in the input start with, see [`prepare_input`]. `None`
+    /// leaves the input as it is.
+    pub comments: Option<String>,
+    /// Solve only this many lines from the start of the checked or tidied
+    /// input, see [`input::first_lines`].
+    pub limit_lines: Option<usize>,
+    /// Once triggered no more parts are started, and with a grace period
+    /// those running are given up on when it's over.
+    pub interrupt: Option<Interrupt>,
+    /// How much the solvers say about their work, see
+    /// [`RunContext::explain`].
+    pub verbosity: Verbosity,
+}
+
+impl Default for PartOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            progress: no_progress(),
+            implementation: None,
+            parse_mode: ParseMode::default(),
+            comments: None,
+            limit_lines: None,
+            interrupt: None,
+            verbosity: Verbosity::default(),
+        }
+    }
+}
+
+/// Errors unless the registered days are for `year`'s event, as there are
+/// no solvers for any other.
+pub fn check_year(year: u16) -> Result<()> {
+    if year == registry::YEAR {
+        return Ok(());
+    }
+    Err(FailureKind::NotImplemented.error(anyhow!(
+        "There are no solvers for {}, only for {}: with --year {} inputs can be downloaded and answers given with submit --value, but not solved",
+        year,
+        registry::YEAR,
+        year
+    )))
+}
+
+/// Guards against a puzzle being registered under the wrong day, see
+/// [`crate::solver::MultiSolver::day_number`].
+pub fn check_day(day: u8, puzzle: &dyn Puzzle) -> Result<()> {
+    match puzzle.day_number() {
+        Some(declared) if declared != day => Err(anyhow!(
+            "Internal error: day {} is registered with '{}', which declares itself day {}",
+            day,
+            puzzle.title(),
+            declared
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The implementation of a part called `name`, or its default when `None`.
+pub fn find_implementation(
+    puzzle: &dyn Puzzle,
+    part: u32,
+    name: Option<&str>,
+) -> Result<Box<dyn Solver>> {
+    let mut implementations = puzzle.implementations(part);
+    if implementations.is_empty() {
+        return Err(anyhow!("Part {} does not exist", part));
+    }
+    let Some(name) = name else {
+        return Ok(implementations.swap_remove(0));
+    };
+    match implementations
+        .iter()
+        .position(|solver| solver.implementation() == name)
+    {
+        Some(index) => Ok(implementations.swap_remove(index)),
+        None => Err(anyhow!(
+            "Part {} has no implementation '{}', available: {}",
+            part,
+            name,
+            implementations
+                .iter()
+                .map(|solver| solver.implementation())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Solves one part of a puzzle. Errors and panics are captured in the result
+/// so a broken part can't take down the rest of the run, as are parts that
+/// exceed the timeout.
+pub fn run_part(
+    day: u8,
+    puzzle: &dyn Puzzle,
+    part: u32,
+    input: &str,
+    options: &PartOptions,
+) -> RunResult {
+    solve_part(day, puzzle, part, input, None, options)
+}
+
+/// Adds where the input came from and what it should have looked like to an
+/// error from parsing it.
+fn explain_parse_error(error: String, hint: Option<&str>, source: Option<&Path>) -> String {
+    let context = source
+        .map(|path| format!("reading {}", input_name(path)))
+        .into_iter()
+        .chain(hint.map(|hint| format!("expected {}", hint)))
+        .collect::<Vec<_>>();
+    if context.is_empty() {
+        error
+    } else {
+        format!("{} ({})", error, context.join(", "))
+    }
+}
+
+/// Strips the comments starting with `comments` from `input`, if asked to,
+/// then checks or tidies it for `puzzle`, see [`input::strip_comments`] and
+/// [`input::prepare`].
+pub fn prepare_input<'a>(
+    input: &'a str,
+    puzzle: &dyn Puzzle,
+    parse_mode: ParseMode,
+    comments: Option<&str>,
+) -> Result<Cow<'a, str>> {
+    let stripped = match comments {
+        Some(prefix) => input::strip_comments(input, prefix, puzzle.inline_comments()),
+        None => Cow::Borrowed(input),
+    };
+    match stripped {
+        Cow::Borrowed(input) => input::prepare(input, parse_mode, puzzle.sectioned_input()),
+        Cow::Owned(input) => input::prepare(&input, parse_mode, puzzle.sectioned_input())
+            .map(|prepared| Cow::Owned(prepared.into_owned())),
+    }
+}
+
+/// [`run_part`] for an input read from `source`, which is named in any parse
+/// error.
+fn solve_part(
+    day: u8,
+    puzzle: &dyn Puzzle,
+    part: u32,
+    input: &str,
+    source: Option<&Path>,
+    options: &PartOptions,
+) -> RunResult {
+    let solver = check_day(day, puzzle)
+        .and_then(|_| find_implementation(puzzle, part, options.implementation.as_deref()));
+    let solver = match solver {
+        Ok(solver) => solver,
+        Err(e) => {
+            return RunResult::failed(day, Some(puzzle), part, FailureKind::Other, e.to_string())
+        }
+    };
+    // a bad download would only get a confusing error from the parser
+    if doctor::looks_like_error_page(input) {
+        let error = format!(
+            "{} {}: aoc download {} --force",
+            source.map_or("The input".to_string(), input_name),
+            doctor::Problem::ErrorPage,
+            day
+        );
+        return RunResult::failed(day, Some(puzzle), part, FailureKind::MissingInput, error);
+    }
+    let input = match prepare_input(
+        input,
+        puzzle,
+        options.parse_mode,
+        options.comments.as_deref(),
+    ) {
+        Ok(input) => input,
+        Err(e) => {
+            let error = explain_parse_error(e.to_string(), puzzle.input_hint(), source);
+            return RunResult::failed(day, Some(puzzle), part, FailureKind::Parse, error);
+        }
+    };
+    let first_lines = options.limit_lines.and_then(|lines| {
+        input::first_lines(&input, lines).map(|first| (first.to_string(), lines))
+    });
+    let (input, truncated) = match first_lines {
+        Some((first, lines)) => (Cow::Owned(first), Some(lines)),
+        None => (input, None),
+    };
+    let description = solver.part_description().1.to_string();
+    let implementation =
+        (puzzle.implementations(part).len() > 1).then(|| solver.implementation().to_string());
+
+    let _span = info_span!("part", day, part).entered();
+    options.progress.set_message(&format!("part {}", part));
+    let ctx = Arc::new(
+        RunContext::with_progress(Arc::clone(&options.progress))
+            .with_parse_mode(options.parse_mode)
+            .with_verbosity(options.verbosity),
+    );
+    let timeout = options.timeout;
+    let interrupt = options
+        .interrupt
+        .as_ref()
+        .filter(|interrupt| interrupt.has_grace_period());
+    let (solved, peak_memory) = mem::measure(|| match (timeout, interrupt) {
+        (None, None) => Worker::Finished(solve_catching_panics(solver.as_ref(), &input, &ctx)),
+        _ => solve_on_worker(solver, &input, Arc::clone(&ctx), timeout, interrupt),
+    });
+    // solvers that don't mark their parsing stage could have failed anywhere
+    let parse_failed = ctx.parse_failed() || ctx.parse_time().is_none();
+    let (answer, timing, status, failure) = match solved {
+        Worker::Finished(Ok((answer, timing))) => {
+            (Some(answer), Some(timing), Status::Solved, None)
+        }
+        Worker::Finished(Err(e)) if parse_failed => (
+            None,
+            None,
+            Status::Failed(explain_parse_error(
+                e.to_string(),
+                puzzle.input_hint(),
+                source,
+            )),
+            Some(FailureKind::Parse),
+        ),
+        Worker::Finished(Err(e)) => (
+            None,
+            None,
+            Status::Failed(e.to_string()),
+            Some(FailureKind::Other),
+        ),
+        Worker::Interrupted => (None, None, Status::Skipped(INTERRUPTED.to_string()), None),
+        Worker::TimedOut => (
+            None,
+            None,
+            Status::TimedOut(format!(
+                "exceeded {}",
+                format_duration(timeout.unwrap_or_default())
+            )),
+            None,
+        ),
+    };
+    RunResult {
+        day,
+        title: puzzle.title().to_string(),
+        part,
+        description,
+        answer,
+        timing,
+        peak_memory,
+        implementation,
+        status,
+        failure,
+        truncated,
+    }
+}
+
+/// Runs every implementation of a part against the same input, erroring if
+/// any two that solved it disagree on the answer.
+pub fn compare_implementations(
+    day: u8,
+    puzzle: &dyn Puzzle,
+    part: u32,
+    input: &str,
+    options: &PartOptions,
+) -> Result<Vec<RunResult>> {
+    let results = puzzle
+        .implementations(part)
+        .iter()
+        .map(|solver| {
+            let options = PartOptions {
+                implementation: Some(solver.implementation().to_string()),
+                ..options.clone()
+            };
+            run_part(day, puzzle, part, input, &options)
+        })
+        .collect::<Vec<_>>();
+
+    let answers = results
+        .iter()
+        .filter_map(|result| Some((result.implementation.as_deref()?, result.answer.as_ref()?)))
+        .collect::<Vec<_>>();
+    if let Some((_, first)) = answers.first() {
+        if answers.iter().any(|(_, answer)| answer != first) {
+            return Err(FailureKind::Mismatch.error(anyhow!(
+                "Day {} part {} implementations disagree: {}",
+                day,
+                part,
+                answers
+                    .iter()
+                    .map(|(name, answer)| format!("{} = {}", name, style::answer_text(answer)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+    }
+    Ok(results)
+}
+
+/// The most steps an implementation may be estimated to take, see
+/// [`Solver::estimated_steps`], for `--cross-check` to run it: a few seconds
+/// of brute force.
+pub const CROSS_CHECK_STEPS: u64 = 100_000_000;
+
+/// [`compare_implementations`] on an input small enough for all of them,
+/// refusing one that any implementation is estimated to take more than
+/// `max_steps` on rather than running it for minutes.
+pub fn cross_check(
+    day: u8,
+    puzzle: &dyn Puzzle,
+    part: u32,
+    input: &str,
+    options: &PartOptions,
+    max_steps: u64,
+) -> Result<Vec<RunResult>> {
+    for solver in puzzle.implementations(part) {
+        if let Some(steps) = solver
+            .estimated_steps(input)
+            .filter(|steps| *steps > max_steps)
+        {
+            return Err(FailureKind::Usage.error(anyhow!(
+                "Day {} part {}'s {} implementation would take about {} steps on this input, over the {} --cross-check allows, cross-check on a smaller input",
+                day,
+                part,
+                solver.implementation(),
+                steps,
+                max_steps
+            )));
+        }
+    }
+    compare_implementations(day, puzzle, part, input, options)
+}
+
+/// Reads the input for a day and solves each of the requested parts against it.
+pub fn run_day(
+    registry: &Registry,
+    day: u8,
+    parts: &[u32],
+    input: &Path,
+    options: &PartOptions,
+) -> Vec<RunResult> {
+    let puzzle = registry.get(day);
+    let failed = |kind: FailureKind, message: String| {
+        parts
+            .iter()
+            .map(|part| RunResult::failed(day, puzzle, *part, kind, message.clone()))
+            .collect()
+    };
+
+    let Some(puzzle) = puzzle else {
+        return failed(
+            FailureKind::NotImplemented,
+            format!("Day {} not implemented", day),
+        );
+    };
+    let data = match load_input(input) {
+        Ok(data) => data,
+        Err(e) => return failed(failure::kind_of(&e), e.to_string()),
+    };
+    parts
+        .iter()
+        .map(|part| match &options.interrupt {
+            Some(interrupt) if interrupt.is_triggered() => {
+                RunResult::interrupted(day, Some(puzzle), *part)
+            }
+            _ => solve_part(day, puzzle, *part, &data, Some(input), options),
+        })
+        .collect()
+}
+
+/// The input path that means read the input from stdin instead.
+pub const STDIN_INPUT: &str = "-";
+
+pub fn is_stdin(input: &Path) -> bool {
+    input == Path::new(STDIN_INPUT)
+}
+
+/// The input path `--paste` stands for, the input is read off the clipboard
+/// instead, see [`clipboard::read`].
+pub const CLIPBOARD_INPUT: &str = "<clipboard>";
+
+pub fn is_clipboard(input: &Path) -> bool {
+    input == Path::new(CLIPBOARD_INPUT)
+}
+
+/// Reads the whole input, from stdin if it's [`STDIN_INPUT`], decompressing
+/// it if it's gzipped. Stdin can only be read once so callers should hold on
+/// to the result.
+pub fn read_input(input: &Path) -> io::Result<String> {
+    if !is_stdin(input) {
+        return input::read(input);
+    }
+    let mut data = vec![];
+    io::stdin().read_to_end(&mut data)?;
+    input::decode(input, data)
+}
+
+/// [`read_input`] with the input named in any error, which is marked as a
+/// missing input when there's no such file, or the clipboard's contents for
+/// [`CLIPBOARD_INPUT`].
+pub fn load_input(input: &Path) -> Result<String> {
+    if is_clipboard(input) {
+        return clipboard::read();
+    }
+    read_input(input).map_err(|e| {
+        let kind = match e.kind() {
+            io::ErrorKind::NotFound => FailureKind::MissingInput,
+            _ => FailureKind::Other,
+        };
+        kind.error(anyhow!("{}: {}", input_name(input), e))
+    })
+}
+
+/// How to refer to an input in messages.
+pub fn input_name(input: &Path) -> String {
+    if is_stdin(input) {
+        "stdin".to_string()
+    } else if is_clipboard(input) {
+        "the clipboard".to_string()
+    } else {
+        input.display().to_string()
+    }
+}
+
+/// The `n`th example for `day` under the data root where `layout` puts it,
+/// which has to exist.
+pub fn resolve_example(day: u8, n: u32, data_root: &Path, layout: &Layout) -> Result<PathBuf> {
+    let path = layout.example(data_root, day, n);
+    if !path.is_file() {
+        return Err(FailureKind::MissingInput.error(anyhow!(
+            "Day {} has no example {}, looked for {}",
+            day,
+            n,
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// The input to solve `day` with: `input` if one was given, otherwise the
+/// day's input under the data root where `layout` puts it, which has to exist.
+pub fn resolve_input(
+    input: Option<&Path>,
+    day: u8,
+    data_root: &Path,
+    layout: &Layout,
+) -> Result<PathBuf> {
+    if let Some(input) = input {
+        return Ok(input.to_path_buf());
+    }
+    let path = layout.input(data_root, day);
+    if !path.is_file() {
+        return Err(FailureKind::MissingInput.error(anyhow!(
+            "No input given and {} doesn't exist ({}), download it with:\n  \
+             aoc download {}",
+            path.display(),
+            layout.describe(),
+            day
+        )));
+    }
+    Ok(path)
+}
+
+/// Whether `input` is a glob pattern such as `inputs/*.txt` for several
+/// inputs, which it only is when there's no file by that name.
+pub fn is_pattern(input: &Path) -> bool {
+    input.to_string_lossy().contains(['*', '?', '['])
+        && !input.exists()
+        && !is_stdin(input)
+        && !is_clipboard(input)
+}
+
+/// The files matching the glob `pattern`, sorted, of which there has to be
+/// at least one.
+pub fn expand_pattern(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.to_string_lossy();
+    let paths = glob::glob(&pattern).map_err(|e| {
+        FailureKind::Usage.error(anyhow!("{} isn't a valid pattern: {}", pattern, e))
+    })?;
+    let mut inputs = paths
+        .filter_map(|path| path.ok())
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    if inputs.is_empty() {
+        return Err(FailureKind::MissingInput.error(anyhow!("No inputs match {}", pattern)));
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Solves the parts of `day` against each of `inputs` in turn, see
+/// [`run_day`], with each input's results alongside it. An input that can't
+/// be read or parsed only fails its own results.
+pub fn run_inputs(
+    registry: &Registry,
+    day: u8,
+    parts: &[u32],
+    inputs: &[PathBuf],
+    options: &PartOptions,
+) -> Vec<(PathBuf, Vec<RunResult>)> {
+    inputs
+        .iter()
+        .map(|input| {
+            let results = run_day(registry, day, parts, input, options);
+            (input.clone(), results)
+        })
+        .collect()
+}
+
+/// Entries for every implemented day, in day order, reading inputs from the
+/// data root where `layout` puts them, by default in numbered directories (eg.
+/// 01, 02, 03, etc.) each holding that day's input in a file called input,
+/// numbered by the day each puzzle declares. Days left out by `filter` are not
+/// included. The inputs don't have to exist, days with a missing one are
+/// skipped when run.
+pub fn all_days(
+    registry: &Registry,
+    data_root: &Path,
+    layout: &Layout,
+    filter: &DayFilter,
+) -> Vec<(u8, PathBuf)> {
+    registry
+        .days()
+        .filter(|(day, puzzle)| filter.matches(*day, &puzzle.metadata()))
+        .map(|(day, puzzle)| {
+            let number = puzzle.day_number().unwrap_or(day);
+            (day, layout.input(data_root, number))
+        })
+        .collect()
+}
+
+pub struct ScheduleOptions {
+    /// Number of days run at once, 1 runs them sequentially.
+    pub jobs: usize,
+    /// Don't start any more days once one has failed.
+    pub fail_fast: bool,
+    pub part: PartOptions,
+    /// Draws a bar per day, which replaces the progress in `part`.
+    pub progress: Option<ProgressBars>,
+    /// Days reported as skipped without being run, eg. slow ones left out of
+    /// a full run with `--skip`.
+    pub skip: Vec<u8>,
+    /// How long days took before, so the slowest can be started first when
+    /// running several at once, see [`schedule::order`]. Without any they're
+    /// started in the order given.
+    pub estimates: Estimates,
+}
+
+/// Why a day in [`ScheduleOptions::skip`] was skipped.
+pub const LEFT_OUT: &str = "left out with --skip";
+
+/// Runs each `(day, input)` entry, days without an input file or in
+/// `options.skip` are skipped.
+///
+/// `report` is called once per day in the order the entries were given,
+/// whatever order they ran in. When running sequentially that happens as soon
+/// as each day finishes, otherwise it happens once every day has finished so
+/// output is never interleaved.
+pub fn run_days(
+    registry: &Registry,
+    entries: &[(u8, PathBuf)],
+    parts: &[u32],
+    options: &ScheduleOptions,
+    mut report: impl FnMut(&[RunResult]),
+) -> Result<Vec<RunResult>> {
+    let cancelled = AtomicBool::new(false);
+    let run_entry = |(day, input): &(u8, PathBuf)| -> Option<Vec<RunResult>> {
+        if cancelled.load(Ordering::SeqCst) {
+            return None;
+        }
+        let interrupted = options
+            .part
+            .interrupt
+            .as_ref()
+            .is_some_and(Interrupt::is_triggered);
+        if interrupted {
+            let puzzle = registry.get(*day);
+            return Some(
+                parts
+                    .iter()
+                    .map(|part| RunResult::interrupted(*day, puzzle, *part))
+                    .collect(),
+            );
+        }
+        let skipped = if options.skip.contains(day) {
+            Some(LEFT_OUT)
+        } else if !input.is_file() {
+            Some("no input file")
+        } else {
+            None
+        };
+        if let Some(reason) = skipped {
+            let puzzle = registry.get(*day);
+            let skipped = Status::Skipped(reason.to_string());
+            return Some(
+                parts
+                    .iter()
+                    .map(|part| RunResult::unsolved(*day, puzzle, *part, skipped.clone()))
+                    .collect(),
+            );
+        }
+        let bar = options.progress.as_ref().map(|bars| {
+            let title = registry.get(*day).map(Puzzle::title).unwrap_or_default();
+            bars.day_bar(title)
+        });
+        let part_options = match &bar {
+            Some(bar) => PartOptions {
+                progress: Arc::new(bar.clone()),
+                ..options.part.clone()
+            },
+            None => options.part.clone(),
+        };
+        let results = run_day(registry, *day, parts, input, &part_options);
+        if let Some(bar) = bar {
+            let failed = results.iter().any(RunResult::is_failure);
+            bar.finish_with_message(if failed { "failed" } else { "done" });
+        }
+        if options.fail_fast && results.iter().any(RunResult::is_failure) {
+            cancelled.store(true, Ordering::SeqCst);
+        }
+        Some(results)
+    };
+
+    let mut all_results = vec![];
+    if options.jobs <= 1 {
+        for entry in entries {
+            let Some(results) = run_entry(entry) else {
+                break;
+            };
+            report(&results);
+            all_results.extend(results);
+        }
+    } else {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(options.jobs)
+            .build()?;
+        // a queue rather than par_iter, which splits the entries up front and
+        // so wouldn't start them in order
+        let order = schedule::order(entries, &options.estimates);
+        let next = AtomicUsize::new(0);
+        let per_day = Mutex::new(vec![None; entries.len()]);
+        pool.scope(|scope| {
+            for _ in 0..options.jobs {
+                scope.spawn(|_| {
+                    while let Some(&index) = order.get(next.fetch_add(1, Ordering::SeqCst)) {
+                        let results = run_entry(&entries[index]);
+                        per_day.lock().expect("no job panicked holding it")[index] = results;
+                    }
+                });
+            }
+        });
+        let per_day = per_day.into_inner().expect("no job panicked holding it");
+        for results in per_day.into_iter().flatten() {
+            report(&results);
+            all_results.extend(results);
+        }
+    }
+    Ok(all_results)
+}
+
+/// Per-day outcome of a multi-day run.
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub succeeded: Vec<u8>,
+    pub failed: Vec<(u8, Vec<String>)>,
+    pub timed_out: Vec<(u8, Vec<String>)>,
+    pub skipped: Vec<u8>,
+    /// Days skipped because they were asked to be, see [`LEFT_OUT`].
+    pub left_out: Vec<u8>,
+    /// Days with parts that didn't run, or didn't finish, because the run was
+    /// interrupted, see [`INTERRUPTED`].
+    pub interrupted: Vec<u8>,
+    /// Time spent solving, summed over every part that ran.
+    pub solve_time: Duration,
+    /// Highest peak memory of any part as `(day, part, bytes)`, only tracked
+    /// with the `mem-stats` feature.
+    pub peak_memory: Option<(u8, u32, u64)>,
+}
+
+impl Summary {
+    pub fn from_results(results: &[RunResult]) -> Self {
+        let mut summary = Self::default();
+        let mut days = results.iter().map(|result| result.day).collect::<Vec<u8>>();
+        days.dedup();
+        for day in days {
+            let day_results = results.iter().filter(|result| result.day == day);
+            let reasons = |timed_out: bool| {
+                day_results
+                    .clone()
+                    .filter_map(|result| {
+                        match &result.status {
+                            Status::Failed(e) if !timed_out => Some(e),
+                            Status::TimedOut(e) if timed_out => Some(e),
+                            _ => None,
+                        }
+                        .map(|e| format!("[Part {}] {}", result.part, e))
+                    })
+                    .collect::<Vec<String>>()
+            };
+            let (failures, timeouts) = (reasons(false), reasons(true));
+            if !failures.is_empty() {
+                summary.failed.push((day, failures));
+            } else if !timeouts.is_empty() {
+                summary.timed_out.push((day, timeouts));
+            } else if day_results.clone().any(RunResult::is_interrupted) {
+                summary.interrupted.push(day);
+            } else if day_results.clone().all(
+                |result| matches!(&result.status, Status::Skipped(reason) if reason == LEFT_OUT),
+            ) {
+                summary.left_out.push(day);
+            } else if day_results
+                .clone()
+                .all(|result| matches!(result.status, Status::Skipped(_)))
+            {
+                summary.skipped.push(day);
+            } else {
+                summary.succeeded.push(day);
+            }
+            for result in day_results.clone() {
+                if let Some(bytes) = result.peak_memory {
+                    if summary.peak_memory.is_none_or(|(_, _, peak)| bytes > peak) {
+                        summary.peak_memory = Some((day, result.part, bytes));
+                    }
+                }
+            }
+            summary.solve_time += day_results
+                .filter_map(|result| result.timing)
+                .map(|timing| timing.total)
+                .sum::<Duration>();
+        }
+        summary
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join(days: impl Iterator<Item = u8>) -> String {
+            let days = days.map(|day| day.to_string()).collect::<Vec<String>>();
+            if days.is_empty() {
+                "none".to_string()
+            } else {
+                days.join(", ")
+            }
+        }
+
+        writeln!(f, "{}", style::heading("Summary:"))?;
+        writeln!(
+            f,
+            "  Succeeded: {}",
+            style::success(join(self.succeeded.iter().copied()))
+        )?;
+        let failed = join(self.failed.iter().map(|(day, _)| *day));
+        if self.failed.is_empty() {
+            writeln!(f, "  Failed: {}", failed)?;
+        } else {
+            writeln!(f, "  Failed: {}", style::failure(failed))?;
+        }
+        for (day, errors) in &self.failed {
+            for error in errors {
+                writeln!(f, "    Day {}: {}", day, style::failure(error))?;
+            }
+        }
+        if !self.timed_out.is_empty() {
+            writeln!(
+                f,
+                "  Timed out: {}",
+                style::warning(join(self.timed_out.iter().map(|(day, _)| *day)))
+            )?;
+            for (day, reasons) in &self.timed_out {
+                for reason in reasons {
+                    writeln!(f, "    Day {}: {}", day, style::warning(reason))?;
+                }
+            }
+        }
+        if !self.left_out.is_empty() {
+            writeln!(
+                f,
+                "  Left out: {}",
+                style::dim(join(self.left_out.iter().copied()))
+            )?;
+        }
+        if !self.interrupted.is_empty() {
+            writeln!(
+                f,
+                "  Interrupted: {}",
+                style::warning(join(self.interrupted.iter().copied()))
+            )?;
+        }
+        write!(
+            f,
+            "  Skipped (no input file): {}",
+            style::dim(join(self.skipped.iter().copied()))
+        )?;
+        if let Some((day, part, bytes)) = self.peak_memory {
+            write!(
+                f,
+                "\n  Peak memory: {} (day {} part {})",
+                mem::format_bytes(bytes),
+                day,
+                part
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::MultiSolver;
+    use std::{fs, thread::sleep, time::Instant};
+
+    struct Sleeper {
+        delay: Duration,
+        answer: i32,
+    }
+
+    impl Solver for Sleeper {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Slept")
+        }
+
+        fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+            sleep(self.delay);
+            if self.answer < 0 {
+                return Err(anyhow!("negative answer"));
+            }
+            Ok(self.answer.into())
+        }
+    }
+
+    struct FakeDay {
+        delay_ms: u64,
+        answer: i32,
+    }
+
+    impl MultiSolver for FakeDay {
+        type PartOne = Sleeper;
+        type PartTwo = Sleeper;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Fake day"
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Sleeper {
+                delay: Duration::from_millis(self.delay_ms),
+                answer: self.answer,
+            }
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            self.get_part_one()
+        }
+    }
+
+    struct Panicker;
+
+    impl MultiSolver for Panicker {
+        type PartOne = Panicker;
+        type PartTwo = Panicker;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Panics"
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Panicker
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            Panicker
+        }
+    }
+
+    impl Solver for Panicker {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Boom")
+        }
+
+        fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+            panic!("boom");
+        }
+    }
+
+    fn timeout(timeout: Duration) -> PartOptions {
+        PartOptions {
+            timeout: Some(timeout),
+            ..PartOptions::default()
+        }
+    }
+
+    /// Parses the input as a number, which it then refuses if negative.
+    struct Numbers;
+
+    impl Solver for Numbers {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Non-negative number")
+        }
+
+        fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+            let n = ctx.parse(|| Ok(input.trim().parse::<i32>()?))?;
+            if n < 0 {
+                return Err(anyhow!("{} is negative", n));
+            }
+            Ok(n.into())
+        }
+    }
+
+    impl MultiSolver for Numbers {
+        type PartOne = Numbers;
+        type PartTwo = Numbers;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Numbers"
+        }
+
+        fn input_hint(&self) -> Option<&str> {
+            Some("a single number like '42'")
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Numbers
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            Numbers
+        }
+    }
+
+    #[test]
+    fn test_check_year() {
+        assert!(check_year(2023).is_ok());
+        let error = check_year(2022).unwrap_err();
+        assert_eq!(failure::kind_of(&error), FailureKind::NotImplemented);
+        assert!(error
+            .to_string()
+            .starts_with("There are no solvers for 2022, only for 2023"));
+    }
+
+    #[test]
+    fn test_input_hint_on_parse_error() -> Result<()> {
+        let registry = Registry::new().register(1, Numbers);
+        let path = input_file("hint");
+        fs::write(&path, "Game 1: 3 blue")?;
+        let results = run_day(&registry, 1, &[1], &path, &PartOptions::default());
+        fs::remove_file(&path)?;
+        assert_eq!(
+            results[0].status,
+            Status::Failed(format!(
+                "invalid digit found in string (reading {}, expected a single number like '42')",
+                path.display()
+            ))
+        );
+
+        // only errors from the parsing stage get the hint
+        let result = run_part(1, &Numbers, 1, "-3", &PartOptions::default());
+        assert_eq!(result.status, Status::Failed("-3 is negative".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_page_input() -> Result<()> {
+        let registry = Registry::new().register(1, Numbers);
+        let path = input_file("error-page");
+        fs::write(&path, "<!DOCTYPE html>\n<html lang=\"en-us\">\n")?;
+        let results = run_day(&registry, 1, &[1], &path, &PartOptions::default());
+        fs::remove_file(&path)?;
+        assert_eq!(
+            results[0].status,
+            Status::Failed(format!(
+                "{} looks like an AoC error page, re-download it with a fresh session: aoc download 1 --force",
+                path.display()
+            ))
+        );
+        assert_eq!(results[0].failure, Some(FailureKind::MissingInput));
+
+        // a '<' further in is left to the parser
+        let result = run_part(1, &Numbers, 1, "7\n<\n", &PartOptions::default());
+        assert!(!matches!(&result.status, Status::Failed(e) if e.contains("error page")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_lines() {
+        let limited = |input, lines, parse_mode| {
+            let options = PartOptions {
+                limit_lines: Some(lines),
+                parse_mode,
+                ..Default::default()
+            };
+            let result = run_part(1, &Numbers, 1, input, &options);
+            (result.answer, result.truncated)
+        };
+        assert_eq!(
+            limited("7\nx\n", 1, ParseMode::Strict),
+            (Some(7.into()), Some(1))
+        );
+        // the lines are counted once the input has been tidied
+        assert_eq!(
+            limited("7 \n\nx\n", 1, ParseMode::Lenient),
+            (Some(7.into()), Some(1))
+        );
+        // an input with no more lines than the limit is solved whole
+        assert_eq!(limited("7\nx\n", 2, ParseMode::Strict), (None, None));
+    }
+
+    fn input_file(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("aoc-2023-runner-{}-{}", std::process::id(), name));
+        fs::write(&path, "").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parallel_results_in_day_order() -> Result<()> {
+        // earlier days are slower, so they finish last when run in parallel
+        let registry = Registry::new()
+            .register(
+                1,
+                FakeDay {
+                    delay_ms: 150,
+                    answer: 1,
+                },
+            )
+            .register(
+                2,
+                FakeDay {
+                    delay_ms: 75,
+                    answer: 2,
+                },
+            )
+            .register(
+                3,
+                FakeDay {
+                    delay_ms: 0,
+                    answer: -1,
+                },
+            );
+        let input = input_file("ordering");
+        let entries = (1..=4).map(|day| (day, input.clone())).collect::<Vec<_>>();
+        let options = ScheduleOptions {
+            jobs: 4,
+            fail_fast: false,
+            part: PartOptions::default(),
+            progress: None,
+            skip: vec![],
+            estimates: Estimates::new(),
+        };
+
+        let mut reported = vec![];
+        let start = Instant::now();
+        let results = run_days(&registry, &entries, &[1], &options, |results| {
+            reported.push(results[0].day)
+        })?;
+        let wall_clock = start.elapsed();
+        fs::remove_file(&input)?;
+
+        assert_eq!(reported, vec![1, 2, 3, 4]);
+        let days = results.iter().map(|result| result.day).collect::<Vec<u8>>();
+        assert_eq!(days, vec![1, 2, 3, 4]);
+        assert_eq!(results[0].answer, Some(1.into()));
+        assert_eq!(results[1].answer, Some(2.into()));
+        assert!(results[2].is_failure());
+        assert_eq!(
+            results[3].status,
+            Status::Failed("Day 4 not implemented".to_string())
+        );
+
+        let summary = Summary::from_results(&results);
+        assert_eq!(summary.succeeded, vec![1, 2]);
+        assert_eq!(summary.failed.len(), 2);
+        assert!(summary.solve_time >= Duration::from_millis(225));
+        assert!(wall_clock < summary.solve_time);
+        Ok(())
+    }
+
+    #[test]
+    fn test_slowest_days_start_first() -> Result<()> {
+        /// Notes when its day starts.
+        struct Logged {
+            day: u8,
+            started: Arc<Mutex<Vec<u8>>>,
+        }
+
+        impl Solver for Logged {
+            fn part_description(&self) -> (u32, &str) {
+                (1, "Logged")
+            }
+
+            fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+                self.started.lock().unwrap().push(self.day);
+                sleep(Duration::from_millis(20));
+                Ok((self.day as i32).into())
+            }
+        }
+
+        struct LoggedDay(u8, Arc<Mutex<Vec<u8>>>);
+
+        impl MultiSolver for LoggedDay {
+            type PartOne = Logged;
+            type PartTwo = Logged;
+
+            fn get_puzzle_title(&self) -> &str {
+                "Logged day"
+            }
+
+            fn get_part_one(&self) -> Self::PartOne {
+                Logged {
+                    day: self.0,
+                    started: Arc::clone(&self.1),
+                }
+            }
+
+            fn get_part_two(&self) -> Self::PartTwo {
+                self.get_part_one()
+            }
+        }
+
+        let started = Arc::new(Mutex::new(vec![]));
+        let registry = (1..=4).fold(Registry::new(), |registry, day| {
+            registry.register(day, LoggedDay(day, Arc::clone(&started)))
+        });
+        let input = input_file("slowest-first");
+        let entries = (1..=4).map(|day| (day, input.clone())).collect::<Vec<_>>();
+        let options = ScheduleOptions {
+            jobs: 2,
+            fail_fast: false,
+            part: PartOptions::default(),
+            progress: None,
+            skip: vec![],
+            estimates: Estimates::from([(1, Duration::from_secs(5)), (3, Duration::from_secs(10))]),
+        };
+
+        let mut reported = vec![];
+        let results = run_days(&registry, &entries, &[1], &options, |results| {
+            reported.push(results[0].day)
+        })?;
+        fs::remove_file(&input)?;
+
+        // days 3 and 1 took longest last time so they're started first...
+        let mut first = started.lock().unwrap()[..2].to_vec();
+        first.sort();
+        assert_eq!(first, vec![1, 3]);
+        // ...but the results still come back in day order
+        assert_eq!(reported, vec![1, 2, 3, 4]);
+        let answers = results.iter().map(|result| result.answer.clone());
+        assert!(answers.eq((1..=4).map(|day| Some(day.into()))));
+        Ok(())
+    }
+
+    fn slow_days(delays_ms: &[u64]) -> Registry {
+        delays_ms
+            .iter()
+            .zip(1..)
+            .fold(Registry::new(), |registry, (delay_ms, day)| {
+                registry.register(
+                    day,
+                    FakeDay {
+                        delay_ms: *delay_ms,
+                        answer: day as i32,
+                    },
+                )
+            })
+    }
+
+    #[test]
+    fn test_interrupt_between_days() -> Result<()> {
+        let registry = slow_days(&[100, 100, 100]);
+        let input = input_file("interrupt");
+        let entries = (1..=3).map(|day| (day, input.clone())).collect::<Vec<_>>();
+        let interrupt = Interrupt::new(None);
+        let options = ScheduleOptions {
+            jobs: 1,
+            fail_fast: false,
+            part: PartOptions {
+                interrupt: Some(interrupt.clone()),
+                ..PartOptions::default()
+            },
+            progress: None,
+            skip: vec![],
+            estimates: Estimates::new(),
+        };
+
+        // part 1 of day 1 is running when it's interrupted
+        let trigger = thread::spawn(move || {
+            sleep(Duration::from_millis(50));
+            interrupt.trigger();
+        });
+        let results = run_days(&registry, &entries, &[1, 2], &options, |_| {})?;
+        trigger.join().unwrap();
+        fs::remove_file(&input)?;
+
+        // without a grace period the running part finishes, nothing else starts
+        assert_eq!(results.len(), 6);
+        assert_eq!(results[0].answer, Some(1.into()));
+        assert!(results[1..].iter().all(RunResult::is_interrupted));
+        let summary = Summary::from_results(&results);
+        assert_eq!(summary.interrupted, vec![1, 2, 3]);
+        assert!(summary.succeeded.is_empty() && summary.skipped.is_empty());
+        assert!(summary.to_string().contains("Interrupted: 1, 2, 3"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_interrupt_grace_period() -> Result<()> {
+        let registry = slow_days(&[20, 5_000]);
+        let input = input_file("grace");
+        let entries = (1..=2).map(|day| (day, input.clone())).collect::<Vec<_>>();
+        let interrupt = Interrupt::new(Some(Duration::from_millis(50)));
+        let options = ScheduleOptions {
+            jobs: 2,
+            fail_fast: false,
+            part: PartOptions {
+                interrupt: Some(interrupt.clone()),
+                ..PartOptions::default()
+            },
+            progress: None,
+            skip: vec![],
+            estimates: Estimates::new(),
+        };
+
+        let trigger = thread::spawn(move || {
+            sleep(Duration::from_millis(100));
+            interrupt.trigger();
+        });
+        let start = Instant::now();
+        let results = run_days(&registry, &entries, &[1], &options, |_| {})?;
+        let wall_clock = start.elapsed();
+        trigger.join().unwrap();
+        fs::remove_file(&input)?;
+
+        // day 2 is given up on once the grace period is over
+        assert!(wall_clock < Duration::from_secs(2), "{:?}", wall_clock);
+        assert_eq!(results[0].answer, Some(1.into()));
+        assert!(results[1].is_interrupted());
+        let summary = Summary::from_results(&results);
+        assert_eq!(summary.succeeded, vec![1]);
+        assert_eq!(summary.interrupted, vec![2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_all_days_attempts_only_registered_days() -> Result<()> {
+        let registry = Registry::new()
+            .register(
+                2,
+                FakeDay {
+                    delay_ms: 0,
+                    answer: 2,
+                },
+            )
+            .register(
+                5,
+                FakeDay {
+                    delay_ms: 0,
+                    answer: 5,
+                },
+            )
+            .register(
+                25,
+                FakeDay {
+                    delay_ms: 0,
+                    answer: 25,
+                },
+            );
+        let data_root =
+            std::env::temp_dir().join(format!("aoc-2023-sparse-{}", std::process::id()));
+        for day in [2, 25] {
+            let dir = data_root.join(format!("{:02}", day));
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join("input"), "")?;
+        }
+
+        let entries = all_days(
+            &registry,
+            &data_root,
+            &Layout::default(),
+            &DayFilter::default(),
+        );
+        let days = entries.iter().map(|(day, _)| *day).collect::<Vec<u8>>();
+        assert_eq!(days, vec![2, 5, 25]);
+
+        let options = ScheduleOptions {
+            jobs: 1,
+            fail_fast: false,
+            part: PartOptions::default(),
+            progress: None,
+            skip: vec![],
+            estimates: Estimates::new(),
+        };
+        let results = run_days(&registry, &entries, &[1], &options, |_| {})?;
+        fs::remove_dir_all(&data_root)?;
+
+        let summary = Summary::from_results(&results);
+        assert_eq!(summary.succeeded, vec![2, 25]);
+        assert_eq!(summary.skipped, vec![5]);
+        assert!(summary.failed.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_skipped_days_are_never_run() -> Result<()> {
+        use std::sync::atomic::AtomicUsize;
+
+        static SOLVED: [AtomicUsize; 3] = [
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+        ];
+
+        struct Counted(usize);
+
+        impl Solver for Counted {
+            fn part_description(&self) -> (u32, &str) {
+                (1, "Counted")
+            }
+
+            fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+                SOLVED[self.0].fetch_add(1, Ordering::SeqCst);
+                Ok(0.into())
+            }
+        }
+
+        impl MultiSolver for Counted {
+            type PartOne = Counted;
+            type PartTwo = Counted;
+
+            fn get_puzzle_title(&self) -> &str {
+                "Counts its runs"
+            }
+
+            fn get_part_one(&self) -> Self::PartOne {
+                Counted(self.0)
+            }
+
+            fn get_part_two(&self) -> Self::PartTwo {
+                Counted(self.0)
+            }
+        }
+
+        let registry = (0..3).fold(Registry::new(), |registry, index| {
+            registry.register(index as u8 + 1, Counted(index))
+        });
+        let input = input_file("skip");
+        let entries = (1..=3).map(|day| (day, input.clone())).collect::<Vec<_>>();
+        for jobs in [1, 3] {
+            let options = ScheduleOptions {
+                jobs,
+                fail_fast: false,
+                part: PartOptions::default(),
+                progress: None,
+                skip: vec![1, 3],
+                estimates: Estimates::new(),
+            };
+            let results = run_days(&registry, &entries, &[1, 2], &options, |_| {})?;
+            let summary = Summary::from_results(&results);
+            assert_eq!(summary.succeeded, vec![2]);
+            assert_eq!(summary.left_out, vec![1, 3]);
+            assert!(summary.skipped.is_empty() && summary.failed.is_empty());
+            assert_eq!(results[0].status, Status::Skipped(LEFT_OUT.to_string()));
+        }
+        fs::remove_file(&input)?;
+
+        let solved = SOLVED
+            .iter()
+            .map(|count| count.load(Ordering::SeqCst))
+            .collect::<Vec<_>>();
+        assert_eq!(solved, vec![0, 4, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_result_json() -> Result<()> {
+        let solved = RunResult {
+            day: 7,
+            part: 2,
+            title: "Day 7: Camel Cards".to_string(),
+            description: "Total winnings".to_string(),
+            answer: Some(5905.into()),
+            timing: Some(Timing {
+                parse: None,
+                total: Duration::from_millis(2),
+            }),
+            peak_memory: None,
+            implementation: None,
+            status: Status::Solved,
+            failure: None,
+            truncated: None,
+        };
+        let json = serde_json::to_string(&solved)?;
+        assert_eq!(
+            json,
+            "{\"day\":7,\"part\":2,\"title\":\"Day 7: Camel Cards\",\"description\":\"Total winnings\",\"answer\":5905,\"duration_ms\":2.0,\"status\":\"solved\"}"
+        );
+        assert_eq!(serde_json::from_str::<RunResult>(&json)?, solved);
+
+        let failed = RunResult {
+            answer: None,
+            timing: None,
+            status: Status::Failed("bad input".to_string()),
+            failure: Some(FailureKind::Parse),
+            ..solved.clone()
+        };
+        let json = serde_json::to_string(&failed)?;
+        assert_eq!(
+            json,
+            "{\"day\":7,\"part\":2,\"title\":\"Day 7: Camel Cards\",\"description\":\"Total winnings\",\"answer\":null,\"status\":\"failed\",\"reason\":\"bad input\",\"failure\":\"parse\"}"
+        );
+        assert_eq!(serde_json::from_str::<RunResult>(&json)?, failed);
+
+        let alternative = RunResult {
+            implementation: Some("brute".to_string()),
+            ..failed.clone()
+        };
+        let json = serde_json::to_string(&alternative)?;
+        assert!(json.contains("\"implementation\":\"brute\""));
+        assert_eq!(serde_json::from_str::<RunResult>(&json)?, alternative);
+
+        // --json prints whole runs, including parts that never got to run
+        let skipped = RunResult {
+            status: Status::Skipped("no input file".to_string()),
+            failure: None,
+            ..failed.clone()
+        };
+        let timed_out = RunResult {
+            status: Status::TimedOut("after 1s".to_string()),
+            failure: None,
+            ..failed
+        };
+        let run = vec![solved, skipped, timed_out];
+        let json = serde_json::to_string(&run)?;
+        assert!(json.contains("\"status\":\"skipped\",\"reason\":\"no input file\""));
+        assert!(json.contains("\"status\":\"timed_out\",\"reason\":\"after 1s\""));
+        assert_eq!(serde_json::from_str::<Vec<RunResult>>(&json)?, run);
+        Ok(())
+    }
+
+    struct Variant {
+        name: &'static str,
+        answer: i32,
+        steps: Option<u64>,
+    }
+
+    impl Solver for Variant {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Has alternatives")
+        }
+
+        fn implementation(&self) -> &str {
+            self.name
+        }
+
+        fn estimated_steps(&self, _input: &str) -> Option<u64> {
+            self.steps
+        }
+
+        fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+            Ok(self.answer.into())
+        }
+    }
+
+    /// Part 1 has a "fast" default and a "slow" alternative, which only gets
+    /// the same answer when `agree` is set. Part 2 has a single implementation.
+    struct Twins {
+        agree: bool,
+    }
+
+    impl MultiSolver for Twins {
+        type PartOne = Variant;
+        type PartTwo = Sleeper;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Twins"
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Variant {
+                name: "fast",
+                answer: 1,
+                steps: None,
+            }
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            Sleeper {
+                delay: Duration::ZERO,
+                answer: 2,
+            }
+        }
+
+        fn alternatives(&self, part: u32) -> Vec<Box<dyn Solver>> {
+            match part {
+                1 => vec![Box::new(Variant {
+                    name: "slow",
+                    answer: if self.agree { 1 } else { 3 },
+                    steps: Some(1000),
+                })],
+                _ => vec![],
+            }
+        }
+    }
+
+    /// Declares itself day 6, to be registered under the wrong day.
+    struct Misregistered;
+
+    impl MultiSolver for Misregistered {
+        type PartOne = Sleeper;
+        type PartTwo = Sleeper;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Day 6: Misregistered"
+        }
+
+        fn day_number(&self) -> Option<u8> {
+            Some(6)
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Sleeper {
+                delay: Duration::ZERO,
+                answer: 1,
+            }
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            self.get_part_one()
+        }
+    }
+
+    #[test]
+    fn test_misregistered_day() {
+        let result = run_part(5, &Misregistered, 1, "", &PartOptions::default());
+        assert_eq!(
+            result.status,
+            Status::Failed(
+                "Internal error: day 5 is registered with 'Day 6: Misregistered', which declares itself day 6"
+                    .to_string()
+            )
+        );
+        assert!(check_day(6, &Misregistered).is_ok());
+        // fakes that don't declare a day are never rejected
+        assert!(check_day(5, &Twins { agree: true }).is_ok());
+    }
+
+    #[test]
+    fn test_select_implementation() {
+        let twins = Twins { agree: false };
+        let default = run_part(1, &twins, 1, "", &PartOptions::default());
+        assert_eq!(default.answer, Some(1.into()));
+        assert_eq!(default.implementation.as_deref(), Some("fast"));
+
+        let options = PartOptions {
+            implementation: Some("slow".to_string()),
+            ..Default::default()
+        };
+        let slow = run_part(1, &twins, 1, "", &options);
+        assert_eq!(slow.answer, Some(3.into()));
+        assert_eq!(slow.implementation.as_deref(), Some("slow"));
+
+        // parts with a single implementation don't name it
+        let single = run_part(1, &twins, 2, "", &PartOptions::default());
+        assert_eq!(single.implementation, None);
+
+        let options = PartOptions {
+            implementation: Some("medium".to_string()),
+            ..Default::default()
+        };
+        let missing = run_part(1, &twins, 1, "", &options);
+        assert_eq!(
+            missing.status,
+            Status::Failed(
+                "Part 1 has no implementation 'medium', available: fast, slow".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_compare_implementations() -> Result<()> {
+        let options = PartOptions::default();
+        let results = compare_implementations(1, &Twins { agree: true }, 1, "", &options)?;
+        let names = results
+            .iter()
+            .map(|result| result.implementation.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![Some("fast"), Some("slow")]);
+
+        let error = compare_implementations(1, &Twins { agree: false }, 1, "", &options)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Day 1 part 1 implementations disagree: fast = 1, slow = 3"
+        );
+
+        let results = compare_implementations(1, &Twins { agree: false }, 2, "", &options)?;
+        assert_eq!(results.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cross_check() -> Result<()> {
+        let options = PartOptions::default();
+        let results = cross_check(1, &Twins { agree: true }, 1, "", &options, 1000)?;
+        assert_eq!(results.len(), 2);
+
+        // a broken fast implementation is caught
+        let error = cross_check(1, &Twins { agree: false }, 1, "", &options, 1000).unwrap_err();
+        assert_eq!(failure::kind_of(&error), FailureKind::Mismatch);
+        assert_eq!(
+            error.to_string(),
+            "Day 1 part 1 implementations disagree: fast = 1, slow = 3"
+        );
+
+        // and an input too big for the slow one refused without running either
+        let error = cross_check(1, &Twins { agree: false }, 1, "", &options, 999).unwrap_err();
+        assert_eq!(failure::kind_of(&error), FailureKind::Usage);
+        assert_eq!(
+            error.to_string(),
+            "Day 1 part 1's slow implementation would take about 1000 steps on this input, over the 999 --cross-check allows, cross-check on a smaller input"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "mem-stats")]
+    #[test]
+    fn test_peak_memory_per_part() {
+        struct Hoarder;
+
+        impl MultiSolver for Hoarder {
+            type PartOne = Sleeper;
+            type PartTwo = Hoarder;
+
+            fn get_puzzle_title(&self) -> &str {
+                "Hoards memory"
+            }
+
+            fn get_part_one(&self) -> Self::PartOne {
+                Sleeper {
+                    delay: Duration::ZERO,
+                    answer: 1,
+                }
+            }
+
+            fn get_part_two(&self) -> Self::PartTwo {
+                Hoarder
+            }
+        }
+
+        impl Solver for Hoarder {
+            fn part_description(&self) -> (u32, &str) {
+                (2, "Hoarded")
+            }
+
+            fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+                let hoard = vec![7u8; 8 * 1024 * 1024];
+                Ok((hoard.iter().filter(|b| **b == 7).count() as i64).into())
+            }
+        }
+
+        let heavy = run_part(1, &Hoarder, 2, "", &PartOptions::default());
+        let light = run_part(1, &Hoarder, 1, "", &PartOptions::default());
+        let heavy = heavy.peak_memory.unwrap();
+        assert!(heavy >= 8 * 1024 * 1024);
+        assert!(light.peak_memory.unwrap() < heavy);
+    }
+
+    #[test]
+    fn test_panicking_part_is_a_failure() {
+        let result = run_part(1, &Panicker, 1, "", &PartOptions::default());
+        assert_eq!(result.status, Status::Failed("panicked: boom".to_string()));
+        let result = run_part(1, &Panicker, 1, "", &timeout(Duration::from_secs(5)));
+        assert_eq!(result.status, Status::Failed("panicked: boom".to_string()));
+    }
+
+    #[test]
+    fn test_progress_reaches_solver() {
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<String>>);
+
+        impl Progress for Recorder {
+            fn set_length(&self, length: u64) {
+                self.0.lock().unwrap().push(format!("length {}", length));
+            }
+
+            fn inc(&self, delta: u64) {
+                self.0.lock().unwrap().push(format!("inc {}", delta));
+            }
+
+            fn set_message(&self, message: &str) {
+                self.0.lock().unwrap().push(message.to_string());
+            }
+        }
+
+        struct Counter;
+
+        impl Solver for Counter {
+            fn part_description(&self) -> (u32, &str) {
+                (1, "Counted")
+            }
+
+            fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+                ctx.progress().set_length(input.len() as u64);
+                ctx.progress().inc(input.len() as u64);
+                Ok((input.len() as i64).into())
+            }
+        }
+
+        impl MultiSolver for Counter {
+            type PartOne = Counter;
+            type PartTwo = Counter;
+
+            fn get_puzzle_title(&self) -> &str {
+                "Counts"
+            }
+
+            fn get_part_one(&self) -> Self::PartOne {
+                Counter
+            }
+
+            fn get_part_two(&self) -> Self::PartTwo {
+                Counter
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let options = PartOptions {
+            progress: recorder.clone(),
+            ..Default::default()
+        };
+        run_part(1, &Counter, 1, "abc", &options);
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec!["part 1", "length 3", "inc 3"]
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let slow = FakeDay {
+            delay_ms: 10_000,
+            answer: 1,
+        };
+        let start = Instant::now();
+        let result = run_part(1, &slow, 1, "", &timeout(Duration::from_millis(50)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            result.status,
+            Status::TimedOut("exceeded 50.0ms".to_string())
+        );
+        assert!(!result.is_failure());
+
+        let quick = FakeDay {
+            delay_ms: 0,
+            answer: 1,
+        };
+        let result = run_part(1, &quick, 1, "", &timeout(Duration::from_secs(5)));
+        assert_eq!(result.answer, Some(1.into()));
+
+        let summary = Summary::from_results(&[run_part(
+            1,
+            &slow,
+            2,
+            "",
+            &timeout(Duration::from_millis(10)),
+        )]);
+        assert_eq!(summary.timed_out.len(), 1);
+        assert!(summary.failed.is_empty());
+    }
+
+    #[test]
+    fn test_timeout_cancels_context() {
+        static STOPPED: AtomicBool = AtomicBool::new(false);
+
+        struct Spinner;
+
+        impl MultiSolver for Spinner {
+            type PartOne = Spinner;
+            type PartTwo = Spinner;
+
+            fn get_puzzle_title(&self) -> &str {
+                "Spins until cancelled"
+            }
+
+            fn get_part_one(&self) -> Self::PartOne {
+                Spinner
+            }
+
+            fn get_part_two(&self) -> Self::PartTwo {
+                Spinner
+            }
+        }
+
+        impl Solver for Spinner {
+            fn part_description(&self) -> (u32, &str) {
+                (1, "Spun")
+            }
+
+            fn solve(&self, _input: &str, ctx: &RunContext) -> Result<Answer> {
+                while !ctx.is_cancelled() {
+                    sleep(Duration::from_millis(1));
+                }
+                STOPPED.store(true, Ordering::SeqCst);
+                Err(anyhow!("cancelled"))
+            }
+        }
+
+        let result = run_part(1, &Spinner, 1, "", &timeout(Duration::from_millis(20)));
+        assert!(matches!(result.status, Status::TimedOut(_)));
+        let start = Instant::now();
+        while !STOPPED.load(Ordering::SeqCst) {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "worker never stopped"
+            );
+            sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_missing_input_skipped_and_fail_fast() -> Result<()> {
+        let registry = Registry::new()
+            .register(
+                1,
+                FakeDay {
+                    delay_ms: 0,
+                    answer: -1,
+                },
+            )
+            .register(
+                2,
+                FakeDay {
+                    delay_ms: 0,
+                    answer: 2,
+                },
+            );
+        let input = input_file("fail-fast");
+        let entries = vec![
+            (1, PathBuf::from("does/not/exist")),
+            (2, input.clone()),
+            (1, input.clone()),
+            (2, input.clone()),
+        ];
+        let options = ScheduleOptions {
+            jobs: 1,
+            fail_fast: true,
+            part: PartOptions::default(),
+            progress: None,
+            skip: vec![],
+            estimates: Estimates::new(),
+        };
+        let results = run_days(&registry, &entries, &[1, 2], &options, |_| {})?;
+        fs::remove_file(&input)?;
+
+        assert_eq!(results.len(), 6);
+        assert!(matches!(results[0].status, Status::Skipped(_)));
+        assert_eq!(results[2].status, Status::Solved);
+        assert!(results[4].is_failure());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_explicit_input() -> Result<()> {
+        let explicit = Path::new("somewhere/else");
+        let missing_root = Path::new("no-such-data-root");
+        assert_eq!(
+            resolve_input(Some(explicit), 3, missing_root, &Layout::default())?,
+            explicit
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_default_input() -> Result<()> {
+        let data_root =
+            std::env::temp_dir().join(format!("aoc-2023-default-{}", std::process::id()));
+        let dir = data_root.join("03");
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("input"), "")?;
+        fs::write(data_root.join("input03.txt"), "")?;
+        let resolved = resolve_input(None, 3, &data_root, &Layout::default());
+        let flat = resolve_input(None, 3, &data_root, &Layout::Flat);
+        let missing = resolve_input(None, 3, &data_root, &"{day}.in".parse()?);
+        fs::remove_dir_all(&data_root)?;
+        assert_eq!(resolved?, data_root.join("03").join("input"));
+        assert_eq!(flat?, data_root.join("input03.txt"));
+        assert!(missing.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_missing_default_input() {
+        let error = resolve_input(None, 3, Path::new("no-such-data-root"), &Layout::default())
+            .unwrap_err()
+            .to_string();
+        let tried = Path::new("no-such-data-root").join("03").join("input");
+        assert!(
+            error.starts_with(&format!(
+                "No input given and {} doesn't exist",
+                tried.display()
+            )),
+            "{}",
+            error
+        );
+        assert!(
+            error.contains("(the nested layout, {day:02}/input)"),
+            "{}",
+            error
+        );
+        assert!(error.ends_with("aoc download 3"), "{}", error);
+    }
+
+    #[test]
+    fn test_run_inputs_matching_a_pattern() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-pattern-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("alice.txt"), "3\n")?;
+        fs::write(dir.join("bob.txt"), "Game 1: 3 blue\n")?;
+        fs::write(dir.join("carol.txt"), "42\n")?;
+        fs::write(dir.join("notes.md"), "7\n")?;
+        let pattern = dir.join("*.txt");
+        let inputs = expand_pattern(&pattern);
+        let none = expand_pattern(&dir.join("*.in"));
+        let registry = Registry::new().register(1, Numbers);
+        let results = inputs
+            .as_ref()
+            .map(|inputs| run_inputs(&registry, 1, &[1], inputs, &PartOptions::default()));
+        fs::remove_dir_all(&dir)?;
+
+        assert!(is_pattern(&pattern));
+        assert!(!is_pattern(Path::new("input")));
+        let answers = results
+            .unwrap()
+            .into_iter()
+            .map(|(input, results)| {
+                let name = input.file_name().unwrap().to_string_lossy().into_owned();
+                (name, results[0].answer.clone(), results[0].is_failure())
+            })
+            .collect::<Vec<_>>();
+        // the malformed input fails on its own, the others are still solved
+        assert_eq!(
+            answers,
+            [
+                ("alice.txt".to_string(), Some(3.into()), false),
+                ("bob.txt".to_string(), None, true),
+                ("carol.txt".to_string(), Some(42.into()), false),
+            ]
+        );
+        let none = none.unwrap_err();
+        assert_eq!(failure::kind_of(&none), FailureKind::MissingInput);
+        assert!(none.to_string().starts_with("No inputs match"));
+        assert!(expand_pattern(Path::new("[")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_example() -> Result<()> {
+        let error = resolve_example(3, 2, Path::new("no-such-data-root"), &Layout::default())
+            .unwrap_err()
+            .to_string();
+        let tried = Path::new("no-such-data-root").join("03").join("example2");
+        assert_eq!(
+            error,
+            format!("Day 3 has no example 2, looked for {}", tried.display())
+        );
+        Ok(())
+    }
+}
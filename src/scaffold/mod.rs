@@ -0,0 +1,195 @@
+//! Generates the files for a new day, for `aoc new`.
+
+use anyhow::{anyhow, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{layout::Layout, registry::kebab_name};
+
+/// The module new days start from. It's real code, compiled by the tests
+/// below, with the placeholders that [`render`] replaces.
+const TEMPLATE: &str = include_str!("template.rs");
+
+const TEMPLATE_STRUCT: &str = "NewDay";
+const TEMPLATE_TITLE: &str = "Day 0: New Day";
+const TEMPLATE_DAY: &str = "Some(0)";
+
+/// A day's module name, eg. `haunted_wasteland` for "Haunted Wasteland".
+pub fn module_name(title: &str) -> String {
+    kebab_name(title).replace('-', "_")
+}
+
+/// A day's puzzle struct name, eg. `HauntedWasteland`.
+pub fn struct_name(title: &str) -> String {
+    kebab_name(title)
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// The module source for a day.
+pub fn render(day: u8, title: &str) -> String {
+    TEMPLATE
+        .replace(TEMPLATE_TITLE, &format!("Day {}: {}", day, title))
+        .replace(TEMPLATE_STRUCT, &struct_name(title))
+        .replace(TEMPLATE_DAY, &format!("Some({})", day))
+}
+
+/// What [`generate`] created, and the lines to add by hand to build and
+/// register the new day.
+#[derive(Debug)]
+pub struct Scaffold {
+    pub files: Vec<PathBuf>,
+    pub wiring: String,
+}
+
+/// Writes the new day's module under `root/src` and an empty input and
+/// example where `layout` puts them under `data_root`. Nothing is written if
+/// any of the files already exist.
+pub fn generate(
+    root: &Path,
+    data_root: &Path,
+    layout: &Layout,
+    day: u8,
+    title: &str,
+) -> Result<Scaffold> {
+    let module = module_name(title);
+    if !module.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(anyhow!(
+            "'{}' doesn't make a module name, titles need to start with a letter",
+            title
+        ));
+    }
+    let source = root.join("src").join(format!("{}.rs", module));
+    let input = layout.input(data_root, day);
+    let example = layout.example(data_root, day, 1);
+    let files = vec![source, input, example];
+
+    let existing = files
+        .iter()
+        .filter(|file| file.exists())
+        .map(|file| file.display().to_string())
+        .collect::<Vec<_>>();
+    if !existing.is_empty() {
+        return Err(anyhow!(
+            "Not overwriting existing files: {}",
+            existing.join(", ")
+        ));
+    }
+
+    for file in &files {
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+    }
+    fs::write(&files[0], render(day, title))?;
+    fs::write(&files[1], "")?;
+    fs::write(&files[2], "")?;
+
+    Ok(Scaffold {
+        files,
+        wiring: wiring(day, title),
+    })
+}
+
+fn wiring(day: u8, title: &str) -> String {
+    let feature = format!("day{:02}", day);
+    let module = module_name(title);
+    format!(
+        "Cargo.toml, under [features] and in default:\n    \
+         {feature} = []\n\
+         src/lib.rs:\n    \
+         #[cfg(feature = \"{feature}\")]\n    \
+         mod {module};\n\
+         Registry::builtin in src/registry.rs:\n    \
+         #[cfg(feature = \"{feature}\")]\n    \
+         let registry = registry.register({day}, crate::{module}::{name});",
+        feature = feature,
+        module = module,
+        day = day,
+        name = struct_name(title),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compiles the template, and runs its tests, as if it were a day.
+    mod template {
+        include!("template.rs");
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(module_name("Haunted Wasteland"), "haunted_wasteland");
+        assert_eq!(struct_name("Haunted Wasteland"), "HauntedWasteland");
+        assert_eq!(struct_name("Trebuchet?!"), "Trebuchet");
+        assert_eq!(struct_name("wait for it"), "WaitForIt");
+    }
+
+    #[test]
+    fn test_render() {
+        let source = render(8, "Haunted Wasteland");
+        assert!(source.contains("pub struct HauntedWasteland;"));
+        assert!(source.contains("impl MultiSolver for HauntedWasteland {"));
+        assert!(source.contains("\"Day 8: Haunted Wasteland\""));
+        assert!(source.contains("///     --- Day 8: Haunted Wasteland ---"));
+        assert!(source.contains("Some(8)"));
+        for placeholder in [TEMPLATE_STRUCT, TEMPLATE_TITLE, TEMPLATE_DAY] {
+            assert!(!source.contains(placeholder), "{}", placeholder);
+        }
+    }
+
+    #[test]
+    fn test_generate() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("aoc-2023-new-{}", std::process::id()));
+        let data_root = root.join("data").join("2023");
+        let generate =
+            |layout: &Layout| generate(&root, &data_root, layout, 8, "Haunted Wasteland");
+        let scaffold = generate(&Layout::default());
+        let again = generate(&Layout::Flat);
+        let source = fs::read_to_string(root.join("src/haunted_wasteland.rs"));
+        let example_exists = data_root.join("08/example").is_file();
+        let flat_input_exists = data_root.join("input08.txt").exists();
+        let flat_example_exists = data_root.join("input08-example.txt").exists();
+        fs::remove_dir_all(&root)?;
+
+        let scaffold = scaffold?;
+        assert_eq!(scaffold.files.len(), 3);
+        assert_eq!(source?, render(8, "Haunted Wasteland"));
+        assert!(example_exists);
+        assert_eq!(scaffold.files[1], data_root.join("08/input"));
+        // the module was there already, so nothing else was written
+        assert!(!flat_input_exists);
+        assert!(!flat_example_exists);
+        assert!(scaffold.wiring.contains(
+            "let registry = registry.register(8, crate::haunted_wasteland::HauntedWasteland);"
+        ));
+        assert!(scaffold.wiring.contains("day08 = []"));
+        let error = again.unwrap_err().to_string();
+        assert!(
+            error.starts_with("Not overwriting existing files"),
+            "{}",
+            error
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_bad_title() {
+        let root = Path::new("no-such-root");
+        let generate = |title| generate(root, &root.join("data"), &Layout::default(), 8, title);
+        assert!(generate("2 Fast").is_err());
+        assert!(generate("?!").is_err());
+        assert!(!root.exists());
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Result};
+
+use crate::{
+    context::RunContext,
+    metadata::Metadata,
+    solver::{Answer, MultiSolver, Solver},
+    statement::puzzle_struct,
+};
+
+puzzle_struct! {
+    ///     --- Day 0: New Day ---
+    ///
+    /// Paste the puzzle statement here, and part two's once it's unlocked.
+    pub struct NewDay;
+}
+pub struct PartOne;
+pub struct PartTwo;
+
+impl MultiSolver for NewDay {
+    type PartOne = PartOne;
+    type PartTwo = PartTwo;
+
+    fn get_puzzle_title(&self) -> &str {
+        "Day 0: New Day"
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        Some(Self::STATEMENT)
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(0)
+    }
+
+    fn get_part_one(&self) -> Self::PartOne {
+        PartOne
+    }
+
+    fn get_part_two(&self) -> Self::PartTwo {
+        PartTwo
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+    }
+}
+
+impl Solver for PartOne {
+    fn part_description(&self) -> (u32, &str) {
+        (1, "Answer")
+    }
+
+    fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+        Err(anyhow!("Part 1 isn't solved yet"))
+    }
+}
+
+impl Solver for PartTwo {
+    fn part_description(&self) -> (u32, &str) {
+        (2, "Answer")
+    }
+
+    fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+        Err(anyhow!("Part 2 isn't solved yet"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::assert_part;
+
+    const EXAMPLE: &str = "\
+";
+
+    #[test]
+    #[ignore = "needs the example from the puzzle statement"]
+    fn test_part_one_example() {
+        assert_part!(NewDay, 1, EXAMPLE, 0);
+    }
+
+    // personal inputs aren't in every clone, see crate::input::real
+    #[cfg(feature = "real-inputs")]
+    #[test]
+    #[ignore = "needs the answer to the real input"]
+    fn test_part_one() -> Result<()> {
+        let day = NewDay.day_number().unwrap();
+        assert_eq!(PartOne.get_solution(&crate::input::real(day))?, 0.into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_examples() -> Result<()> {
+        assert_eq!(NewDay.self_test()?, 0);
+        Ok(())
+    }
+}
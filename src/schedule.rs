@@ -0,0 +1,134 @@
+//! Ordering the days of a parallel run so the slowest start first, going by
+//! how long they took before. Starting a slow day last leaves the other jobs
+//! idle while it finishes.
+
+use anyhow::{Context, Result};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{baseline::Baseline, report::Record, runner::Status};
+
+/// How long each day took before, both parts together.
+pub type Estimates = BTreeMap<u8, Duration>;
+
+/// Each of `year`'s days' time from a baseline, see [`crate::baseline`].
+pub fn from_baseline(baseline: &Baseline, year: u16) -> Estimates {
+    let mut estimates = Estimates::new();
+    for time in baseline.parts.iter().filter(|time| time.year == year) {
+        *estimates.entry(time.day).or_default() += time.duration;
+    }
+    estimates
+}
+
+/// Each day's time from a results file written with `--output`, either a
+/// JSON array or JSON Lines from `--append`. When a part was run more than
+/// once its last time is used.
+pub fn from_results_file(path: &Path) -> Result<Estimates> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Can't read results from {}", path.display()))?;
+    let records = match serde_json::from_str::<Vec<Record>>(&text) {
+        Ok(records) => records,
+        Err(_) => text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str::<Record>)
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("{} isn't a results file", path.display()))?,
+    };
+    let mut parts = BTreeMap::new();
+    for record in records {
+        let result = record.result;
+        if let (Status::Solved, Some(timing)) = (&result.status, result.timing) {
+            parts.insert((result.day, result.part), timing.total);
+        }
+    }
+    let mut estimates = Estimates::new();
+    for ((day, _), duration) in parts {
+        *estimates.entry(day).or_default() += duration;
+    }
+    Ok(estimates)
+}
+
+/// The order to start `entries` in, as indices into it: the slowest day by
+/// `estimates` first, with days that have no estimate last and ties in the
+/// order given, so without any estimates it's just the order given.
+pub fn order(entries: &[(u8, PathBuf)], estimates: &Estimates) -> Vec<usize> {
+    let mut order = (0..entries.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&index| {
+        let estimate = estimates.get(&entries[index].0);
+        (estimate.is_none(), estimate.copied().map(std::cmp::Reverse))
+    });
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(days: &[u8]) -> Vec<(u8, PathBuf)> {
+        days.iter()
+            .map(|day| (*day, PathBuf::from(format!("{:02}/input", day))))
+            .collect()
+    }
+
+    #[test]
+    fn test_order() {
+        let entries = entries(&[1, 2, 3, 5, 7]);
+        assert_eq!(order(&entries, &Estimates::new()), [0, 1, 2, 3, 4]);
+        let estimates = Estimates::from([
+            (2, Duration::from_millis(5)),
+            (5, Duration::from_secs(3)),
+            (7, Duration::from_millis(5)),
+            (9, Duration::from_secs(60)),
+        ]);
+        // day 5 is slowest, 2 and 7 tie, 1 and 3 have never been timed
+        assert_eq!(order(&entries, &estimates), [3, 1, 4, 0, 2]);
+    }
+
+    #[test]
+    fn test_from_results_file() -> Result<()> {
+        let record = |day, part, duration_ms, solved| {
+            let status = if solved {
+                r#""status":"solved""#
+            } else {
+                r#""status":"failed","reason":"bad input""#
+            };
+            format!(
+                r#"{{"timestamp":1,"day":{},"part":{},"title":"","description":"","answer":1,"duration_ms":{},{}}}"#,
+                day, part, duration_ms, status
+            )
+        };
+        let lines = [
+            record(1, 1, 2.0, true),
+            record(1, 2, 3.0, true),
+            record(5, 1, 900.0, true),
+            record(5, 2, 4000.0, true),
+            // a later run of day 5 part 2 replaces the first
+            record(5, 2, 100.0, true),
+            record(6, 1, 50.0, false),
+        ];
+        let path =
+            std::env::temp_dir().join(format!("aoc-2023-schedule-{}.json", std::process::id()));
+        fs::write(&path, lines.join("\n") + "\n")?;
+        let appended = from_results_file(&path);
+        fs::write(&path, format!("[{}]", lines.join(",")))?;
+        let array = from_results_file(&path);
+        fs::write(&path, "not results")?;
+        let garbage = from_results_file(&path);
+        fs::remove_file(&path)?;
+
+        let expected = Estimates::from([
+            (1, Duration::from_millis(5)),
+            (5, Duration::from_millis(1000)),
+        ]);
+        assert_eq!(appended?, expected);
+        assert_eq!(array?, expected);
+        assert!(garbage.is_err());
+        assert_eq!(order(&entries(&[1, 5, 6]), &expected), [1, 0, 2]);
+        Ok(())
+    }
+}
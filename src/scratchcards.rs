@@ -1,79 +1,97 @@
-use std::{fs::read_to_string, path::PathBuf, vec};
-
-use crate::solver::{MultiSolver, Solver};
+use crate::{
+    context::{RunContext, Verbosity},
+    metadata::{Difficulty, Metadata, RuntimeClass},
+    solver::{Answer, MultiSolver, Solver},
+    statement::puzzle_struct,
+    util::parse::{first_int, ints},
+    validate::{parse_lines, strict, Malformed, ParseStats},
+};
 use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::str::FromStr;
 
-///     --- Day 4: Scratchcards ---
-///
-/// The gondola takes you up. Strangely, though, the ground doesn't seem to be coming with you; you're not climbing a mountain. As the circle of Snow Island recedes below you, an entire new landmass suddenly appears above you! The gondola carries you to the surface of the new island and lurches into the station.
-///
-/// As you exit the gondola, the first thing you notice is that the air here is much warmer than it was on Snow Island. It's also quite humid. Is this where the water source is?
-///
-/// The next thing you notice is an Elf sitting on the floor across the station in what seems to be a pile of colorful square cards.
-///
-/// "Oh! Hello!" The Elf excitedly runs over to you. "How may I be of service?" You ask about water sources.
-///
-/// "I'm not sure; I just operate the gondola lift. That does sound like something we'd have, though - this is Island Island, after all! I bet the gardener would know. He's on a different island, though - er, the small kind surrounded by water, not the floating kind. We really need to come up with a better naming scheme. Tell you what: if you can help me with something quick, I'll let you borrow my boat and you can go visit the gardener. I got all these scratchcards as a gift, but I can't figure out what I've won."
-///
-/// The Elf leads you over to the pile of colorful cards. There, you discover dozens of scratchcards, all with their opaque covering already scratched off. Picking one up, it looks like each card has two lists of numbers separated by a vertical bar (|): a list of winning numbers and then a list of numbers you have. You organize the information into a table (your puzzle input).
-///
-/// As far as the Elf has been able to figure out, you have to figure out which of the numbers you have appear in the list of winning numbers. The first match makes the card worth one point and each match after the first doubles the point value of that card.
-///
-/// For example:
-/// ```
-/// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-/// Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-/// Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-/// Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-/// Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-/// Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-/// ```
-/// In the above example, card 1 has five winning numbers (41, 48, 83, 86, and 17) and eight numbers you have (83, 86, 6, 31, 17, 9, 48, and 53). Of the numbers you have, four of them (48, 83, 17, and 86) are winning numbers! That means card 1 is worth 8 points (1 for the first match, then doubled three times for each of the three matches after the first).
-///
-///     - Card 2 has two winning numbers (32 and 61), so it is worth 2 points.
-///     - Card 3 has two winning numbers (1 and 21), so it is worth 2 points.
-///     - Card 4 has one winning number (84), so it is worth 1 point.
-///     - Card 5 has no winning numbers, so it is worth no points.
-///     - Card 6 has no winning numbers, so it is worth no points.
-///
-/// So, in this example, the Elf's pile of scratchcards is worth 13 points.
-///
-/// Take a seat in the large pile of colorful cards. How many points are they worth in total?
-///
-///     --- Part Two ---
-///
-/// Just as you're about to report your findings to the Elf, one of you realizes that the rules have actually been printed on the back of every card this whole time.
-///
-/// There's no such thing as "points". Instead, scratchcards only cause you to win more scratchcards equal to the number of winning numbers you have.
-///
-/// Specifically, you win copies of the scratchcards below the winning card equal to the number of matches. So, if card 10 were to have 5 matching numbers, you would win one copy each of cards 11, 12, 13, 14, and 15.
-///
-/// Copies of scratchcards are scored like normal scratchcards and have the same card number as the card they copied. So, if you win a copy of card 10 and it has 5 matching numbers, it would then win a copy of the same cards that the original card 10 won: cards 11, 12, 13, 14, and 15. This process repeats until none of the copies cause you to win any more cards. (Cards will never make you copy a card past the end of the table.)
-///
-/// This time, the above example goes differently:
-/// ```
-/// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-/// Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-/// Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-/// Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-/// Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-/// Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-/// ```
-///     - Card 1 has four matching numbers, so you win one copy each of the next four cards: cards 2, 3, 4, and 5.
-///     - Your original card 2 has two matching numbers, so you win one copy each of cards 3 and 4.
-///     - Your copy of card 2 also wins one copy each of cards 3 and 4.
-///     - Your four instances of card 3 (one original and three copies) have two matching numbers, so you win four copies each of cards 4 and 5.
-///     - Your eight instances of card 4 (one original and seven copies) have one matching number, so you win eight copies of card 5.
-///     - Your fourteen instances of card 5 (one original and thirteen copies) have no matching numbers and win no more cards.
-///     - Your one instance of card 6 (one original) has no matching numbers and wins no more cards.
-///
-/// Once all of the originals and copies have been processed, you end up with 1 instance of card 1, 2 instances of card 2, 4 instances of card 3, 8 instances of card 4, 14 instances of card 5, and 1 instance of card 6. In total, this example pile of scratchcards causes you to ultimately have 30 scratchcards!
-///
-/// Process all of the original and copied scratchcards until no more scratchcards are won. Including the original set of scratchcards, how many total scratchcards do you end up with?
-pub struct Scratchcards;
+puzzle_struct! {
+    ///     --- Day 4: Scratchcards ---
+    ///
+    /// The gondola takes you up. Strangely, though, the ground doesn't seem to be coming with you; you're not climbing a mountain. As the circle of Snow Island recedes below you, an entire new landmass suddenly appears above you! The gondola carries you to the surface of the new island and lurches into the station.
+    ///
+    /// As you exit the gondola, the first thing you notice is that the air here is much warmer than it was on Snow Island. It's also quite humid. Is this where the water source is?
+    ///
+    /// The next thing you notice is an Elf sitting on the floor across the station in what seems to be a pile of colorful square cards.
+    ///
+    /// "Oh! Hello!" The Elf excitedly runs over to you. "How may I be of service?" You ask about water sources.
+    ///
+    /// "I'm not sure; I just operate the gondola lift. That does sound like something we'd have, though - this is Island Island, after all! I bet the gardener would know. He's on a different island, though - er, the small kind surrounded by water, not the floating kind. We really need to come up with a better naming scheme. Tell you what: if you can help me with something quick, I'll let you borrow my boat and you can go visit the gardener. I got all these scratchcards as a gift, but I can't figure out what I've won."
+    ///
+    /// The Elf leads you over to the pile of colorful cards. There, you discover dozens of scratchcards, all with their opaque covering already scratched off. Picking one up, it looks like each card has two lists of numbers separated by a vertical bar (|): a list of winning numbers and then a list of numbers you have. You organize the information into a table (your puzzle input).
+    ///
+    /// As far as the Elf has been able to figure out, you have to figure out which of the numbers you have appear in the list of winning numbers. The first match makes the card worth one point and each match after the first doubles the point value of that card.
+    ///
+    /// For example:
+    /// ```
+    /// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    /// Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+    /// Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+    /// Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+    /// Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+    /// Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+    /// ```
+    /// In the above example, card 1 has five winning numbers (41, 48, 83, 86, and 17) and eight numbers you have (83, 86, 6, 31, 17, 9, 48, and 53). Of the numbers you have, four of them (48, 83, 17, and 86) are winning numbers! That means card 1 is worth 8 points (1 for the first match, then doubled three times for each of the three matches after the first).
+    ///
+    ///     - Card 2 has two winning numbers (32 and 61), so it is worth 2 points.
+    ///     - Card 3 has two winning numbers (1 and 21), so it is worth 2 points.
+    ///     - Card 4 has one winning number (84), so it is worth 1 point.
+    ///     - Card 5 has no winning numbers, so it is worth no points.
+    ///     - Card 6 has no winning numbers, so it is worth no points.
+    ///
+    /// So, in this example, the Elf's pile of scratchcards is worth 13 points.
+    ///
+    /// Take a seat in the large pile of colorful cards. How many points are they worth in total?
+    ///
+    ///     --- Part Two ---
+    ///
+    /// Just as you're about to report your findings to the Elf, one of you realizes that the rules have actually been printed on the back of every card this whole time.
+    ///
+    /// There's no such thing as "points". Instead, scratchcards only cause you to win more scratchcards equal to the number of winning numbers you have.
+    ///
+    /// Specifically, you win copies of the scratchcards below the winning card equal to the number of matches. So, if card 10 were to have 5 matching numbers, you would win one copy each of cards 11, 12, 13, 14, and 15.
+    ///
+    /// Copies of scratchcards are scored like normal scratchcards and have the same card number as the card they copied. So, if you win a copy of card 10 and it has 5 matching numbers, it would then win a copy of the same cards that the original card 10 won: cards 11, 12, 13, 14, and 15. This process repeats until none of the copies cause you to win any more cards. (Cards will never make you copy a card past the end of the table.)
+    ///
+    /// This time, the above example goes differently:
+    /// ```
+    /// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    /// Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+    /// Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+    /// Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+    /// Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+    /// Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+    /// ```
+    ///     - Card 1 has four matching numbers, so you win one copy each of the next four cards: cards 2, 3, 4, and 5.
+    ///     - Your original card 2 has two matching numbers, so you win one copy each of cards 3 and 4.
+    ///     - Your copy of card 2 also wins one copy each of cards 3 and 4.
+    ///     - Your four instances of card 3 (one original and three copies) have two matching numbers, so you win four copies each of cards 4 and 5.
+    ///     - Your eight instances of card 4 (one original and seven copies) have one matching number, so you win eight copies of card 5.
+    ///     - Your fourteen instances of card 5 (one original and thirteen copies) have no matching numbers and win no more cards.
+    ///     - Your one instance of card 6 (one original) has no matching numbers and wins no more cards.
+    ///
+    /// Once all of the originals and copies have been processed, you end up with 1 instance of card 1, 2 instances of card 2, 4 instances of card 3, 8 instances of card 4, 14 instances of card 5, and 1 instance of card 6. In total, this example pile of scratchcards causes you to ultimately have 30 scratchcards!
+    ///
+    /// Process all of the original and copied scratchcards until no more scratchcards are won. Including the original set of scratchcards, how many total scratchcards do you end up with?
+    pub struct Scratchcards;
+}
 pub struct PartOne;
 pub struct PartTwo;
 
+const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
 impl MultiSolver for Scratchcards {
     type PartOne = PartOne;
     type PartTwo = PartTwo;
@@ -82,6 +100,18 @@ impl MultiSolver for Scratchcards {
         "Day 4: Scratchcards"
     }
 
+    fn input_hint(&self) -> Option<&str> {
+        Some("lines like 'Card 1: 41 48 83 | 83 86 6'")
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        Some(Self::STATEMENT)
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(4)
+    }
+
     fn get_part_one(&self) -> Self::PartOne {
         PartOne
     }
@@ -89,6 +119,129 @@ impl MultiSolver for Scratchcards {
     fn get_part_two(&self) -> Self::PartTwo {
         PartTwo
     }
+
+    fn parse_stats(&self, input: &str) -> Option<ParseStats> {
+        let (cards, malformed) = parse_cards(input);
+        let numbers = cards
+            .iter()
+            .map(|card| card.winning_numbers.len() + card.my_numbers.len())
+            .sum();
+        Some(
+            ParseStats::new(input, malformed)
+                .with_count("cards", cards.len())
+                .with_count("numbers", numbers),
+        )
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            tags: &["parsing", "counting"],
+            difficulty: Some(Difficulty::Easy),
+            runtime: RuntimeClass::Fast,
+        }
+    }
+}
+
+/// One line of the input, the winning numbers and the numbers you have.
+struct Card {
+    id: i32,
+    winning_numbers: Vec<i32>,
+    my_numbers: Vec<i32>,
+}
+
+impl Card {
+    fn num_matches(&self) -> usize {
+        self.my_numbers
+            .iter()
+            .filter(|n| self.winning_numbers.contains(n))
+            .count()
+    }
+}
+
+impl FromStr for Card {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let (card, numbers) = line
+            .split_once(':')
+            .ok_or(anyhow!("Bad string input, {:?}", line))?;
+        let (winning_numbers, my_numbers) = numbers.split_once('|').ok_or(anyhow!(
+            "Expected a '|' between the two lists of numbers in {:?}",
+            line
+        ))?;
+        Ok(Card {
+            id: first_int::<i32>(card)?,
+            winning_numbers: ints::<i32>(winning_numbers)?,
+            my_numbers: ints::<i32>(my_numbers)?,
+        })
+    }
+}
+
+fn parse_cards(input: &str) -> (Vec<Card>, Vec<Malformed>) {
+    parse_lines(input, str::parse)
+}
+
+/// How each scratchcard played out, behind both parts' answers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Day4Analysis {
+    /// In the order they're listed.
+    pub cards: Vec<CardAnalysis>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CardAnalysis {
+    pub id: i32,
+    /// How many of the numbers you have are winning numbers.
+    pub matches: usize,
+    /// What it's worth, 1 for the first match doubled for each after it.
+    pub points: i32,
+    /// How many of it you end up with, the original and the copies won.
+    pub instances: i32,
+}
+
+/// Scores each scratchcard in `input`, and counts its copies.
+pub fn analyze(input: &str) -> Result<Day4Analysis> {
+    analyze_cards(strict(parse_cards(input))?)
+}
+
+/// [`analyze`] for cards already parsed. Copies of cards past the end of
+/// the table, which the puzzle says are never won, aren't counted.
+fn analyze_cards(cards: Vec<Card>) -> Result<Day4Analysis> {
+    let points = cards
+        .iter()
+        .map(|card| match card.num_matches() {
+            0 => Ok(0),
+            num_matches => 2_i32
+                .checked_pow(num_matches as u32 - 1)
+                .ok_or_else(|| anyhow!("Card {} is worth too many points", card.id)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let too_many = || anyhow!("The number of scratchcards won is too big");
+    let mut scratchcard_id_occurances = maplit::btreemap! {};
+    let mut analyzed = vec![];
+    for (card, points) in cards.iter().zip(points) {
+        let id = card.id;
+        let this_count = scratchcard_id_occurances.entry(id).or_insert(0_i32);
+        *this_count = this_count.checked_add(1).ok_or_else(too_many)?;
+        let this_count = *this_count;
+        let num_matches = card.num_matches();
+        for offset in 1..=num_matches {
+            let dup_id = i32::try_from(offset)
+                .ok()
+                .and_then(|offset| id.checked_add(offset))
+                .ok_or_else(|| anyhow!("Card {} wins cards past the last card id", id))?;
+            let count = scratchcard_id_occurances.entry(dup_id).or_insert(0);
+            *count = count.checked_add(this_count).ok_or_else(too_many)?;
+        }
+        analyzed.push(CardAnalysis {
+            id,
+            matches: num_matches,
+            points,
+            instances: this_count,
+        });
+    }
+    Ok(Day4Analysis { cards: analyzed })
 }
 
 impl Solver for PartOne {
@@ -96,48 +249,28 @@ impl Solver for PartOne {
         (1, "Total point value of scratchcards")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let mut scores = vec![];
-        for line in read_to_string(filepath)?.lines() {
-            let numbers_start = line
-                .find(':')
-                .ok_or(anyhow!("Bad string input, {:?}", line))?;
-            let numbers = &line[numbers_start + 1..];
-            let mut number_sections = numbers.split('|');
-            let mut winning_numbers = vec![];
-            if let Some(wining_numbers_section) = number_sections.next() {
-                for number in wining_numbers_section.trim().split(' ') {
-                    if number.is_empty() {
-                        continue;
-                    }
-                    let number = number.parse::<i32>()?;
-                    winning_numbers.push(number);
-                }
-            }
-
-            let mut num_matches = 0;
-            if let Some(my_numbers_section) = number_sections.next() {
-                for number in my_numbers_section.trim().split(' ') {
-                    if number.is_empty() {
-                        continue;
-                    }
-                    let number = number.parse::<i32>()?;
-                    if winning_numbers.contains(&number) {
-                        num_matches += 1;
-                    }
-                }
-            }
-
-            let score = if num_matches == 0 {
-                0
-            } else {
-                let score_power = num_matches - 1;
-                2_i32.pow((score_power) as u32)
-            };
-            scores.push(score);
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        let cards = ctx.parse(|| strict(parse_cards(input)))?;
+        let analysis = analyze_cards(cards)?;
+        for card in &analysis.cards {
+            ctx.explain(Verbosity::Verbose, || {
+                format!(
+                    "Card {}: {} match(es), worth {} point(s)",
+                    card.id, card.matches, card.points
+                )
+            });
         }
 
-        Ok(scores.iter().sum::<i32>())
+        let sum = analysis
+            .cards
+            .iter()
+            .try_fold(0_i32, |sum, card| sum.checked_add(card.points))
+            .ok_or_else(|| anyhow!("The total points are too big"))?;
+        Ok(sum.into())
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 13.into()))
     }
 }
 
@@ -146,90 +279,147 @@ impl Solver for PartTwo {
         (2, "Total scratchcards won")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let mut scratchcard_id_occurances = maplit::btreemap! {};
-        for line in read_to_string(filepath)?.lines() {
-            let mut card_nums_split = line.split(':');
-            let id = card_nums_split
-                .next()
-                .ok_or(anyhow!("Bad string input, {:?}", line))?
-                .rsplit(' ')
-                .next()
-                .ok_or(anyhow!("Bad string input, {:?}", line))?
-                .parse::<i32>()?;
-            let numbers = card_nums_split
-                .next()
-                .ok_or(anyhow!("Bad string input, {:?}", card_nums_split.next()))?;
-            let mut number_sections = numbers.split('|');
-            let mut winning_numbers = vec![];
-            if let Some(wining_numbers_section) = number_sections.next() {
-                for number in wining_numbers_section.trim().split(' ') {
-                    if number.is_empty() {
-                        continue;
-                    }
-                    winning_numbers.push(number.parse::<i32>()?);
-                }
-            }
-
-            let mut my_numbers = vec![];
-            if let Some(my_numbers_section) = number_sections.next() {
-                for number in my_numbers_section.trim().split(' ') {
-                    if number.is_empty() {
-                        continue;
-                    }
-                    my_numbers.push(number.parse::<i32>()?);
-                }
-            }
-
-            scratchcard_id_occurances
-                .entry(id)
-                .and_modify(|count| *count += 1)
-                .or_insert(1);
-            let dup_ids = {
-                let num_matches = my_numbers
-                    .iter()
-                    .filter(|n| winning_numbers.contains(n))
-                    .count();
-                if num_matches > 0 {
-                    Some(((id + 1)..(id + num_matches as i32 + 1)).collect::<Vec<i32>>())
-                } else {
-                    None
-                }
-            };
-            let this_count = scratchcard_id_occurances.get(&id).unwrap().clone();
-            if let Some(duplicated_card_ids) = dup_ids {
-                for id in duplicated_card_ids {
-                    scratchcard_id_occurances
-                        .entry(id)
-                        .and_modify(|count| *count += this_count)
-                        .or_insert(this_count);
-                }
-            }
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        let cards = ctx.parse(|| strict(parse_cards(input)))?;
+        let analysis = analyze_cards(cards)?;
+        for card in &analysis.cards {
+            ctx.explain(Verbosity::Verbose, || {
+                format!(
+                    "Card {}: {} match(es), {} instance(s)",
+                    card.id, card.matches, card.instances
+                )
+            });
         }
 
-        Ok(scratchcard_id_occurances.values().cloned().sum::<i32>())
+        let sum = analysis
+            .cards
+            .iter()
+            .try_fold(0_i32, |sum, card| sum.checked_add(card.instances))
+            .ok_or_else(|| anyhow!("The number of scratchcards won is too big"))?;
+        Ok(sum.into())
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 30.into()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::assert_part;
 
+    #[test]
+    fn test_hint_for_wrong_days_input() {
+        let result = crate::runner::run_part(
+            4,
+            &Scratchcards,
+            1,
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue",
+            &Default::default(),
+        );
+        let crate::runner::Status::Failed(error) = result.status else {
+            panic!("expected day 2's input to fail, got {:?}", result.status);
+        };
+        assert!(error.contains("expected lines like 'Card 1: 41 48 83 | 83 86 6'"));
+    }
+
+    #[test]
+    fn test_part_one_example() {
+        assert_part!(Scratchcards, 1, EXAMPLE, 13);
+    }
+
+    #[test]
+    fn test_part_two_example() {
+        assert_part!(Scratchcards, 2, EXAMPLE, 30);
+    }
+
+    #[test]
+    fn test_analyze_example() -> Result<()> {
+        let analysis = analyze(EXAMPLE)?;
+        let cards = analysis
+            .cards
+            .iter()
+            .map(|card| (card.id, card.matches, card.points, card.instances))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cards,
+            [
+                (1, 4, 8, 1),
+                (2, 2, 2, 2),
+                (3, 2, 2, 4),
+                (4, 1, 1, 8),
+                (5, 0, 0, 14),
+                (6, 0, 0, 1)
+            ]
+        );
+        let json = serde_json::to_value(&analysis)?;
+        assert_eq!(json["cards"][3]["instances"], 8);
+        assert!(analyze("Card 1: 1 2\n").is_err());
+        Ok(())
+    }
+
+    // inputs `cargo fuzz run day04` found panicking
+    #[test]
+    fn test_fuzz_regressions() {
+        let ones = format!("Card 1: {}| {}\n", "1 ".repeat(40), "1 ".repeat(40));
+        assert_eq!(
+            PartOne.solve_str(&ones).unwrap_err().to_string(),
+            "Card 1 is worth too many points"
+        );
+        assert_eq!(
+            PartTwo
+                .solve_str("Card 2147483647: 1 | 1\n")
+                .unwrap_err()
+                .to_string(),
+            "Card 2147483647 wins cards past the last card id"
+        );
+    }
+
+    // personal inputs aren't in every clone, see crate::input::real
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_one() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/04/input");
+        let filepath = crate::input::real(4);
         let solver = PartOne;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 15268);
+        assert_eq!(solution, 15268.into());
         Ok(())
     }
 
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_two() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/04/input");
+        let filepath = crate::input::real(4);
         let solver = PartTwo;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 6283755);
+        assert_eq!(solution, 6283755.into());
         Ok(())
     }
+
+    #[test]
+    fn test_parse_modes() {
+        use crate::{
+            input::ParseMode,
+            runner::{run_part, PartOptions, Status},
+        };
+        // a hand-made copy of the example, with trailing spaces and a blank line
+        let messy = EXAMPLE.replacen('\n', "  \n\n", 1);
+        let column = EXAMPLE.lines().next().unwrap().len() + 1;
+        let strict = run_part(4, &Scratchcards, 1, &messy, &PartOptions::default());
+        let Status::Failed(error) = strict.status else {
+            panic!("expected strict parsing to fail, got {:?}", strict.status);
+        };
+        assert!(
+            error.starts_with(&format!("Line 1, column {}: trailing whitespace", column)),
+            "{}",
+            error
+        );
+        let options = PartOptions {
+            parse_mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+        let lenient = run_part(4, &Scratchcards, 1, &messy, &options);
+        assert_eq!(lenient.answer, Some(13.into()));
+    }
 }
@@ -0,0 +1,64 @@
+//! A deliberately slow stand-in for a day, only with the `slow-test-day`
+//! feature, so tests can interrupt the binary while a day is running. It's
+//! registered as day 24, which no real puzzle in this crate is yet.
+
+use crate::{
+    context::RunContext,
+    solver::{Answer, MultiSolver, Solver},
+};
+use anyhow::{anyhow, Result};
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+pub const DAY: u8 = 24;
+
+pub struct SlowTestDay;
+
+/// Sleeps for as many milliseconds as the input says, then answers with
+/// them, stopping early if the runner gives up on it.
+pub struct Sleep;
+
+impl Solver for Sleep {
+    fn part_description(&self) -> (u32, &str) {
+        (1, "Milliseconds slept")
+    }
+
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        let millis = ctx.parse(|| Ok(input.trim().parse::<u64>()?))?;
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(millis) {
+            if ctx.is_cancelled() {
+                return Err(anyhow!("cancelled"));
+            }
+            sleep(Duration::from_millis(5));
+        }
+        Ok((millis as i64).into())
+    }
+}
+
+impl MultiSolver for SlowTestDay {
+    type PartOne = Sleep;
+    type PartTwo = Sleep;
+
+    fn get_puzzle_title(&self) -> &str {
+        "Day 24: Slow Test Day"
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(DAY)
+    }
+
+    fn input_hint(&self) -> Option<&str> {
+        Some("how many milliseconds to take, eg. '500'")
+    }
+
+    fn get_part_one(&self) -> Self::PartOne {
+        Sleep
+    }
+
+    fn get_part_two(&self) -> Self::PartTwo {
+        Sleep
+    }
+}
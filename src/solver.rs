@@ -1,5 +1,211 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{anyhow, Result};
+use core::fmt;
+use std::{
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{context::RunContext, metadata::Metadata, validate::ParseStats};
+
+/// The result of solving a puzzle part.
+///
+/// Most puzzles have a numeric answer, but a few expect a string, so both are
+/// representable and compare by value. Serialized as a bare JSON number or
+/// string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl Answer {
+    /// The answer with every character hidden, saying only how long it is, eg.
+    /// `•••• (4 digits)`, see [`crate::style::answer_text`].
+    pub fn masked(&self) -> String {
+        let (length, unit) = match self {
+            Answer::Integer(n) => (n.unsigned_abs().to_string().len(), "digit"),
+            Answer::Text(s) => (s.chars().count(), "character"),
+        };
+        format!(
+            "{} ({} {}{})",
+            "•".repeat(self.to_string().chars().count()),
+            length,
+            unit,
+            if length == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Reads an answer as written by [`Answer`]'s `Display`, an integer when it
+/// is one and text otherwise.
+impl FromStr for Answer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(anyhow!("An answer can't be empty"));
+        }
+        Ok(s.parse::<i64>()
+            .map(Answer::Integer)
+            .unwrap_or_else(|_| Answer::Text(s.to_string())))
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Integer(n)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+/// Wall-clock time taken by a single part run. The parse time is only known
+/// for solvers that mark their parsing stage with [`RunContext::parse`].
+///
+/// Serialized as `duration_ms` and, when known, `parse_ms`, both fractional
+/// milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timing {
+    #[serde(
+        rename = "parse_ms",
+        with = "millis::option",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parse: Option<Duration>,
+    #[serde(rename = "duration_ms", with = "millis")]
+    pub total: Duration,
+}
+
+impl Timing {
+    pub fn solve(&self) -> Duration {
+        self.total.saturating_sub(self.parse.unwrap_or_default())
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.parse {
+            Some(parse) => write!(
+                f,
+                "parse {}, solve {}",
+                format_duration(parse),
+                format_duration(self.solve())
+            ),
+            None => write!(f, "{}", format_duration(self.total)),
+        }
+    }
+}
+
+/// Serializes durations as fractional milliseconds, the unit used by every
+/// machine readable output.
+pub mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn to_millis(duration: Duration) -> f64 {
+        duration.as_secs_f64() * 1_000.0
+    }
+
+    pub fn from_millis(millis: f64) -> Duration {
+        Duration::from_nanos((millis * 1_000_000.0).round() as u64)
+    }
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(to_millis(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(from_millis(f64::deserialize(deserializer)?))
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            duration: &Option<Duration>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match duration {
+                Some(duration) => super::serialize(duration, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Duration>, D::Error> {
+            Ok(Option::<f64>::deserialize(deserializer)?.map(from_millis))
+        }
+    }
+}
+
+/// Parses a duration such as "500ms", "1.5s" or "2m", a bare number is taken
+/// as seconds.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| anyhow!("Invalid duration '{}'", s))?;
+    let seconds = match unit.trim() {
+        "ms" => value / 1_000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        unit => return Err(anyhow!("Unknown duration unit '{}', use ms, s or m", unit)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| anyhow!("Duration '{}' is out of range", s))
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1_000_000.0;
+    if micros < 1_000.0 {
+        format!("{:.1}µs", micros)
+    } else if micros < 1_000_000.0 {
+        format!("{:.1}ms", micros / 1_000.0)
+    } else {
+        format!("{:.2}s", micros / 1_000_000.0)
+    }
+}
 
 pub trait MultiSolver {
     type PartOne: Solver;
@@ -8,27 +214,390 @@ pub trait MultiSolver {
     fn get_part_one(&self) -> Self::PartOne;
     fn get_part_two(&self) -> Self::PartTwo;
 
-    fn run_all(&self, filepath: &PathBuf) -> Result<()> {
-        let part_one = self.get_part_one();
-        let part_two = self.get_part_two();
-        println!("{}", self.get_puzzle_title());
-        part_one.run(filepath, None)?;
-        part_two.run(filepath, None)?;
-        Ok(())
+    /// The day of the event this puzzle was set on, checked against the day
+    /// it is registered under. `None` skips the check.
+    fn day_number(&self) -> Option<u8> {
+        None
+    }
+
+    /// What the input should look like, eg. "lines like 'Card 1: 41 48 | 83
+    /// 86'". Added to errors from parsing the input, where a common cause is
+    /// pointing a day at another day's input.
+    fn input_hint(&self) -> Option<&str> {
+        None
+    }
+
+    /// Parses the input without solving it, reporting what was found and any
+    /// malformed lines, for `--parse-only`. `None` if the day can't do this.
+    fn parse_stats(&self, _input: &str) -> Option<ParseStats> {
+        None
+    }
+
+    /// Whether blank lines separate sections of the input, eg. day 5's maps,
+    /// rather than being stray, see [`crate::input::prepare`].
+    fn sectioned_input(&self) -> bool {
+        false
+    }
+
+    /// Whether a comment can follow a line's content with `--allow-comments`,
+    /// eg. `12 34 # note`, see [`crate::input::strip_comments`]. Days whose
+    /// inputs are full of `#`s turn this off, leaving only whole line comments.
+    fn inline_comments(&self) -> bool {
+        true
+    }
+
+    /// The puzzle statement quoted in the day's doc comment, see
+    /// [`crate::statement`].
+    fn statement(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Tags, difficulty and runtime class used to list and filter the days.
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+    }
+
+    /// Other implementations of a part, kept around for cross-checking and
+    /// benchmarking against the default returned by `get_part_one` or
+    /// `get_part_two`. Each needs a distinct [`Solver::implementation`] name.
+    fn alternatives(&self, _part: u32) -> Vec<Box<dyn Solver>> {
+        vec![]
+    }
+
+    /// Runs both parts against their embedded puzzle examples, returning the
+    /// number of parts that had an example to check.
+    fn self_test(&self) -> Result<usize> {
+        let checked = [
+            self.get_part_one().self_test(),
+            self.get_part_two().self_test(),
+        ];
+        let mut count = 0;
+        for result in checked {
+            if result.map_err(|e| anyhow!("{}: {}", self.get_puzzle_title(), e))? {
+                count += 1;
+            }
+        }
+        Ok(count)
     }
 }
 
-pub trait Solver {
+pub trait Solver: Send + Sync {
     fn part_description(&self) -> (u32, &str);
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32>;
 
-    fn run(&self, filepath: &PathBuf, title: Option<&str>) -> Result<()> {
-        if title.is_some() {
-            println!("{}", title.unwrap());
+    /// Distinguishes this solver from the other implementations of the same
+    /// part, see [`MultiSolver::alternatives`].
+    fn implementation(&self) -> &str {
+        "default"
+    }
+
+    /// Solves the puzzle input. Diagnostics should be emitted with the
+    /// `tracing` macros, eg. `debug!` for progress through the input and
+    /// `trace!` for per-line or per-item detail, rather than printed, so they
+    /// stay hidden unless enabled with RUST_LOG (eg. `RUST_LOG=debug`).
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer>;
+
+    /// Roughly how many steps solving `input` takes, for implementations
+    /// whose work grows with the numbers in the input rather than its size,
+    /// like a brute force over every seed. `--cross-check` won't run one
+    /// estimated past [`crate::runner::CROSS_CHECK_STEPS`]. `None` when that's
+    /// no concern, or when the input doesn't parse.
+    fn estimated_steps(&self, _input: &str) -> Option<u64> {
+        None
+    }
+
+    /// The example input given in the puzzle statement along with its
+    /// expected answer, if one has been embedded for this part.
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        None
+    }
+
+    /// Solves an input held in memory with a fresh context, for callers such
+    /// as benchmarks that don't need timing or progress.
+    fn solve_str(&self, input: &str) -> Result<Answer> {
+        self.solve(input, &RunContext::new())
+    }
+
+    fn get_solution(&self, filepath: &Path) -> Result<Answer> {
+        let data = crate::input::read(filepath)?;
+        self.solve_str(&data)
+    }
+
+    /// Solves the input, measuring the wall-clock time taken.
+    fn solve_timed(&self, input: &str) -> Result<(Answer, Timing)> {
+        self.solve_timed_with(input, &RunContext::new())
+    }
+
+    /// Like [`Solver::solve_timed`] but with a context supplied by the caller.
+    fn solve_timed_with(&self, input: &str, ctx: &RunContext) -> Result<(Answer, Timing)> {
+        let start = Instant::now();
+        let solution = self.solve(input, ctx)?;
+        let timing = Timing {
+            parse: ctx.parse_time(),
+            total: start.elapsed(),
+        };
+        Ok((solution, timing))
+    }
+
+    /// Solves the embedded example and compares it with the expected answer.
+    /// Returns `Ok(false)` when there is no example to check.
+    fn self_test(&self) -> Result<bool> {
+        let Some((input, expected)) = self.example() else {
+            return Ok(false);
+        };
+        let (part, _) = self.part_description();
+        let actual = self
+            .solve(input, &RunContext::new())
+            .map_err(|e| anyhow!("[Part {}] example failed to solve: {}", part, e))?;
+        if actual != expected {
+            return Err(anyhow!(
+                "[Part {}] example answer mismatch, expected: {}, actual: {}",
+                part,
+                crate::style::answer_text(&expected),
+                crate::style::answer_text(&actual)
+            ));
         }
-        let solution = self.get_solution(filepath)?;
-        let (part, desc) = self.part_description();
-        println!("[Part {}] {}: {}", part, desc, solution);
+        Ok(true)
+    }
+}
+
+/// Object safe view of a [`MultiSolver`], so days with different part types
+/// can be stored and run side by side.
+pub trait Puzzle: Send + Sync {
+    fn title(&self) -> &str;
+    fn day_number(&self) -> Option<u8>;
+    fn statement(&self) -> Option<&'static str>;
+    fn input_hint(&self) -> Option<&str>;
+    fn parse_stats(&self, input: &str) -> Option<ParseStats>;
+    fn sectioned_input(&self) -> bool;
+    fn inline_comments(&self) -> bool;
+    /// The solver for part 1 or 2, `None` for any other part number.
+    fn solver(&self, part: u32) -> Option<Box<dyn Solver>>;
+    fn self_test(&self) -> Result<usize>;
+    fn metadata(&self) -> Metadata;
+    /// Every implementation of a part, the default first. Empty for any part
+    /// other than 1 or 2.
+    fn implementations(&self, part: u32) -> Vec<Box<dyn Solver>>;
+}
+
+impl<T> Puzzle for T
+where
+    T: MultiSolver + Send + Sync,
+    T::PartOne: 'static,
+    T::PartTwo: 'static,
+{
+    fn title(&self) -> &str {
+        self.get_puzzle_title()
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        MultiSolver::day_number(self)
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        MultiSolver::statement(self)
+    }
+
+    fn input_hint(&self) -> Option<&str> {
+        MultiSolver::input_hint(self)
+    }
+
+    fn parse_stats(&self, input: &str) -> Option<ParseStats> {
+        MultiSolver::parse_stats(self, input)
+    }
+
+    fn sectioned_input(&self) -> bool {
+        MultiSolver::sectioned_input(self)
+    }
+
+    fn inline_comments(&self) -> bool {
+        MultiSolver::inline_comments(self)
+    }
+
+    fn solver(&self, part: u32) -> Option<Box<dyn Solver>> {
+        match part {
+            1 => Some(Box::new(self.get_part_one())),
+            2 => Some(Box::new(self.get_part_two())),
+            _ => None,
+        }
+    }
+
+    fn self_test(&self) -> Result<usize> {
+        MultiSolver::self_test(self)
+    }
+
+    fn metadata(&self) -> Metadata {
+        MultiSolver::metadata(self)
+    }
+
+    fn implementations(&self, part: u32) -> Vec<Box<dyn Solver>> {
+        self.solver(part)
+            .into_iter()
+            .chain(self.alternatives(part))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer() -> Result<()> {
+        assert_eq!("5744979".parse::<Answer>()?, 5744979.into());
+        assert_eq!(" -12 ".parse::<Answer>()?, Answer::Integer(-12));
+        assert_eq!("EFGH".parse::<Answer>()?, "EFGH".into());
+        // too big for an integer answer, so it can only match as text
+        assert_eq!(
+            "99999999999999999999".parse::<Answer>()?,
+            "99999999999999999999".into()
+        );
+        assert!("".parse::<Answer>().is_err());
+        assert!("  ".parse::<Answer>().is_err());
         Ok(())
     }
+
+    struct Doubler(i32);
+
+    impl Solver for Doubler {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Doubled")
+        }
+
+        fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+            let n = ctx.parse(|| Ok(input.trim().parse::<i32>()?))?;
+            Ok((n * 2).into())
+        }
+
+        fn example(&self) -> Option<(&'static str, Answer)> {
+            Some(("21", self.0.into()))
+        }
+    }
+
+    #[test]
+    fn test_self_test_pass() -> Result<()> {
+        assert!(Doubler(42).self_test()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_timed_splits_parse() -> Result<()> {
+        let (solution, timing) = Doubler(42).solve_timed("21")?;
+        assert_eq!(solution, 42.into());
+        let parse = timing.parse.expect("parse stage should be timed");
+        assert!(parse <= timing.total);
+        assert_eq!(timing.solve(), timing.total - parse);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(1_500)), "1.5µs");
+        assert_eq!(format_duration(Duration::from_micros(1_200)), "1.2ms");
+        assert_eq!(format_duration(Duration::from_millis(2_500)), "2.50s");
+    }
+
+    #[test]
+    fn test_masked_answer() {
+        assert_eq!(Answer::from(24933642).masked(), "•••••••• (8 digits)");
+        assert_eq!(Answer::Integer(-7).masked(), "•• (1 digit)");
+        assert_eq!(Answer::from("EFGH").masked(), "•••• (4 characters)");
+    }
+
+    #[test]
+    fn test_answer_json() -> Result<()> {
+        assert_eq!(serde_json::to_string(&Answer::from(42))?, "42");
+        assert_eq!(serde_json::to_string(&Answer::from("AB"))?, "\"AB\"");
+        assert_eq!(serde_json::from_str::<Answer>("-7")?, (-7).into());
+        assert_eq!(serde_json::from_str::<Answer>("\"7\"")?, "7".into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_timing_json() -> Result<()> {
+        let timing = Timing {
+            parse: Some(Duration::from_micros(250)),
+            total: Duration::from_micros(1_500),
+        };
+        let json = serde_json::to_string(&timing)?;
+        assert_eq!(json, "{\"parse_ms\":0.25,\"duration_ms\":1.5}");
+        assert_eq!(serde_json::from_str::<Timing>(&json)?, timing);
+
+        let timing = Timing {
+            parse: None,
+            total: Duration::from_millis(3),
+        };
+        let json = serde_json::to_string(&timing)?;
+        assert_eq!(json, "{\"duration_ms\":3.0}");
+        assert_eq!(serde_json::from_str::<Timing>(&json)?, timing);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_duration() -> Result<()> {
+        assert_eq!(parse_duration("500ms")?, Duration::from_millis(500));
+        assert_eq!(parse_duration("1.5s")?, Duration::from_millis(1_500));
+        assert_eq!(parse_duration("2")?, Duration::from_secs(2));
+        assert_eq!(parse_duration("2m")?, Duration::from_secs(120));
+        assert!(parse_duration("2h").is_err());
+        assert!(parse_duration("soon").is_err());
+        let error = parse_duration("99999999999999999999999").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Duration '99999999999999999999999' is out of range"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_test_mismatch() {
+        let error = Doubler(41).self_test().unwrap_err().to_string();
+        assert!(error.contains("[Part 1]"));
+        assert!(error.contains("expected: 41"));
+        assert!(error.contains("actual: 42"));
+    }
+
+    struct Unexampled;
+
+    impl Solver for Unexampled {
+        fn part_description(&self) -> (u32, &str) {
+            (2, "Nothing")
+        }
+
+        fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+            Ok(0.into())
+        }
+    }
+
+    /// A day whose part 1 example expects the answer it holds, and whose part
+    /// 2 has no example.
+    struct Stub(i32);
+
+    impl MultiSolver for Stub {
+        type PartOne = Doubler;
+        type PartTwo = Unexampled;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Day 0: Stub"
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Doubler(self.0)
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            Unexampled
+        }
+    }
+
+    #[test]
+    fn test_puzzle_self_test() {
+        let puzzle: &dyn Puzzle = &Stub(42);
+        // only the parts with an example count
+        assert_eq!(puzzle.self_test().unwrap(), 1);
+        let error = MultiSolver::self_test(&Stub(41)).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Day 0: Stub: [Part 1] example answer mismatch, expected: 41, actual: 42"
+        );
+    }
 }
@@ -0,0 +1,90 @@
+//! The puzzle statements quoted in each day's doc comment, kept retrievable
+//! from the binary with `--describe`.
+
+/// Declares a puzzle's unit struct, keeping its doc comment as the struct's
+/// documentation and also as a `STATEMENT` const.
+// unused when every day is feature-gated out
+#[allow(unused_macros)]
+macro_rules! puzzle_struct {
+    ($(#[doc = $doc:literal])* $vis:vis struct $name:ident;) => {
+        $(#[doc = $doc])*
+        $vis struct $name;
+
+        impl $name {
+            pub const STATEMENT: &'static str = concat!($($doc, "\n"),*);
+        }
+    };
+}
+
+#[allow(unused_imports)]
+pub(crate) use puzzle_struct;
+
+/// Heading that starts the second half of every statement.
+pub const PART_TWO_HEADING: &str = "--- Part Two ---";
+
+/// The statement as written, without the space that follows each `///`.
+pub fn render(statement: &str) -> String {
+    statement
+        .lines()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Just the text of part 1 or 2, `None` for any other part or when the
+/// statement has no part 2 yet.
+pub fn part(statement: &str, part: u32) -> Option<String> {
+    split_part(&render(statement), part)
+}
+
+/// [`part`] of a statement that's rendered already, eg. a puzzle description
+/// fetched with `aoc fetch-puzzle`.
+pub fn split_part(text: &str, part: u32) -> Option<String> {
+    let split = text.find(PART_TWO_HEADING).map(|index| {
+        // back up to the start of the heading's line to keep its indent
+        text[..index].rfind('\n').map_or(0, |newline| newline + 1)
+    });
+    match (part, split) {
+        (1, Some(split)) => Some(text[..split].trim_end().to_string()),
+        (1, None) => Some(text.trim_end().to_string()),
+        (2, Some(split)) => Some(text[split..].trim_end().to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    puzzle_struct! {
+        ///     --- Day 0: Test ---
+        ///
+        /// Part one text.
+        /// ```
+        /// 1 2 3
+        /// ```
+        ///
+        ///     --- Part Two ---
+        ///
+        /// Part two text.
+        struct Described;
+    }
+
+    #[test]
+    fn test_statement_captured() {
+        let text = render(Described::STATEMENT);
+        assert!(text.starts_with("    --- Day 0: Test ---\n\nPart one text.\n```\n1 2 3\n```"));
+        assert!(text.ends_with("Part two text."));
+    }
+
+    #[test]
+    fn test_parts() {
+        let one = part(Described::STATEMENT, 1).unwrap();
+        assert!(one.ends_with("```\n1 2 3\n```"));
+        assert!(!one.contains("Part Two"));
+        let two = part(Described::STATEMENT, 2).unwrap();
+        assert_eq!(two, "    --- Part Two ---\n\nPart two text.");
+        assert_eq!(part(Described::STATEMENT, 3), None);
+        assert_eq!(part("\n Only part one.\n", 2), None);
+    }
+}
@@ -0,0 +1,282 @@
+//! The stars on adventofcode.com, for `aoc status`, set against the days
+//! implemented here and the answers recorded in answers.toml, so it's plain
+//! which days have been solved but not brought here and the other way
+//! round. Without the calendar, eg. offline, the recorded answers stand in
+//! for the stars.
+
+use anyhow::{anyhow, Result};
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{answers::Answers, download, http::Client, registry::LAST_DAY, style};
+
+/// Days to a row of [`render`]'s grid.
+const ROW_DAYS: u8 = 5;
+
+pub fn calendar_url(year: u16) -> String {
+    format!("https://adventofcode.com/{}", year)
+}
+
+/// The event's calendar page, as it looks when logged in with `session`.
+pub fn fetch_calendar(client: &dyn Client, year: u16, session: &str) -> Result<String> {
+    let url = calendar_url(year);
+    let response = client.get(&url, session)?;
+    match response.status {
+        // logged out, the calendar is there but without any stars
+        200 if response.body.contains("/auth/logout") => Ok(response.body),
+        200 | 400 | 500 => Err(download::session_rejected(response.status)),
+        status => Err(anyhow!("adventofcode.com answered {} for {}", status, url)),
+    }
+}
+
+/// The stars of each day linked from a calendar page, 0 for a day that's
+/// out but unsolved. Days not out yet aren't linked, so they're left out.
+pub fn parse_calendar(html: &str) -> Result<BTreeMap<u8, u8>> {
+    // only the calendar, other parts of the page can link to days too
+    let calendar = html
+        .split_once("<pre class=\"calendar")
+        .and_then(|(_, rest)| rest.split_once("</pre>"))
+        .map_or(html, |(calendar, _)| calendar);
+    let mut stars = BTreeMap::new();
+    for link in calendar.split("<a ").skip(1) {
+        let tag = link.split('>').next().unwrap_or_default();
+        let Some(day) = tag
+            .split_once("/day/")
+            .and_then(|(_, rest)| {
+                let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+                rest[..digits].parse::<u8>().ok()
+            })
+            .filter(|day| (1..=LAST_DAY).contains(day))
+        else {
+            continue;
+        };
+        // the label says it in words, the classes are there either way
+        let day_stars = if tag.contains("two stars") || tag.contains("calendar-verycomplete") {
+            2
+        } else if tag.contains("one star") || tag.contains("calendar-complete") {
+            1
+        } else {
+            0
+        };
+        stars.insert(day, day_stars);
+    }
+    if stars.is_empty() {
+        return Err(anyhow!("There's no calendar in the page"));
+    }
+    Ok(stars)
+}
+
+/// Where a day stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayStatus {
+    pub day: u8,
+    /// Its stars on adventofcode.com, or the parts recorded in answers.toml
+    /// without the calendar.
+    pub stars: u8,
+    /// Its parts with an answer recorded in answers.toml.
+    pub recorded: u8,
+    pub implemented: bool,
+}
+
+/// Every day's status, from the `calendar`'s stars when there is one.
+pub fn days(
+    calendar: Option<&BTreeMap<u8, u8>>,
+    answers: &Answers,
+    implemented: &[u8],
+) -> Vec<DayStatus> {
+    (1..=LAST_DAY)
+        .map(|day| {
+            let recorded = (1..=2)
+                .filter(|part| answers.get(day, *part).is_some())
+                .count() as u8;
+            let stars = match calendar {
+                Some(calendar) => calendar.get(&day).copied().unwrap_or_default(),
+                None => recorded,
+            };
+            DayStatus {
+                day,
+                stars,
+                recorded,
+                implemented: implemented.contains(&day),
+            }
+        })
+        .collect()
+}
+
+/// A grid of the days with their stars, marking those implemented here,
+/// then which days are implemented but not solved, solved but not
+/// implemented, and, with the `online` stars, solved but not recorded.
+pub fn render(days: &[DayStatus], online: bool) -> String {
+    let mut text = String::new();
+    for row in days.chunks(ROW_DAYS as usize) {
+        let cells = row
+            .iter()
+            .map(|status| {
+                let stars = format!(
+                    "{}{}",
+                    "★".repeat(status.stars as usize),
+                    "☆".repeat(2 - status.stars as usize)
+                );
+                let stars = match status.stars {
+                    2 => style::success(stars),
+                    1 => style::warning(stars),
+                    _ => style::dim(stars),
+                };
+                let mark = if status.implemented { "✓" } else { " " };
+                format!("{:>2} {} {}", status.day, stars, mark)
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(text, "{}", cells.join("   ").trim_end());
+    }
+    let source = if online {
+        "on adventofcode.com"
+    } else {
+        "recorded in answers.toml"
+    };
+    let _ = writeln!(
+        text,
+        "{}",
+        style::dim(format!("★ stars {}, ✓ implemented here", source))
+    );
+
+    let list = |days: Vec<u8>| {
+        days.iter()
+            .map(|day| day.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut problems = vec![
+        (
+            "Implemented but not solved",
+            days.iter()
+                .filter(|status| status.implemented && status.stars < 2)
+                .map(|status| status.day)
+                .collect::<Vec<_>>(),
+        ),
+        (
+            "Solved but not implemented here",
+            days.iter()
+                .filter(|status| !status.implemented && status.stars > 0)
+                .map(|status| status.day)
+                .collect(),
+        ),
+    ];
+    if online {
+        problems.push((
+            "Solved but not recorded in answers.toml",
+            days.iter()
+                .filter(|status| status.stars > status.recorded)
+                .map(|status| status.day)
+                .collect(),
+        ));
+    }
+    for (problem, days) in problems {
+        if !days.is_empty() {
+            let _ = writeln!(text, "{}: {}", problem, list(days));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Response;
+
+    const CALENDAR: &str = include_str!("fixtures/calendar.html");
+
+    struct Mock(Response);
+
+    impl Client for Mock {
+        fn get(&self, _url: &str, _session: &str) -> Result<Response> {
+            Ok(self.0.clone())
+        }
+
+        fn post(&self, _url: &str, _session: &str, _form: &[(&str, &str)]) -> Result<Response> {
+            unreachable!("the calendar is only fetched")
+        }
+    }
+
+    #[test]
+    fn test_parse_calendar() -> Result<()> {
+        let stars = parse_calendar(CALENDAR)?;
+        let expected = (1..=7)
+            .map(|day| (day, 2))
+            .chain([(8, 1), (9, 0)])
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(stars, expected);
+
+        // without the <pre>, the links are still found
+        let bare = "<a href=\"/2023/day/3\" aria-label=\"Day 3, one star\">3</a>";
+        assert_eq!(parse_calendar(bare)?, BTreeMap::from([(3, 1)]));
+        assert!(parse_calendar("<html><body>Oops</body></html>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_calendar() {
+        let fetch = |status, body: &str| {
+            fetch_calendar(
+                &Mock(Response {
+                    status,
+                    body: body.to_string(),
+                }),
+                2023,
+                "abc",
+            )
+        };
+        assert!(fetch(200, CALENDAR).is_ok());
+        assert_eq!(calendar_url(2022), "https://adventofcode.com/2022");
+        for (status, body) in [(200, "<html>[Log In]</html>"), (400, "")] {
+            let error = fetch(status, body).unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("adventofcode.com didn't take the session cookie"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let answers = Answers::parse("[1]\npart1 = 1\npart2 = 2\n[2]\npart1 = 3\n")?;
+        let calendar = parse_calendar(CALENDAR)?;
+        let days = days(Some(&calendar), &answers, &[1, 2, 9, 10]);
+        assert_eq!(
+            days[1],
+            DayStatus {
+                day: 2,
+                stars: 2,
+                recorded: 1,
+                implemented: true
+            }
+        );
+        let text = render(&days, true);
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], " 1 ★★ ✓    2 ★★ ✓    3 ★★      4 ★★      5 ★★");
+        assert_eq!(lines[1], " 6 ★★      7 ★★      8 ★☆      9 ☆☆ ✓   10 ☆☆ ✓");
+        assert_eq!(lines[5], "★ stars on adventofcode.com, ✓ implemented here");
+        assert_eq!(lines[6], "Implemented but not solved: 9, 10");
+        assert_eq!(
+            lines[7],
+            "Solved but not implemented here: 3, 4, 5, 6, 7, 8"
+        );
+        assert_eq!(
+            lines[8],
+            "Solved but not recorded in answers.toml: 2, 3, 4, 5, 6, 7, 8"
+        );
+
+        // offline, the recorded answers are the stars
+        let days = super::days(None, &answers, &[1, 2, 9]);
+        let text = render(&days, false);
+        assert!(text.starts_with(" 1 ★★ ✓    2 ★☆ ✓    3 ☆☆"), "{}", text);
+        assert!(text.contains("★ stars recorded in answers.toml"));
+        assert!(
+            text.ends_with("Implemented but not solved: 2, 9\n"),
+            "{}",
+            text
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+//! Colours for terminal output, so every command marks answers, failures and
+//! timings the same way. Styling is off until [`set_enabled`] turns it on, so
+//! anything formatted without going through `main`, eg. in tests, is plain.
+//! The same goes for hiding answers, see [`set_answers_hidden`].
+
+use console::{Style, StyledObject};
+use std::{
+    io::IsTerminal,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::solver::Answer;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static ANSWERS_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Masks the answers in everything printed for people to read, for
+/// `--hide-answers` when streaming. JSON output and files keep them.
+pub fn set_answers_hidden(hidden: bool) {
+    ANSWERS_HIDDEN.store(hidden, Ordering::Relaxed);
+}
+
+pub fn answers_hidden() -> bool {
+    ANSWERS_HIDDEN.load(Ordering::Relaxed)
+}
+
+/// An answer as it's shown to people, [`Answer::masked`] when answers are
+/// hidden.
+pub fn answer_text(answer: &Answer) -> String {
+    if answers_hidden() {
+        answer.masked()
+    } else {
+        answer.to_string()
+    }
+}
+
+/// [`answer_text`] marked as a success.
+pub fn answer(answer: &Answer) -> StyledObject<String> {
+    success(answer_text(answer))
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Whether to colour output by default: only when both stdout and stderr are
+/// terminals, and `NO_COLOR` isn't set, see <https://no-color.org>.
+pub fn auto_enabled() -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !no_color && std::io::stdout().is_terminal() && std::io::stderr().is_terminal()
+}
+
+fn styled<D>(value: D, style: Style) -> StyledObject<D> {
+    style.force_styling(enabled()).apply_to(value)
+}
+
+/// Answers and anything that passed.
+pub fn success<D>(value: D) -> StyledObject<D> {
+    styled(value, Style::new().green())
+}
+
+/// Failures and errors.
+pub fn failure<D>(value: D) -> StyledObject<D> {
+    styled(value, Style::new().red())
+}
+
+/// Timeouts, and anything else that didn't fail but didn't finish either.
+pub fn warning<D>(value: D) -> StyledObject<D> {
+    styled(value, Style::new().yellow())
+}
+
+/// Timings and other details that are secondary to the answers.
+pub fn dim<D>(value: D) -> StyledObject<D> {
+    styled(value, Style::new().dim())
+}
+
+/// Puzzle titles and other headings.
+pub fn heading<D>(value: D) -> StyledObject<D> {
+    styled(value, Style::new().bold())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_by_default() {
+        assert_eq!(success(42).to_string(), "42");
+        assert_eq!(failure("failed").to_string(), "failed");
+        assert_eq!(format!("{:>6}", dim("1ms")), "   1ms");
+    }
+}
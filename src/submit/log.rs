@@ -0,0 +1,350 @@
+//! Every answer given for a day, kept in `submissions.toml` next to its
+//! puzzle in the data root, so `aoc submit` can refuse to give one that's
+//! known to be wrong, or any once the part is solved, as each wrong answer
+//! locks out the next for longer. Each submission is a table:
+//!
+//! ```toml
+//! [[submission]]
+//! part = 2
+//! answer = 5905
+//! verdict = "too_low"
+//! timestamp = 1701925800
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use super::Verdict;
+use crate::{solver::Answer, style::answer_text};
+
+/// The file in a day's directory its submissions are kept in.
+pub const SUBMISSIONS_FILE: &str = "submissions.toml";
+
+/// Where the submissions for `day` are kept.
+pub fn path(data_root: &Path, day: u8) -> PathBuf {
+    data_root.join(format!("{:02}", day)).join(SUBMISSIONS_FILE)
+}
+
+/// A [`Verdict`] as it's logged, without how long a rate limit was for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Logged {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited,
+    AlreadyCompleted,
+}
+
+impl From<Verdict> for Logged {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Correct => Logged::Correct,
+            Verdict::TooHigh => Logged::TooHigh,
+            Verdict::TooLow => Logged::TooLow,
+            Verdict::WrongNoHint => Logged::Wrong,
+            Verdict::RateLimited(_) => Logged::RateLimited,
+            Verdict::AlreadyCompleted => Logged::AlreadyCompleted,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Submission {
+    pub part: u32,
+    pub answer: Answer,
+    pub verdict: Logged,
+    /// When it was given, in seconds since the Unix epoch.
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Log {
+    #[serde(default, rename = "submission")]
+    submissions: Vec<Submission>,
+}
+
+/// The submissions logged at `path`, none when there's no log yet.
+pub fn load(path: &Path) -> Result<Vec<Submission>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).with_context(|| format!("Can't read {}", path.display())),
+    };
+    let log: Log = toml::from_str(&text)
+        .with_context(|| format!("Bad submissions file {}", path.display()))?;
+    Ok(log.submissions)
+}
+
+/// Adds `submission` to the log at `path`, creating it and its directory as
+/// needed.
+pub fn append(path: &Path, submission: Submission) -> Result<()> {
+    let mut submissions = load(path)?;
+    submissions.push(submission);
+    let text = toml::to_string(&Log { submissions })?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+    }
+    fs::write(path, text).with_context(|| format!("Can't write {}", path.display()))
+}
+
+/// What the verdicts so far say of a part's answer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Known {
+    /// The answer that was accepted.
+    pub correct: Option<Answer>,
+    /// Answers found wrong, with their verdicts.
+    pub wrong: Vec<(Answer, Logged)>,
+    /// The least answer found too high, the answer is below it.
+    pub below: Option<i64>,
+    /// The greatest answer found too low, the answer is above it.
+    pub above: Option<i64>,
+}
+
+/// What the verdicts for `part` in `submissions` say of its answer.
+pub fn known(submissions: &[Submission], part: u32) -> Known {
+    let mut known = Known::default();
+    for submission in submissions.iter().filter(|s| s.part == part) {
+        let answer = &submission.answer;
+        match submission.verdict {
+            Logged::Correct => known.correct = Some(answer.clone()),
+            Logged::TooHigh | Logged::TooLow | Logged::Wrong => {
+                known.wrong.push((answer.clone(), submission.verdict))
+            }
+            // not checked
+            Logged::RateLimited | Logged::AlreadyCompleted => {}
+        }
+        if let Answer::Integer(n) = *answer {
+            match submission.verdict {
+                Logged::TooHigh => known.below = Some(known.below.map_or(n, |b| b.min(n))),
+                Logged::TooLow => known.above = Some(known.above.map_or(n, |a| a.max(n))),
+                _ => {}
+            }
+        }
+    }
+    known
+}
+
+/// Whether an answer should be given, given what's [`Known`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Submit,
+    /// Give it, though it goes against the bounds found so far, saying why.
+    Warn(String),
+    /// Don't give it, saying why.
+    Refuse(String),
+}
+
+/// Whether to give `answer` for a part, refusing one that's known wrong or
+/// any once the part's solved, and warning of one outside the bounds that
+/// too high and too low verdicts have set.
+pub fn check(known: &Known, answer: &Answer) -> Decision {
+    if let Some(correct) = &known.correct {
+        return Decision::Refuse(if correct == answer {
+            format!("{} was already accepted", answer_text(answer))
+        } else {
+            format!("This part is solved already, with {}", answer_text(correct))
+        });
+    }
+    if let Some((_, verdict)) = known.wrong.iter().find(|(wrong, _)| wrong == answer) {
+        let why = match verdict {
+            Logged::TooHigh => ", it's too high",
+            Logged::TooLow => ", it's too low",
+            _ => "",
+        };
+        return Decision::Refuse(format!(
+            "{} was already given and was wrong{}",
+            answer_text(answer),
+            why
+        ));
+    }
+    let Answer::Integer(n) = *answer else {
+        return Decision::Submit;
+    };
+    match (known.above, known.below) {
+        (_, Some(below)) if n >= below => Decision::Warn(format!(
+            "{} is at least {}, which was already too high",
+            answer_text(answer),
+            answer_text(&below.into())
+        )),
+        (Some(above), _) if n <= above => Decision::Warn(format!(
+            "{} is at most {}, which was already too low",
+            answer_text(answer),
+            answer_text(&above.into())
+        )),
+        _ => Decision::Submit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(part: u32, answer: impl Into<Answer>, verdict: Logged) -> Submission {
+        Submission {
+            part,
+            answer: answer.into(),
+            verdict,
+            timestamp: 1701925800,
+        }
+    }
+
+    #[test]
+    fn test_known() {
+        let submissions = [
+            submission(1, 500, Logged::TooHigh),
+            submission(1, 300, Logged::TooHigh),
+            submission(1, 100, Logged::TooLow),
+            submission(1, 200, Logged::TooLow),
+            submission(1, 250, Logged::RateLimited),
+            submission(1, 260, Logged::Wrong),
+            submission(2, 1000, Logged::TooLow),
+        ];
+        assert_eq!(
+            known(&submissions, 1),
+            Known {
+                correct: None,
+                wrong: vec![
+                    (500.into(), Logged::TooHigh),
+                    (300.into(), Logged::TooHigh),
+                    (100.into(), Logged::TooLow),
+                    (200.into(), Logged::TooLow),
+                    (260.into(), Logged::Wrong),
+                ],
+                below: Some(300),
+                above: Some(200),
+            }
+        );
+        let two = known(&submissions, 2);
+        assert_eq!((two.above, two.below), (Some(1000), None));
+        assert_eq!(known(&submissions, 3), Known::default());
+
+        // text answers have no bounds
+        let text = known(&[submission(1, "abc", Logged::TooHigh)], 1);
+        assert_eq!(text.wrong, [("abc".into(), Logged::TooHigh)]);
+        assert_eq!(text.below, None);
+    }
+
+    #[test]
+    fn test_check_known_answers() {
+        let known = known(
+            &[
+                submission(1, 500, Logged::TooHigh),
+                submission(1, 260, Logged::Wrong),
+                submission(1, "abc", Logged::Wrong),
+                submission(1, 250, Logged::RateLimited),
+            ],
+            1,
+        );
+        assert_eq!(
+            check(&known, &500.into()),
+            Decision::Refuse("500 was already given and was wrong, it's too high".to_string())
+        );
+        assert_eq!(
+            check(&known, &260.into()),
+            Decision::Refuse("260 was already given and was wrong".to_string())
+        );
+        assert!(matches!(check(&known, &"abc".into()), Decision::Refuse(_)));
+        // one that wasn't checked can be given again
+        assert_eq!(check(&known, &250.into()), Decision::Submit);
+        assert_eq!(check(&known, &"abd".into()), Decision::Submit);
+    }
+
+    #[test]
+    fn test_check_solved() {
+        let known = known(
+            &[
+                submission(2, 41, Logged::TooLow),
+                submission(2, 42, Logged::Correct),
+                submission(2, 42, Logged::AlreadyCompleted),
+            ],
+            2,
+        );
+        assert_eq!(known.correct, Some(42.into()));
+        assert_eq!(
+            check(&known, &42.into()),
+            Decision::Refuse("42 was already accepted".to_string())
+        );
+        assert_eq!(
+            check(&known, &43.into()),
+            Decision::Refuse("This part is solved already, with 42".to_string())
+        );
+        // even one that was wrong says it's solved
+        assert_eq!(
+            check(&known, &41.into()),
+            Decision::Refuse("This part is solved already, with 42".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check_bounds() {
+        let known = known(
+            &[
+                submission(1, 300, Logged::TooHigh),
+                submission(1, 200, Logged::TooLow),
+            ],
+            1,
+        );
+        for n in [201, 250, 299] {
+            assert_eq!(check(&known, &n.into()), Decision::Submit, "{}", n);
+        }
+        assert_eq!(
+            check(&known, &301.into()),
+            Decision::Warn("301 is at least 300, which was already too high".to_string())
+        );
+        assert_eq!(
+            check(&known, &150.into()),
+            Decision::Warn("150 is at most 200, which was already too low".to_string())
+        );
+        assert!(matches!(check(&known, &(-5).into()), Decision::Warn(_)));
+        // bounds say nothing of text
+        assert_eq!(check(&known, &"x".into()), Decision::Submit);
+
+        // with only one bound, the other side is open
+        let high = super::known(&[submission(1, 300, Logged::TooHigh)], 1);
+        assert_eq!(check(&high, &i64::MIN.into()), Decision::Submit);
+        assert!(matches!(check(&high, &1000.into()), Decision::Warn(_)));
+        let low = super::known(&[submission(1, 200, Logged::TooLow)], 1);
+        assert_eq!(check(&low, &i64::MAX.into()), Decision::Submit);
+        assert!(matches!(check(&low, &0.into()), Decision::Warn(_)));
+        assert_eq!(check(&Known::default(), &0.into()), Decision::Submit);
+    }
+
+    #[test]
+    fn test_log() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-submissions-{}", std::process::id()));
+        let path = path(&dir, 7);
+        assert!(path.ends_with("07/submissions.toml"));
+        assert!(load(&path)?.is_empty());
+        let submissions = [
+            submission(1, 6440, Logged::Correct),
+            submission(2, "JJ", Logged::Wrong),
+        ];
+        for s in &submissions {
+            append(&path, s.clone())?;
+        }
+        assert_eq!(load(&path)?, submissions);
+        let text = fs::read_to_string(&path)?;
+        assert!(
+            text.starts_with(
+                "[[submission]]\npart = 1\nanswer = 6440\nverdict = \"correct\"\ntimestamp = 1701925800\n"
+            ),
+            "{}",
+            text
+        );
+        assert_eq!(Logged::from(Verdict::WrongNoHint), Logged::Wrong);
+
+        fs::write(&path, "[[submission]]\npart = 1\n")?;
+        assert!(load(&path).is_err());
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
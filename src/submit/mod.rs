@@ -0,0 +1,286 @@
+//! Submitting answers to adventofcode.com with the session cookie, for
+//! `aoc submit`, and making out what the page sent back says of them.
+
+pub mod log;
+
+use anyhow::{anyhow, Result};
+use std::{fmt, time::Duration};
+
+use crate::{download, http::Client, solver::Answer};
+
+/// What adventofcode.com made of a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying which way.
+    WrongNoHint,
+    /// Not checked, as an answer was given too recently. Holds how much longer
+    /// to wait before the next.
+    RateLimited(Duration),
+    /// Not checked, as the part is solved already.
+    AlreadyCompleted,
+}
+
+impl Verdict {
+    /// Whether the answer was checked and found wrong.
+    pub fn is_wrong(self) -> bool {
+        matches!(
+            self,
+            Verdict::TooHigh | Verdict::TooLow | Verdict::WrongNoHint
+        )
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "That's the right answer!"),
+            Verdict::TooHigh => write!(f, "That's not the right answer, it's too high"),
+            Verdict::TooLow => write!(f, "That's not the right answer, it's too low"),
+            Verdict::WrongNoHint => write!(f, "That's not the right answer"),
+            Verdict::RateLimited(wait) => write!(
+                f,
+                "An answer was given too recently, wait {} before trying again",
+                format_wait(*wait)
+            ),
+            Verdict::AlreadyCompleted => write!(f, "This part is solved already"),
+        }
+    }
+}
+
+pub fn answer_url(year: u16, day: u8) -> String {
+    format!("https://adventofcode.com/{}/day/{}/answer", year, day)
+}
+
+/// Submits `answer` for `part` of day `day`, returning what was made of it.
+pub fn submit(
+    client: &dyn Client,
+    year: u16,
+    day: u8,
+    part: u32,
+    answer: &Answer,
+    session: Option<&str>,
+) -> Result<Verdict> {
+    let Some(session) = session else {
+        return Err(download::no_session("Submitting an answer"));
+    };
+    let url = answer_url(year, day);
+    let response = client.post(
+        &url,
+        session,
+        &[
+            ("level", &part.to_string()),
+            ("answer", &answer.to_string()),
+        ],
+    )?;
+    match response.status {
+        200 if !download::logged_out(&response.body) => parse_verdict(&response.body),
+        200 | 400 | 500 => Err(download::session_rejected(response.status)),
+        status => Err(anyhow!("adventofcode.com answered {} for {}", status, url)),
+    }
+}
+
+/// The verdict in the page sent back for an answer, which is in its
+/// `<article>`.
+pub fn parse_verdict(html: &str) -> Result<Verdict> {
+    let text = article_text(html)
+        .ok_or_else(|| anyhow!("adventofcode.com's response has no verdict in it"))?;
+    if text.contains("That's the right answer") {
+        Ok(Verdict::Correct)
+    } else if text.contains("That's not the right answer") {
+        Ok(if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::WrongNoHint
+        })
+    } else if text.contains("You gave an answer too recently") {
+        let wait = text
+            .split_once("You have ")
+            .and_then(|(_, rest)| rest.split_once(" left to wait"))
+            .and_then(|(wait, _)| parse_wait(wait))
+            .ok_or_else(|| anyhow!("Can't tell how long to wait from \"{}\"", text))?;
+        Ok(Verdict::RateLimited(wait))
+    } else if text.contains("Did you already complete it") {
+        Ok(Verdict::AlreadyCompleted)
+    } else {
+        Err(anyhow!(
+            "Can't make out adventofcode.com's verdict in \"{}\"",
+            text
+        ))
+    }
+}
+
+/// The text of the page's `<article>`, without its tags and with runs of
+/// whitespace collapsed.
+fn article_text(html: &str) -> Option<String> {
+    let (_, article) = html.split_once("<article")?;
+    let (article, _) = article.split_once("</article>")?;
+    let mut text = String::new();
+    let mut in_tag = true; // still in <article ...>
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text
+        .replace("&apos;", "'")
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// A wait such as `4m 32s` or `1h 2m 3s`.
+fn parse_wait(wait: &str) -> Option<Duration> {
+    let mut seconds = 0;
+    for amount in wait.split_whitespace() {
+        let split = amount.find(|c: char| !c.is_ascii_digit())?;
+        let (n, unit) = amount.split_at(split);
+        let n = n.parse::<u64>().ok()?;
+        seconds += match unit {
+            "h" => n * 3600,
+            "m" => n * 60,
+            "s" => n,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// A wait as adventofcode.com writes them, see [`parse_wait`].
+fn format_wait(wait: Duration) -> String {
+    let seconds = wait.as_secs();
+    match (seconds / 60, seconds % 60) {
+        (0, s) => format!("{}s", s),
+        (m, 0) => format!("{}m", m),
+        (m, s) => format!("{}m {}s", m, s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Response;
+    use std::cell::RefCell;
+
+    struct Mock {
+        response: Response,
+        /// Each post's URL, session and form, as `key=value&...`.
+        posted: RefCell<Vec<(String, String, String)>>,
+    }
+
+    impl Client for Mock {
+        fn get(&self, _url: &str, _session: &str) -> Result<Response> {
+            unreachable!("submitting only POSTs")
+        }
+
+        fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response> {
+            let form = form
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join("&");
+            self.posted
+                .borrow_mut()
+                .push((url.to_string(), session.to_string(), form));
+            Ok(self.response.clone())
+        }
+    }
+
+    fn mock(status: u16, body: &str) -> Mock {
+        Mock {
+            response: Response {
+                status,
+                body: body.to_string(),
+            },
+            posted: RefCell::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_verdict() -> Result<()> {
+        let fixtures = [
+            (include_str!("fixtures/correct.html"), Verdict::Correct),
+            (include_str!("fixtures/too_high.html"), Verdict::TooHigh),
+            (include_str!("fixtures/too_low.html"), Verdict::TooLow),
+            (include_str!("fixtures/wrong.html"), Verdict::WrongNoHint),
+            (
+                include_str!("fixtures/rate_limited.html"),
+                Verdict::RateLimited(Duration::from_secs(4 * 60 + 32)),
+            ),
+            (
+                include_str!("fixtures/already_completed.html"),
+                Verdict::AlreadyCompleted,
+            ),
+        ];
+        for (html, verdict) in fixtures {
+            assert_eq!(parse_verdict(html)?, verdict);
+        }
+        assert!(parse_verdict("<html><body>Oops</body></html>").is_err());
+        assert!(parse_verdict("<article><p>Something new</p></article>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_waits() {
+        assert_eq!(parse_wait("34s"), Some(Duration::from_secs(34)));
+        assert_eq!(parse_wait("1h 2m 3s"), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_wait("soon"), None);
+        assert_eq!(format_wait(Duration::from_secs(34)), "34s");
+        assert_eq!(format_wait(Duration::from_secs(300)), "5m");
+        assert_eq!(format_wait(Duration::from_secs(272)), "4m 32s");
+    }
+
+    #[test]
+    fn test_submit() -> Result<()> {
+        let client = mock(200, include_str!("fixtures/too_low.html"));
+        let verdict = submit(&client, 2023, 7, 2, &Answer::from(5905), Some("abc"))?;
+        assert_eq!(verdict, Verdict::TooLow);
+        assert!(verdict.is_wrong());
+        assert_eq!(
+            client.posted.take(),
+            [(
+                "https://adventofcode.com/2023/day/7/answer".to_string(),
+                "abc".to_string(),
+                "level=2&answer=5905".to_string()
+            )]
+        );
+
+        let verdict = submit(&client, 2015, 25, 1, &Answer::from(1), Some("abc"))?;
+        assert_eq!(verdict, Verdict::TooLow);
+        assert_eq!(
+            client.posted.take()[0].0,
+            "https://adventofcode.com/2015/day/25/answer"
+        );
+
+        let error = submit(&client, 2023, 7, 2, &Answer::from(5905), None).unwrap_err();
+        assert!(error.to_string().contains("set AOC_SESSION"), "{}", error);
+        assert!(client.posted.take().is_empty());
+
+        for client in [
+            mock(400, "Bad Request\n"),
+            mock(
+                200,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+        ] {
+            let error = submit(&client, 2023, 7, 1, &Answer::from(1), Some("stale")).unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("adventofcode.com didn't take the session cookie"),
+                "{}",
+                error
+            );
+        }
+        Ok(())
+    }
+}
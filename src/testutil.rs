@@ -0,0 +1,163 @@
+//! Helpers for the days' tests, solving inputs held in memory rather than
+//! read from files.
+
+use crate::{
+    solver::{Answer, Puzzle, Solver},
+    style,
+};
+
+/// How many lines of the input a failure shows.
+const EXCERPT_LINES: usize = 5;
+
+/// Asserts that a day solves an input to an answer, either with the default
+/// implementation of a part, eg. `assert_part!(CamelCards, 1, EXAMPLE, 6440)`,
+/// or with a given solver, eg.
+/// `assert_part!(WaitForIt, PartTwo(Strategy::Brute), EXAMPLE, 71503)`.
+/// Anything that converts into an [`Answer`] can be expected.
+macro_rules! assert_part {
+    ($day:expr, $part:literal, $input:expr, $expected:expr) => {
+        $crate::testutil::check_part(&$day, $part, $input, $expected.into())
+    };
+    ($day:expr, $solver:expr, $input:expr, $expected:expr) => {
+        $crate::testutil::check_solver(
+            $crate::solver::Puzzle::title(&$day),
+            &$solver,
+            $input,
+            $expected.into(),
+        )
+    };
+}
+
+pub(crate) use assert_part;
+
+/// See [`assert_part!`].
+#[track_caller]
+pub fn check_part(puzzle: &dyn Puzzle, part: u32, input: &str, expected: Answer) {
+    let solver = puzzle
+        .solver(part)
+        .unwrap_or_else(|| panic!("{} has no part {}", puzzle.title(), part));
+    check_solver(puzzle.title(), solver.as_ref(), input, expected);
+}
+
+/// See [`assert_part!`].
+#[track_caller]
+pub fn check_solver(title: &str, solver: &dyn Solver, input: &str, expected: Answer) {
+    let (part, _) = solver.part_description();
+    let name = match solver.implementation() {
+        "default" => format!("{} part {}", title, part),
+        implementation => format!("{} part {} ({})", title, part, implementation),
+    };
+    match solver.solve_str(input) {
+        Ok(actual) if actual == expected => {}
+        Ok(actual) => panic!(
+            "{} gave {} instead of {} on\n{}",
+            name,
+            style::answer_text(&actual),
+            style::answer_text(&expected),
+            excerpt(input)
+        ),
+        Err(e) => panic!("{} failed: {} on\n{}", name, e, excerpt(input)),
+    }
+}
+
+/// The first few lines of `input`, and how many more there are.
+fn excerpt(input: &str) -> String {
+    let lines = input.lines().collect::<Vec<_>>();
+    let mut excerpt = lines
+        .iter()
+        .take(EXCERPT_LINES)
+        .map(|line| format!("  {}\n", line))
+        .collect::<String>();
+    if lines.len() > EXCERPT_LINES {
+        excerpt.push_str(&format!(
+            "  … {} more line(s)\n",
+            lines.len() - EXCERPT_LINES
+        ));
+    }
+    if lines.is_empty() {
+        excerpt.push_str("  (an empty input)\n");
+    }
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::RunContext, solver::MultiSolver};
+    use anyhow::{anyhow, Result};
+
+    /// Part 1 counts the lines, part 2 always fails.
+    struct Lines;
+
+    struct Count;
+
+    struct Fail;
+
+    impl Solver for Count {
+        fn part_description(&self) -> (u32, &str) {
+            (1, "Lines")
+        }
+
+        fn solve(&self, input: &str, _ctx: &RunContext) -> Result<Answer> {
+            Ok((input.lines().count() as i64).into())
+        }
+    }
+
+    impl Solver for Fail {
+        fn part_description(&self) -> (u32, &str) {
+            (2, "Nothing")
+        }
+
+        fn solve(&self, _input: &str, _ctx: &RunContext) -> Result<Answer> {
+            Err(anyhow!("No luck"))
+        }
+    }
+
+    impl MultiSolver for Lines {
+        type PartOne = Count;
+        type PartTwo = Fail;
+
+        fn get_puzzle_title(&self) -> &str {
+            "Day 0: Lines"
+        }
+
+        fn get_part_one(&self) -> Self::PartOne {
+            Count
+        }
+
+        fn get_part_two(&self) -> Self::PartTwo {
+            Fail
+        }
+    }
+
+    fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+        let payload = std::panic::catch_unwind(f).expect_err("expected a panic");
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_assert_part() {
+        assert_part!(Lines, 1, "a\nb\n", 2);
+        assert_part!(Lines, Count, "a\nb\nc\n", 3);
+
+        let input = (1..=7).map(|n| format!("line {}\n", n)).collect::<String>();
+        assert_eq!(
+            panic_message(|| assert_part!(Lines, 1, &input, 6)),
+            "Day 0: Lines part 1 gave 7 instead of 6 on
+  line 1
+  line 2
+  line 3
+  line 4
+  line 5
+  … 2 more line(s)
+"
+        );
+        assert_eq!(
+            panic_message(|| assert_part!(Lines, 2, "", 0)),
+            "Day 0: Lines part 2 failed: No luck on\n  (an empty input)\n"
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! A log of how long each part took, a JSON object per line, for tracking
+//! performance across CI runs with `--timings-log`, and the trends in it for
+//! `aoc timings summarize`.
+//!
+//! Answers are only logged as hashes, so a log can be committed publicly
+//! without giving them away.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    input, registry, report,
+    runner::{RunResult, Status},
+    solver::{format_duration, Answer},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingRecord {
+    /// When the part ran, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The event the day's from, so logs shared between years' solutions
+    /// keep them apart. Logs from before it was recorded are all 2023's.
+    #[serde(default = "registry_year")]
+    pub year: u16,
+    /// The commit the binary was built from, when known.
+    pub git_sha: Option<String>,
+    pub day: u8,
+    pub part: u32,
+    pub duration_ms: f64,
+    /// See [`input::hash`], which the answer's text is hashed with too.
+    pub answer_hash: String,
+    /// `None` for inputs that can't be read again, eg. stdin.
+    pub input_hash: Option<String>,
+}
+
+impl TimingRecord {
+    pub fn new(
+        day: u8,
+        part: u32,
+        duration: Duration,
+        answer: &Answer,
+        input_hash: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.as_secs())
+                .unwrap_or_default(),
+            git_sha: report::build_sha().map(str::to_string),
+            year: registry::YEAR,
+            day,
+            part,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            answer_hash: input::hash(&answer.to_string()),
+            input_hash,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.duration_ms.max(0.0) / 1000.0)
+    }
+}
+
+fn registry_year() -> u16 {
+    registry::YEAR
+}
+
+/// A record for each solved part among `results`, with the hash of its
+/// day's input from `input_hash`.
+pub fn records(
+    results: &[RunResult],
+    input_hash: impl Fn(u8) -> Option<String>,
+) -> Vec<TimingRecord> {
+    results
+        .iter()
+        .filter(|result| result.status == Status::Solved)
+        .filter_map(|result| {
+            let answer = result.answer.as_ref()?;
+            let timing = result.timing?;
+            Some(TimingRecord::new(
+                result.day,
+                result.part,
+                timing.total,
+                answer,
+                input_hash(result.day),
+            ))
+        })
+        .collect()
+}
+
+/// Adds `records` to the log at `path`, creating it if need be. They're
+/// written with a single append so runs sharing a log never interleave.
+pub fn append(path: &Path, records: &[TimingRecord]) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let lines = records
+        .iter()
+        .map(|record| Ok(format!("{}\n", serde_json::to_string(record)?)))
+        .collect::<Result<String>>()?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .with_context(|| format!("Can't write timings to {}", path.display()))
+}
+
+/// The records in the log at `path`, in the order they were written.
+pub fn load(path: &Path) -> Result<Vec<TimingRecord>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Can't read timings from {}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("{}:{} isn't a timing record", path.display(), index + 1))
+        })
+        .collect()
+}
+
+/// How one part's time has gone over the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trend {
+    pub day: u8,
+    pub part: u32,
+    pub runs: usize,
+    pub last: Duration,
+    pub best: Duration,
+    /// The time before the last, `None` after a single run.
+    pub previous: Option<Duration>,
+}
+
+/// Each part's trend over the records of `year`'s event, in day and part
+/// order.
+pub fn trends(records: &[TimingRecord], year: u16) -> Vec<Trend> {
+    let mut parts = BTreeMap::<(u8, u32), Vec<Duration>>::new();
+    for record in records.iter().filter(|record| record.year == year) {
+        parts
+            .entry((record.day, record.part))
+            .or_default()
+            .push(record.duration());
+    }
+    parts
+        .into_iter()
+        .filter_map(|((day, part), times)| {
+            Some(Trend {
+                day,
+                part,
+                runs: times.len(),
+                last: *times.last()?,
+                best: *times.iter().min()?,
+                previous: times.len().checked_sub(2).map(|index| times[index]),
+            })
+        })
+        .collect()
+}
+
+/// The columns of [`table`].
+pub const COLUMNS: [&str; 6] = ["Day", "Part", "Runs", "Last", "Best", "Change"];
+
+/// The trends as a markdown table, the change being the last time's from the
+/// one before.
+pub fn table(trends: &[Trend]) -> String {
+    let rows = trends
+        .iter()
+        .map(|trend| {
+            [
+                trend.day.to_string(),
+                trend.part.to_string(),
+                trend.runs.to_string(),
+                format_duration(trend.last),
+                format_duration(trend.best),
+                trend
+                    .previous
+                    .map(|previous| change(previous, trend.last))
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    report::markdown(COLUMNS, [true; 6], &rows)
+}
+
+fn change(from: Duration, to: Duration) -> String {
+    if to >= from {
+        format!("+{}", format_duration(to - from))
+    } else {
+        format!("-{}", format_duration(from - to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(day: u8, part: u32, millis: f64) -> TimingRecord {
+        TimingRecord {
+            duration_ms: millis,
+            ..TimingRecord::new(day, part, Duration::ZERO, &Answer::from(6440), None)
+        }
+    }
+
+    #[test]
+    fn test_trends() {
+        let records = [
+            record(7, 1, 4.0),
+            record(1, 1, 2.0),
+            record(7, 1, 3.0),
+            // another year's day 7 is left out
+            TimingRecord {
+                year: 2022,
+                ..record(7, 1, 1.0)
+            },
+            record(7, 1, 3.5),
+        ];
+        assert_eq!(
+            trends(&records, 2023),
+            [
+                Trend {
+                    day: 1,
+                    part: 1,
+                    runs: 1,
+                    last: Duration::from_millis(2),
+                    best: Duration::from_millis(2),
+                    previous: None,
+                },
+                Trend {
+                    day: 7,
+                    part: 1,
+                    runs: 3,
+                    last: Duration::from_micros(3500),
+                    best: Duration::from_millis(3),
+                    previous: Some(Duration::from_millis(3)),
+                },
+            ]
+        );
+        assert_eq!(
+            change(Duration::from_millis(3), Duration::from_millis(2)),
+            "-1.0ms"
+        );
+        assert_eq!(trends(&records, 2022)[0].best, Duration::from_millis(1));
+        assert!(trends(&records, 2024).is_empty());
+    }
+
+    #[test]
+    fn test_append() -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("aoc-2023-timings-{}.jsonl", std::process::id()));
+        append(&path, &[record(1, 1, 2.0), record(1, 2, 3.0)])?;
+        append(&path, &[record(1, 1, 1.0)])?;
+        let text = fs::read_to_string(&path);
+        let loaded = load(&path);
+        fs::remove_file(&path)?;
+
+        assert_eq!(text?.lines().count(), 3);
+        let loaded = loaded?;
+        assert_eq!((loaded[2].day, loaded[2].duration_ms), (1, 1.0));
+        // the answer is only there as a hash
+        assert_eq!(loaded[0].answer_hash, input::hash("6440"));
+        assert!(!serde_json::to_string(&loaded[0])?.contains("6440"));
+        assert_eq!(loaded[0].year, 2023);
+
+        // records from before the year was logged are 2023's
+        let old = r#"{"timestamp":1,"git_sha":null,"day":1,"part":1,"duration_ms":2.0,"answer_hash":"x","input_hash":null}"#;
+        assert_eq!(serde_json::from_str::<TimingRecord>(old)?.year, 2023);
+        Ok(())
+    }
+}
@@ -1,54 +1,77 @@
 use anyhow::{anyhow, Result};
-use std::{fs::read_to_string, path::PathBuf};
-
-use crate::solver::{MultiSolver, Solver};
-
-///     --- Day 1: Trebuchet?! ---
-///
-/// Something is wrong with global snow production, and you've been selected to take a look. The Elves have even given you a map; on it, they've used stars to mark the top fifty locations that are likely to be having problems.
-///
-/// You've been doing this long enough to know that to restore snow operations, you need to check all fifty stars by December 25th.
-///
-/// Collect stars by solving puzzles. Two puzzles will be made available on each day in the Advent calendar; the second puzzle is unlocked when you complete the first. Each puzzle grants one star. Good luck!
-///
-/// You try to ask why they can't just use a weather machine ("not powerful enough") and where they're even sending you ("the sky") and why your map looks mostly blank ("you sure ask a lot of questions") and hang on did you just say the sky ("of course, where do you think snow comes from") when you realize that the Elves are already loading you into a trebuchet ("please hold still, we need to strap you in").
-///
-/// As they're making the final adjustments, they discover that their calibration document (your puzzle input) has been amended by a very young Elf who was apparently just excited to show off her art skills. Consequently, the Elves are having trouble reading the values on the document.
-///
-/// The newly-improved calibration document consists of lines of text; each line originally contained a specific calibration value that the Elves now need to recover. On each line, the calibration value can be found by combining the first digit and the last digit (in that order) to form a single two-digit number.
-///
-/// For example:
-/// ```
-/// 1abc2  
-/// pqr3stu8vwx  
-/// a1b2c3d4e5f  
-/// treb7uchet  
-/// ```
-/// In this example, the calibration values of these four lines are 12, 38, 15, and 77. Adding these together produces 142.
-///
-/// Consider your entire calibration document. What is the sum of all of the calibration values?
-///
-///     --- Part Two ---
-///
-/// Your calculation isn't quite right. It looks like some of the digits are actually spelled out with letters: one, two, three, four, five, six, seven, eight, and nine also count as valid "digits".
-///
-/// Equipped with this new information, you now need to find the real first and last digit on each line. For example:
-/// ```
-/// two1nine
-/// eightwothree
-/// abcone2threexyz
-/// xtwone3four
-/// 4nineeightseven2
-/// zoneight234
-/// 7pqrstsixteen
-/// ```
-/// In this example, the calibration values are 29, 83, 13, 24, 42, 14, and 76. Adding these together produces 281.
-///
-/// What is the sum of all of the calibration values?
-pub struct Trebuchet;
+
+use crate::{
+    context::RunContext,
+    metadata::{Difficulty, Metadata, RuntimeClass},
+    solver::{Answer, MultiSolver, Solver},
+    statement::puzzle_struct,
+};
+
+puzzle_struct! {
+    ///     --- Day 1: Trebuchet?! ---
+    ///
+    /// Something is wrong with global snow production, and you've been selected to take a look. The Elves have even given you a map; on it, they've used stars to mark the top fifty locations that are likely to be having problems.
+    ///
+    /// You've been doing this long enough to know that to restore snow operations, you need to check all fifty stars by December 25th.
+    ///
+    /// Collect stars by solving puzzles. Two puzzles will be made available on each day in the Advent calendar; the second puzzle is unlocked when you complete the first. Each puzzle grants one star. Good luck!
+    ///
+    /// You try to ask why they can't just use a weather machine ("not powerful enough") and where they're even sending you ("the sky") and why your map looks mostly blank ("you sure ask a lot of questions") and hang on did you just say the sky ("of course, where do you think snow comes from") when you realize that the Elves are already loading you into a trebuchet ("please hold still, we need to strap you in").
+    ///
+    /// As they're making the final adjustments, they discover that their calibration document (your puzzle input) has been amended by a very young Elf who was apparently just excited to show off her art skills. Consequently, the Elves are having trouble reading the values on the document.
+    ///
+    /// The newly-improved calibration document consists of lines of text; each line originally contained a specific calibration value that the Elves now need to recover. On each line, the calibration value can be found by combining the first digit and the last digit (in that order) to form a single two-digit number.
+    ///
+    /// For example:
+    /// ```
+    /// 1abc2
+    /// pqr3stu8vwx
+    /// a1b2c3d4e5f
+    /// treb7uchet
+    /// ```
+    /// In this example, the calibration values of these four lines are 12, 38, 15, and 77. Adding these together produces 142.
+    ///
+    /// Consider your entire calibration document. What is the sum of all of the calibration values?
+    ///
+    ///     --- Part Two ---
+    ///
+    /// Your calculation isn't quite right. It looks like some of the digits are actually spelled out with letters: one, two, three, four, five, six, seven, eight, and nine also count as valid "digits".
+    ///
+    /// Equipped with this new information, you now need to find the real first and last digit on each line. For example:
+    /// ```
+    /// two1nine
+    /// eightwothree
+    /// abcone2threexyz
+    /// xtwone3four
+    /// 4nineeightseven2
+    /// zoneight234
+    /// 7pqrstsixteen
+    /// ```
+    /// In this example, the calibration values are 29, 83, 13, 24, 42, 14, and 76. Adding these together produces 281.
+    ///
+    /// What is the sum of all of the calibration values?
+    pub struct Trebuchet;
+}
 pub struct PartOne;
 pub struct PartTwo;
 
+const EXAMPLE_PART_ONE: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+const EXAMPLE_PART_TWO: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
 impl MultiSolver for Trebuchet {
     type PartOne = PartOne;
     type PartTwo = PartTwo;
@@ -57,6 +80,18 @@ impl MultiSolver for Trebuchet {
         "Day 1: Trebuchet?!"
     }
 
+    fn input_hint(&self) -> Option<&str> {
+        Some("lines of letters and digits like '1abc2' or 'two1nine'")
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        Some(Self::STATEMENT)
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(1)
+    }
+
     fn get_part_one(&self) -> Self::PartOne {
         PartOne
     }
@@ -64,6 +99,14 @@ impl MultiSolver for Trebuchet {
     fn get_part_two(&self) -> Self::PartTwo {
         PartTwo
     }
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            tags: &["parsing", "strings"],
+            difficulty: Some(Difficulty::Easy),
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 impl Solver for PartOne {
@@ -71,22 +114,23 @@ impl Solver for PartOne {
         (1, "Sum of calibration values")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
+    fn solve(&self, input: &str, _ctx: &RunContext) -> Result<Answer> {
         let mut result = 0;
-        for line in read_to_string(filepath)?.lines() {
-            let left = line.find(char::is_numeric).ok_or(anyhow!(
-                "Couldn't find a digit in the input string '{}'",
-                line
-            ))?;
-            let right = line.rfind(char::is_numeric).ok_or(anyhow!(
+        for line in input.lines() {
+            // only ASCII digits count, other numerals aren't one byte long
+            let mut digits = line.chars().filter_map(|c| c.to_digit(10));
+            let left = digits.next().ok_or(anyhow!(
                 "Couldn't find a digit in the input string '{}'",
                 line
             ))?;
-            let concatenated =
-                (10 * line[left..=left].parse::<i32>()?) + line[right..=right].parse::<i32>()?;
-            result += concatenated;
+            let right = digits.next_back().unwrap_or(left);
+            result += (10 * left + right) as i32;
         }
-        Ok(result)
+        Ok(result.into())
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE_PART_ONE, 142.into()))
     }
 }
 
@@ -95,109 +139,109 @@ impl Solver for PartTwo {
         (2, "Sum of calibration values")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
+    fn solve(&self, input: &str, _ctx: &RunContext) -> Result<Answer> {
         fn extract_digit(s: &str) -> Option<i32> {
-            let len = s.len();
-            for (i, c) in s.chars().enumerate() {
+            for (i, c) in s.char_indices() {
+                let rest = &s[i..];
                 match c {
                     'o' => {
-                        if i + 3 <= len && &s[i..i + 3] == "one" {
+                        if rest.starts_with("one") {
                             return Some(1);
                         }
                     }
                     't' => {
-                        if i + 3 <= len && &s[i..i + 3] == "two" {
+                        if rest.starts_with("two") {
                             return Some(2);
-                        } else if i + 5 <= len && &s[i..i + 5] == "three" {
+                        } else if rest.starts_with("three") {
                             return Some(3);
                         }
                     }
                     'f' => {
-                        if i + 4 <= len && &s[i..i + 4] == "four" {
+                        if rest.starts_with("four") {
                             return Some(4);
-                        } else if i + 4 <= len && &s[i..i + 4] == "five" {
+                        } else if rest.starts_with("five") {
                             return Some(5);
                         }
                     }
                     's' => {
-                        if i + 3 <= len && &s[i..i + 3] == "six" {
+                        if rest.starts_with("six") {
                             return Some(6);
-                        } else if i + 5 <= len && &s[i..i + 5] == "seven" {
+                        } else if rest.starts_with("seven") {
                             return Some(7);
                         }
                     }
                     'e' => {
-                        if i + 5 <= len && &s[i..i + 5] == "eight" {
+                        if rest.starts_with("eight") {
                             return Some(8);
                         }
                     }
                     'n' => {
-                        if i + 4 <= len && &s[i..i + 4] == "nine" {
+                        if rest.starts_with("nine") {
                             return Some(9);
                         }
                     }
                     _ => {
-                        if c.is_numeric() {
-                            return Some(c.to_digit(10).expect("This character can't be converted to a digit despite testing as numeric...") as i32);
+                        if let Some(digit) = c.to_digit(10) {
+                            return Some(digit as i32);
                         }
                     }
                 }
             }
-            return None;
+            None
         }
 
         fn rextract_digit(s: &str) -> Option<i32> {
-            let len = s.len();
-            for (i, c) in s.chars().rev().enumerate() {
+            for (i, c) in s.char_indices().rev() {
+                let rest = &s[..i + c.len_utf8()];
                 match c {
                     'e' => {
-                        if len - i >= 3 && &s[len - i - 3..len - i] == "one" {
+                        if rest.ends_with("one") {
                             return Some(1);
-                        } else if len - i >= 5 && &s[len - i - 5..len - i] == "three" {
+                        } else if rest.ends_with("three") {
                             return Some(3);
-                        } else if len - i >= 4 && &s[len - i - 4..len - i] == "five" {
+                        } else if rest.ends_with("five") {
                             return Some(5);
-                        } else if len - i >= 4 && &s[len - i - 4..len - i] == "nine" {
+                        } else if rest.ends_with("nine") {
                             return Some(9);
                         }
                     }
                     'o' => {
-                        if len - i >= 3 && &s[len - i - 3..len - i] == "two" {
+                        if rest.ends_with("two") {
                             return Some(2);
                         }
                     }
                     'r' => {
-                        if len - i >= 4 && &s[len - i - 4..len - i] == "four" {
+                        if rest.ends_with("four") {
                             return Some(4);
                         }
                     }
                     'x' => {
-                        if len - i >= 3 && &s[len - i - 3..len - i] == "six" {
+                        if rest.ends_with("six") {
                             return Some(6);
                         }
                     }
                     'n' => {
-                        if len - i >= 5 && &s[len - i - 5..len - i] == "seven" {
+                        if rest.ends_with("seven") {
                             return Some(7);
                         }
                     }
                     't' => {
-                        if len - i >= 5 && &s[len - i - 5..len - i] == "eight" {
+                        if rest.ends_with("eight") {
                             return Some(8);
                         }
                     }
                     _ => {
-                        if c.is_numeric() {
-                            return Some(c.to_digit(10).expect("This character can't be converted to a digit despite testing as numeric...") as i32);
+                        if let Some(digit) = c.to_digit(10) {
+                            return Some(digit as i32);
                         }
                     }
                 }
             }
-            return None;
+            None
         }
 
         let mut result = 0;
-        for line in read_to_string(filepath)?.lines() {
+        for line in input.lines() {
             let left = extract_digit(line).ok_or(anyhow!(
                 "Couldn't find a number (digit or spelled) in the input string '{}'",
                 line
@@ -208,29 +252,81 @@ impl Solver for PartTwo {
             ))?;
             result += (10 * left) + right;
         }
-        Ok(result)
+        Ok(result.into())
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE_PART_TWO, 281.into()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::assert_part;
+
+    #[test]
+    fn test_part_one_example() {
+        assert_part!(Trebuchet, 1, EXAMPLE_PART_ONE, 142);
+    }
+
+    #[test]
+    fn test_part_two_example() {
+        assert_part!(Trebuchet, 2, EXAMPLE_PART_TWO, 281);
+    }
+
+    // inputs `cargo fuzz run day01` found panicking
+    #[test]
+    fn test_fuzz_regressions() {
+        // non-ASCII numerals aren't digits, nor a byte long
+        assert!(PartOne.solve_str("۰").is_err());
+        assert_part!(Trebuchet, 1, "a۰1b", 11);
+    }
 
+    // personal inputs aren't in every clone, see crate::input::real
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_one() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/01/input");
+        let filepath = crate::input::real(1);
         let solver = PartOne;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 54605);
+        assert_eq!(solution, 54605.into());
         Ok(())
     }
 
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_two() -> anyhow::Result<()> {
-        let filepath = std::path::PathBuf::from("data/01/input");
+        let filepath = crate::input::real(1);
         let solver = PartTwo;
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 55429);
+        assert_eq!(solution, 55429.into());
         Ok(())
     }
+
+    #[test]
+    fn test_parse_modes() {
+        use crate::{
+            input::ParseMode,
+            runner::{run_part, PartOptions, Status},
+        };
+        // a hand-made copy of the example, with trailing spaces and a blank line
+        let messy = EXAMPLE_PART_ONE.replacen('\n', "  \n\n", 1);
+        let column = EXAMPLE_PART_ONE.lines().next().unwrap().len() + 1;
+        let strict = run_part(1, &Trebuchet, 1, &messy, &PartOptions::default());
+        let Status::Failed(error) = strict.status else {
+            panic!("expected strict parsing to fail, got {:?}", strict.status);
+        };
+        assert!(
+            error.starts_with(&format!("Line 1, column {}: trailing whitespace", column)),
+            "{}",
+            error
+        );
+        let options = PartOptions {
+            parse_mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+        let lenient = run_part(1, &Trebuchet, 1, &messy, &options);
+        assert_eq!(lenient.answer, Some(142.into()));
+    }
 }
@@ -0,0 +1,295 @@
+//! Large made-up inputs for stress testing, benchmarking and fuzzing, for
+//! `aoc gen`. Each is valid for its day, so it parses in strict mode and
+//! solves. They're drawn from a seeded RNG, so the same seed and sizes give
+//! the same input.
+
+use anyhow::{anyhow, Result};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::fmt::Write;
+
+/// The days with a generator, see [`for_day`].
+pub const DAYS: [u8; 6] = [1, 2, 3, 4, 5, 7];
+
+/// How dense the sparse things are in [`for_day`] when not given: spelled
+/// digits in calibration documents and symbols in schematics.
+pub const DEFAULT_DENSITY: f64 = 0.3;
+
+/// The width of the schematics [`for_day`] generates, as in the real inputs.
+pub const SCHEMATIC_WIDTH: usize = 140;
+
+/// The number of maps in an almanac, from seed-to-soil to
+/// humidity-to-location.
+pub const ALMANAC_MAPS: usize = 7;
+
+const SPELLED: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// `#` is left out, a row starting with one would be taken for a comment.
+const SYMBOLS: [char; 9] = ['*', '+', '$', '/', '@', '=', '%', '-', '&'];
+
+const CARDS: [char; 13] = [
+    'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
+];
+
+pub fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// An input for `day` of about `lines` lines, each day's sparse things at
+/// `density`, see [`DEFAULT_DENSITY`].
+pub fn for_day(day: u8, lines: usize, density: f64, seed: u64) -> Result<String> {
+    let rng = &mut rng(seed);
+    match day {
+        1 => Ok(calibration(rng, lines, density)),
+        2 => Ok(games(rng, lines)),
+        3 => Ok(schematic(rng, lines, SCHEMATIC_WIDTH, density)),
+        4 => Ok(scratchcards(rng, lines)),
+        5 => Ok(almanac(
+            rng,
+            ALMANAC_MAPS,
+            lines.saturating_sub(ALMANAC_MAPS * 2 + 2) / ALMANAC_MAPS,
+        )),
+        7 => Ok(hands(rng, lines)),
+        _ => Err(anyhow!(
+            "There's no generator for day {}, only for days {}",
+            day,
+            DAYS.map(|day| day.to_string()).join(", ")
+        )),
+    }
+}
+
+/// A calibration document of `lines` lines of letters and digits, with
+/// `spelled` of the digits spelled out. Each line has at least one digit
+/// that isn't, which part 1 needs.
+pub fn calibration(rng: &mut impl Rng, lines: usize, spelled: f64) -> String {
+    let mut document = String::new();
+    for _ in 0..lines {
+        let digits = rng.gen_range(1..=6);
+        let plain = rng.gen_range(0..digits);
+        let mut line = String::new();
+        for digit in 0..digits {
+            for _ in 0..rng.gen_range(0..4) {
+                line.push(rng.gen_range('a'..='z'));
+            }
+            let value = rng.gen_range(1..=9);
+            if digit != plain && rng.gen_bool(spelled) {
+                line.push_str(SPELLED[value - 1]);
+            } else {
+                line.push(char::from(b'0' + value as u8));
+            }
+        }
+        document.push_str(&line);
+        document.push('\n');
+    }
+    document
+}
+
+/// `count` games of up to 6 rounds of cubes.
+pub fn games(rng: &mut impl Rng, count: usize) -> String {
+    let mut games = String::new();
+    for id in 1..=count {
+        let rounds = (0..rng.gen_range(1..=6))
+            .map(|_| {
+                let mut colours = ["red", "green", "blue"];
+                colours.shuffle(rng);
+                colours[..rng.gen_range(1..=3)]
+                    .iter()
+                    .map(|colour| format!("{} {}", rng.gen_range(1..=20), colour))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(games, "Game {}: {}", id, rounds.join("; "));
+    }
+    games
+}
+
+/// A `rows` by `width` schematic of numbers of up to 3 digits and, in about
+/// `symbols` of the gaps between them, a symbol.
+pub fn schematic(rng: &mut impl Rng, rows: usize, width: usize, symbols: f64) -> String {
+    let mut schematic = String::new();
+    for _ in 0..rows {
+        let mut row = String::new();
+        while row.len() < width {
+            let left = width - row.len();
+            if rng.gen_bool(0.2) {
+                let digits = rng.gen_range(1..=3).min(left);
+                let number =
+                    rng.gen_range(10_u32.pow(digits as u32 - 1)..10_u32.pow(digits as u32));
+                row.push_str(&number.to_string());
+                if row.len() < width {
+                    row.push('.');
+                }
+            } else if rng.gen_bool(symbols) {
+                row.push(*SYMBOLS.choose(rng).expect("there are symbols"));
+            } else {
+                row.push('.');
+            }
+        }
+        schematic.push_str(&row);
+        schematic.push('\n');
+    }
+    schematic
+}
+
+/// `count` scratchcards of 10 winning numbers and 25 of yours. Under one
+/// match a card on average, so the copies won stay few, and none win copies
+/// of cards past the last.
+pub fn scratchcards(rng: &mut impl Rng, count: usize) -> String {
+    let mut cards = String::new();
+    let numbers = (1..100).collect::<Vec<u32>>();
+    for id in 1..=count {
+        let mut chosen = numbers
+            .choose_multiple(rng, 35)
+            .copied()
+            .collect::<Vec<_>>();
+        let (winning, others) = chosen.split_at_mut(10);
+        let matches = if rng.gen_bool(0.6) {
+            0
+        } else {
+            rng.gen_range(1..=3)
+        }
+        .min(count - id);
+        let mut yours = winning[..matches]
+            .iter()
+            .chain(&others[..25 - matches])
+            .copied()
+            .collect::<Vec<_>>();
+        yours.shuffle(rng);
+        let list = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|number| format!("{:>2}", number))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let _ = writeln!(
+            cards,
+            "Card {:>3}: {} | {}",
+            id,
+            list(winning),
+            list(&yours)
+        );
+    }
+    cards
+}
+
+/// An almanac of 10 seed ranges and `maps` maps, each of `ranges` ranges
+/// that don't overlap, all within `u32`.
+pub fn almanac(rng: &mut impl Rng, maps: usize, ranges: usize) -> String {
+    const NAMES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let name = |index: usize| match NAMES.get(index) {
+        Some(name) => name.to_string(),
+        None => format!("stage{}", index),
+    };
+    let limit = u64::from(u32::MAX);
+    let seeds = (0..10)
+        .map(|_| {
+            let start = rng.gen_range(0..limit / 2);
+            format!("{} {}", start, rng.gen_range(1..limit / 20))
+        })
+        .collect::<Vec<_>>();
+    let mut almanac = format!("seeds: {}\n", seeds.join(" "));
+    for map in 0..maps {
+        let _ = write!(almanac, "\n{}-to-{} map:\n", name(map), name(map + 1));
+        // the ranges lie between consecutive cuts, so they can't overlap
+        let mut cuts = (0..ranges * 2)
+            .map(|_| rng.gen_range(0..limit))
+            .collect::<Vec<_>>();
+        cuts.sort_unstable();
+        cuts.dedup();
+        for span in cuts.chunks_exact(2) {
+            let (source, length) = (span[0], span[1] - span[0]);
+            let destination = rng.gen_range(0..=limit - length);
+            let _ = writeln!(almanac, "{} {} {}", destination, source, length);
+        }
+    }
+    almanac
+}
+
+/// `count` hands of 5 cards, each with a bid of up to 1000.
+pub fn hands(rng: &mut impl Rng, count: usize) -> String {
+    let mut hands = String::new();
+    for _ in 0..count {
+        let hand = (0..5)
+            .map(|_| *CARDS.choose(rng).expect("there are cards"))
+            .collect::<String>();
+        let _ = writeln!(hands, "{} {}", hand, rng.gen_range(1..=1000));
+    }
+    hands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registry;
+
+    #[test]
+    fn test_inputs_solve() -> Result<()> {
+        let registry = Registry::builtin();
+        for day in DAYS {
+            let Some(puzzle) = registry.get(day) else {
+                continue;
+            };
+            for seed in 0..3 {
+                let input = for_day(day, 200, DEFAULT_DENSITY, seed)?;
+                for part in 1..=2 {
+                    let solver = puzzle.solver(part).expect("days have two parts");
+                    solver
+                        .solve_str(&input)
+                        .map_err(|e| anyhow!("Day {} part {} seed {}: {}", day, part, seed, e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_seed_same_input() -> Result<()> {
+        for day in DAYS {
+            assert_eq!(for_day(day, 50, 0.5, 7)?, for_day(day, 50, 0.5, 7)?);
+            assert_ne!(for_day(day, 50, 0.5, 7)?, for_day(day, 50, 0.5, 8)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sizes() -> Result<()> {
+        assert_eq!(calibration(&mut rng(1), 25, 0.3).lines().count(), 25);
+        let schematic = schematic(&mut rng(1), 10, 140, 0.3);
+        assert!(schematic.lines().all(|row| row.len() == 140));
+        assert_eq!(schematic.lines().count(), 10);
+        let almanac = almanac(&mut rng(1), 3, 4);
+        assert_eq!(almanac.matches("map:").count(), 3);
+        assert_eq!(
+            almanac
+                .lines()
+                .filter(|line| line.split(' ').count() == 3)
+                .count(),
+            12
+        );
+        assert!(for_day(6, 10, 0.3, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_spelled_density() {
+        let no_spelled = calibration(&mut rng(1), 100, 0.0);
+        assert!(SPELLED.iter().all(|word| !no_spelled.contains(word)));
+        let spelled = calibration(&mut rng(1), 100, 1.0);
+        // all but one digit a line are spelled
+        assert!(spelled
+            .lines()
+            .all(|line| line.chars().filter(char::is_ascii_digit).count() == 1));
+    }
+}
@@ -0,0 +1,370 @@
+use anyhow::{anyhow, Result};
+use core::fmt;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+};
+
+/// A node's index in a [`Graph`], handed out in insertion order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A directed graph whose nodes are labelled with `N`, eg. a module or brick
+/// name. Each distinct label is interned once so the algorithms can work on
+/// plain indices.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    edges: Vec<Vec<NodeId>>,
+}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            ids: HashMap::new(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<N: Eq + Hash + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id for `node`, adding it if it isn't in the graph yet.
+    pub fn node(&mut self, node: N) -> NodeId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        self.edges.push(vec![]);
+        id
+    }
+
+    pub fn id(&self, node: &N) -> Option<NodeId> {
+        self.ids.get(node).copied()
+    }
+
+    /// Adds an edge `from -> to`, adding either node if needed.
+    pub fn add_edge(&mut self, from: N, to: N) -> (NodeId, NodeId) {
+        let (from, to) = (self.node(from), self.node(to));
+        self.add_edge_ids(from, to);
+        (from, to)
+    }
+}
+
+impl<N> Graph<N> {
+    pub fn add_edge_ids(&mut self, from: NodeId, to: NodeId) {
+        self.edges[from.0].push(to);
+    }
+
+    pub fn label(&self, id: NodeId) -> &N {
+        &self.nodes[id.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.iter().map(Vec::len).sum()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    /// The nodes `id` has an edge to, in the order the edges were added.
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[id.0].iter().copied()
+    }
+
+    /// How many edges lead into each node, indexed by [`NodeId::index`].
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.len()];
+        for to in self.edges.iter().flatten() {
+            degrees[to.0] += 1;
+        }
+        degrees
+    }
+
+    /// Every node ordered so that each edge goes from an earlier node to a
+    /// later one, ties broken by insertion order. Fails if the graph has a
+    /// cycle, naming one of the nodes on it.
+    pub fn toposort(&self) -> Result<Vec<NodeId>>
+    where
+        N: fmt::Debug,
+    {
+        let mut in_degrees = self.in_degrees();
+        let mut ready = self
+            .ids()
+            .filter(|id| in_degrees[id.0] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(id)) = ready.pop() {
+            order.push(id);
+            for to in self.neighbours(id) {
+                in_degrees[to.0] -= 1;
+                if in_degrees[to.0] == 0 {
+                    ready.push(Reverse(to));
+                }
+            }
+        }
+        if order.len() < self.len() {
+            // anything left over is on, or downstream of, a cycle
+            let stuck = self.ids().find(|id| in_degrees[id.0] > 0);
+            let on_cycle = stuck.map(|id| self.find_cycle_from(id, &in_degrees));
+            return Err(anyhow!(
+                "Graph has a cycle through {:?}",
+                on_cycle.map(|id| self.label(id))
+            ));
+        }
+        Ok(order)
+    }
+
+    /// Walks edges backwards from `start` through the nodes toposort couldn't
+    /// place until one repeats, which must be on a cycle. Every such node has
+    /// an unplaced predecessor, otherwise it would have been placed.
+    fn find_cycle_from(&self, start: NodeId, in_degrees: &[usize]) -> NodeId {
+        let mut seen = vec![false; self.len()];
+        let mut id = start;
+        while !seen[id.0] {
+            seen[id.0] = true;
+            let Some(next) = self.upstream_stuck(id, in_degrees) else {
+                break;
+            };
+            id = next;
+        }
+        id
+    }
+
+    fn upstream_stuck(&self, id: NodeId, in_degrees: &[usize]) -> Option<NodeId> {
+        self.ids()
+            .find(|from| in_degrees[from.0] > 0 && self.edges[from.0].contains(&id))
+    }
+
+    /// The weakly connected components, ie. ignoring edge direction, each
+    /// sorted by id and listed in order of their first node.
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut undirected = vec![vec![]; self.len()];
+        for from in self.ids() {
+            for to in self.neighbours(from) {
+                undirected[from.0].push(to);
+                undirected[to.0].push(from);
+            }
+        }
+        let mut component_of = vec![None; self.len()];
+        let mut components = vec![];
+        for start in self.ids() {
+            if component_of[start.0].is_some() {
+                continue;
+            }
+            let index = components.len();
+            component_of[start.0] = Some(index);
+            let mut component = vec![];
+            let mut stack = vec![start];
+            while let Some(id) = stack.pop() {
+                component.push(id);
+                for next in &undirected[id.0] {
+                    if component_of[next.0].is_none() {
+                        component_of[next.0] = Some(index);
+                        stack.push(*next);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components
+    }
+
+    /// The strongly connected components, each sorted by id, with every
+    /// component listed after any component it has an edge into (reverse
+    /// topological order), as Tarjan's algorithm finds them.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        const UNVISITED: usize = usize::MAX;
+        let mut index = vec![UNVISITED; self.len()];
+        let mut low_link = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+
+        for root in self.ids() {
+            if index[root.0] != UNVISITED {
+                continue;
+            }
+            // (node, how many of its edges have been followed) in place of
+            // recursion, which real inputs can be deep enough to overflow
+            let mut calls = vec![(root, 0)];
+            index[root.0] = next_index;
+            low_link[root.0] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root.0] = true;
+
+            while let Some((id, edge)) = calls.last_mut() {
+                let id = *id;
+                if let Some(to) = self.edges[id.0].get(*edge).copied() {
+                    *edge += 1;
+                    if index[to.0] == UNVISITED {
+                        index[to.0] = next_index;
+                        low_link[to.0] = next_index;
+                        next_index += 1;
+                        stack.push(to);
+                        on_stack[to.0] = true;
+                        calls.push((to, 0));
+                    } else if on_stack[to.0] {
+                        low_link[id.0] = low_link[id.0].min(index[to.0]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some((parent, _)) = calls.last() {
+                    low_link[parent.0] = low_link[parent.0].min(low_link[id.0]);
+                }
+                if low_link[id.0] == index[id.0] {
+                    let mut component = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member.0] = false;
+                        component.push(member);
+                        if member == id {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&'static str, &'static str)]) -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        for (from, to) in edges {
+            graph.add_edge(*from, *to);
+        }
+        graph
+    }
+
+    fn labels(graph: &Graph<&'static str>, ids: &[NodeId]) -> Vec<&'static str> {
+        ids.iter().map(|id| *graph.label(*id)).collect()
+    }
+
+    #[test]
+    fn test_interning() {
+        let mut graph = graph(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        let a = graph.id(&"a").unwrap();
+        assert_eq!(graph.node("a"), a);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.id(&"z"), None);
+        assert_eq!(
+            labels(&graph, &graph.neighbours(a).collect::<Vec<_>>()),
+            vec!["b", "c"]
+        );
+        assert_eq!(graph.in_degrees(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_toposort() -> Result<()> {
+        // shirt before tie before jacket, belt before jacket, socks before shoes
+        let graph = graph(&[
+            ("shirt", "tie"),
+            ("tie", "jacket"),
+            ("shirt", "belt"),
+            ("belt", "jacket"),
+            ("socks", "shoes"),
+        ]);
+        let order = labels(&graph, &graph.toposort()?);
+        assert_eq!(
+            order,
+            vec!["shirt", "tie", "belt", "jacket", "socks", "shoes"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_toposort_cycle() {
+        let cyclic = graph(&[("start", "a"), ("a", "b"), ("b", "c"), ("c", "a")]);
+        let error = cyclic.toposort().unwrap_err().to_string();
+        assert!(error.starts_with("Graph has a cycle through"), "{}", error);
+        assert!(["\"a\"", "\"b\"", "\"c\""]
+            .iter()
+            .any(|node| error.ends_with(&format!("Some({})", node))));
+
+        assert!(graph(&[("loop", "loop")]).toposort().is_err());
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut graph = graph(&[("a", "b"), ("c", "b"), ("d", "e")]);
+        graph.node("f");
+        let components = graph
+            .connected_components()
+            .iter()
+            .map(|component| labels(&graph, component))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            components,
+            vec![vec!["a", "b", "c"], vec!["d", "e"], vec!["f"]]
+        );
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = graph(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "d"),
+            ("e", "f"),
+        ]);
+        let components = graph
+            .strongly_connected_components()
+            .iter()
+            .map(|component| labels(&graph, component))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            components,
+            vec![vec!["f"], vec!["d", "e"], vec!["a", "b", "c"]]
+        );
+    }
+
+    #[test]
+    fn test_empty() -> Result<()> {
+        let graph: Graph<u32> = Graph::new();
+        assert!(graph.is_empty());
+        assert!(graph.toposort()?.is_empty());
+        assert!(graph.connected_components().is_empty());
+        assert!(graph.strongly_connected_components().is_empty());
+        Ok(())
+    }
+}
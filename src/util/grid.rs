@@ -0,0 +1,286 @@
+use anyhow::{anyhow, Result};
+use core::fmt;
+use std::str::FromStr;
+
+use super::point::Point2;
+
+/// Offsets of the 4 orthogonal neighbours in reading order.
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of all 8 neighbours, including diagonals, in reading order.
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular 2-D grid stored row by row, addressed as `(row, col)` with
+/// `(0, 0)` in the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Builds a grid from text, one row per line, converting each character
+    /// with `parse_cell`. Every line must be the same length.
+    pub fn parse(input: &str, mut parse_cell: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let mut cells = vec![];
+        let mut rows = 0;
+        let mut cols = None;
+        for (row, line) in input.lines().enumerate() {
+            let before = cells.len();
+            for c in line.chars() {
+                cells.push(parse_cell(c)?);
+            }
+            let width = cells.len() - before;
+            match cols {
+                None => cols = Some(width),
+                Some(cols) if cols != width => {
+                    return Err(anyhow!(
+                        "Grid row {} has {} columns, expected {}",
+                        row,
+                        width,
+                        cols
+                    ))
+                }
+                _ => {}
+            }
+            rows += 1;
+        }
+        Ok(Self {
+            cells,
+            rows,
+            cols: cols.unwrap_or(0),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn in_bounds(&self, row: usize, col: usize) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.in_bounds(row, col)
+            .then(|| &self.cells[row * self.cols + col])
+    }
+
+    /// The cell at a point, `None` if it's outside the grid.
+    pub fn get_point(&self, point: Point2<i64>) -> Option<&T> {
+        let (row, col) = point.to_row_col()?;
+        self.get(row, col)
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if self.in_bounds(row, col) {
+            Some(&mut self.cells[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> Result<()> {
+        let (rows, cols) = (self.rows, self.cols);
+        let cell = self
+            .get_mut(row, col)
+            .ok_or_else(|| anyhow!("({}, {}) is outside the {}x{} grid", row, col, rows, cols))?;
+        *cell = value;
+        Ok(())
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.rows).then(|| &self.cells[row * self.cols..(row + 1) * self.cols])
+    }
+
+    /// Every cell as `(row, col, value)` in reading order.
+    pub fn enumerate(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (i / self.cols, i % self.cols, cell))
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        row: usize,
+        col: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |(d_row, d_col)| {
+            let row = row.checked_add_signed(*d_row)?;
+            let col = col.checked_add_signed(*d_col)?;
+            self.in_bounds(row, col).then_some((row, col))
+        })
+    }
+
+    /// The in-bounds orthogonal neighbours of a cell, in reading order.
+    pub fn neighbours4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(row, col, &NEIGHBOURS_4)
+    }
+
+    /// The in-bounds neighbours of a cell including diagonals, in reading order.
+    pub fn neighbours8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(row, col, &NEIGHBOURS_8)
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Grid::parse(s, Ok)
+    }
+}
+
+/// Renders the grid back to text, one line per row.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(row).unwrap_or_default() {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NON_SQUARE: &str = "\
+abcd
+efgh
+ijkl
+";
+
+    #[test]
+    fn test_parse_non_square() -> Result<()> {
+        let grid: Grid<char> = NON_SQUARE.parse()?;
+        assert_eq!((grid.rows(), grid.cols()), (3, 4));
+        assert_eq!(grid.get(0, 3), Some(&'d'));
+        assert_eq!(grid.get(2, 0), Some(&'i'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 4), None);
+        assert_eq!(grid.row(1), Some(&['e', 'f', 'g', 'h'][..]));
+        assert_eq!(grid.to_string(), NON_SQUARE.trim_end());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_ragged_rows() {
+        let error = "abc\nde\n".parse::<Grid<char>>().unwrap_err().to_string();
+        assert!(error.contains("row 1 has 2 columns, expected 3"));
+    }
+
+    #[test]
+    fn test_parse_cells() -> Result<()> {
+        let grid = Grid::parse("12\n34", |c| {
+            c.to_digit(10).ok_or_else(|| anyhow!("not a digit: {}", c))
+        })?;
+        assert_eq!(grid.enumerate().map(|(_, _, n)| n).sum::<u32>(), 10);
+        assert!(Grid::parse("1x", |c| c.to_digit(10).ok_or_else(|| anyhow!("bad"))).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_point() -> Result<()> {
+        let grid: Grid<char> = NON_SQUARE.parse()?;
+        assert_eq!(grid.get_point(Point2::new(3, 0)), Some(&'d'));
+        assert_eq!(grid.get_point(Point2::from_row_col(2, 1)), Some(&'j'));
+        assert_eq!(grid.get_point(Point2::new(-1, 0)), None);
+        assert_eq!(grid.get_point(Point2::new(4, 0)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set() -> Result<()> {
+        let mut grid = Grid::new(2, 3, '.');
+        grid.set(1, 2, '#')?;
+        assert_eq!(grid.to_string(), "...\n..#");
+        assert!(grid.set(2, 0, '#').is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_enumerate() -> Result<()> {
+        let grid: Grid<char> = "ab\ncd\nef".parse()?;
+        let cells = grid.enumerate().collect::<Vec<_>>();
+        assert_eq!(cells[0], (0, 0, &'a'));
+        assert_eq!(cells[3], (1, 1, &'d'));
+        assert_eq!(cells[5], (2, 1, &'f'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours_interior() -> Result<()> {
+        let grid: Grid<char> = NON_SQUARE.parse()?;
+        let four = grid.neighbours4(1, 1).collect::<Vec<_>>();
+        assert_eq!(four, vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+        let eight = grid.neighbours8(1, 1).collect::<Vec<_>>();
+        assert_eq!(
+            eight,
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours_edges() -> Result<()> {
+        let grid: Grid<char> = NON_SQUARE.parse()?;
+        assert_eq!(
+            grid.neighbours4(0, 0).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            grid.neighbours8(0, 0).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (1, 1)]
+        );
+        assert_eq!(
+            grid.neighbours8(2, 3).collect::<Vec<_>>(),
+            vec![(1, 2), (1, 3), (2, 2)]
+        );
+        assert_eq!(
+            grid.neighbours4(0, 3).collect::<Vec<_>>(),
+            vec![(0, 2), (1, 3)]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+//! Number theory and algebra helpers.
+
+use anyhow::{anyhow, Result};
+use core::fmt;
+use std::ops::{RangeInclusive, Rem};
+
+/// The integer types the gcd and lcm helpers work over.
+pub trait Integer: Copy + PartialEq + fmt::Display + Rem<Output = Self> {
+    const ZERO: Self;
+    fn checked_abs(self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty => $abs:expr),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+
+            fn checked_abs(self) -> Option<Self> {
+                $abs(self)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn checked_div(self, other: Self) -> Option<Self> {
+                <$t>::checked_div(self, other)
+            }
+        })*
+    };
+}
+
+impl_integer!(u64 => Some, i64 => i64::checked_abs);
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0)` is 0.
+///
+/// Panics if the result doesn't fit, which can only happen for `i64::MIN`,
+/// see [`checked_gcd`].
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd overflowed")
+}
+
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Result<T> {
+    let overflow = || anyhow!("gcd({}, {}) overflows", a, b);
+    let (mut x, mut y) = (
+        a.checked_abs().ok_or_else(overflow)?,
+        b.checked_abs().ok_or_else(overflow)?,
+    );
+    while y != T::ZERO {
+        (x, y) = (y, x % y);
+    }
+    Ok(x)
+}
+
+/// Least common multiple, always non-negative. The lcm with 0 is 0.
+///
+/// Panics on overflow, see [`checked_lcm`].
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflowed")
+}
+
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Result<T> {
+    let divisor = checked_gcd(a, b)?;
+    if divisor == T::ZERO {
+        return Ok(T::ZERO);
+    }
+    a.checked_div(divisor)
+        .and_then(|a| a.checked_mul(b))
+        .and_then(T::checked_abs)
+        .ok_or_else(|| anyhow!("lcm({}, {}) overflows", a, b))
+}
+
+/// The lcm of every value, eg. when several cycles line up. Errors when there
+/// are no values or the result overflows.
+pub fn checked_lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Result<T> {
+    let mut values = values.into_iter();
+    let first = values
+        .next()
+        .ok_or_else(|| anyhow!("lcm of no values"))?
+        .checked_abs()
+        .ok_or_else(|| anyhow!("lcm overflows"))?;
+    values.try_fold(first, checked_lcm)
+}
+
+/// The inclusive range of integers `x` where `a*x² + b*x + c > 0`, for a
+/// downward opening quadratic (`a < 0`). `None` when no integer is strictly
+/// positive, so integers where the quadratic is exactly 0 are excluded.
+///
+/// Day 6 is the motivating case: holding the button for `x` of `t`
+/// milliseconds beats a record distance `d` when `-x² + t*x - d > 0`.
+pub fn quadratic_integer_bounds(a: i64, b: i64, c: i64) -> Result<Option<RangeInclusive<i64>>> {
+    if a >= 0 {
+        return Err(anyhow!(
+            "Only downward opening quadratics have a bounded positive range, got a = {}",
+            a
+        ));
+    }
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+    let value = |x: i128| a * x * x + b * x + c;
+    let discriminant = b * b - 4 * a * c;
+    if discriminant <= 0 {
+        return Ok(None);
+    }
+
+    // the float roots are only a starting point, the exact integer
+    // evaluation settles which side of the boundary each candidate is on
+    let root = (discriminant as f64).sqrt();
+    let vertex = -b as f64 / (2 * a) as f64;
+    let half_width = root / (2 * a).abs() as f64;
+    let mut low = (vertex - half_width).ceil() as i128;
+    let mut high = (vertex + half_width).floor() as i128;
+    while value(low - 1) > 0 {
+        low -= 1;
+    }
+    while low <= high && value(low) <= 0 {
+        low += 1;
+    }
+    while value(high + 1) > 0 {
+        high += 1;
+    }
+    while high >= low && value(high) <= 0 {
+        high -= 1;
+    }
+    if low > high {
+        return Ok(None);
+    }
+
+    let to_i64 = |x: i128| {
+        i64::try_from(x).map_err(|_| anyhow!("Quadratic bound {} doesn't fit in an i64", x))
+    };
+    Ok(Some(to_i64(low)?..=to_i64(high)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_gcd_lcm() -> Result<()> {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0u64, 0), 0);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(-4i64, 6), 12);
+        assert_eq!(lcm(0i64, 6), 0);
+        assert_eq!(checked_lcm_all([2u64, 3, 4, 5])?, 60);
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert!(checked_gcd(i64::MIN, 0).is_err());
+        assert!(checked_lcm(u64::MAX, u64::MAX - 1).is_err());
+        assert!(checked_lcm_all(Vec::<u64>::new()).is_err());
+    }
+
+    #[test]
+    fn test_quadratic_bounds() -> Result<()> {
+        // day 6 example races: time 7 record 9, time 30 record 200
+        assert_eq!(quadratic_integer_bounds(-1, 7, -9)?, Some(2..=5));
+        // the roots are exactly 10 and 20, which only tie the record
+        assert_eq!(quadratic_integer_bounds(-1, 30, -200)?, Some(11..=19));
+        assert_eq!(quadratic_integer_bounds(-1, 2, -1)?, None);
+        assert!(quadratic_integer_bounds(1, 0, 0).is_err());
+        Ok(())
+    }
+
+    fn brute_force_gcd(a: u64, b: u64) -> u64 {
+        (1..=a.max(b))
+            .rev()
+            .find(|d| a.is_multiple_of(*d) && b.is_multiple_of(*d))
+            .unwrap_or(0)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_gcd_matches_brute_force(a in 0u64..200, b in 0u64..200) {
+            prop_assert_eq!(gcd(a, b), brute_force_gcd(a, b));
+            prop_assert_eq!(gcd(-(a as i64), b as i64), brute_force_gcd(a, b) as i64);
+        }
+
+        #[test]
+        fn prop_lcm_matches_brute_force(a in 1u64..100, b in 1u64..100) {
+            let expected = (1..=a * b).find(|m| m.is_multiple_of(a) && m.is_multiple_of(b)).unwrap();
+            prop_assert_eq!(lcm(a, b), expected);
+        }
+
+        #[test]
+        fn prop_quadratic_bounds_match_brute_force(
+            a in -5i64..0,
+            b in -60i64..60,
+            c in -200i64..200,
+        ) {
+            let positive = (-1_000i64..=1_000)
+                .filter(|x| a * x * x + b * x + c > 0)
+                .collect::<Vec<i64>>();
+            let expected = positive
+                .first()
+                .zip(positive.last())
+                .map(|(low, high)| *low..=*high);
+            prop_assert_eq!(quadratic_integer_bounds(a, b, c).unwrap(), expected);
+        }
+    }
+}
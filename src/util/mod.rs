@@ -0,0 +1,9 @@
+//! Building blocks shared between the puzzle solutions.
+
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod math;
+pub mod parse;
+pub mod point;
+pub mod ranges;
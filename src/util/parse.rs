@@ -0,0 +1,93 @@
+//! Helpers for pulling numbers out of puzzle input lines.
+
+use anyhow::{anyhow, Result};
+use core::fmt;
+use std::str::FromStr;
+
+/// Parses every whitespace separated token of `s` as an integer. Any amount of
+/// whitespace may separate the tokens, but every token must be a number, the
+/// error names the first one that isn't along with its (1-based) column.
+pub fn ints<T>(s: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    tokens(s)
+        .map(|(column, token)| {
+            token.parse::<T>().map_err(|e| {
+                anyhow!(
+                    "Invalid integer '{}' at column {}: {}",
+                    token,
+                    column + 1,
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+/// Finds the first integer anywhere in `s`, eg. the 12 in "Card 12:". A `-`
+/// directly before the digits makes it negative.
+pub fn first_int<T>(s: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let start = s
+        .find(|c: char| c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("No integer found in '{}'", s))?;
+    let start = if s[..start].ends_with('-') {
+        start - 1
+    } else {
+        start
+    };
+    let end = s[start + 1..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(s.len(), |end| start + 1 + end);
+    let token = &s[start..end];
+    token
+        .parse::<T>()
+        .map_err(|e| anyhow!("Invalid integer '{}' at column {}: {}", token, start + 1, e))
+}
+
+/// Whitespace separated tokens along with their byte offset in `s`.
+fn tokens(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.split(char::is_whitespace)
+        .filter(|token| !token.is_empty())
+        .map(move |token| (token.as_ptr() as usize - s.as_ptr() as usize, token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() -> Result<()> {
+        assert_eq!(ints::<u32>("41 48 83  86 17")?, vec![41, 48, 83, 86, 17]);
+        assert_eq!(ints::<i64>("  -3\t7   -11 ")?, vec![-3, 7, -11]);
+        assert_eq!(ints::<u8>("")?, Vec::<u8>::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ints_junk() {
+        let error = ints::<i32>("1  2 x3 4").unwrap_err().to_string();
+        assert!(error.contains("'x3'"), "{}", error);
+        assert!(error.contains("column 6"), "{}", error);
+
+        let error = ints::<u32>("5 -5").unwrap_err().to_string();
+        assert!(error.contains("'-5' at column 3"), "{}", error);
+    }
+
+    #[test]
+    fn test_first_int() -> Result<()> {
+        assert_eq!(first_int::<u32>("Card   12: 41 48")?, 12);
+        assert_eq!(first_int::<i32>("x=-4, y=2")?, -4);
+        assert_eq!(first_int::<i32>("7")?, 7);
+        assert!(first_int::<i32>("none here").is_err());
+
+        let error = first_int::<u8>("seeds: 300").unwrap_err().to_string();
+        assert!(error.contains("'300' at column 8"), "{}", error);
+        Ok(())
+    }
+}
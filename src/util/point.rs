@@ -0,0 +1,263 @@
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// A point, or offset, on a 2-D plane. Grid puzzles use `x` for the column
+/// and `y` for the row, so `y` grows downwards and [`Direction::North`] is
+/// `-y`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Point2<i64> {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    /// The point for a `(row, col)` index into a [`Grid`](super::grid::Grid).
+    pub fn from_row_col(row: usize, col: usize) -> Self {
+        Self::new(col as i64, row as i64)
+    }
+
+    /// The `(row, col)` index of the point, `None` if it's left of or above
+    /// the grid's top left corner.
+    pub fn to_row_col(self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+    }
+
+    /// The next point over in `direction`.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+
+    /// The point `n` steps away in `direction`.
+    pub fn steps(self, direction: Direction, n: i64) -> Self {
+        self + direction.offset() * n
+    }
+
+    pub fn manhattan(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The 4 orthogonally adjacent points, clockwise from north.
+    pub fn neighbours4(self) -> impl Iterator<Item = Self> {
+        Direction::CARDINAL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// All 8 adjacent points including diagonals, clockwise from north.
+    pub fn neighbours8(self) -> impl Iterator<Item = Self> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, scale: T) -> Self {
+        Self::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A compass direction, north being up the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// The 4 orthogonal directions, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// All 8 directions including diagonals, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// The offset of one step in this direction.
+    pub fn offset(self) -> Point2<i64> {
+        match self {
+            Direction::North => Point2::new(0, -1),
+            Direction::NorthEast => Point2::new(1, -1),
+            Direction::East => Point2::new(1, 0),
+            Direction::SouthEast => Point2::new(1, 1),
+            Direction::South => Point2::new(0, 1),
+            Direction::SouthWest => Point2::new(-1, 1),
+            Direction::West => Point2::new(-1, 0),
+            Direction::NorthWest => Point2::new(-1, -1),
+        }
+    }
+
+    /// A quarter turn anticlockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Turns clockwise by `eighths` of a full turn.
+    fn rotate(self, eighths: usize) -> Self {
+        Self::ALL[(self.index() + eighths) % Self::ALL.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turning() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+            assert_eq!(direction.turn_left().is_diagonal(), direction.is_diagonal());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::SouthEast.turn_right(), Direction::SouthWest);
+        assert_eq!(Direction::NorthWest.opposite(), Direction::SouthEast);
+    }
+
+    #[test]
+    fn test_stepping_off_the_origin() {
+        let origin = Point2::ORIGIN;
+        assert_eq!(origin.step(Direction::North), Point2::new(0, -1));
+        assert_eq!(origin.step(Direction::North).to_row_col(), None);
+        assert_eq!(origin.step(Direction::West).to_row_col(), None);
+        assert_eq!(origin.step(Direction::SouthEast).to_row_col(), Some((1, 1)));
+        assert_eq!(origin.steps(Direction::East, 3), Point2::new(3, 0));
+        assert_eq!(
+            origin.steps(Direction::South, 2).steps(Direction::North, 2),
+            origin
+        );
+        assert_eq!(origin.neighbours4().count(), 4);
+        assert_eq!(
+            origin
+                .neighbours8()
+                .filter(|p| p.to_row_col().is_some())
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_row_col_round_trip() {
+        let point = Point2::from_row_col(2, 5);
+        assert_eq!(point, Point2::new(5, 2));
+        assert_eq!(point.to_row_col(), Some((2, 5)));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut point = Point2::new(1, 2) + Point2::new(3, -4) * 2;
+        assert_eq!(point, Point2::new(7, -6));
+        point -= Point2::new(7, -6);
+        assert_eq!(point, Point2::ORIGIN);
+        point += Point2::new(1, 1);
+        assert_eq!(point - Point2::new(1, 1), Point2::ORIGIN);
+        assert_eq!(point.to_string(), "(1, 1)");
+    }
+
+    #[test]
+    fn test_manhattan_symmetric() {
+        let points = [
+            Point2::ORIGIN,
+            Point2::new(3, -4),
+            Point2::new(-7, 2),
+            Point2::new(-1_000_000, 1_000_000),
+        ];
+        for a in points {
+            assert_eq!(a.manhattan(a), 0);
+            for b in points {
+                assert_eq!(a.manhattan(b), b.manhattan(a));
+            }
+        }
+        assert_eq!(Point2::new(3, -4).manhattan(Point2::new(-7, 2)), 16);
+    }
+}
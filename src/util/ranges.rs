@@ -0,0 +1,226 @@
+use core::fmt;
+
+/// A half-open span of integers `start..end`. Unlike [`std::ops::Range`] it is
+/// `Copy` and the set operations below never produce a span with
+/// `start > end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Span {
+    /// A span covering `start..end`, empty if `end <= start`.
+    pub fn new(start: u64, end: u64) -> Self {
+        Self {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    /// The `len` integers from `start`, `None` if that runs past `u64::MAX`.
+    pub fn with_len(start: u64, len: u64) -> Option<Self> {
+        Some(Self::new(start, start.checked_add(len)?))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The integers in both spans, `None` if they don't overlap. Spans that
+    /// only touch, eg. `0..5` and `5..10`, don't overlap.
+    pub fn intersection(&self, other: &Span) -> Option<Span> {
+        let span = Span::new(self.start.max(other.start), self.end.min(other.end));
+        (!span.is_empty()).then_some(span)
+    }
+
+    /// The parts of this span not covered by `other`: nothing, one piece, or
+    /// two pieces when `other` falls strictly inside, in ascending order.
+    pub fn subtract(&self, other: &Span) -> Vec<Span> {
+        let Some(overlap) = self.intersection(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+        [
+            Span::new(self.start, overlap.start),
+            Span::new(overlap.end, self.end),
+        ]
+        .into_iter()
+        .filter(|span| !span.is_empty())
+        .collect()
+    }
+
+    /// The span shifted by `delta`, `None` if either end would leave the `u64`
+    /// range.
+    pub fn offset(&self, delta: i64) -> Option<Span> {
+        Some(Span::new(
+            self.start.checked_add_signed(delta)?,
+            self.end.checked_add_signed(delta)?,
+        ))
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A set of integers stored as sorted, disjoint spans. Overlapping and
+/// adjacent spans are merged as they are inserted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpanSet {
+    spans: Vec<Span>,
+}
+
+impl SpanSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, span: Span) {
+        if span.is_empty() {
+            return;
+        }
+        // every span from `first` up to `last` overlaps or touches the new one
+        let first = self.spans.partition_point(|s| s.end < span.start);
+        let last = self.spans.partition_point(|s| s.start <= span.end);
+        let merged = self.spans[first..last].iter().fold(span, |merged, s| {
+            Span::new(merged.start.min(s.start), merged.end.max(s.end))
+        });
+        self.spans.splice(first..last, [merged]);
+    }
+
+    /// The disjoint spans in ascending order.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// How many integers are in the set.
+    pub fn total_len(&self) -> u64 {
+        self.spans.iter().map(Span::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.spans.first().map(|span| span.start)
+    }
+}
+
+impl FromIterator<Span> for SpanSet {
+    fn from_iter<I: IntoIterator<Item = Span>>(iter: I) -> Self {
+        let mut set = SpanSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Span> for SpanSet {
+    fn extend<I: IntoIterator<Item = Span>>(&mut self, iter: I) {
+        for span in iter {
+            self.insert(span);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intersection() {
+        assert_eq!(
+            Span::new(0, 10).intersection(&Span::new(5, 15)),
+            Some(Span::new(5, 10))
+        );
+        assert_eq!(
+            Span::new(0, 10).intersection(&Span::new(2, 3)),
+            Some(Span::new(2, 3))
+        );
+        // adjacent spans share no integers
+        assert_eq!(Span::new(0, 5).intersection(&Span::new(5, 10)), None);
+        assert_eq!(Span::new(0, 5).intersection(&Span::new(7, 10)), None);
+        assert_eq!(Span::new(3, 3).intersection(&Span::new(0, 10)), None);
+    }
+
+    #[test]
+    fn test_subtract() {
+        let span = Span::new(0, 10);
+        assert_eq!(
+            span.subtract(&Span::new(3, 5)),
+            vec![Span::new(0, 3), Span::new(5, 10)]
+        );
+        assert_eq!(span.subtract(&Span::new(5, 20)), vec![Span::new(0, 5)]);
+        assert_eq!(span.subtract(&Span::new(10, 20)), vec![span]);
+        assert_eq!(span.subtract(&Span::new(0, 10)), vec![]);
+        assert_eq!(span.subtract(&Span::new(0, u64::MAX)), vec![]);
+        assert_eq!(Span::new(4, 4).subtract(&Span::new(0, 1)), vec![]);
+    }
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(Span::new(5, 10).offset(-5), Some(Span::new(0, 5)));
+        assert_eq!(Span::new(5, 10).offset(3), Some(Span::new(8, 13)));
+        assert_eq!(Span::new(5, 10).offset(-6), None);
+        assert_eq!(Span::new(u64::MAX - 5, u64::MAX).offset(1), None);
+        assert_eq!(
+            Span::new(u64::MAX - 5, u64::MAX).offset(-1),
+            Some(Span::new(u64::MAX - 6, u64::MAX - 1))
+        );
+    }
+
+    #[test]
+    fn test_extremes() {
+        let top = Span::new(u64::MAX - 1, u64::MAX);
+        assert_eq!(top.len(), 1);
+        assert!(top.contains(u64::MAX - 1));
+        assert!(!top.contains(u64::MAX));
+        assert_eq!(Span::with_len(u64::MAX - 1, 1), Some(top));
+        assert_eq!(Span::with_len(u64::MAX - 1, 2), None);
+        assert_eq!(Span::new(10, 2), Span::new(10, 10));
+
+        let everything: SpanSet = [Span::new(0, 10), Span::new(5, u64::MAX)]
+            .into_iter()
+            .collect();
+        assert_eq!(everything.spans(), &[Span::new(0, u64::MAX)]);
+        assert_eq!(everything.total_len(), u64::MAX);
+    }
+
+    #[test]
+    fn test_span_set_merges() {
+        let mut set = SpanSet::new();
+        set.insert(Span::new(10, 20));
+        set.insert(Span::new(30, 40));
+        set.insert(Span::new(0, 5));
+        assert_eq!(set.total_len(), 25);
+        assert_eq!(set.min(), Some(0));
+
+        // adjacent spans are merged even though they don't overlap
+        set.insert(Span::new(5, 10));
+        assert_eq!(set.spans(), &[Span::new(0, 20), Span::new(30, 40)]);
+
+        // one span bridging and overlapping several
+        set.insert(Span::new(15, 35));
+        assert_eq!(set.spans(), &[Span::new(0, 40)]);
+        assert_eq!(set.total_len(), 40);
+    }
+
+    #[test]
+    fn test_span_set_empty() {
+        let mut set = SpanSet::new();
+        set.insert(Span::new(7, 7));
+        assert!(set.is_empty());
+        assert_eq!(set.total_len(), 0);
+        assert_eq!(set.min(), None);
+    }
+}
@@ -0,0 +1,149 @@
+//! Checking an input without solving it, for `--parse-only`.
+
+use anyhow::{anyhow, Result};
+use core::fmt;
+
+/// A problem found while parsing an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Malformed {
+    /// 1-based line number, `None` when the problem isn't on any one line, eg.
+    /// a missing section.
+    pub line: Option<usize>,
+    pub error: String,
+}
+
+impl Malformed {
+    pub fn at(line: usize, error: impl fmt::Display) -> Self {
+        Self {
+            line: Some(line),
+            error: error.to_string(),
+        }
+    }
+
+    pub fn input(error: impl fmt::Display) -> Self {
+        Self {
+            line: None,
+            error: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Malformed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+/// What a day's parser made of an input: how many lines, how many of each
+/// kind of thing it found, and which lines it couldn't make sense of.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    pub lines: usize,
+    /// Eg. `("games", 100)`, in the order they should be reported.
+    pub counts: Vec<(&'static str, usize)>,
+    pub malformed: Vec<Malformed>,
+}
+
+impl ParseStats {
+    pub fn new(input: &str, malformed: Vec<Malformed>) -> Self {
+        Self {
+            lines: input.lines().count(),
+            counts: vec![],
+            malformed,
+        }
+    }
+
+    pub fn with_count(mut self, name: &'static str, count: usize) -> Self {
+        self.counts.push((name, count));
+        self
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.malformed.is_empty()
+    }
+}
+
+impl fmt::Display for ParseStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "lines: {}", self.lines)?;
+        for (name, count) in &self.counts {
+            writeln!(f, "{}: {}", name, count)?;
+        }
+        if self.malformed.is_empty() {
+            return write!(f, "malformed: none");
+        }
+        write!(f, "malformed: {}", self.malformed.len())?;
+        for malformed in &self.malformed {
+            write!(f, "\n  {}", malformed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses every non-blank line, carrying on past bad lines so they can all
+/// be reported.
+pub fn parse_lines<T>(
+    input: &str,
+    mut parse_line: impl FnMut(&str) -> Result<T>,
+) -> (Vec<T>, Vec<Malformed>) {
+    let mut parsed = vec![];
+    let mut malformed = vec![];
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(value) => parsed.push(value),
+            Err(e) => malformed.push(Malformed::at(index + 1, e)),
+        }
+    }
+    (parsed, malformed)
+}
+
+/// The parsed value, or the first problem as an error for solvers that can't
+/// carry on past one.
+pub fn strict<T>((parsed, malformed): (T, Vec<Malformed>)) -> Result<T> {
+    match malformed.into_iter().next() {
+        Some(malformed) => Err(anyhow!("{}", malformed)),
+        None => Ok(parsed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines_collects_every_problem() {
+        let (parsed, malformed) = parse_lines("1\nx\n\n3\ny", |line| Ok(line.parse::<i32>()?));
+        assert_eq!(parsed, vec![1, 3]);
+        let lines = malformed.iter().map(|m| m.line).collect::<Vec<_>>();
+        assert_eq!(lines, vec![Some(2), Some(5)]);
+    }
+
+    #[test]
+    fn test_strict() {
+        let error = strict(parse_lines("1\nx", |line| Ok(line.parse::<i32>()?))).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid digit found in string");
+        assert_eq!(
+            strict(parse_lines("1\n2\n", |line| Ok(line.parse::<i32>()?))).unwrap(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let stats =
+            ParseStats::new("a\nb\nc", vec![Malformed::at(2, "bad")]).with_count("things", 2);
+        assert!(!stats.is_valid());
+        assert_eq!(
+            stats.to_string(),
+            "lines: 3\nthings: 2\nmalformed: 1\n  line 2: bad"
+        );
+        let stats = ParseStats::new("a", vec![]).with_count("things", 1);
+        assert_eq!(stats.to_string(), "lines: 1\nthings: 1\nmalformed: none");
+    }
+}
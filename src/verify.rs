@@ -0,0 +1,579 @@
+//! Checking each part against the example from its puzzle statement, and
+//! against its input's recorded answer, see [`crate::answers`], for `aoc
+//! verify`, and reporting the checks as JUnit XML or TAP for CI.
+
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fmt::{self, Write as _},
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::Answers,
+    context::RunContext,
+    layout::Layout,
+    mismatch,
+    registry::Registry,
+    runner::{self, PartOptions, RunResult, Status},
+    solver::{Answer, Solver},
+    style,
+};
+
+/// How a part's example, or input, went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Passed,
+    /// Solved, but not to the expected answer.
+    Failed(Answer),
+    /// Couldn't be solved at all.
+    Error(String),
+}
+
+/// One part's example or input checked, a test case in the reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub day: u8,
+    pub part: u32,
+    pub title: String,
+    /// Whether it's the part's input that was checked, rather than its
+    /// example.
+    pub input: bool,
+    /// The answer in the puzzle statement, or the one recorded for the input.
+    pub expected: Answer,
+    pub duration: Duration,
+    pub verdict: Verdict,
+}
+
+impl Case {
+    pub fn name(&self) -> String {
+        let name = format!("{} part {}", self.title, self.part);
+        if self.input {
+            name + " input"
+        } else {
+            name
+        }
+    }
+
+    fn checked(&self) -> &'static str {
+        if self.input {
+            "input"
+        } else {
+            "example"
+        }
+    }
+
+    /// What went wrong, `None` when it passed. Answers are masked when
+    /// they're hidden, see [`style::answers_hidden`].
+    pub fn message(&self) -> Option<String> {
+        match &self.verdict {
+            Verdict::Passed => None,
+            Verdict::Failed(actual) => Some(format!(
+                "[Part {}] {} answer mismatch, expected: {}, actual: {}",
+                self.part,
+                self.checked(),
+                style::answer_text(&self.expected),
+                style::answer_text(actual)
+            )),
+            Verdict::Error(e) => Some(format!(
+                "[Part {}] {} failed to solve: {}",
+                self.part,
+                self.checked(),
+                e
+            )),
+        }
+    }
+}
+
+/// Solves `solver`'s example, `None` when it has none to check.
+pub fn check(day: u8, title: &str, solver: &dyn Solver) -> Option<Case> {
+    let (input, expected) = solver.example()?;
+    let (part, _) = solver.part_description();
+    let start = Instant::now();
+    let solved = solver.solve(input, &RunContext::new());
+    let duration = start.elapsed();
+    let verdict = match solved {
+        Ok(actual) if actual == expected => Verdict::Passed,
+        Ok(actual) => Verdict::Failed(actual),
+        Err(e) => Verdict::Error(e.to_string()),
+    };
+    Some(Case {
+        day,
+        part,
+        title: title.to_string(),
+        input: false,
+        expected,
+        duration,
+        verdict,
+    })
+}
+
+/// The check of a part solved from its input, see [`crate::runner::run_day`],
+/// against the answer recorded for it.
+pub fn input_case(result: &RunResult, expected: &Answer) -> Case {
+    let verdict = match (&result.status, &result.answer) {
+        (Status::Solved, Some(actual)) if actual == expected => Verdict::Passed,
+        (Status::Solved, Some(actual)) => Verdict::Failed(actual.clone()),
+        (Status::Solved, None) => Verdict::Error("no answer".to_string()),
+        (Status::Failed(reason) | Status::TimedOut(reason) | Status::Skipped(reason), _) => {
+            Verdict::Error(reason.clone())
+        }
+    };
+    Case {
+        day: result.day,
+        part: result.part,
+        title: result.title.clone(),
+        input: true,
+        expected: expected.clone(),
+        duration: result.timing.map(|timing| timing.total).unwrap_or_default(),
+        verdict,
+    }
+}
+
+/// Checks every part with an example of `days`, or of every day in
+/// `registry` when `days` is empty, in day and part order.
+pub fn run(registry: &Registry, days: &[u8]) -> Vec<Case> {
+    registry
+        .days()
+        .filter(|(day, _)| days.is_empty() || days.contains(day))
+        .flat_map(|(day, puzzle)| {
+            [1, 2].into_iter().filter_map(move |part| {
+                let solver = puzzle.solver(part)?;
+                check(day, puzzle.title(), solver.as_ref())
+            })
+        })
+        .collect()
+}
+
+/// The parts' inputs checked against the answers recorded for them, see
+/// [`check_answers`].
+#[derive(Debug, Clone, Default)]
+pub struct AnswersReport {
+    /// A case per part with a recorded answer and an input, in day and part
+    /// order.
+    pub cases: Vec<Case>,
+    /// Parts with no answer recorded, which weren't checked, by day and part.
+    pub skipped: Vec<(u8, u32)>,
+    /// Those of the skipped parts that were solved anyway, to record their
+    /// answers.
+    pub unrecorded: Vec<RunResult>,
+}
+
+impl AnswersReport {
+    /// The cases that didn't give their recorded answer.
+    pub fn failures(&self) -> impl Iterator<Item = &Case> {
+        self.cases
+            .iter()
+            .filter(|case| case.verdict != Verdict::Passed)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+/// A line per failure naming its day and part, see [`Case::message`].
+impl fmt::Display for AnswersReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for case in self.failures() {
+            if let Some(message) = case.message() {
+                writeln!(f, "{}: {}", case.title, message)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Solves the input in the data root of each part of `days`, or of every day
+/// in `registry` when `days` is empty, that has an answer in `answers`, and
+/// checks it gives that answer. Parts without one are skipped, unless
+/// `solve_unrecorded` says to solve them for their answers to be recorded.
+/// Days without an input are left out altogether.
+pub fn check_answers(
+    registry: &Registry,
+    days: &[u8],
+    data_root: &Path,
+    layout: &Layout,
+    answers: &Answers,
+    solve_unrecorded: impl Fn(u8, u32) -> bool,
+) -> AnswersReport {
+    let mut report = AnswersReport::default();
+    for (day, puzzle) in registry.days() {
+        if !days.is_empty() && !days.contains(&day) {
+            continue;
+        }
+        let parts = [1, 2]
+            .into_iter()
+            .filter(|part| puzzle.solver(*part).is_some())
+            .collect::<Vec<_>>();
+        report.skipped.extend(
+            parts
+                .iter()
+                .filter(|part| answers.get(day, **part).is_none())
+                .map(|part| (day, *part)),
+        );
+        let input = layout.input(data_root, day);
+        if !input.is_file() {
+            continue;
+        }
+        let parts = parts
+            .into_iter()
+            .filter(|part| answers.get(day, *part).is_some() || solve_unrecorded(day, *part))
+            .collect::<Vec<_>>();
+        if parts.is_empty() {
+            continue;
+        }
+        for result in runner::run_day(registry, day, &parts, &input, &PartOptions::default()) {
+            match answers.get(day, result.part) {
+                Some(expected) => report.cases.push(input_case(&result, expected)),
+                None => report.unrecorded.push(result),
+            }
+        }
+    }
+    report
+}
+
+/// How `case`'s wrong answer may have come about, see [`mismatch::explain`],
+/// with the other part's expected answer for the same example or input from
+/// among `cases`. Nothing when it didn't give a wrong answer.
+pub fn explain(case: &Case, cases: &[Case]) -> Vec<String> {
+    let Verdict::Failed(actual) = &case.verdict else {
+        return vec![];
+    };
+    let other_part = cases
+        .iter()
+        .find(|other| other.day == case.day && other.part != case.part && other.input == case.input)
+        .map(|other| &other.expected);
+    mismatch::explain(&case.expected, actual, other_part)
+}
+
+/// The error for a day's failed cases, as `aoc verify` has always reported
+/// them.
+pub fn day_error(cases: &[&Case]) -> Option<anyhow::Error> {
+    let messages = cases
+        .iter()
+        .filter_map(|case| Some(format!("{}: {}", case.title, case.message()?)))
+        .collect::<Vec<_>>();
+    (!messages.is_empty()).then(|| anyhow!("{}", messages.join("\n")))
+}
+
+/// The cases as a JUnit XML report, a test case per part with a `failure`
+/// for a wrong answer and an `error` for one that couldn't be solved.
+pub fn junit(cases: &[Case]) -> String {
+    let count =
+        |failed: fn(&Verdict) -> bool| cases.iter().filter(|case| failed(&case.verdict)).count();
+    let failures = count(|verdict| matches!(verdict, Verdict::Failed(_)));
+    let errors = count(|verdict| matches!(verdict, Verdict::Error(_)));
+    let time = cases.iter().map(|case| case.duration).sum::<Duration>();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let counts = format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\"",
+        cases.len(),
+        failures,
+        errors,
+        time.as_secs_f64()
+    );
+    let _ = writeln!(xml, "<testsuites name=\"aoc-2023\" {}>", counts);
+    let _ = writeln!(xml, "  <testsuite name=\"examples\" {}>", counts);
+    for case in cases {
+        let _ = write!(
+            xml,
+            "    <testcase classname=\"day{:02}\" name=\"{}\" time=\"{:.6}\"",
+            case.day,
+            xml_escape(&case.name()),
+            case.duration.as_secs_f64()
+        );
+        let element = match case.verdict {
+            Verdict::Passed => None,
+            Verdict::Failed(_) => Some("failure"),
+            Verdict::Error(_) => Some("error"),
+        };
+        match (element, case.message()) {
+            (Some(element), Some(message)) => {
+                let message = xml_escape(&message);
+                let _ = writeln!(
+                    xml,
+                    ">\n      <{0} message=\"{1}\">{1}</{0}>\n    </testcase>",
+                    element, message
+                );
+            }
+            _ => xml.push_str("/>\n"),
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// The cases as a TAP version 13 report, with what went wrong in each
+/// failing case's YAML block.
+pub fn tap(cases: &[Case]) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", cases.len());
+    for (number, case) in (1..).zip(cases) {
+        let ok = if case.verdict == Verdict::Passed {
+            "ok"
+        } else {
+            "not ok"
+        };
+        // a # would start a directive, and a line break a new line of TAP
+        let name = case.name().replace('#', "\\#").replace(['\n', '\r'], " ");
+        let _ = writeln!(tap, "{} {} - {}", ok, number, name);
+        let _ = writeln!(tap, "  ---");
+        if let Some(message) = case.message() {
+            let severity = match case.verdict {
+                Verdict::Error(_) => "error",
+                _ => "fail",
+            };
+            let _ = writeln!(tap, "  message: {}", yaml_string(&message));
+            let _ = writeln!(tap, "  severity: {}", severity);
+        }
+        let _ = writeln!(
+            tap,
+            "  duration_ms: {:.3}",
+            case.duration.as_secs_f64() * 1000.0
+        );
+        let _ = writeln!(tap, "  ...");
+    }
+    tap
+}
+
+/// Writes a [`junit`] or [`tap`] report to `path`.
+pub fn write(path: &Path, report: &str) -> Result<()> {
+    fs::write(path, report).with_context(|| format!("Can't write the report to {}", path.display()))
+}
+
+/// Escapes `text` for an XML attribute or element, dropping the control
+/// characters XML 1.0 can't have at all.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\t' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `text` as a double quoted YAML string, which is also a JSON string.
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cases() -> Vec<Case> {
+        let case = |day, part, verdict| Case {
+            day,
+            part,
+            title: format!("Day {}: Pipes & <Brackets> #{}", day, day),
+            input: false,
+            expected: (part * 140 + 1).into(),
+            duration: Duration::from_micros(1500),
+            verdict,
+        };
+        vec![
+            case(1, 1, Verdict::Passed),
+            case(1, 2, Verdict::Failed(280.into())),
+            case(
+                3,
+                1,
+                Verdict::Error("line 2: \"x\" isn't a number\u{7}".to_string()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_junit() -> Result<()> {
+        let xml = junit(&cases());
+        let document = roxmltree::Document::parse(&xml)?;
+        let suites = document.root_element();
+        assert_eq!(suites.tag_name().name(), "testsuites");
+        assert_eq!(suites.attribute("tests"), Some("3"));
+        assert_eq!(suites.attribute("failures"), Some("1"));
+        assert_eq!(suites.attribute("errors"), Some("1"));
+        let testcases = suites
+            .descendants()
+            .filter(|node| node.has_tag_name("testcase"))
+            .collect::<Vec<_>>();
+        assert_eq!(testcases.len(), 3);
+        assert_eq!(
+            testcases[0].attribute("name"),
+            Some("Day 1: Pipes & <Brackets> #1 part 1")
+        );
+        assert_eq!(testcases[0].attribute("classname"), Some("day01"));
+        assert_eq!(testcases[0].attribute("time"), Some("0.001500"));
+        assert_eq!(
+            testcases[0]
+                .children()
+                .filter(|node| node.is_element())
+                .count(),
+            0
+        );
+
+        let failure = testcases[1]
+            .children()
+            .find(|node| node.has_tag_name("failure"))
+            .expect("the mismatch is a failure");
+        assert_eq!(
+            failure.attribute("message"),
+            Some("[Part 2] example answer mismatch, expected: 281, actual: 280")
+        );
+        let error = testcases[2]
+            .children()
+            .find(|node| node.has_tag_name("error"))
+            .expect("the unsolved part is an error");
+        // the control character XML can't hold is dropped
+        assert_eq!(
+            error.text(),
+            Some("[Part 1] example failed to solve: line 2: \"x\" isn't a number")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tap() {
+        let tap = tap(&cases());
+        let lines = tap.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[..3],
+            [
+                "TAP version 13",
+                "1..3",
+                "ok 1 - Day 1: Pipes & <Brackets> \\#1 part 1"
+            ]
+        );
+        assert!(lines.contains(&"not ok 2 - Day 1: Pipes & <Brackets> \\#1 part 2"));
+        assert!(lines.contains(
+            &"  message: \"[Part 2] example answer mismatch, expected: 281, actual: 280\""
+        ));
+        assert!(lines.contains(&"not ok 3 - Day 3: Pipes & <Brackets> \\#3 part 1"));
+        assert!(lines.contains(&"  severity: error"));
+        assert_eq!(lines.iter().filter(|line| **line == "  ...").count(), 3);
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut cases = cases();
+        assert!(explain(&cases[0], &cases).is_empty());
+        assert_eq!(
+            explain(&cases[1], &cases),
+            [
+                "expected: 281",
+                "actual:   280",
+                "            ^",
+                "hint: it's off by one"
+            ]
+        );
+        // part 1's example answer
+        cases[1].verdict = Verdict::Failed(141.into());
+        assert_eq!(
+            explain(&cases[1], &cases).last().map(String::as_str),
+            Some("hint: it's the other part's answer, did you run the wrong part?")
+        );
+    }
+
+    #[test]
+    fn test_input_case() {
+        let result = |status, answer: Option<Answer>| RunResult {
+            day: 7,
+            part: 2,
+            title: "Day 7: Camel Cards".to_string(),
+            description: "Total winnings".to_string(),
+            answer,
+            timing: None,
+            peak_memory: None,
+            implementation: None,
+            status,
+            failure: None,
+            truncated: None,
+        };
+        let expected = Answer::from(5905);
+        let passed = input_case(&result(Status::Solved, Some(5905.into())), &expected);
+        assert_eq!(passed.verdict, Verdict::Passed);
+        assert_eq!(passed.name(), "Day 7: Camel Cards part 2 input");
+        let failed = input_case(&result(Status::Solved, Some(5906.into())), &expected);
+        assert_eq!(
+            failed.message().as_deref(),
+            Some("[Part 2] input answer mismatch, expected: 5905, actual: 5906")
+        );
+        let timed_out = input_case(
+            &result(Status::TimedOut("took over 1s".to_string()), None),
+            &expected,
+        );
+        assert_eq!(
+            timed_out.verdict,
+            Verdict::Error("took over 1s".to_string())
+        );
+    }
+
+    #[cfg(feature = "day07")]
+    #[test]
+    fn test_check_answers() -> Result<()> {
+        let registry = Registry::builtin();
+        let (example, _) = registry
+            .get(7)
+            .unwrap()
+            .solver(1)
+            .unwrap()
+            .example()
+            .unwrap();
+        let data_root =
+            std::env::temp_dir().join(format!("aoc-2023-check-answers-{}", std::process::id()));
+        fs::create_dir_all(data_root.join("07"))?;
+        fs::write(data_root.join("07").join("input"), example)?;
+        let layout = Layout::default();
+        let check = |answers: &str, solve_unrecorded: bool| -> Result<AnswersReport> {
+            let answers = Answers::parse(answers)?;
+            Ok(check_answers(
+                &registry,
+                &[7],
+                &data_root,
+                &layout,
+                &answers,
+                |_, _| solve_unrecorded,
+            ))
+        };
+        let right = check("[7]\npart1 = 6440\n", false);
+        let wrong = check("[7]\npart1 = 6440\npart2 = 5906\n", false);
+        let unrecorded = check("", true);
+        fs::remove_dir_all(&data_root)?;
+
+        let right = right?;
+        assert!(right.passed());
+        assert_eq!(right.cases.len(), 1);
+        assert_eq!(right.skipped, [(7, 2)]);
+        assert!(right.unrecorded.is_empty());
+        let wrong = wrong?;
+        assert!(!wrong.passed());
+        assert_eq!(
+            wrong.to_string(),
+            "Day 7: Camel Cards: [Part 2] input answer mismatch, expected: 5906, actual: 5905\n"
+        );
+        let unrecorded = unrecorded?;
+        assert!(unrecorded.cases.is_empty());
+        assert_eq!(unrecorded.skipped, [(7, 1), (7, 2)]);
+        assert_eq!(unrecorded.unrecorded.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_error() {
+        let cases = cases();
+        assert!(day_error(&[&cases[0]]).is_none());
+        let error = day_error(&[&cases[0], &cases[1]]).unwrap().to_string();
+        assert_eq!(
+            error,
+            "Day 1: Pipes & <Brackets> #1: [Part 2] example answer mismatch, expected: 281, actual: 280"
+        );
+    }
+}
@@ -1,74 +1,115 @@
-use std::{fs::read_to_string, path::PathBuf};
-
-use crate::solver::{MultiSolver, Solver};
+use crate::{
+    context::RunContext,
+    metadata::{Difficulty, Metadata, RuntimeClass},
+    solver::{Answer, MultiSolver, Solver},
+    statement::puzzle_struct,
+    util::{
+        math::quadratic_integer_bounds,
+        parse::{first_int, ints},
+    },
+};
 use anyhow::{anyhow, Result};
 
-///     --- Day 6: Wait For It ---
-///
-/// The ferry quickly brings you across Island Island. After asking around, you discover that there is indeed normally a large pile of sand somewhere near here, but you don't see anything besides lots of water and the small island where the ferry has docked.
-///
-/// As you try to figure out what to do next, you notice a poster on a wall near the ferry dock. "Boat races! Open to the public! Grand prize is an all-expenses-paid trip to Desert Island!" That must be where the sand comes from! Best of all, the boat races are starting in just a few minutes.
-///
-/// You manage to sign up as a competitor in the boat races just in time. The organizer explains that it's not really a traditional race - instead, you will get a fixed amount of time during which your boat has to travel as far as it can, and you win if your boat goes the farthest.
-///
-/// As part of signing up, you get a sheet of paper (your puzzle input) that lists the time allowed for each race and also the best distance ever recorded in that race. To guarantee you win the grand prize, you need to make sure you go farther in each race than the current record holder.
-///
-/// The organizer brings you over to the area where the boat races are held. The boats are much smaller than you expected - they're actually toy boats, each with a big button on top. Holding down the button charges the boat, and releasing the button allows the boat to move. Boats move faster if their button was held longer, but time spent holding the button counts against the total race time. You can only hold the button at the start of the race, and boats don't move until the button is released.
-///
-/// For example:
-/// ```
-/// Time:      7  15   30
-/// Distance:  9  40  200
-/// ```
-/// This document describes three races:
-///
-///     - The first race lasts 7 milliseconds. The record distance in this race is 9 millimeters.
-///     - The second race lasts 15 milliseconds. The record distance in this race is 40 millimeters.
-///     - The third race lasts 30 milliseconds. The record distance in this race is 200 millimeters.
-///
-/// Your toy boat has a starting speed of zero millimeters per millisecond. For each whole millisecond you spend at the beginning of the race holding down the button, the boat's speed increases by one millimeter per millisecond.
-///
-/// So, because the first race lasts 7 milliseconds, you only have a few options:
-///
-///     - Don't hold the button at all (that is, hold it for 0 milliseconds) at the start of the race. The boat won't move; it will have traveled 0 millimeters by the end of the race.
-///     - Hold the button for 1 millisecond at the start of the race. Then, the boat will travel at a speed of 1 millimeter per millisecond for 6 milliseconds, reaching a total distance traveled of 6 millimeters.
-///     - Hold the button for 2 milliseconds, giving the boat a speed of 2 millimeters per millisecond. It will then get 5 milliseconds to move, reaching a total distance of 10 millimeters.
-///     - Hold the button for 3 milliseconds. After its remaining 4 milliseconds of travel time, the boat will have gone 12 millimeters.
-///     - Hold the button for 4 milliseconds. After its remaining 3 milliseconds of travel time, the boat will have gone 12 millimeters.
-///     - Hold the button for 5 milliseconds, causing the boat to travel a total of 10 millimeters.
-///     - Hold the button for 6 milliseconds, causing the boat to travel a total of 6 millimeters.
-///     - Hold the button for 7 milliseconds. That's the entire duration of the race. You never let go of the button. The boat can't move until you let go of the button. Please make sure you let go of the button so the boat gets to move. 0 millimeters.
-///
-/// Since the current record for this race is 9 millimeters, there are actually 4 different ways you could win: you could hold the button for 2, 3, 4, or 5 milliseconds at the start of the race.
-///
-/// In the second race, you could hold the button for at least 4 milliseconds and at most 11 milliseconds and beat the record, a total of 8 different ways to win.
-///
-/// In the third race, you could hold the button for at least 11 milliseconds and no more than 19 milliseconds and still beat the record, a total of 9 ways you could win.
-///
-/// To see how much margin of error you have, determine the number of ways you can beat the record in each race; in this example, if you multiply these values together, you get 288 (4 * 8 * 9).
-///
-/// Determine the number of ways you could beat the record in each race. What do you get if you multiply these numbers together?
-///
-///     --- Part Two ---
-///
-/// As the race is about to start, you realize the piece of paper with race times and record distances you got earlier actually just has very bad kerning. There's really only one race - ignore the spaces between the numbers on each line.
-///
-/// So, the example from before:
-/// ```
-/// Time:      7  15   30
-/// Distance:  9  40  200
-/// ````
-/// ...now instead means this:
-/// ```
-/// Time:      71530
-/// Distance:  940200
-/// ```
-/// Now, you have to figure out how many ways there are to win this single race. In this example, the race lasts for 71530 milliseconds and the record distance you need to beat is 940200 millimeters. You could hold the button anywhere from 14 to 71516 milliseconds and beat the record, a total of 71503 ways!
-///
-/// How many ways can you beat the record in this one much longer race?
-pub struct WaitForIt;
-pub struct PartOne;
-pub struct PartTwo;
+puzzle_struct! {
+    ///     --- Day 6: Wait For It ---
+    ///
+    /// The ferry quickly brings you across Island Island. After asking around, you discover that there is indeed normally a large pile of sand somewhere near here, but you don't see anything besides lots of water and the small island where the ferry has docked.
+    ///
+    /// As you try to figure out what to do next, you notice a poster on a wall near the ferry dock. "Boat races! Open to the public! Grand prize is an all-expenses-paid trip to Desert Island!" That must be where the sand comes from! Best of all, the boat races are starting in just a few minutes.
+    ///
+    /// You manage to sign up as a competitor in the boat races just in time. The organizer explains that it's not really a traditional race - instead, you will get a fixed amount of time during which your boat has to travel as far as it can, and you win if your boat goes the farthest.
+    ///
+    /// As part of signing up, you get a sheet of paper (your puzzle input) that lists the time allowed for each race and also the best distance ever recorded in that race. To guarantee you win the grand prize, you need to make sure you go farther in each race than the current record holder.
+    ///
+    /// The organizer brings you over to the area where the boat races are held. The boats are much smaller than you expected - they're actually toy boats, each with a big button on top. Holding down the button charges the boat, and releasing the button allows the boat to move. Boats move faster if their button was held longer, but time spent holding the button counts against the total race time. You can only hold the button at the start of the race, and boats don't move until the button is released.
+    ///
+    /// For example:
+    /// ```
+    /// Time:      7  15   30
+    /// Distance:  9  40  200
+    /// ```
+    /// This document describes three races:
+    ///
+    ///     - The first race lasts 7 milliseconds. The record distance in this race is 9 millimeters.
+    ///     - The second race lasts 15 milliseconds. The record distance in this race is 40 millimeters.
+    ///     - The third race lasts 30 milliseconds. The record distance in this race is 200 millimeters.
+    ///
+    /// Your toy boat has a starting speed of zero millimeters per millisecond. For each whole millisecond you spend at the beginning of the race holding down the button, the boat's speed increases by one millimeter per millisecond.
+    ///
+    /// So, because the first race lasts 7 milliseconds, you only have a few options:
+    ///
+    ///     - Don't hold the button at all (that is, hold it for 0 milliseconds) at the start of the race. The boat won't move; it will have traveled 0 millimeters by the end of the race.
+    ///     - Hold the button for 1 millisecond at the start of the race. Then, the boat will travel at a speed of 1 millimeter per millisecond for 6 milliseconds, reaching a total distance traveled of 6 millimeters.
+    ///     - Hold the button for 2 milliseconds, giving the boat a speed of 2 millimeters per millisecond. It will then get 5 milliseconds to move, reaching a total distance of 10 millimeters.
+    ///     - Hold the button for 3 milliseconds. After its remaining 4 milliseconds of travel time, the boat will have gone 12 millimeters.
+    ///     - Hold the button for 4 milliseconds. After its remaining 3 milliseconds of travel time, the boat will have gone 12 millimeters.
+    ///     - Hold the button for 5 milliseconds, causing the boat to travel a total of 10 millimeters.
+    ///     - Hold the button for 6 milliseconds, causing the boat to travel a total of 6 millimeters.
+    ///     - Hold the button for 7 milliseconds. That's the entire duration of the race. You never let go of the button. The boat can't move until you let go of the button. Please make sure you let go of the button so the boat gets to move. 0 millimeters.
+    ///
+    /// Since the current record for this race is 9 millimeters, there are actually 4 different ways you could win: you could hold the button for 2, 3, 4, or 5 milliseconds at the start of the race.
+    ///
+    /// In the second race, you could hold the button for at least 4 milliseconds and at most 11 milliseconds and beat the record, a total of 8 different ways to win.
+    ///
+    /// In the third race, you could hold the button for at least 11 milliseconds and no more than 19 milliseconds and still beat the record, a total of 9 ways you could win.
+    ///
+    /// To see how much margin of error you have, determine the number of ways you can beat the record in each race; in this example, if you multiply these values together, you get 288 (4 * 8 * 9).
+    ///
+    /// Determine the number of ways you could beat the record in each race. What do you get if you multiply these numbers together?
+    ///
+    ///     --- Part Two ---
+    ///
+    /// As the race is about to start, you realize the piece of paper with race times and record distances you got earlier actually just has very bad kerning. There's really only one race - ignore the spaces between the numbers on each line.
+    ///
+    /// So, the example from before:
+    /// ```
+    /// Time:      7  15   30
+    /// Distance:  9  40  200
+    /// ````
+    /// ...now instead means this:
+    /// ```
+    /// Time:      71530
+    /// Distance:  940200
+    /// ```
+    /// Now, you have to figure out how many ways there are to win this single race. In this example, the race lasts for 71530 milliseconds and the record distance you need to beat is 940200 millimeters. You could hold the button anywhere from 14 to 71516 milliseconds and beat the record, a total of 71503 ways!
+    ///
+    /// How many ways can you beat the record in this one much longer race?
+    pub struct WaitForIt;
+}
+pub struct PartOne(Strategy);
+pub struct PartTwo(Strategy);
+
+/// How the winning holds of a race are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Solves the quadratic for the range of winning holds.
+    ClosedForm,
+    /// Tries every hold, kept to cross-check the closed form.
+    Brute,
+}
+
+impl Strategy {
+    fn name(&self) -> &'static str {
+        match self {
+            Strategy::ClosedForm => "closed-form",
+            Strategy::Brute => "brute",
+        }
+    }
+
+    fn count_winning_strategies(&self, time: u64, record: u64) -> Result<i64> {
+        match self {
+            Strategy::ClosedForm => count_winning_strategies(time, record),
+            Strategy::Brute => Ok((0..=time)
+                .filter(|hold| hold * (time - hold) > record)
+                .count() as i64),
+        }
+    }
+}
+
+const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
 
 impl MultiSolver for WaitForIt {
     type PartOne = PartOne;
@@ -78,19 +119,84 @@ impl MultiSolver for WaitForIt {
         "Day 6: Wait for It"
     }
 
+    fn input_hint(&self) -> Option<&str> {
+        Some("two lines like 'Time: 7 15 30' and 'Distance: 9 40 200'")
+    }
+
+    fn statement(&self) -> Option<&'static str> {
+        Some(Self::STATEMENT)
+    }
+
+    fn day_number(&self) -> Option<u8> {
+        Some(6)
+    }
+
     fn get_part_one(&self) -> Self::PartOne {
-        PartOne
+        PartOne(Strategy::ClosedForm)
     }
 
     fn get_part_two(&self) -> Self::PartTwo {
-        PartTwo
+        PartTwo(Strategy::ClosedForm)
+    }
+
+    fn alternatives(&self, part: u32) -> Vec<Box<dyn Solver>> {
+        match part {
+            1 => vec![Box::new(PartOne(Strategy::Brute))],
+            2 => vec![Box::new(PartTwo(Strategy::Brute))],
+            _ => vec![],
+        }
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            tags: &["math"],
+            difficulty: Some(Difficulty::Easy),
+            runtime: RuntimeClass::Fast,
+        }
     }
 }
 
-fn calculate_distance_that_will_be_travelled(x: u64, threshold: u64) -> u64 {
-    let remaining_time = threshold.checked_sub(x).unwrap_or(0);
-    let dist = x * remaining_time;
-    dist
+/// Holding the button for `hold` of the race's `time` travels
+/// `hold * (time - hold)`, so the winning holds are where
+/// `-hold² + time * hold - record > 0`.
+fn count_winning_strategies(time: u64, record: u64) -> Result<i64> {
+    let bounds = quadratic_integer_bounds(-1, i64::try_from(time)?, -i64::try_from(record)?)?;
+    Ok(bounds.map_or(0, |holds| holds.end() - holds.start() + 1))
+}
+
+/// What follows the labels of the first two lines, the times and then the
+/// record distances.
+fn race_lines(input: &str) -> Result<[&str; 2]> {
+    let values = input
+        .lines()
+        .take(2)
+        .map(|line| line.split(':').nth(1).map(|line| line.trim()))
+        .collect::<Option<Vec<&str>>>();
+    match values.as_deref() {
+        Some(&[times, records]) => Ok([times, records]),
+        _ => Err(anyhow!(
+            "Expected a line of times and one of distances, each after a label and a ':'"
+        )),
+    }
+}
+
+/// Each race's time and record distance, for part 1.
+fn parse_races(input: &str) -> Result<Vec<(u64, u64)>> {
+    let [times, records] = race_lines(input)?;
+    let times = ints::<u64>(times)?;
+    let records = ints::<u64>(records)?;
+    Ok(times.into_iter().zip(records).collect())
+}
+
+/// The one race's time and record distance, for part 2.
+fn parse_race(input: &str) -> Result<(u64, u64)> {
+    let [time, record] = race_lines(input)?;
+    // the kerning is bad, the spaces between the numbers don't count
+    let unkerned = |value: &str| value.split_whitespace().collect::<String>();
+    Ok((
+        first_int::<u64>(&unkerned(time))?,
+        first_int::<u64>(&unkerned(record))?,
+    ))
 }
 
 impl Solver for PartOne {
@@ -98,45 +204,40 @@ impl Solver for PartOne {
         (1, "Count of combinations of winning strategies")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let data = read_to_string(filepath)?;
-        let raw_values = data
-            .lines()
-            .take(2)
-            .map(|line| {
-                line.split(':')
-                    .skip(1)
-                    .take(1)
-                    .next()
-                    .map(|line| line.trim())
-            })
-            .collect::<Option<Vec<&str>>>()
-            .ok_or(anyhow!("Faild to parse input!"))?;
-
-        let race_info = raw_values[0]
-            .split_whitespace()
-            .zip(raw_values[1].split_whitespace())
-            .map(|(time, record)| {
-                let time = time.parse::<u64>()?;
-                let record = record.parse::<u64>()?;
-                Ok((time, record))
-            })
-            .collect::<Result<Vec<(u64, u64)>>>()?;
+    fn implementation(&self) -> &str {
+        self.0.name()
+    }
+
+    /// A hold per millisecond of every race, for the brute force.
+    fn estimated_steps(&self, input: &str) -> Option<u64> {
+        match self.0 {
+            Strategy::Brute => Some(
+                parse_races(input)
+                    .ok()?
+                    .iter()
+                    .fold(0, |steps, (time, _)| steps.saturating_add(*time)),
+            ),
+            Strategy::ClosedForm => None,
+        }
+    }
+
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        let race_info = ctx.parse(|| parse_races(input))?;
 
         let mut winning_strategies = vec![];
-        for info in race_info {
-            let winning_strategy_count_for_race = (1..info.0).fold(0, |acc, x| {
-                let dist = calculate_distance_that_will_be_travelled(x, info.0);
-                if dist > info.1 {
-                    acc + 1
-                } else {
-                    acc
-                }
-            });
-            winning_strategies.push(winning_strategy_count_for_race);
+        for (time, record) in race_info {
+            winning_strategies.push(self.0.count_winning_strategies(time, record)?);
         }
 
-        Ok(winning_strategies.iter().product::<i32>())
+        let product = winning_strategies
+            .into_iter()
+            .try_fold(1i64, |product, count| product.checked_mul(count))
+            .ok_or_else(|| anyhow!("The product of the counts is too big"))?;
+        Ok(product.into())
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 288.into()))
     }
 }
 
@@ -145,64 +246,95 @@ impl Solver for PartTwo {
         (2, "Winning strategy count")
     }
 
-    fn get_solution(&self, filepath: &PathBuf) -> Result<i32> {
-        let data = read_to_string(filepath)?;
-        let raw_values = data
-            .lines()
-            .take(2)
-            .map(|line| {
-                line.split(':')
-                    .skip(1)
-                    .take(1)
-                    .next()
-                    .map(|line| line.trim())
-            })
-            .collect::<Option<Vec<&str>>>()
-            .ok_or(anyhow!("Faild to parse input!"))?;
-
-        let race_info_parts = raw_values[0]
-            .split_whitespace()
-            .zip(raw_values[1].split_whitespace())
-            .map(|(time, record)| Ok((time, record)))
-            .collect::<Result<Vec<(&str, &str)>>>()?;
-
-        let (time, record) = race_info_parts.iter().fold(
-            (String::new(), String::new()),
-            |(time, record), (time_part, record_part)| (time + time_part, record + record_part),
-        );
-        let time = time.parse::<u64>()?;
-        let record = record.parse::<u64>()?;
-        let wining_strategy_count = (1..time).fold(0, |acc, x| {
-            let dist = calculate_distance_that_will_be_travelled(x, time);
-            if dist > record {
-                acc + 1
-            } else {
-                acc
-            }
-        });
-        Ok(wining_strategy_count)
+    fn implementation(&self) -> &str {
+        self.0.name()
+    }
+
+    /// A hold per millisecond of the race, for the brute force.
+    fn estimated_steps(&self, input: &str) -> Option<u64> {
+        match self.0 {
+            Strategy::Brute => Some(parse_race(input).ok()?.0),
+            Strategy::ClosedForm => None,
+        }
+    }
+
+    fn solve(&self, input: &str, ctx: &RunContext) -> Result<Answer> {
+        let (time, record) = ctx.parse(|| parse_race(input))?;
+        Ok(self.0.count_winning_strategies(time, record)?.into())
+    }
+
+    fn example(&self) -> Option<(&'static str, Answer)> {
+        Some((EXAMPLE, 71503.into()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::assert_part;
+
+    #[test]
+    fn test_part_one_example() {
+        assert_part!(WaitForIt, 1, EXAMPLE, 288);
+    }
+
+    #[test]
+    fn test_part_two_example() {
+        assert_part!(WaitForIt, 2, EXAMPLE, 71503);
+    }
+
+    #[test]
+    fn test_estimated_steps() {
+        assert_eq!(PartOne(Strategy::Brute).estimated_steps(EXAMPLE), Some(52));
+        assert_eq!(
+            PartTwo(Strategy::Brute).estimated_steps(EXAMPLE),
+            Some(71530)
+        );
+        assert_eq!(PartTwo(Strategy::ClosedForm).estimated_steps(EXAMPLE), None);
+        assert_eq!(PartTwo(Strategy::Brute).estimated_steps("Time: 7"), None);
+    }
 
+    // inputs `cargo fuzz run day06` found panicking
+    #[test]
+    fn test_fuzz_regressions() {
+        let error = |solver: &dyn Solver| solver.solve_str("Time: 7").unwrap_err().to_string();
+        let expected =
+            "Expected a line of times and one of distances, each after a label and a ':'";
+        assert_eq!(error(&PartOne(Strategy::ClosedForm)), expected);
+        assert_eq!(error(&PartTwo(Strategy::ClosedForm)), expected);
+    }
+
+    // personal inputs aren't in every clone, see crate::input::real
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_one() -> Result<()> {
-        let filepath = std::path::PathBuf::from("data/06/input");
-        let solver = PartOne;
+        let filepath = crate::input::real(6);
+        let solver = PartOne(Strategy::ClosedForm);
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 252000);
+        assert_eq!(solution, 252000.into());
         Ok(())
     }
 
+    #[cfg(feature = "real-inputs")]
     #[test]
     fn test_part_two() -> Result<()> {
-        let filepath = std::path::PathBuf::from("data/06/input");
-        let solver = PartTwo;
+        let filepath = crate::input::real(6);
+        let solver = PartTwo(Strategy::ClosedForm);
         let solution = solver.get_solution(&filepath)?;
-        assert_eq!(solution, 36992486);
+        assert_eq!(solution, 36992486.into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_brute_matches_closed_form() -> Result<()> {
+        assert_part!(WaitForIt, PartOne(Strategy::Brute), EXAMPLE, 288);
+        assert_part!(WaitForIt, PartTwo(Strategy::Brute), EXAMPLE, 71503);
+        for (time, record) in [(7, 9), (15, 40), (30, 200), (30, 0), (4, 4), (0, 0)] {
+            assert_eq!(
+                Strategy::Brute.count_winning_strategies(time, record)?,
+                Strategy::ClosedForm.count_winning_strategies(time, record)?
+            );
+        }
         Ok(())
     }
 }
@@ -0,0 +1,132 @@
+//! Re-running something whenever files change, for `aoc watch`.
+
+use anyhow::{anyhow, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+/// How long to wait for a burst of events, eg. an editor's write then rename,
+/// to finish before re-running.
+pub const DEBOUNCE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Changed,
+    /// Ctrl-C was pressed.
+    Stop,
+}
+
+/// Waits out the rest of a burst of events that started with `first`,
+/// collapsing them into a single one. Stopping wins over any changes.
+pub fn debounce(events: &Receiver<Event>, first: Event, window: Duration) -> Event {
+    let mut event = first;
+    let mut deadline = Instant::now() + window;
+    while event != Event::Stop {
+        match events.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(next) => {
+                event = next;
+                deadline = Instant::now() + window;
+            }
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => return Event::Stop,
+        }
+    }
+    event
+}
+
+/// Calls `on_change` once straight away, then again each time any of `files`
+/// is written, created or replaced, until Ctrl-C is pressed.
+///
+/// The files' directories are watched rather than the files themselves,
+/// editors often save by replacing the file, which would end a watch on it.
+pub fn watch(files: &[PathBuf], mut on_change: impl FnMut()) -> Result<()> {
+    let files = files
+        .iter()
+        .map(|file| absolute(file))
+        .collect::<BTreeSet<_>>();
+    let (sender, events) = channel();
+
+    let changes = sender.clone();
+    let watched = files.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        let relevant = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) && event.paths.iter().any(|path| watched.contains(path));
+        if relevant {
+            // the receiver only goes away once watching has stopped
+            changes.send(Event::Changed).ok();
+        }
+    })?;
+    let directories = files
+        .iter()
+        .filter_map(|file| file.parent())
+        .collect::<BTreeSet<_>>();
+    for directory in directories {
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(|e| anyhow!("Can't watch {}: {}", directory.display(), e))?;
+    }
+    ctrlc::set_handler(move || {
+        sender.send(Event::Stop).ok();
+    })?;
+
+    on_change();
+    while let Ok(event) = events.recv() {
+        match debounce(&events, event, DEBOUNCE) {
+            Event::Changed => on_change(),
+            Event::Stop => break,
+        }
+    }
+    Ok(())
+}
+
+/// Event paths are absolute, so the files being watched need to be too.
+fn absolute(file: &Path) -> PathBuf {
+    std::env::current_dir()
+        .map(|dir| dir.join(file))
+        .unwrap_or_else(|_| file.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_debounce_collapses_bursts() {
+        let (sender, events) = channel();
+        for _ in 0..5 {
+            sender.send(Event::Changed).unwrap();
+        }
+        assert_eq!(
+            debounce(&events, Event::Changed, Duration::from_millis(10)),
+            Event::Changed
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_debounce_stops() {
+        let (sender, events) = channel();
+        sender.send(Event::Stop).unwrap();
+        sender.send(Event::Changed).unwrap();
+        assert_eq!(
+            debounce(&events, Event::Changed, Duration::from_millis(10)),
+            Event::Stop
+        );
+
+        drop(sender);
+        assert_eq!(
+            debounce(&events, Event::Changed, Duration::from_millis(10)),
+            Event::Stop
+        );
+    }
+}
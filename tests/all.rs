@@ -0,0 +1,107 @@
+//! Runs every day over the data root in tests/fixtures: inputs for days 1, 6
+//! and 7, a malformed one for day 4 and none for the rest. The table and
+//! summary list every day built, so this only checks a build of all of them.
+#![cfg(all(
+    feature = "day01",
+    feature = "day02",
+    feature = "day03",
+    feature = "day04",
+    feature = "day05",
+    feature = "day06",
+    feature = "day07"
+))]
+
+use aoc_2023::registry::Registry;
+use assert_cmd::Command;
+use std::{fs, path::Path};
+
+/// A copy of tests/fixtures in a temporary directory, as runs write their
+/// outcomes to the data root.
+fn fixture_tree() -> std::path::PathBuf {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let dir = std::env::temp_dir().join(format!("aoc-2023-all-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for entry in fs::read_dir(&fixtures).expect("tests/fixtures is missing") {
+        let day = entry.expect("can't list tests/fixtures").file_name();
+        fs::create_dir_all(dir.join(&day)).expect("can't create a day's directory");
+        fs::copy(
+            fixtures.join(&day).join("input"),
+            dir.join(&day).join("input"),
+        )
+        .expect("can't copy an input");
+    }
+    dir
+}
+
+#[test]
+fn test_all_days_over_a_data_root() {
+    let dir = fixture_tree();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+        .args(["all", "all"])
+        .arg(&dir)
+        .args(["--no-timing", "--color", "never"])
+        .current_dir(&dir)
+        .env("RUST_BACKTRACE", "0")
+        .env("RUST_LIB_BACKTRACE", "0")
+        .env_remove("AOC_DATA_DIR")
+        .env_remove("AOC_LAYOUT")
+        .env_remove("AOC_INPUT_NAME")
+        .write_stdin("")
+        // day 4's failure fails the run, but only once every day has run
+        .assert()
+        .code(4)
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines = stdout.lines().collect::<Vec<_>>();
+
+    for expected in [
+        "[Part 1] Sum of calibration values: 142",
+        "[Part 1, closed-form] Count of combinations of winning strategies: 288",
+        "[Part 2, closed-form] Winning strategy count: 71503",
+        "[Part 1] Total winnings: 6440",
+        "[Part 2] Total winnings: 5905",
+        "|   1 | Trebuchet?!                     |     142 |     142 |      | ?      |",
+        "|   2 | Cube Conundrum                  | skipped | skipped |      | –      |",
+        "|   4 | Scratchcards                    |  failed |  failed |      | !      |",
+        "|   7 | Camel Cards                     |    6440 |    5905 |      | ?      |",
+        "  Succeeded: 1, 6, 7",
+        "  Failed: 4",
+    ] {
+        assert!(
+            lines.contains(&expected),
+            "no {:?} in\n{}",
+            expected,
+            stdout
+        );
+    }
+    // every day built without an input in the fixtures, eg. slow-test-day's
+    let skipped = Registry::builtin()
+        .days()
+        .map(|(day, _)| day)
+        .filter(|day| ![1, 4, 6, 7].contains(day))
+        .map(|day| day.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let expected = format!("  Skipped (no input file): {}", skipped);
+    assert!(
+        lines.contains(&expected.as_str()),
+        "no {:?} in\n{}",
+        expected,
+        stdout
+    );
+    assert!(
+        stdout.contains(
+            "[Part 1] Total point value of scratchcards: failed: line 1: Invalid integer 'x6'"
+        ),
+        "{}",
+        stdout
+    );
+    // days past the last one built aren't listed at all
+    assert!(!stdout.contains("|   8 |"), "{}", stdout);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1 day(s) failed"),
+        "{:?}",
+        output
+    );
+}
@@ -0,0 +1,94 @@
+//! Checks every implemented day's real input against the answer recorded for
+//! it in `answers.toml`, as `aoc verify` does, when built with the
+//! `real-inputs` feature:
+//!
+//! ```text
+//! cargo test --features real-inputs --test answers
+//! ```
+//!
+//! The data root is the one `aoc` uses, so `AOC_DATA_DIR` and `aoc.toml` are
+//! honoured. Parts with no recorded answer are skipped, but the answers file
+//! has to be there.
+
+#![cfg(any(feature = "real-inputs", feature = "day07"))]
+
+use aoc_2023::{
+    answers::Answers,
+    layout::Layout,
+    registry::Registry,
+    verify::{self, AnswersReport},
+};
+use std::path::Path;
+
+/// Checks the answers recorded in `data_root` against its inputs.
+fn check_recorded(data_root: &Path, layout: &Layout) -> anyhow::Result<AnswersReport> {
+    let answers = Answers::load(data_root)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "No answers recorded in {}, record them with aoc verify --record",
+            data_root.display()
+        )
+    })?;
+    let report = verify::check_answers(
+        &Registry::builtin(),
+        &[],
+        data_root,
+        layout,
+        &answers,
+        |_, _| false,
+    );
+    for (day, part) in &report.skipped {
+        eprintln!("Day {} part {} has no answer recorded, skipped", day, part);
+    }
+    Ok(report)
+}
+
+#[cfg(feature = "real-inputs")]
+#[test]
+fn test_recorded_answers() -> anyhow::Result<()> {
+    use aoc_2023::config::{Overrides, Settings};
+
+    let settings = Settings::from_env(&Overrides::default())?;
+    let report = check_recorded(&settings.data_root(), &settings.layout)?;
+    assert!(
+        report.passed(),
+        "{} part(s) didn't give their recorded answer:\n{}",
+        report.failures().count(),
+        report
+    );
+    Ok(())
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_wrong_recorded_answer_is_caught() -> anyhow::Result<()> {
+    let data_root =
+        std::env::temp_dir().join(format!("aoc-2023-wrong-answer-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07"))?;
+    std::fs::write(
+        data_root.join("07").join("input"),
+        "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n",
+    )?;
+    std::fs::write(
+        data_root.join("answers.toml"),
+        "year = 2023\n[7]\npart1 = 6440\npart2 = 5906\n",
+    )?;
+    let report = check_recorded(&data_root, &Layout::default());
+    let missing = check_recorded(&data_root.join("empty"), &Layout::default());
+    std::fs::remove_dir_all(&data_root)?;
+
+    let report = report?;
+    assert!(!report.passed());
+    assert_eq!(report.failures().count(), 1);
+    let failure = report.failures().next().unwrap();
+    assert_eq!((failure.day, failure.part), (7, 2));
+    assert!(
+        report
+            .to_string()
+            .starts_with("Day 7: Camel Cards: [Part 2]"),
+        "{}",
+        report
+    );
+    // no answers file is a failure, not nothing to check
+    assert!(missing.is_err());
+    Ok(())
+}
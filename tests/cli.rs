@@ -0,0 +1,1706 @@
+//! Runs the built binary end to end.
+
+use aoc_2023::runner::{RunResult, Status};
+use std::{
+    io::{ErrorKind, Write},
+    process::{Command, Output, Stdio},
+};
+
+#[cfg(feature = "day07")]
+const CAMEL_CARDS_EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+fn aoc_with_stdin(args: &[&str], stdin: &str) -> Output {
+    run_with_stdin(
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023")).args(args),
+        stdin,
+    )
+}
+
+/// Like [`aoc_with_stdin`], with `data_root` as the data root unless the args
+/// give one, so runs that keep state there leave the repo's data/ alone.
+#[cfg(feature = "day07")]
+fn aoc_with_data_root(args: &[&str], stdin: &str, data_root: &std::path::Path) -> Output {
+    run_with_stdin(
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(args)
+            .env("AOC_DATA_DIR", data_root),
+        stdin,
+    )
+}
+
+fn run_with_stdin(command: &mut Command, stdin: &str) -> Output {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start aoc-2023");
+    let written = child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(stdin.as_bytes());
+    // runs rejected before reading their input close stdin early
+    if let Err(e) = written {
+        assert_eq!(e.kind(), ErrorKind::BrokenPipe, "failed to write stdin");
+    }
+    child.wait_with_output().expect("aoc-2023 didn't finish")
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_input_from_stdin() {
+    let output = aoc_with_stdin(
+        &["7", "part1", "-", "--no-timing", "--color", "never"],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        vec!["Day 7: Camel Cards", "[Part 1] Total winnings: 6440"]
+    );
+
+    let output = aoc_with_stdin(&["7", "-", "--no-timing"], CAMEL_CARDS_EXAMPLE);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("[Part 2] Total winnings: 5905"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_day_required_without_a_terminal() {
+    // stdin is piped, so there's no one to pick a day
+    let output = aoc_with_stdin(&[], "");
+    assert_eq!(output.status.code(), Some(2), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("required arguments were not provided"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_stdin_rejected_for_all_days() {
+    let output = aoc_with_stdin(&["all", "-"], "");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("can't read from stdin"), "{}", stderr);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_expected_answers_match() {
+    for args in [
+        &["7", "-", "--no-timing", "--answer", "6440,5905"][..],
+        &[
+            "7",
+            "-",
+            "--no-timing",
+            "--answer",
+            "6440",
+            "--answer",
+            "5905",
+        ],
+        &["7", "part2", "-", "--no-timing", "--answer", "5905"],
+    ] {
+        let output = aoc_with_stdin(args, CAMEL_CARDS_EXAMPLE);
+        assert!(output.status.success(), "{:?}: {:?}", args, output);
+    }
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_expected_answer_mismatch() {
+    let output = aoc_with_stdin(
+        &["7", "-", "--no-timing", "--answer", "6440,1"],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    assert!(!output.status.success());
+    // the answers are still printed as usual
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("[Part 2] Total winnings: 5905"),
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "[Part 2] doesn't match the expected answer\n  expected:    1\n  actual:   5905\n            ^^^^\n"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("[Part 1] doesn't match"), "{}", stderr);
+
+    let hint = |answers: &str| {
+        let output = aoc_with_stdin(&["7", "-", "--answer", answers], CAMEL_CARDS_EXAMPLE);
+        assert_eq!(output.status.code(), Some(5), "{:?}", output);
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+    assert!(hint("6440,5904").contains("  hint: it's off by one\n"));
+    let swapped = hint("5905,6440");
+    assert_eq!(
+        swapped
+            .matches("hint: it's the other part's answer, did you run the wrong part?")
+            .count(),
+        2,
+        "{}",
+        swapped
+    );
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_malformed_expected_answers() {
+    for args in [
+        &["7", "part1", "-", "--answer", ""][..],
+        &["7", "part1", "-", "--answer", "6440,"],
+        // one answer per part that runs
+        &["7", "part1", "-", "--answer", "6440,5905"],
+        &["7", "-", "--answer", "6440"],
+    ] {
+        let output = aoc_with_stdin(args, CAMEL_CARDS_EXAMPLE);
+        assert!(!output.status.success(), "{:?}: {:?}", args, output);
+        assert!(output.stdout.is_empty(), "{:?}: {:?}", args, output);
+    }
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_json_results() {
+    let output = aoc_with_stdin(&["7", "-", "--json"], CAMEL_CARDS_EXAMPLE);
+    assert!(output.status.success(), "{:?}", output);
+    let results: Vec<RunResult> =
+        serde_json::from_slice(&output.stdout).expect("stdout is only the JSON results");
+    assert_eq!(
+        results
+            .iter()
+            .map(|result| (
+                result.day,
+                result.part,
+                result.answer.clone(),
+                &result.status
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (7, 1, Some(6440.into()), &Status::Solved),
+            (7, 2, Some(5905.into()), &Status::Solved),
+        ]
+    );
+    assert_eq!(results[0].title, "Day 7: Camel Cards");
+    assert!(results.iter().all(|result| result.timing.is_some()));
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_bench_json() {
+    let output = aoc_with_stdin(
+        &["bench", "7", "all", "-", "-n", "2", "--json"],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let records = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("a JSON line per part"))
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 2);
+    // answers that are numbers stay numbers, not strings
+    assert_eq!(records[0]["answer"], serde_json::json!(6440));
+    assert_eq!(records[1]["answer"], serde_json::json!(5905));
+    assert!(records.iter().all(|record| record["samples"] == 1));
+    assert!(records.iter().all(|record| record["median_ms"].is_f64()));
+}
+
+#[test]
+fn test_json_results_for_all_days() {
+    let data_root = std::env::temp_dir().join("aoc-2023-no-such-data");
+    let output = aoc_with_stdin(&["all", &data_root.to_string_lossy(), "--json"], "");
+    assert!(output.status.success(), "{:?}", output);
+    let results: Vec<RunResult> =
+        serde_json::from_slice(&output.stdout).expect("stdout is only the JSON results");
+    // without any inputs every day is in there, skipped
+    assert!(results
+        .iter()
+        .all(|result| matches!(result.status, Status::Skipped(_))));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Skipped (no input file)"), "{}", stderr);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_skip_days() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-skip-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07")).unwrap();
+    // the day would fail if it ran
+    std::fs::write(data_root.join("07").join("input"), "not camel cards\n").unwrap();
+    let output = aoc_with_stdin(
+        &["all", &data_root.to_string_lossy(), "--skip", "7", "--json"],
+        "",
+    );
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let results: Vec<RunResult> =
+        serde_json::from_slice(&output.stdout).expect("stdout is only the JSON results");
+    let skipped = results
+        .iter()
+        .filter(|result| result.day == 7)
+        .map(|result| &result.status)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        skipped,
+        vec![&Status::Skipped(aoc_2023::runner::LEFT_OUT.to_string()); 2]
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Left out: 7"), "{}", stderr);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_rerun_failed() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-rerun-{}", std::process::id()));
+    let input = data_root.join("07").join("input");
+    std::fs::create_dir_all(input.parent().unwrap()).unwrap();
+    std::fs::write(&input, "not camel cards\n").unwrap();
+    let root = data_root.to_string_lossy();
+    let failing = aoc_with_stdin(&["all", &root], "");
+    let rerun = aoc_with_stdin(&["all", &root, "--rerun-failed", "--json"], "");
+    // a failure on an input that has changed since is stale
+    std::fs::write(&input, CAMEL_CARDS_EXAMPLE).unwrap();
+    let stale = aoc_with_stdin(&["all", &root, "--rerun-failed"], "");
+    std::fs::write(&input, "not camel cards\n").unwrap();
+    let reset = aoc_with_stdin(&["all", &root, "--skip", "7", "--reset"], "");
+    let after_reset = aoc_with_stdin(&["all", &root, "--rerun-failed"], "");
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(!failing.status.success(), "{:?}", failing);
+    let results: Vec<RunResult> =
+        serde_json::from_slice(&rerun.stdout).expect("stdout is only the JSON results");
+    let days = results
+        .iter()
+        .map(|result| (result.day, result.part))
+        .collect::<Vec<_>>();
+    assert_eq!(days, vec![(7, 1), (7, 2)]);
+    assert!(
+        results.iter().all(|result| result.status != Status::Solved),
+        "{:?}",
+        results
+    );
+    for output in [stale, after_reset] {
+        assert!(output.status.success(), "{:?}", output);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("nothing to rerun"), "{}", stdout);
+    }
+    assert!(reset.status.success(), "{:?}", reset);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_gzipped_input() {
+    use flate2::{write::GzEncoder, Compression};
+
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-gzip-{}", std::process::id()));
+    let input = data_root.join("07").join("input.gz");
+    std::fs::create_dir_all(input.parent().unwrap()).unwrap();
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(CAMEL_CARDS_EXAMPLE.as_bytes()).unwrap();
+    std::fs::write(&input, encoder.finish().unwrap()).unwrap();
+    let root = data_root.to_string_lossy();
+    let explicit = aoc_with_data_root(
+        &["7", &input.to_string_lossy(), "--answer", "6440,5905"],
+        "",
+        &data_root,
+    );
+    // input.gz stands in for a missing input
+    let default = aoc_with_stdin(&["7", "--data-dir", &root, "--answer", "6440,5905"], "");
+    let all = aoc_with_stdin(&["all", &root, "--json"], "");
+    std::fs::write(&input, CAMEL_CARDS_EXAMPLE).unwrap();
+    let corrupt = aoc_with_stdin(&["7", "--data-dir", &root], "");
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(explicit.status.success(), "{:?}", explicit);
+    assert!(default.status.success(), "{:?}", default);
+    let results: Vec<RunResult> =
+        serde_json::from_slice(&all.stdout).expect("stdout is only the JSON results");
+    let day7 = results
+        .iter()
+        .filter(|result| result.day == 7)
+        .map(|result| {
+            (
+                &result.status,
+                result.answer.as_ref().map(|a| a.to_string()),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        day7,
+        vec![
+            (&Status::Solved, Some("6440".to_string())),
+            (&Status::Solved, Some("5905".to_string())),
+        ]
+    );
+    assert!(!corrupt.status.success(), "{:?}", corrupt);
+    // the failure is reported with each part's result
+    let stdout = String::from_utf8_lossy(&corrupt.stdout);
+    assert!(
+        stdout.contains(&format!("{}: can't decompress it as gzip", input.display())),
+        "{}",
+        stdout
+    );
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_limit_lines() {
+    let output = aoc_with_stdin(
+        &[
+            "7",
+            "part1",
+            "-",
+            "--limit-lines",
+            "2",
+            "--no-timing",
+            "--color",
+            "never",
+        ],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        vec![
+            "Day 7: Camel Cards",
+            "(from only the first 2 line(s) of the input)",
+            "[Part 1] Total winnings: 2133"
+        ]
+    );
+
+    let output = aoc_with_stdin(
+        &["7", "-", "--limit-lines", "2", "--json"],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    let results: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("stdout is only the JSON results");
+    assert!(
+        results
+            .iter()
+            .all(|result| result["truncated_to_lines"] == 2),
+        "{:?}",
+        results
+    );
+    // nothing's marked when there's nothing to cut
+    let output = aoc_with_stdin(
+        &["7", "-", "--limit-lines", "5", "--json"],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("truncated_to_lines"), "{}", stdout);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_comments_in_input() {
+    let annotated = format!(
+        "# the hands from the statement\n{}QQQJA 483 # the joker case\n",
+        CAMEL_CARDS_EXAMPLE.trim_end_matches("QQQJA 483\n")
+    );
+    let allowed = aoc_with_stdin(
+        &["7", "-", "--allow-comments", "--answer", "6440,5905"],
+        &annotated,
+    );
+    assert!(allowed.status.success(), "{:?}", allowed);
+    // real inputs don't have comments, so they're only stripped when asked
+    let rejected = aoc_with_stdin(&["7", "-"], &annotated);
+    assert_eq!(rejected.status.code(), Some(4), "{:?}", rejected);
+    let prefixed = aoc_with_stdin(
+        &[
+            "7",
+            "-",
+            "--allow-comments",
+            "--comment-prefix",
+            "//",
+            "--answer",
+            "6440,5905",
+        ],
+        &annotated.replace('#', "//"),
+    );
+    assert!(prefixed.status.success(), "{:?}", prefixed);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_hide_answers() {
+    let hidden = |args: &[&str]| {
+        let args = [&["--hide-answers", "--color", "never"], args].concat();
+        aoc_with_stdin(&args, CAMEL_CARDS_EXAMPLE)
+    };
+    let leaked = |output: &Output| {
+        let printed = [&output.stdout[..], &output.stderr[..]].concat();
+        let printed = String::from_utf8_lossy(&printed);
+        ["6440", "5905"]
+            .iter()
+            .any(|answer| printed.contains(answer))
+    };
+
+    let output = hidden(&["7", "-", "--no-timing"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Day 7: Camel Cards\n\
+         [Part 1] Total winnings: •••• (4 digits)\n\
+         [Part 2] Total winnings: •••• (4 digits)\n"
+    );
+    assert!(!leaked(&output));
+
+    // checking an answer says whether it matched, but not what either was
+    let output = hidden(&["7", "-", "--answer", "6441,5905"]);
+    assert_eq!(output.status.code(), Some(5), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("[Part 1] doesn't match the expected answer"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("6441") && !leaked(&output), "{}", stderr);
+
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-hidden-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07")).unwrap();
+    std::fs::write(data_root.join("07").join("input"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let output_file = data_root.join("results.json");
+    let table = hidden(&[
+        "all",
+        &data_root.to_string_lossy(),
+        "--format",
+        "csv",
+        "--output",
+        &output_file.to_string_lossy(),
+    ]);
+    let written = std::fs::read_to_string(&output_file).unwrap();
+    // JSON is for scripts, so it keeps the answers
+    let json = hidden(&["7", "-", "--json"]);
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(table.status.success(), "{:?}", table);
+    let stdout = String::from_utf8_lossy(&table.stdout);
+    assert!(
+        stdout.contains("7,Camel Cards,•••• (4 digits),•••• (4 digits)"),
+        "{}",
+        stdout
+    );
+    assert!(!leaked(&table));
+    assert!(
+        written.contains("6440") && written.contains("5905"),
+        "{}",
+        written
+    );
+    assert!(leaked(&json));
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_paste_without_a_clipboard() {
+    // as over SSH, so the test never sees a real clipboard
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+        .args(["7", "--paste"])
+        .env_remove("DISPLAY")
+        .env_remove("WAYLAND_DISPLAY")
+        .output()
+        .expect("failed to run aoc-2023");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if cfg!(feature = "clipboard") {
+        assert_eq!(output.status.code(), Some(3), "{:?}", output);
+        assert!(stdout.contains("Can't read the clipboard"), "{}", stdout);
+    } else {
+        assert_eq!(output.status.code(), Some(2), "{:?}", output);
+        assert!(
+            stdout.contains("This build can't read the clipboard"),
+            "{}",
+            stdout
+        );
+    }
+
+    let several = aoc_with_stdin(&["all", "--paste"], "");
+    assert_eq!(several.status.code(), Some(2), "{:?}", several);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_summary_table() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-summary-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07")).unwrap();
+    std::fs::write(data_root.join("07").join("input"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let root = data_root.to_string_lossy();
+    let run = || {
+        aoc_with_stdin(
+            &[
+                "all",
+                &root,
+                "--hide-answers",
+                "--no-timing",
+                "--color",
+                "never",
+            ],
+            "",
+        )
+    };
+    let unchecked = run();
+    let answers = data_root.join("answers.toml");
+    std::fs::write(&answers, "[7]\npart1 = 6440\npart2 = 5905\n").unwrap();
+    let verified = run();
+    std::fs::write(&answers, "[7]\npart1 = 6440\npart2 = 5906\n").unwrap();
+    let wrong = run();
+    std::fs::write(&answers, "[7]\npart3 = 1\n").unwrap();
+    let bad = run();
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    let stdout = |output: &Output| String::from_utf8_lossy(&output.stdout).into_owned();
+    // the other days have no input, so are skipped
+    let row = |output: &Output| {
+        stdout(output)
+            .lines()
+            .find(|line| line.starts_with("|   7 | Camel Cards "))
+            .map(|line| {
+                line.trim_end_matches('|')
+                    .split(" | ")
+                    .map(|cell| cell.trim().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    assert!(unchecked.status.success(), "{:?}", unchecked);
+    assert_eq!(
+        row(&unchecked)[2..],
+        ["•••• (4 digits)", "•••• (4 digits)", "", "?"]
+    );
+    assert!(!stdout(&unchecked).contains("stars"));
+
+    assert!(verified.status.success(), "{:?}", verified);
+    assert_eq!(row(&verified)[5], "✓");
+    let printed = stdout(&verified);
+    assert!(printed.contains("\n2/50 stars\n"), "{}", printed);
+    assert!(
+        !printed.contains("6440") && !printed.contains("5905"),
+        "{}",
+        printed
+    );
+
+    assert_eq!(row(&wrong)[5], "✗");
+    assert!(stdout(&wrong).contains("\n1/50 stars\n"));
+    assert!(!bad.status.success(), "{:?}", bad);
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("Bad answers file"));
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_html_report() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-report-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07")).unwrap();
+    std::fs::write(data_root.join("07").join("input"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let report = data_root.join("report.html");
+    let output = aoc_with_stdin(
+        &[
+            "all",
+            &data_root.to_string_lossy(),
+            "--report",
+            &report.to_string_lossy(),
+        ],
+        "",
+    );
+    let html = std::fs::read_to_string(&report);
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let html = html.unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"), "{}", html);
+    assert!(
+        html.contains("<span class=\"answer\">6440</span>"),
+        "{}",
+        html
+    );
+    assert!(html.contains(&format!(
+        "<dt>Day 7 input</dt><dd class=\"input\"><code>{}</code></dd>",
+        aoc_2023::input::hash(CAMEL_CARDS_EXAMPLE)
+    )));
+    assert!(!html.contains("{{"), "{}", html);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_timings_log() {
+    let log = std::env::temp_dir().join(format!("aoc-2023-timings-{}.jsonl", std::process::id()));
+    let log_path = log.to_string_lossy();
+    let first = aoc_with_stdin(&["7", "-", "--timings-log", &log_path], CAMEL_CARDS_EXAMPLE);
+    let second = aoc_with_stdin(&["7", "-", "--timings-log", &log_path], CAMEL_CARDS_EXAMPLE);
+    let text = std::fs::read_to_string(&log);
+    let summary = aoc_with_stdin(&["timings", "summarize", &log_path], "");
+    std::fs::remove_file(&log).unwrap();
+
+    assert!(
+        first.status.success() && second.status.success(),
+        "{:?}",
+        second
+    );
+    let text = text.unwrap();
+    let records = text
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 4, "{}", text);
+    assert_eq!(records[2]["day"], 7);
+    assert_eq!(records[3]["part"], 2);
+    assert_eq!(
+        records[0]["answer_hash"],
+        aoc_2023::input::hash("6440").as_str()
+    );
+    // the answers themselves stay out of the log
+    assert!(!text.contains("6440") && !text.contains("5905"), "{}", text);
+
+    assert!(summary.status.success(), "{:?}", summary);
+    let summary = String::from_utf8_lossy(&summary.stdout);
+    let lines = summary.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4, "{}", summary);
+    assert!(lines[0].starts_with("| Day | Part | Runs |"), "{}", summary);
+    for (line, part) in lines[2..].iter().zip(["1", "2"]) {
+        let cells = line
+            .trim_matches('|')
+            .split('|')
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        assert_eq!(cells[..3], ["7", part, "2"], "{}", summary);
+        assert!(cells[5].starts_with(['+', '-']), "{}", summary);
+    }
+}
+
+#[test]
+fn test_verify_reports() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-verify-{}", std::process::id()));
+    std::fs::create_dir_all(&data_root).unwrap();
+    let root = data_root.to_string_lossy();
+    let tap_file = data_root.join("results.tap");
+    let junit = aoc_with_stdin(&["verify", "--data-dir", &root, "--format", "junit"], "");
+    let tap = aoc_with_stdin(
+        &[
+            "verify",
+            "--data-dir",
+            &root,
+            "--format",
+            "tap",
+            "--output",
+            &tap_file.to_string_lossy(),
+        ],
+        "",
+    );
+    let tap_report = std::fs::read_to_string(&tap_file);
+    let plain = aoc_with_stdin(&["verify", "--data-dir", &root, "--output", "x.xml"], "");
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(junit.status.success(), "{:?}", junit);
+    let xml = String::from_utf8_lossy(&junit.stdout);
+    let document = roxmltree::Document::parse(&xml).unwrap();
+    let suites = document.root_element();
+    assert_eq!(suites.tag_name().name(), "testsuites");
+    assert_eq!(suites.attribute("failures"), Some("0"));
+    let tests = suites
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+        .count();
+    assert_eq!(suites.attribute("tests"), Some(tests.to_string().as_str()));
+
+    // written to a file, the lines per day are still printed
+    assert!(tap.status.success(), "{:?}", tap);
+    let tap_report = tap_report.unwrap();
+    assert!(tap_report.starts_with(&format!("TAP version 13\n1..{}\n", tests)));
+    assert!(!tap_report.contains("not ok"), "{}", tap_report);
+    assert!(!String::from_utf8_lossy(&tap.stdout).contains("TAP"));
+
+    assert_eq!(plain.status.code(), Some(2), "{:?}", plain);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_input_layout() {
+    let data_root =
+        std::env::temp_dir().join(format!("aoc-2023-input-name-{}", std::process::id()));
+    let dir = data_root.join("07");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("day07.txt"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let root = data_root.to_string_lossy();
+    let all = aoc_with_stdin(
+        &["all", &root, "--input-name", "day{day:02}.txt", "--json"],
+        "",
+    );
+    let single = aoc_with_stdin(
+        &[
+            "7",
+            "--data-dir",
+            &root,
+            "--input-name",
+            "day{day:02}.txt",
+            "--json",
+        ],
+        "",
+    );
+    // the default name isn't there
+    let default = aoc_with_stdin(&["7", "--data-dir", &root], "");
+    std::fs::write(data_root.join("input07.txt"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let flat = aoc_with_stdin(&["7", "--data-dir", &root, "--layout", "flat"], "");
+    let missing = aoc_with_stdin(&["all", &root, "--layout", "{day}.in", "--json"], "");
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(all.status.success(), "{:?}", all);
+    let results: Vec<RunResult> = serde_json::from_slice(&all.stdout).unwrap();
+    for result in &results {
+        if result.day == 7 {
+            assert_eq!(result.status, Status::Solved);
+        } else {
+            assert!(matches!(result.status, Status::Skipped(_)), "{:?}", result);
+        }
+    }
+    assert!(single.status.success(), "{:?}", single);
+    let results: Vec<RunResult> = serde_json::from_slice(&single.stdout).unwrap();
+    assert_eq!(results[1].answer, Some(5905.into()));
+    assert!(!default.status.success());
+    let stderr = String::from_utf8_lossy(&default.stderr);
+    assert!(stderr.contains("input doesn't exist"), "{}", stderr);
+    assert!(stderr.contains("the nested layout"), "{}", stderr);
+
+    assert!(flat.status.success(), "{:?}", flat);
+    let stderr = String::from_utf8_lossy(&missing.stderr);
+    assert!(stderr.contains("with the layout {day}.in"), "{}", stderr);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_exit_codes() {
+    let data_root =
+        std::env::temp_dir().join(format!("aoc-2023-exit-codes-{}", std::process::id()));
+    // where the failed run of a missing input is remembered
+    std::fs::create_dir_all(&data_root).unwrap();
+    let missing = std::env::temp_dir().join(format!("aoc-2023-missing-{}", std::process::id()));
+    let missing = missing.to_string_lossy();
+    for (args, stdin, code) in [
+        (&["7", "-", "--no-timing"][..], CAMEL_CARDS_EXAMPLE, 0),
+        (&["7", "--bogus"], "", 2),
+        (&["all", "--answer", "1"], "", 2),
+        (&["7", &missing], "", 3),
+        (&["7", "--data-dir", &missing], "", 3),
+        (&["7", "-"], "32T3K 765 \n", 4),
+        (
+            &["7", "part1", "-", "--answer", "1"],
+            CAMEL_CARDS_EXAMPLE,
+            5,
+        ),
+        (&["7", "-", "--timeout", "0ms"], CAMEL_CARDS_EXAMPLE, 6),
+        (&["25"], "", 7),
+    ] {
+        let output = aoc_with_data_root(args, stdin, &data_root);
+        assert_eq!(output.status.code(), Some(code), "{:?}: {:?}", args, output);
+    }
+    std::fs::remove_dir_all(&data_root).unwrap();
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_baselines() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-baselines-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("data").join("07")).unwrap();
+    // big enough that any part takes well over the 1ms noise floor
+    let cards = "23456789TJQKA".as_bytes();
+    let hands = (0..20_000_usize)
+        .map(|i| {
+            let hand = (0..5)
+                .map(|j| cards[(i * 7 + j * j * 31 + i / 13) % cards.len()] as char)
+                .collect::<String>();
+            format!("{} {}\n", hand, i % 1000 + 1)
+        })
+        .collect::<String>();
+    let input = dir.join("data").join("07").join("input");
+    std::fs::write(&input, &hands).unwrap();
+    let aoc = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(["all", "--data-dir", "data"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+
+    let saved = aoc(&["--save-baseline", "main"]);
+    assert!(saved.status.success(), "{:?}", saved);
+    let file = dir.join(".aoc").join("baselines").join("main.json");
+    let text = std::fs::read_to_string(&file).expect("the baseline was saved");
+    let mut baseline: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let parts = baseline["parts"].as_array_mut().unwrap();
+    assert_eq!(parts.len(), 2);
+    assert!(parts
+        .iter()
+        .all(|part| part["day"] == 7 && part["samples"] == 1));
+
+    let compared = aoc(&[
+        "--compare-baseline",
+        "main",
+        "--regression-threshold",
+        "1000",
+    ]);
+    assert!(compared.status.success(), "{:?}", compared);
+    let stdout = String::from_utf8_lossy(&compared.stdout);
+    assert!(
+        stdout.contains("Compared with baseline 'main':"),
+        "{}",
+        stdout
+    );
+
+    // a baseline a hundred times faster than now is a regression
+    for part in parts.iter_mut() {
+        part["duration_ms"] = (part["duration_ms"].as_f64().unwrap() / 100.0).into();
+    }
+    std::fs::write(&file, baseline.to_string()).unwrap();
+    let regressed = aoc(&["--compare-baseline", "main", "--bench", "2"]);
+    assert_eq!(regressed.status.code(), Some(8), "{:?}", regressed);
+    let stdout = String::from_utf8_lossy(&regressed.stdout);
+    assert_eq!(stdout.matches("| regressed |").count(), 2, "{}", stdout);
+
+    // and times on another input aren't compared at all
+    std::fs::write(&input, &hands[..hands.len() / 2]).unwrap();
+    let refused = aoc(&["--compare-baseline", "main"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(refused.status.code(), Some(1), "{:?}", refused);
+    let stderr = String::from_utf8_lossy(&refused.stderr);
+    assert!(
+        stderr.contains("The input of day(s) 7 isn't the one the baseline was saved with"),
+        "{}",
+        stderr
+    );
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_doctor() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-doctor-{}", std::process::id()));
+    for (day, input) in [("07", "32T3K 765"), ("25", "jqt: rhn xhk nvd\n")] {
+        std::fs::create_dir_all(data_root.join(day)).unwrap();
+        std::fs::write(data_root.join(day).join("input"), input).unwrap();
+    }
+    let output = aoc_with_stdin(
+        &["doctor", &data_root.to_string_lossy(), "--color", "never"],
+        "",
+    );
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(!output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let row = |day: &str| {
+        stdout
+            .lines()
+            .find(|line| line.trim_start().starts_with(&format!("{} ", day)))
+            .unwrap_or_else(|| panic!("no row for day {} in {}", day, stdout))
+            .to_string()
+    };
+    assert!(
+        row("7").ends_with("input doesn't end with a newline"),
+        "{}",
+        stdout
+    );
+    assert!(
+        row("25").ends_with("has an input but isn't implemented"),
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Fail: "), "{}", stderr);
+}
+
+#[test]
+fn test_table_formats() {
+    let data_root = std::env::temp_dir().join("aoc-2023-no-such-data");
+    let data_root = data_root.to_string_lossy();
+    let output = aoc_with_stdin(&["all", &data_root, "--format", "csv"], "");
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next(), Some("Day,Title,Part 1,Part 2,Time"));
+    // nothing but the table, every day skipped without its input
+    assert!(
+        lines.all(|line| line.ends_with(",skipped,skipped,")),
+        "{}",
+        stdout
+    );
+
+    let output = aoc_with_stdin(&["all", &data_root, "--format", "markdown"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("| Day | Title "), "{}", stdout);
+    assert!(!stdout.contains("Summary"), "{}", stdout);
+
+    let output = aoc_with_stdin(&["all", "--format", "csv", "--json"], "");
+    assert!(!output.status.success());
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_color() {
+    let output = aoc_with_stdin(
+        &["7", "part1", "-", "--color", "always"],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b["), "{}", stdout);
+    assert!(stdout.contains("6440"), "{}", stdout);
+
+    // not a terminal, so off by default
+    let output = aoc_with_stdin(&["7", "part1", "-"], CAMEL_CARDS_EXAMPLE);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains('\x1b'));
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_output_file() {
+    let path = std::env::temp_dir().join(format!("aoc-2023-output-{}.json", std::process::id()));
+    let output_path = path.to_string_lossy();
+    let run = |args: &[&str]| {
+        let output = aoc_with_stdin(args, CAMEL_CARDS_EXAMPLE);
+        assert!(output.status.success(), "{:?}", output);
+        // the usual output still goes to the terminal
+        assert!(String::from_utf8_lossy(&output.stdout).contains("[Part 1] Total winnings: 6440"));
+    };
+    run(&["7", "-", "--output", &output_path]);
+    let written = std::fs::read_to_string(&path).expect("results were written");
+    std::fs::remove_file(&path).expect("results file can be removed");
+    run(&["7", "part1", "-", "--output", &output_path, "--append"]);
+    run(&["7", "-", "--output", &output_path, "--append"]);
+    let appended = std::fs::read_to_string(&path).expect("results were appended");
+    std::fs::remove_file(&path).expect("results file can be removed");
+
+    let records: Vec<serde_json::Value> =
+        serde_json::from_str(&written).expect("the results file is a JSON array");
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        assert!(record["timestamp"].is_u64(), "{}", record);
+        serde_json::from_value::<RunResult>(record.clone()).expect("a record is a run result");
+    }
+    assert_eq!(records[1]["answer"], 5905);
+
+    let lines = appended
+        .lines()
+        .map(|line| serde_json::from_str::<RunResult>(line).expect("each line is a record"))
+        .map(|result| (result.part, result.answer))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            (1, Some(6440.into())),
+            (1, Some(6440.into())),
+            (2, Some(5905.into())),
+        ]
+    );
+}
+
+#[cfg(all(feature = "day06", feature = "day07"))]
+#[test]
+fn test_batch() {
+    let output = aoc_with_stdin(&["batch", "tests/batch/jobs.toml", "-j", "2"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // the last job's expected answer is wrong on purpose
+    assert_eq!(output.status.code(), Some(5), "{:?}", output);
+    let verdicts = stdout
+        .lines()
+        .skip(2)
+        .take(4)
+        .map(|line| {
+            let cells = line.split('|').map(str::trim).collect::<Vec<_>>();
+            (cells[1], cells[5], cells[6], cells[8])
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        verdicts,
+        [
+            ("1", "288", "288", "passed"),
+            ("2", "71503", "71503", "passed"),
+            ("3", "6440", "", "solved"),
+            ("4", "5905", "6440", "mismatch"),
+        ],
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 job(s) didn't give the expected answer"),
+        "{}",
+        stderr
+    );
+
+    let missing = aoc_with_stdin(&["batch", "tests/batch/missing.toml"], "");
+    assert_eq!(missing.status.code(), Some(1), "{:?}", missing);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_inputs_matching_a_pattern() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-shared-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("alice.txt"), CAMEL_CARDS_EXAMPLE).unwrap();
+    std::fs::write(dir.join("bob.txt"), "32T3K 765\nnot a hand\n").unwrap();
+    std::fs::write(dir.join("carol.txt"), "32T3K 765\n").unwrap();
+    let pattern = dir.join("*.txt").to_string_lossy().into_owned();
+    let output = aoc_with_stdin(&["7", "all", &pattern, "--no-timing"], "");
+    let json = aoc_with_stdin(&["7", "all", &pattern, "--json"], "");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // bob's malformed input fails without stopping the others being solved
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows = stdout
+        .lines()
+        .skip(2)
+        .take(3)
+        .map(|line| {
+            let cells = line.split('|').map(str::trim).collect::<Vec<_>>();
+            let name = cells[1].rsplit(std::path::MAIN_SEPARATOR).next().unwrap();
+            (name.to_string(), cells[2].to_string(), cells[3].to_string())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            ("alice.txt", "6440", "5905"),
+            ("bob.txt", "failed", "failed"),
+            ("carol.txt", "765", "765"),
+        ]
+        .map(|(name, part1, part2)| (
+            name.to_string(),
+            part1.to_string(),
+            part2.to_string()
+        )),
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 of 3 input(s) failed"), "{}", stderr);
+    assert_eq!(json.status.code(), Some(2), "{:?}", json);
+}
+
+// day 7 is only the latest without the slow test day
+#[cfg(all(feature = "day07", not(feature = "slow-test-day")))]
+#[test]
+fn test_latest_day() {
+    // its own data root, so it doesn't need the real input
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-latest-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07")).unwrap();
+    std::fs::write(data_root.join("07").join("input"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let root = data_root.to_string_lossy();
+    let latest = aoc_with_stdin(&["latest", "--no-timing", "--data-dir", &root], "");
+    let day7 = aoc_with_stdin(&["7", "--no-timing", "--data-dir", &root], "");
+    std::fs::remove_dir_all(&data_root).unwrap();
+    assert!(latest.status.success(), "{:?}", latest);
+    assert!(
+        String::from_utf8_lossy(&latest.stdout).contains("Camel Cards"),
+        "{:?}",
+        latest
+    );
+    assert_eq!(latest.stdout, day7.stdout);
+}
+
+#[cfg(feature = "day01")]
+#[test]
+fn test_input_for_part_two() {
+    // each part of day 1 has its own example
+    let dir = std::env::temp_dir().join(format!("aoc-2023-input2-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let example = dir.join("example");
+    let example2 = dir.join("example2");
+    std::fs::write(&example, "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n").unwrap();
+    std::fs::write(
+        &example2,
+        "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen\n",
+    )
+    .unwrap();
+    let (example, example2) = (example.to_string_lossy(), example2.to_string_lossy());
+    let output = aoc_with_stdin(
+        &[
+            "1", "all", &example, "--input2", &example2, "--answer", "142,281",
+        ],
+        "",
+    );
+    let stdin = aoc_with_stdin(
+        &["1", "all", &example, "--input2", "-", "--answer", "142,281"],
+        "two1nine\n",
+    );
+    let one_part = aoc_with_stdin(&["1", "part1", &example, "--input2", &example2], "");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "Part 1 read {}, part 2 read {}",
+            example, example2
+        )),
+        "{}",
+        stdout
+    );
+    // part 2 gets 29 from stdin, not the 281 expected of its example
+    assert_eq!(stdin.status.code(), Some(5), "{:?}", stdin);
+    assert!(String::from_utf8_lossy(&stdin.stdout).contains("part 2 read stdin"));
+    assert_eq!(one_part.status.code(), Some(2), "{:?}", one_part);
+}
+
+#[cfg(all(unix, feature = "day07", feature = "slow-test-day"))]
+#[test]
+fn test_ctrl_c_keeps_finished_days() {
+    use std::{thread::sleep, time::Duration};
+
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-ctrl-c-{}", std::process::id()));
+    for (day, input) in [("07", CAMEL_CARDS_EXAMPLE), ("24", "60000\n")] {
+        std::fs::create_dir_all(data_root.join(day)).unwrap();
+        std::fs::write(data_root.join(day).join("input"), input).unwrap();
+    }
+    let root = data_root.to_string_lossy();
+    let interrupt = |args: &[&str], signals: usize| {
+        let child = Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(["all", &root, "--no-timing"])
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to start aoc-2023");
+        // long enough for day 7 to finish and day 24 to be running
+        sleep(Duration::from_millis(1000));
+        for _ in 0..signals {
+            let killed = Command::new("kill")
+                .args(["-INT", &child.id().to_string()])
+                .status()
+                .expect("failed to run kill");
+            assert!(killed.success());
+            sleep(Duration::from_millis(100));
+        }
+        child.wait_with_output().expect("aoc-2023 didn't finish")
+    };
+    let graceful = interrupt(&["--grace-period", "200ms"], 1);
+    // without a grace period a second Ctrl-C doesn't wait for day 24
+    let impatient = interrupt(&[], 2);
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert_eq!(graceful.status.code(), Some(130), "{:?}", graceful);
+    let stdout = String::from_utf8_lossy(&graceful.stdout);
+    assert!(
+        stdout.contains("[Part 1] Total winnings: 6440"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Succeeded: 7"), "{}", stdout);
+    assert!(stdout.contains("Interrupted: 24"), "{}", stdout);
+    let stderr = String::from_utf8_lossy(&graceful.stderr);
+    assert!(
+        stderr.contains("Interrupted, 1 day(s) didn't finish"),
+        "{}",
+        stderr
+    );
+    assert_eq!(impatient.status.code(), Some(130), "{:?}", impatient);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_record_answers() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-record-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07")).unwrap();
+    std::fs::write(data_root.join("07").join("input"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let answers = data_root.join("answers.toml");
+    std::fs::write(&answers, "# accepted answers\n[7]\npart1 = 6440\n").unwrap();
+    let root = data_root.to_string_lossy();
+    let run = aoc_with_stdin(&["7", "--data-dir", &root, "--record", "--no-timing"], "");
+    let recorded = std::fs::read_to_string(&answers);
+    let verified = aoc_with_stdin(&["verify", "7", "--data-dir", &root], "");
+    std::fs::write(&answers, "[7]\npart1 = 6440\npart2 = 5906\n").unwrap();
+    let wrong = aoc_with_stdin(&["verify", "7", "--data-dir", &root], "");
+    // or with the data directory given after the day
+    let wrong_in_dir = aoc_with_stdin(&["verify", "7", &root], "");
+    std::fs::remove_file(&answers).unwrap();
+    let verify_recorded = aoc_with_stdin(&["verify", "7", "--data-dir", &root, "--record"], "");
+    let verify_recorded_answers = std::fs::read_to_string(&answers);
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(run.status.success(), "{:?}", run);
+    assert!(String::from_utf8_lossy(&run.stderr).contains("Recorded 1 answer(s)"));
+    assert_eq!(
+        recorded.unwrap(),
+        "year = 2023\n# accepted answers\n[7]\npart1 = 6440\npart2 = 5905\n"
+    );
+    assert!(verified.status.success(), "{:?}", verified);
+    assert!(String::from_utf8_lossy(&verified.stdout)
+        .contains("Day 7: 2 example(s) and 2 recorded answer(s) passed"));
+    assert!(!wrong.status.success(), "{:?}", wrong);
+    assert!(String::from_utf8_lossy(&wrong.stderr)
+        .contains("[Part 2] input answer mismatch, expected: 5906, actual: 5905"));
+    assert_eq!(wrong_in_dir.status.code(), wrong.status.code());
+    assert!(verify_recorded.status.success(), "{:?}", verify_recorded);
+    assert_eq!(
+        verify_recorded_answers.unwrap(),
+        "year = 2023\n\n[7]\npart1 = 6440\npart2 = 5905\n"
+    );
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_budgets() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-budgets-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("data").join("07")).unwrap();
+    std::fs::write(
+        dir.join("data").join("07").join("input"),
+        CAMEL_CARDS_EXAMPLE,
+    )
+    .unwrap();
+    let aoc = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(["--data-dir", "data", "--no-timing"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+    let unenforced = aoc(&["--enforce-budget"]);
+    let roomy = aoc(&["all", "--budget", "1s", "--enforce-budget"]);
+    std::fs::write(dir.join("aoc.toml"), "[budgets]\n7 = \"0ms\"\n").unwrap();
+    let warned = aoc(&["all"]);
+    let enforced = aoc(&["7", "--enforce-budget"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(unenforced.status.code(), Some(2), "{:?}", unenforced);
+    assert!(roomy.status.success(), "{:?}", roomy);
+    assert!(String::from_utf8_lossy(&roomy.stdout).contains("\nBudget: 1.00s\n"));
+
+    // over budget is only a warning without --enforce-budget
+    assert!(warned.status.success(), "{:?}", warned);
+    let stdout = String::from_utf8_lossy(&warned.stdout);
+    assert!(stdout.contains(" Budget | Status |"), "{}", stdout);
+    let row = stdout
+        .lines()
+        .find(|line| line.starts_with("|   7 |"))
+        .unwrap_or_default();
+    assert!(row.contains("0.0µs ✗"), "{}", row);
+    let stderr = String::from_utf8_lossy(&warned.stderr);
+    assert!(stderr.contains("Day 7 took"), "{}", stderr);
+    assert!(stderr.contains("over its 0.0µs budget"), "{}", stderr);
+    assert_eq!(enforced.status.code(), Some(9), "{:?}", enforced);
+    assert!(String::from_utf8_lossy(&enforced.stderr).contains("1 budget(s) exceeded"));
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_budgets_count_solve_time() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-solve-budget-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("data").join("07")).unwrap();
+    std::fs::write(
+        dir.join("data").join("07").join("input"),
+        CAMEL_CARDS_EXAMPLE,
+    )
+    .unwrap();
+    let aoc = |budget: &str| {
+        std::fs::write(
+            dir.join("aoc.toml"),
+            format!("[budgets]\n7 = \"{}\"\n", budget),
+        )
+        .unwrap();
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(["7", "--data-dir", "data", "--enforce-budget"])
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+    // any solve at all takes longer than nothing
+    let over = aoc("0ms");
+    let within = aoc("5s");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(over.status.code(), Some(9), "{:?}", over);
+    assert!(String::from_utf8_lossy(&over.stderr).contains("over its 0.0µs budget"));
+    assert!(within.status.success(), "{:?}", within);
+}
+
+#[cfg(feature = "day04")]
+#[test]
+fn test_verbose_solvers() {
+    let cards = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+";
+    let quiet = aoc_with_stdin(&["4", "part1", "-", "--no-timing"], cards);
+    let verbose = aoc_with_stdin(&["4", "part1", "-", "--no-timing", "-v"], cards);
+
+    assert!(quiet.status.success(), "{:?}", quiet);
+    assert!(quiet.stderr.is_empty(), "{:?}", quiet);
+    assert!(verbose.status.success(), "{:?}", verbose);
+    // the work goes to stderr, leaving stdout as it was
+    assert_eq!(verbose.stdout, quiet.stdout);
+    assert_eq!(
+        String::from_utf8_lossy(&verbose.stderr),
+        "Card 1: 4 match(es), worth 8 point(s)\nCard 2: 2 match(es), worth 2 point(s)\n"
+    );
+}
+
+#[test]
+fn test_readme_report() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-readme-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("data")).unwrap();
+    std::fs::write(dir.join("data").join("answers.toml"), "[7]\npart1 = 6440\n").unwrap();
+    std::fs::write(
+        dir.join("timings.jsonl"),
+        "{\"timestamp\":0,\"git_sha\":null,\"day\":7,\"part\":1,\"duration_ms\":2.5,\"answer_hash\":\"a\",\"input_hash\":null}\n",
+    )
+    .unwrap();
+    let readme = "# Advent of Code\n\n<!-- BEGIN aoc-2023 results -->\n<!-- END aoc-2023 results -->\n\nNotes.\n";
+    std::fs::write(dir.join("README.md"), readme).unwrap();
+    let aoc = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(["report", "readme", "--data-dir", "data"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+    let updated = aoc(&["--timings", "timings.jsonl"]);
+    let text = std::fs::read_to_string(dir.join("README.md"));
+    let again = aoc(&["--timings", "timings.jsonl"]);
+    std::fs::write(dir.join("NOTES.md"), "# No markers\n").unwrap();
+    let unmarked = aoc(&["--file", "NOTES.md"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(updated.status.success(), "{:?}", updated);
+    let text = text.unwrap();
+    assert!(text.starts_with("# Advent of Code\n\n<!-- BEGIN aoc-2023 results -->\n| Day |"));
+    assert!(text.ends_with("<!-- END aoc-2023 results -->\n\nNotes.\n"));
+    let row = text
+        .lines()
+        .find(|line| line.starts_with("|   7 |"))
+        .unwrap_or_default();
+    assert!(row.contains("⭐"), "{}", row);
+    assert!(row.ends_with(" 2.5ms |"), "{}", row);
+    assert!(String::from_utf8_lossy(&again.stdout).contains("is up to date"));
+    assert!(!unmarked.status.success(), "{:?}", unmarked);
+    assert!(String::from_utf8_lossy(&unmarked.stderr).contains("There's no <!-- BEGIN"));
+}
+
+#[cfg(all(feature = "history", feature = "day07"))]
+#[test]
+fn test_history() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-history-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("example.txt"), CAMEL_CARDS_EXAMPLE).unwrap();
+    let aoc = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+    let before = aoc(&["history", "7"]);
+    let runs = [
+        aoc(&["7", "example.txt", "--record-history"]),
+        aoc(&["7", "part1", "example.txt", "--record-history"]),
+    ];
+    let history = aoc(&["history", "7"]);
+    let part2 = aoc(&["history", "7", "2"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(String::from_utf8_lossy(&before.stdout).contains("No runs of day 7"));
+    for run in runs {
+        assert!(run.status.success(), "{:?}", run);
+    }
+    assert!(history.status.success(), "{:?}", history);
+    let stdout = String::from_utf8_lossy(&history.stdout);
+    assert!(stdout.starts_with("| Run | When"), "{}", stdout);
+    assert_eq!(stdout.matches(" solved |").count(), 3, "{}", stdout);
+    assert!(stdout.contains("\nPart 1: "), "{}", stdout);
+    let stdout = String::from_utf8_lossy(&part2.stdout);
+    assert_eq!(stdout.matches(" solved |").count(), 1, "{}", stdout);
+    assert!(!stdout.contains("Part 1: "), "{}", stdout);
+}
+
+#[cfg(not(feature = "history"))]
+#[test]
+fn test_history_needs_feature() {
+    let output = aoc_with_stdin(&["history", "7"], "");
+    assert_eq!(output.status.code(), Some(2), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("build it with the history feature"));
+}
+
+#[test]
+fn test_badge_report() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-badge-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("data")).unwrap();
+    std::fs::write(
+        dir.join("data").join("answers.toml"),
+        "[7]\npart1 = 6440\npart2 = 5905\n",
+    )
+    .unwrap();
+    let aoc = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(["report", "badge", "--data-dir", "data"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+    let written = aoc(&["--output", "badge.json"]);
+    let badge = std::fs::read_to_string(dir.join("badge.json"));
+    let day = aoc(&["--day", "7"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(written.status.success(), "{:?}", written);
+    let badge: serde_json::Value = serde_json::from_str(&badge.unwrap()).unwrap();
+    assert_eq!(
+        badge,
+        serde_json::json!({
+            "schemaVersion": 1,
+            "label": "AoC 2023",
+            "message": "2/50 ⭐",
+            "color": "red",
+        })
+    );
+    let day: serde_json::Value = serde_json::from_slice(&day.stdout).unwrap();
+    assert_eq!(day["message"], "2/2 ⭐");
+    assert_eq!(day["color"], "green");
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_unreachable_webhook_only_warns() {
+    // nothing listens on port 1, so the post fails straight away
+    let output = aoc_with_stdin(
+        &["7", "-", "--notify-webhook", "http://127.0.0.1:1/hook"],
+        CAMEL_CARDS_EXAMPLE,
+    );
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Can't post to the webhook"));
+
+    let output = aoc_with_stdin(&["7", "-", "--notify-webhook"], CAMEL_CARDS_EXAMPLE);
+    assert_eq!(output.status.code(), Some(2), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--notify-webhook needs a URL"));
+}
+
+#[cfg(feature = "day06")]
+#[test]
+fn test_describe_prefers_a_fetched_puzzle() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-describe-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("06")).unwrap();
+    std::fs::write(
+        data_root.join("06").join("puzzle.md"),
+        "## --- Day 6: Wait For It ---\n\nPart one.\n\n## --- Part Two ---\n\nPart two.\n",
+    )
+    .unwrap();
+    let data_dir = data_root.to_string_lossy();
+    let all = aoc_with_stdin(&["6", "--describe", "--data-dir", &data_dir], "");
+    let part = aoc_with_stdin(&["6", "part2", "--describe", "--data-dir", &data_dir], "");
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(all.status.success(), "{:?}", all);
+    assert_eq!(
+        String::from_utf8_lossy(&all.stdout),
+        "## --- Day 6: Wait For It ---\n\nPart one.\n\n## --- Part Two ---\n\nPart two.\n"
+    );
+    assert!(part.status.success(), "{:?}", part);
+    assert_eq!(
+        String::from_utf8_lossy(&part.stdout),
+        "## --- Part Two ---\n\nPart two.\n"
+    );
+}
+
+#[test]
+fn test_status_offline_shows_the_recorded_answers() {
+    let data_root = std::env::temp_dir().join(format!("aoc-2023-status-{}", std::process::id()));
+    std::fs::create_dir_all(&data_root).unwrap();
+    std::fs::write(
+        data_root.join("answers.toml"),
+        "[22]\npart1 = 1\n[25]\npart1 = 2\npart2 = 3\n",
+    )
+    .unwrap();
+    let output = aoc_with_stdin(
+        &[
+            "status",
+            "--offline",
+            "--data-dir",
+            &data_root.to_string_lossy(),
+        ],
+        "",
+    );
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    // day 24 is marked implemented in slow-test-day builds, so only the
+    // recorded days' cells are checked
+    assert!(
+        lines[4].starts_with("21 ☆☆     22 ★☆     23 ☆☆"),
+        "{}",
+        stdout
+    );
+    assert!(lines[4].ends_with("25 ★★"), "{}", stdout);
+    assert_eq!(
+        lines[5],
+        "★ stars recorded in answers.toml, ✓ implemented here"
+    );
+    assert_eq!(
+        lines.last(),
+        Some(&"Solved but not implemented here: 22, 25"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_profiles() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-profiles-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("data").join("work")).unwrap();
+    std::fs::write(dir.join("work.session"), "work-cookie\n").unwrap();
+    std::fs::write(
+        dir.join("aoc.toml"),
+        "[profiles.personal]\n\n[profiles.work]\nsession_file = \"work.session\"\n",
+    )
+    .unwrap();
+    // only the work profile has any answers
+    std::fs::write(
+        dir.join("data").join("work").join("answers.toml"),
+        "[25]\npart1 = 1\npart2 = 2\n",
+    )
+    .unwrap();
+    let aoc = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args(args)
+            .current_dir(&dir)
+            .env_remove("AOC_PROFILE")
+            .env_remove("AOC_DATA_DIR")
+            .env_remove("AOC_YEAR")
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+    let none = aoc(&["profile", "list"]);
+    let set = aoc(&["profile", "set-default", "work"]);
+    let listed = aoc(&["profile", "list"]);
+    let unknown = aoc(&["profile", "set-default", "team"]);
+    let work = aoc(&["status", "--offline"]);
+    let personal = aoc(&["status", "--offline", "--profile", "personal"]);
+    let config = std::fs::read_to_string(dir.join("aoc.toml")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(none.status.success(), "{:?}", none);
+    assert_eq!(
+        String::from_utf8_lossy(&none.stdout),
+        "  personal  data/personal\n  work      data/work, session in work.session\n\
+         None is in use, pick one with --profile or set a default\n"
+    );
+    assert!(set.status.success(), "{:?}", set);
+    assert!(
+        config.starts_with("default_profile = \"work\"\n"),
+        "{}",
+        config
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&listed.stdout),
+        "  personal  data/personal\n* work      data/work, session in work.session (default)\n"
+    );
+    assert!(!unknown.status.success());
+    assert!(String::from_utf8_lossy(&unknown.stderr)
+        .contains("There's no profile team in aoc.toml, only personal, work"));
+
+    // each profile's answers are its own
+    assert!(work.status.success(), "{:?}", work);
+    assert!(String::from_utf8_lossy(&work.stdout).contains("25 ★★"));
+    assert!(personal.status.success(), "{:?}", personal);
+    assert!(String::from_utf8_lossy(&personal.stdout).contains("25 ☆☆"));
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_submit_refuses_logged_answers() {
+    let data_root =
+        std::env::temp_dir().join(format!("aoc-2023-submissions-{}", std::process::id()));
+    std::fs::create_dir_all(data_root.join("07")).unwrap();
+    std::fs::write(
+        data_root.join("07").join("submissions.toml"),
+        "[[submission]]\npart = 1\nanswer = 6440\nverdict = \"correct\"\ntimestamp = 1701925800\n\n\
+         [[submission]]\npart = 2\nanswer = 5000\nverdict = \"too_low\"\ntimestamp = 1701926000\n",
+    )
+    .unwrap();
+    let data_dir = data_root.to_string_lossy();
+    let submit = |part, value| {
+        Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+            .args([
+                "submit",
+                "7",
+                part,
+                "--value",
+                value,
+                "--data-dir",
+                &data_dir,
+            ])
+            .env("AOC_SESSION", "not-a-real-session")
+            .output()
+            .expect("failed to run aoc-2023")
+    };
+    let solved = submit("1", "6441");
+    let wrong = submit("2", "5000");
+    std::fs::remove_dir_all(&data_root).unwrap();
+
+    // both refused without asking adventofcode.com
+    assert_eq!(solved.status.code(), Some(2), "{:?}", solved);
+    assert!(
+        String::from_utf8_lossy(&solved.stderr)
+            .contains("Day 7 part 1 is solved already, with 6440"),
+        "{:?}",
+        solved
+    );
+    assert_eq!(wrong.status.code(), Some(2), "{:?}", wrong);
+    assert!(
+        String::from_utf8_lossy(&wrong.stderr)
+            .contains("Not submitting it: 5000 was already given and was wrong, it's too low"),
+        "{:?}",
+        wrong
+    );
+}
+
+#[test]
+fn test_other_years_cant_be_run() {
+    let output = aoc_with_stdin(&["all", "--year", "2022"], "");
+    assert_eq!(output.status.code(), Some(7), "{:?}", output);
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("There are no solvers for 2022, only for 2023"),
+        "{:?}",
+        output
+    );
+}
+
+#[test]
+fn test_new_day_follows_the_data_settings() {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-new-day-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc-2023"))
+        .args(["new", "8", "--title", "Haunted Wasteland"])
+        .args(["--data-dir", "inputs", "--layout", "flat"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run aoc-2023");
+    let module = dir.join("src").join("haunted_wasteland.rs").is_file();
+    let input = dir.join("inputs").join("input08.txt").is_file();
+    let example = dir.join("inputs").join("input08-example.txt").is_file();
+    let default_data = dir.join("data").exists();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(module);
+    assert!(input, "{:?}", output);
+    assert!(example, "{:?}", output);
+    assert!(!default_data);
+}
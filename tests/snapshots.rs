@@ -0,0 +1,96 @@
+//! The binary's exact output, which scripts read like an API. A change here
+//! fails until it's accepted with `cargo insta review`, so output only
+//! changes on purpose.
+
+use assert_cmd::Command;
+use std::fs;
+
+/// Runs the binary with `args` and `--color never`, in an empty directory
+/// and without the `AOC_*` environment, so only the arguments decide the
+/// output, expecting it to exit with `code`. Returns its stdout and stderr.
+fn aoc(args: &[&str], code: i32) -> (String, String) {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-snapshots-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("can't create the temporary directory");
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aoc-2023"));
+    command
+        .args(args)
+        .args(["--color", "never"])
+        .current_dir(&dir)
+        .env("RUST_BACKTRACE", "0")
+        .env("RUST_LIB_BACKTRACE", "0")
+        .write_stdin("");
+    for (name, _) in std::env::vars().filter(|(name, _)| name.starts_with("AOC_")) {
+        command.env_remove(name);
+    }
+    let output = command.assert().code(code).get_output().clone();
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+/// One of the inputs committed under tests/batch.
+#[cfg(any(feature = "day06", feature = "day07"))]
+fn example(name: &str) -> String {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "batch", name]
+        .iter()
+        .collect::<std::path::PathBuf>()
+        .display()
+        .to_string()
+}
+
+#[cfg(feature = "day06")]
+#[test]
+fn test_wait_for_it() {
+    let (stdout, _) = aoc(&["6", "all", &example("wait-for-it.txt"), "--no-timing"], 0);
+    insta::assert_snapshot!(stdout);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_camel_cards() {
+    let (stdout, _) = aoc(&["7", "all", &example("camel-cards.txt"), "--no-timing"], 0);
+    insta::assert_snapshot!(stdout);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_camel_cards_part_one() {
+    let (stdout, _) = aoc(
+        &["7", "part1", &example("camel-cards.txt"), "--no-timing"],
+        0,
+    );
+    insta::assert_snapshot!(stdout);
+}
+
+#[test]
+fn test_usage_error() {
+    let (_, stderr) = aoc(&["1", "--bogus"], 2);
+    insta::assert_snapshot!(stderr);
+}
+
+// the error lists the days built, so it's only the same with exactly the
+// default ones
+#[cfg(all(
+    feature = "day01",
+    feature = "day02",
+    feature = "day03",
+    feature = "day04",
+    feature = "day05",
+    feature = "day06",
+    feature = "day07",
+    not(feature = "slow-test-day")
+))]
+#[test]
+fn test_unknown_day() {
+    let (_, stderr) = aoc(&["9"], 7);
+    insta::assert_snapshot!(stderr);
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_missing_input() {
+    let (stdout, stderr) = aoc(&["7", "--no-timing"], 3);
+    assert_eq!(stdout, "");
+    insta::assert_snapshot!(stderr);
+}